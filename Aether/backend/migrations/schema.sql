@@ -89,6 +89,7 @@ CREATE TABLE IF NOT EXISTS vocab_details (
     root_id UUID,
     query_count INTEGER NOT NULL DEFAULT 0,
     is_important BOOLEAN NOT NULL DEFAULT FALSE,
+    lemma TEXT,
     FOREIGN KEY (id) REFERENCES nodes(id) ON DELETE CASCADE,
     FOREIGN KEY (root_id) REFERENCES vocab_details(id) ON DELETE SET NULL
 );
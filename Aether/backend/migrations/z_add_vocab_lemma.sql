@@ -0,0 +1,8 @@
+-- Migration: Vocabulary lemma column
+-- Stores the dictionary form of each word so inflected forms ("running", "ran")
+-- resolve to the same entry ("run").
+-- Kept alone in this file, and sorted before z_fix_vocab_schema.sql, which
+-- rebuilds vocab_details with the column: after the first run the ALTER
+-- fails with "duplicate column" and nothing else is skipped.
+
+ALTER TABLE vocab_details ADD COLUMN lemma TEXT;
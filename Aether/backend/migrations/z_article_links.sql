@@ -0,0 +1,22 @@
+-- Migration: Wiki links between articles
+-- One row per [[Title]] / [[Title|alias]] link, rebuilt on every save of the
+-- source. Rows without a target are links to pages nobody has written yet.
+
+CREATE TABLE IF NOT EXISTS article_links (
+    id UUID PRIMARY KEY,
+    source_id UUID NOT NULL,
+    source_block_id UUID,
+    target_id UUID,
+    target_title TEXT NOT NULL,
+    alias TEXT,
+    context TEXT NOT NULL DEFAULT '',
+    knowledge_base_id UUID,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (source_id) REFERENCES nodes(id) ON DELETE CASCADE,
+    -- Deleting the target turns its incoming links back into wanted pages
+    FOREIGN KEY (target_id) REFERENCES nodes(id) ON DELETE SET NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_article_links_source ON article_links(source_id);
+CREATE INDEX IF NOT EXISTS idx_article_links_target ON article_links(target_id);
+CREATE INDEX IF NOT EXISTS idx_article_links_title ON article_links(target_title);
@@ -0,0 +1,17 @@
+-- Migration: Block reference index
+-- One row per ((uuid)) embed or [[uuid]] link found in a document's blocks.
+-- Rebuilt for the document on every save; read to resolve transclusions and
+-- to find where a block is used before it is deleted.
+
+CREATE TABLE IF NOT EXISTS block_references (
+    id UUID PRIMARY KEY,
+    source_document_id UUID NOT NULL,
+    source_block_id UUID NOT NULL,
+    target_block_id UUID NOT NULL,
+    kind TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (source_document_id) REFERENCES nodes(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_block_references_source ON block_references(source_document_id);
+CREATE INDEX IF NOT EXISTS idx_block_references_target ON block_references(target_block_id);
@@ -25,6 +25,8 @@ CREATE TABLE IF NOT EXISTS vocab_details_new (
     root_id UUID,
     query_count INTEGER NOT NULL DEFAULT 0,
     is_important BOOLEAN NOT NULL DEFAULT FALSE,
+    -- Added by z_add_vocab_lemma.sql, which runs first; must stay the last column
+    lemma TEXT,
     FOREIGN KEY (id) REFERENCES nodes(id) ON DELETE CASCADE,
     FOREIGN KEY (root_id) REFERENCES vocab_roots(id) ON DELETE SET NULL
 );
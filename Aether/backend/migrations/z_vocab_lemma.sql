@@ -0,0 +1,9 @@
+-- Migration: Vocabulary lemmas (backfill and indexes)
+-- The column is added by z_add_vocab_lemma.sql. The indexes are created here,
+-- after z_fix_vocab_schema.sql has rebuilt the table.
+
+-- Backfill: plain lowercase until the word is saved again through the lemmatizer
+UPDATE vocab_details SET lemma = LOWER(word) WHERE lemma IS NULL;
+
+CREATE INDEX IF NOT EXISTS idx_vocab_details_lemma ON vocab_details(lemma);
+CREATE INDEX IF NOT EXISTS idx_vocab_details_root ON vocab_details(root_id);
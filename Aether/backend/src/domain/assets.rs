@@ -0,0 +1,18 @@
+use std::sync::OnceLock;
+use regex::Regex;
+use uuid::Uuid;
+
+/// Images referenced as `/api/assets/uuid` or `[[asset:uuid]]`.
+pub fn asset_ids(text: &str) -> Vec<Uuid> {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    let re = RE.get_or_init(|| Regex::new(r"(?:/api/assets/|\[\[asset:)([0-9a-fA-F-]{36})").unwrap());
+    let mut ids = Vec::new();
+    for cap in re.captures_iter(text) {
+        if let Ok(id) = Uuid::parse_str(&cap[1]) {
+            if !ids.contains(&id) {
+                ids.push(id);
+            }
+        }
+    }
+    ids
+}
@@ -0,0 +1,195 @@
+use chrono::Utc;
+use serde_json::Value;
+use thiserror::Error;
+use uuid::Uuid;
+use super::models::Block;
+use super::parser::parse_markdown_to_blocks;
+use super::strategies::apply_searchable_trait;
+pub use super::render::render_markdown;
+
+/// Block types an article body can hold; anything else has no Markdown form.
+pub const ARTICLE_BLOCK_TYPES: &[&str] = &[
+    "paragraph", "heading", "code", "math", "list", "table", "blockquote", "image",
+    "thematic_break", "html", "front_matter", "directive",
+];
+
+#[derive(Debug, Error, PartialEq)]
+pub enum BlockEditError {
+    #[error("Block not found: {0}")]
+    NotFound(Uuid),
+    #[error("Block revision conflict: expected {expected}, current is {current}")]
+    StaleRevision { expected: i64, current: i64 },
+    #[error("Block type '{0}' cannot be stored in an article body")]
+    UnsupportedType(String),
+    #[error("Block content does not survive the Markdown body: {0}")]
+    NotRoundTrip(String),
+}
+
+/// Fails unless `block` is still at the revision the client edited.
+pub fn check_revision(block: &Block, expected: Option<i64>) -> Result<(), BlockEditError> {
+    match expected {
+        Some(expected) if expected != block.revision as i64 => {
+            Err(BlockEditError::StaleRevision { expected, current: block.revision as i64 })
+        }
+        _ => Ok(()),
+    }
+}
+
+pub fn new_block(document_id: Uuid, type_name: &str, payload: Value) -> Result<Block, BlockEditError> {
+    if !ARTICLE_BLOCK_TYPES.contains(&type_name) {
+        return Err(BlockEditError::UnsupportedType(type_name.to_string()));
+    }
+    Ok(Block {
+        id: Uuid::new_v4(),
+        document_id,
+        type_name: type_name.to_string(),
+        ordinal: 0,
+        revision: 1,
+        payload,
+        created_at: Utc::now(),
+        updated_at: Utc::now(),
+    })
+}
+
+/// Blocks of an article stored before the blocks table existed, parsed from
+/// its body. Ids derive from (document, ordinal), so a later request parsing
+/// the same body finds the blocks a client listed.
+pub fn split_unstored(document_id: Uuid, markdown: &str) -> Vec<Block> {
+    let mut blocks = parse_markdown_to_blocks(document_id, markdown);
+    for block in &mut blocks {
+        block.id = Uuid::new_v5(&document_id, &block.ordinal.to_be_bytes());
+    }
+    blocks
+}
+
+/// Inserts at `index` (clamped to the end) and renumbers the ordinals.
+pub fn insert(blocks: &mut Vec<Block>, block: Block, index: usize) {
+    let index = index.min(blocks.len());
+    blocks.insert(index, block);
+    renumber(blocks);
+}
+
+pub fn update(blocks: &mut [Block], id: Uuid, payload: Value) -> Result<(), BlockEditError> {
+    let block = find_mut(blocks, id)?;
+    block.payload = payload;
+    touch(block);
+    Ok(())
+}
+
+/// Moves a block to `index` among the document's blocks (clamped to the end).
+pub fn move_to(blocks: &mut Vec<Block>, id: Uuid, index: usize) -> Result<(), BlockEditError> {
+    let from = position(blocks, id)?;
+    let mut block = blocks.remove(from);
+    touch(&mut block);
+    insert(blocks, block, index);
+    Ok(())
+}
+
+pub fn remove(blocks: &mut Vec<Block>, id: Uuid) -> Result<Block, BlockEditError> {
+    let index = position(blocks, id)?;
+    let block = blocks.remove(index);
+    renumber(blocks);
+    Ok(block)
+}
+
+/// Renders the blocks and parses the result back, so what is stored in the
+/// blocks table is exactly what the body yields on the next full save. The
+/// parsed (normalized) payloads are adopted, `text_mirror` is refreshed, and
+/// the body is returned. Fails when a block would turn into something else,
+/// e.g. a paragraph with a blank line splitting into two.
+pub fn normalize(document_id: Uuid, blocks: &mut [Block]) -> Result<String, BlockEditError> {
+    let body = render_markdown(blocks);
+    let parsed = parse_markdown_to_blocks(document_id, &body);
+    if parsed.len() != blocks.len() {
+        return Err(BlockEditError::NotRoundTrip(format!(
+            "{} blocks are read back as {}", blocks.len(), parsed.len()
+        )));
+    }
+    for (block, reparsed) in blocks.iter_mut().zip(parsed) {
+        if block.type_name != reparsed.type_name {
+            return Err(BlockEditError::NotRoundTrip(format!(
+                "a {} block is read back as a {}", block.type_name, reparsed.type_name
+            )));
+        }
+        if shape(&block.payload) != shape(&reparsed.payload) {
+            return Err(BlockEditError::NotRoundTrip(format!(
+                "the nested blocks of a {} block change", block.type_name
+            )));
+        }
+        block.payload = reparsed.payload;
+        apply_searchable_trait(block);
+    }
+    Ok(render_markdown(blocks))
+}
+
+/// Carries block identities over a full re-parse of the body: a parsed block
+/// takes the id of an unclaimed existing block with identical content, else
+/// of the unclaimed block of the same type at the same position (bumping its
+/// revision). Everything else is new.
+pub fn reconcile(existing: &[Block], parsed: Vec<Block>) -> Vec<Block> {
+    let mut claimed = vec![false; existing.len()];
+    let mut matches: Vec<Option<usize>> = parsed.iter().map(|p| {
+        let found = existing.iter().enumerate().position(|(i, e)| {
+            !claimed[i] && e.type_name == p.type_name && e.payload == p.payload
+        });
+        if let Some(i) = found {
+            claimed[i] = true;
+        }
+        found
+    }).collect();
+
+    for (n, p) in parsed.iter().enumerate() {
+        if matches[n].is_some() {
+            continue;
+        }
+        if let Some(i) = existing.iter().position(|e| e.ordinal == p.ordinal && e.type_name == p.type_name) {
+            if !claimed[i] {
+                claimed[i] = true;
+                matches[n] = Some(i);
+            }
+        }
+    }
+
+    parsed.into_iter().zip(matches).map(|(mut p, found)| {
+        if let Some(i) = found {
+            let e = &existing[i];
+            p.id = e.id;
+            p.created_at = e.created_at;
+            if e.payload == p.payload {
+                p.revision = e.revision;
+                p.updated_at = e.updated_at;
+            } else {
+                p.revision = e.revision + 1;
+            }
+        }
+        p
+    }).collect()
+}
+
+/// The types of a block's nested blocks, recursively: `list_item[paragraph],list_item[...]`.
+fn shape(payload: &Value) -> String {
+    let children = payload.get("children").and_then(|c| c.as_array()).map(Vec::as_slice).unwrap_or(&[]);
+    children.iter()
+        .map(|child| format!("{}[{}]", child["type"].as_str().unwrap_or(""), shape(&child["payload"])))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+fn renumber(blocks: &mut [Block]) {
+    for (i, b) in blocks.iter_mut().enumerate() {
+        b.ordinal = i as i32;
+    }
+}
+
+fn touch(block: &mut Block) {
+    block.revision += 1;
+    block.updated_at = Utc::now();
+}
+
+fn position(blocks: &[Block], id: Uuid) -> Result<usize, BlockEditError> {
+    blocks.iter().position(|b| b.id == id).ok_or(BlockEditError::NotFound(id))
+}
+
+fn find_mut(blocks: &mut [Block], id: Uuid) -> Result<&mut Block, BlockEditError> {
+    blocks.iter_mut().find(|b| b.id == id).ok_or(BlockEditError::NotFound(id))
+}
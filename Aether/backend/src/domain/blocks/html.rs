@@ -0,0 +1,423 @@
+use serde_json::Value;
+use uuid::Uuid;
+use super::models::Block;
+
+// Blocks to HTML, for exports. Everything authored is escaped: raw HTML is
+// omitted (CommonMark's "safe" mode) and only http(s)/mailto/relative URLs
+// survive. Math stays TeX, wrapped the way KaTeX's auto-render (and pandoc's
+// `--katex`) expect: `<span class="math inline">\(...\)</span>`.
+
+/// Where the renderer asks about URLs it cannot resolve on its own.
+pub trait HtmlResolver {
+    /// The `src` to emit for an image (`/api/assets/uuid`, `[[asset:uuid]]`, a URL...); `None` drops it.
+    fn image_src(&mut self, src: &str) -> Option<String> {
+        safe_url(src)
+    }
+
+    /// The `href` to emit for a Markdown link; `None` keeps only the label.
+    fn link_href(&mut self, href: &str) -> Option<String> {
+        safe_url(href)
+    }
+
+    /// The page a `[[Title]]` wiki link points to, if it exists.
+    fn wiki_href(&self, _title: &str) -> Option<String> {
+        None
+    }
+}
+
+/// Keeps URLs as written.
+pub struct PlainResolver;
+
+impl HtmlResolver for PlainResolver {}
+
+pub fn render_html(blocks: &[Block], resolver: &mut dyn HtmlResolver) -> String {
+    blocks.iter()
+        .map(|b| render_block(&b.type_name, &b.payload, Some(b.id), false, resolver))
+        .filter(|s| !s.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// The anchor a block renders with; `((uuid))`/`[[uuid]]` references link to it.
+pub fn block_anchor(id: Uuid) -> String {
+    format!("blk-{}", id)
+}
+
+pub fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// http(s), mailto, fragment and relative URLs; `javascript:` and friends are dropped.
+pub fn safe_url(url: &str) -> Option<String> {
+    let url = url.trim();
+    let scheme_end = url.find(':');
+    let path_start = url.find(['/', '?', '#']).unwrap_or(url.len());
+    match scheme_end {
+        Some(end) if end < path_start => {
+            let scheme = url[..end].to_lowercase();
+            matches!(scheme.as_str(), "http" | "https" | "mailto").then(|| url.to_string())
+        }
+        _ => Some(url.to_string()),
+    }
+}
+
+fn render_block(type_name: &str, payload: &Value, id: Option<Uuid>, tight: bool, resolver: &mut dyn HtmlResolver) -> String {
+    let field = |name: &str| payload.get(name).and_then(|v| v.as_str()).unwrap_or("").to_string();
+    let anchor = id.map(|id| format!(" id=\"{}\"", block_anchor(id))).unwrap_or_default();
+    match type_name {
+        "heading" => {
+            let level = payload.get("level").and_then(|v| v.as_u64()).unwrap_or(1).clamp(1, 6);
+            format!("<h{level}{anchor}>{}</h{level}>", render_inline(&field("text"), resolver))
+        }
+        // Tight list items hold their text without a paragraph around it
+        "paragraph" if tight => render_inline(&field("markdown"), resolver),
+        "paragraph" => format!("<p{anchor}>{}</p>", render_inline(&field("markdown"), resolver)),
+        "code" => {
+            let language = field("language");
+            let class = match language.split_whitespace().next() {
+                Some(lang) => format!(" class=\"language-{}\"", escape(lang)),
+                None => String::new(),
+            };
+            format!("<pre{anchor}><code{class}>{}</code></pre>", escape(&field("code")))
+        }
+        "math" => format!("<div{anchor} class=\"math display\">\\[{}\\]</div>", escape(field("latex").trim())),
+        "thematic_break" => "<hr />".to_string(),
+        "image" => {
+            let img = image_tag(&field("alt"), &field("src"), payload.get("title").and_then(|v| v.as_str()), resolver);
+            if img.is_empty() { img } else { format!("<figure{anchor}>{img}</figure>") }
+        }
+        "html" => "<!-- raw HTML omitted -->".to_string(),
+        "front_matter" => String::new(),
+        "blockquote" => format!("<blockquote{anchor}>\n{}\n</blockquote>", render_children(payload, false, resolver)),
+        "directive" => {
+            let name: String = field("name").chars().filter(|c| c.is_ascii_alphanumeric() || *c == '-').collect();
+            format!("<div{anchor} class=\"directive directive-{name}\">\n{}\n</div>", render_children(payload, false, resolver))
+        }
+        "list" => render_list(payload, &anchor, resolver),
+        "table" => render_table(payload, &anchor, resolver),
+
+        // Math KB Types
+        "axiom" | "theorem" | "definition" => {
+            let note = if type_name == "definition" { field("term") } else { field("label") };
+            let mut title = capitalize(type_name);
+            if !note.is_empty() {
+                title.push_str(&format!(" ({})", render_inline(&note, resolver)));
+            }
+            format!(
+                "<div{anchor} class=\"math-block {type_name}\"><strong>{title}.</strong> {}</div>",
+                render_inline(&field("content"), resolver)
+            )
+        }
+        "proof" => format!(
+            "<div{anchor} class=\"math-block proof\"><em>Proof.</em> {} <span class=\"qed\">{}</span></div>",
+            render_inline(&field("steps"), resolver),
+            escape(payload.get("qcd_symbol").and_then(|v| v.as_str()).unwrap_or("■")),
+        ),
+
+        _ => {
+            let text = field("text_mirror");
+            if text.is_empty() { text } else { format!("<p{anchor}>{}</p>", escape(&text)) }
+        }
+    }
+}
+
+fn capitalize(s: &str) -> String {
+    let mut chars = s.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+fn children(payload: &Value) -> &[Value] {
+    payload.get("children").and_then(|v| v.as_array()).map(Vec::as_slice).unwrap_or(&[])
+}
+
+fn render_children(payload: &Value, tight: bool, resolver: &mut dyn HtmlResolver) -> String {
+    children(payload).iter()
+        .map(|child| render_block(child["type"].as_str().unwrap_or(""), &child["payload"], None, tight, resolver))
+        .filter(|s| !s.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn render_list(payload: &Value, anchor: &str, resolver: &mut dyn HtmlResolver) -> String {
+    let ordered = payload.get("ordered").and_then(|v| v.as_bool()).unwrap_or(false);
+    let tight = payload.get("tight").and_then(|v| v.as_bool()).unwrap_or(true);
+    let start = payload.get("start").and_then(|v| v.as_u64()).unwrap_or(1);
+
+    let open = match (ordered, start) {
+        (false, _) => format!("<ul{anchor}>"),
+        (true, 1) => format!("<ol{anchor}>"),
+        (true, n) => format!("<ol{anchor} start=\"{n}\">"),
+    };
+    let mut out = vec![open];
+    for item in children(payload) {
+        let item = &item["payload"];
+        let checkbox = match item.get("checked").and_then(|v| v.as_bool()) {
+            Some(true) => "<input type=\"checkbox\" checked=\"checked\" disabled=\"disabled\" /> ",
+            Some(false) => "<input type=\"checkbox\" disabled=\"disabled\" /> ",
+            None => "",
+        };
+        out.push(format!("<li>{}{}</li>", checkbox, render_children(item, tight, resolver)));
+    }
+    out.push(if ordered { "</ol>" } else { "</ul>" }.to_string());
+    out.join("\n")
+}
+
+fn render_table(payload: &Value, anchor: &str, resolver: &mut dyn HtmlResolver) -> String {
+    let header: Vec<&str> = payload["header"].as_array().map(|a| a.iter().map(|c| c.as_str().unwrap_or("")).collect()).unwrap_or_default();
+    let align = |i: usize| match payload["align"].get(i).and_then(|v| v.as_str()) {
+        Some(a @ ("left" | "center" | "right")) => format!(" style=\"text-align: {}\"", a),
+        _ => String::new(),
+    };
+
+    let mut out = vec![format!("<table{anchor}>"), "<thead>".to_string(), "<tr>".to_string()];
+    for (i, cell) in header.iter().enumerate() {
+        out.push(format!("<th{}>{}</th>", align(i), render_inline(cell, resolver)));
+    }
+    out.extend(["</tr>".to_string(), "</thead>".to_string(), "<tbody>".to_string()]);
+    for row in payload["rows"].as_array().map(Vec::as_slice).unwrap_or(&[]) {
+        out.push("<tr>".to_string());
+        for i in 0..header.len() {
+            let cell = row.get(i).and_then(|c| c.as_str()).unwrap_or("");
+            out.push(format!("<td{}>{}</td>", align(i), render_inline(cell, resolver)));
+        }
+        out.push("</tr>".to_string());
+    }
+    out.extend(["</tbody>".to_string(), "</table>".to_string()]);
+    out.join("\n")
+}
+
+fn image_tag(alt: &str, src: &str, title: Option<&str>, resolver: &mut dyn HtmlResolver) -> String {
+    let Some(src) = resolver.image_src(src) else { return String::new() };
+    let title = title.map(|t| format!(" title=\"{}\"", escape(t))).unwrap_or_default();
+    format!("<img src=\"{}\" alt=\"{}\"{} />", escape(&src), escape(alt), title)
+}
+
+/// Inline Markdown: code spans, math, links and images, emphasis, strikethrough,
+/// autolinks, hard breaks, wiki links and block references.
+pub fn render_inline(text: &str, resolver: &mut dyn HtmlResolver) -> String {
+    let mut out = String::new();
+    let mut i = 0;
+    while i < text.len() {
+        let rest = &text[i..];
+        let c = rest.chars().next().unwrap();
+
+        if c == '\\' {
+            match rest[1..].chars().next() {
+                Some(next) if next.is_ascii_punctuation() => {
+                    out.push_str(&escape(&next.to_string()));
+                    i += 2;
+                    continue;
+                }
+                Some('\n') => {
+                    out.push_str("<br />\n");
+                    i += 2;
+                    continue;
+                }
+                _ => {}
+            }
+        }
+
+        if let Some((html, len)) = inline_construct(rest, i == 0 || !is_word_char(text[..i].chars().last()), resolver) {
+            out.push_str(&html);
+            i += len;
+            continue;
+        }
+
+        if c == '\n' {
+            // Two trailing spaces make a hard break
+            if out.ends_with("  ") {
+                out.truncate(out.trim_end_matches(' ').len());
+                out.push_str("<br />");
+            }
+            out.push('\n');
+        } else {
+            out.push_str(&escape(&c.to_string()));
+        }
+        i += c.len_utf8();
+    }
+    out
+}
+
+fn is_word_char(c: Option<char>) -> bool {
+    c.is_some_and(|c| c.is_alphanumeric())
+}
+
+/// The construct starting at `rest`, rendered, with its byte length.
+fn inline_construct(rest: &str, word_start: bool, resolver: &mut dyn HtmlResolver) -> Option<(String, usize)> {
+    let c = rest.chars().next()?;
+    match c {
+        '`' => {
+            let ticks = rest.len() - rest.trim_start_matches('`').len();
+            let fence = &rest[..ticks];
+            let end = find_exact_run(&rest[ticks..], fence)?;
+            let code = rest[ticks..ticks + end].replace('\n', " ");
+            let code = if code.starts_with(' ') && code.ends_with(' ') && !code.trim().is_empty() { &code[1..code.len() - 1] } else { code.as_str() };
+            Some((format!("<code>{}</code>", escape(code)), ticks + end + ticks))
+        }
+        '$' => {
+            let (delimiter, class, open, close) = if rest.starts_with("$$") {
+                ("$$", "display", "\\[", "\\]")
+            } else {
+                ("$", "inline", "\\(", "\\)")
+            };
+            let body = &rest[delimiter.len()..];
+            let end = body.find(delimiter)?;
+            let tex = &body[..end];
+            // `$5 and $6` is not math
+            if tex.trim().is_empty() || (delimiter == "$" && (tex.starts_with(' ') || tex.ends_with(' '))) {
+                return None;
+            }
+            Some((format!("<span class=\"math {class}\">{open}{}{close}</span>", escape(tex)), delimiter.len() * 2 + end))
+        }
+        '!' if rest.starts_with("![") => {
+            let (alt, close) = bracketed(&rest[1..])?;
+            let (src, title, len) = link_target(&rest[1 + close..])?;
+            Some((image_tag(alt, &src, title.as_deref(), resolver), 1 + close + len))
+        }
+        '[' => {
+            if let Some(inner) = rest.strip_prefix("[[").and_then(|r| r.split_once("]]")).map(|(inner, _)| inner) {
+                if !inner.contains(['[', ']', '\n']) && !inner.trim().is_empty() {
+                    return Some((render_double_bracket(inner, resolver), inner.len() + 4));
+                }
+            }
+            let (label, close) = bracketed(rest)?;
+            let (href, title, len) = link_target(&rest[close..])?;
+            let label = render_inline(label, resolver);
+            let title = title.map(|t| format!(" title=\"{}\"", escape(&t))).unwrap_or_default();
+            let html = match resolver.link_href(&href) {
+                Some(href) => format!("<a href=\"{}\"{}>{}</a>", escape(&href), title, label),
+                None => label,
+            };
+            Some((html, close + len))
+        }
+        '(' if rest.starts_with("((") => {
+            let id = rest.get(2..38).and_then(|s| Uuid::parse_str(s).ok())?;
+            if !rest[38..].starts_with("))") {
+                return None;
+            }
+            Some((format!("<a class=\"block-ref embed\" href=\"#{}\">↪</a>", block_anchor(id)), 40))
+        }
+        '<' => {
+            let end = rest.find('>')?;
+            let url = &rest[1..end];
+            let lower = url.to_lowercase();
+            if url.contains(char::is_whitespace) || !(lower.starts_with("http://") || lower.starts_with("https://") || lower.starts_with("mailto:")) {
+                return None;
+            }
+            Some((format!("<a href=\"{}\">{}</a>", escape(url), escape(url)), end + 1))
+        }
+        '~' if rest.starts_with("~~") => emphasis(rest, "~~", "del", resolver),
+        '*' if rest.starts_with("**") => emphasis(rest, "**", "strong", resolver),
+        '*' => emphasis(rest, "*", "em", resolver),
+        // Intraword underscores (snake_case) are not emphasis
+        '_' if word_start && rest.starts_with("__") => emphasis(rest, "__", "strong", resolver),
+        '_' if word_start => emphasis(rest, "_", "em", resolver),
+        _ => None,
+    }
+}
+
+fn emphasis(rest: &str, delimiter: &str, tag: &str, resolver: &mut dyn HtmlResolver) -> Option<(String, usize)> {
+    let body = &rest[delimiter.len()..];
+    if body.starts_with(char::is_whitespace) {
+        return None;
+    }
+    let end = find_exact_run(body, delimiter)?;
+    let inner = &body[..end];
+    if inner.is_empty() || inner.ends_with(char::is_whitespace) {
+        return None;
+    }
+    Some((format!("<{tag}>{}</{tag}>", render_inline(inner, resolver)), delimiter.len() * 2 + end))
+}
+
+/// Offset of the next run of exactly `run` (not part of a longer run of the same character).
+fn find_exact_run(text: &str, run: &str) -> Option<usize> {
+    let ch = run.chars().next()?;
+    let mut from = 0;
+    while let Some(offset) = text[from..].find(run) {
+        let start = from + offset;
+        let len = text[start..].len() - text[start..].trim_start_matches(ch).len();
+        if len == run.len() {
+            return Some(start);
+        }
+        from = start + len;
+    }
+    None
+}
+
+/// `[label]` with nested brackets: the label and the offset just past `]`.
+fn bracketed(text: &str) -> Option<(&str, usize)> {
+    let mut depth = 0;
+    for (i, c) in text.char_indices() {
+        match c {
+            '[' => depth += 1,
+            ']' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some((&text[1..i], i + 1));
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// `(url "title")` right after a link label: url, title and length.
+fn link_target(text: &str) -> Option<(String, Option<String>, usize)> {
+    let inner = text.strip_prefix('(')?;
+    // Parentheses inside the URL must balance
+    let mut depth = 0;
+    let end = inner.char_indices().find(|&(_, c)| {
+        match c {
+            '(' => depth += 1,
+            ')' if depth == 0 => return true,
+            ')' => depth -= 1,
+            _ => {}
+        }
+        false
+    })?.0;
+    let inner = inner[..end].trim();
+    let (url, title) = match inner.split_once(char::is_whitespace) {
+        Some((url, title)) => {
+            let title = title.trim();
+            let title = title.strip_prefix('"').and_then(|t| t.strip_suffix('"')).unwrap_or(title);
+            (url, Some(title.to_string()))
+        }
+        None => (inner, None),
+    };
+    let url = url.strip_prefix('<').and_then(|u| u.strip_suffix('>')).unwrap_or(url);
+    Some((url.to_string(), title, end + 2))
+}
+
+/// `[[uuid]]` block link, `[[asset:uuid]]` image or `[[Title|alias]]` wiki link.
+fn render_double_bracket(inner: &str, resolver: &mut dyn HtmlResolver) -> String {
+    if let Ok(id) = Uuid::parse_str(inner.trim()) {
+        return format!("<a class=\"block-ref\" href=\"#{}\">↗</a>", block_anchor(id));
+    }
+    if inner.starts_with("asset:") {
+        return image_tag("", &format!("[[{}]]", inner), None, resolver);
+    }
+    let (target, alias) = match inner.split_once('|') {
+        Some((target, alias)) if !alias.trim().is_empty() => (target.trim(), alias.trim()),
+        Some((target, _)) => (target.trim(), target.trim()),
+        None => (inner.trim(), inner.trim()),
+    };
+    match resolver.wiki_href(target) {
+        Some(href) => format!("<a class=\"wiki-link\" href=\"{}\">{}</a>", escape(&href), escape(alias)),
+        None => format!("<span class=\"wiki-link wanted\" title=\"{}\">{}</span>", escape(target), escape(alias)),
+    }
+}
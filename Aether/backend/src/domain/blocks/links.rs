@@ -0,0 +1,172 @@
+use std::ops::Range;
+use uuid::Uuid;
+use super::models::Block;
+use super::strategies::visit_text;
+
+/// Characters of context kept on each side of a link for backlink snippets.
+const CONTEXT_CHARS: usize = 80;
+
+/// A `[[Title]]` or `[[Title|alias]]` link as written.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WikiLink {
+    pub target: String,
+    pub alias: Option<String>,
+    pub span: Range<usize>, // Byte range of the whole `[[...]]`
+}
+
+/// A wiki link found in an article's blocks, with the text around it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WikiLinkOccurrence {
+    pub source_block_id: Uuid,
+    pub target: String,
+    pub alias: Option<String>,
+    pub context: String,
+}
+
+/// Finds the wiki links in a piece of inline Markdown, outside code spans.
+/// `[[uuid]]` is a block link (see `strategies::scan_block_references`), not a title.
+pub fn parse_wiki_links(text: &str) -> Vec<WikiLink> {
+    let code = code_spans(text);
+    let mut links = Vec::new();
+    let mut from = 0;
+    while let Some(offset) = text[from..].find("[[") {
+        let start = from + offset;
+        from = start + 2;
+        if code.iter().any(|r| r.contains(&start)) {
+            continue;
+        }
+        let Some(len) = text[start + 2..].find("]]") else { break };
+        let inner = &text[start + 2..start + 2 + len];
+        if inner.contains(['[', ']', '\n']) {
+            continue;
+        }
+        let (target, alias) = match inner.split_once('|') {
+            Some((target, alias)) => (target.trim(), Some(alias.trim().to_string()).filter(|a| !a.is_empty())),
+            None => (inner.trim(), None),
+        };
+        if target.is_empty() || Uuid::parse_str(target).is_ok() {
+            continue;
+        }
+        let end = start + 2 + len + 2;
+        links.push(WikiLink { target: target.to_string(), alias, span: start..end });
+        from = end;
+    }
+    links
+}
+
+/// Wiki links across an article's blocks, skipping code and math.
+pub fn scan_wiki_links(blocks: &[Block]) -> Vec<WikiLinkOccurrence> {
+    let mut found = Vec::new();
+    for block in blocks {
+        visit_text(&block.type_name, &block.payload, &mut |text| {
+            for link in parse_wiki_links(text) {
+                found.push(WikiLinkOccurrence {
+                    source_block_id: block.id,
+                    context: context(text, &link.span),
+                    target: link.target,
+                    alias: link.alias,
+                });
+            }
+        });
+    }
+    found
+}
+
+/// Points the links to `from` (matched case-insensitively) at `to` in a
+/// Markdown body, keeping aliases. Fenced code, math blocks and code spans
+/// are left alone. Returns the new body and the number of links rewritten.
+pub fn rewrite_wiki_links(markdown: &str, from: &str, to: &str) -> (String, usize) {
+    let from = from.trim().to_lowercase();
+    let mut out = String::with_capacity(markdown.len());
+    let mut count = 0;
+    let mut fence: Option<String> = None;
+
+    for line in markdown.split_inclusive('\n') {
+        let trimmed = line.trim();
+        if let Some(open) = &fence {
+            if trimmed.starts_with(open.as_str()) && trimmed.chars().all(|c| open.starts_with(c)) {
+                fence = None;
+            }
+            out.push_str(line);
+            continue;
+        }
+        let marker = trimmed.chars().take_while(|c| *c == '`' || *c == '~').collect::<String>();
+        if marker.len() >= 3 && marker.chars().all(|c| c == marker.chars().next().unwrap()) {
+            fence = Some(marker);
+            out.push_str(line);
+            continue;
+        }
+        if trimmed == "$$" {
+            fence = Some("$$".to_string());
+            out.push_str(line);
+            continue;
+        }
+
+        let mut last = 0;
+        for link in parse_wiki_links(line) {
+            if link.target.to_lowercase() != from {
+                continue;
+            }
+            out.push_str(&line[last..link.span.start]);
+            match &link.alias {
+                Some(alias) => out.push_str(&format!("[[{}|{}]]", to, alias)),
+                None => out.push_str(&format!("[[{}]]", to)),
+            }
+            last = link.span.end;
+            count += 1;
+        }
+        out.push_str(&line[last..]);
+    }
+    (out, count)
+}
+
+/// Byte ranges of inline code spans: a run of backticks up to the next run of the same length.
+pub fn code_spans(text: &str) -> Vec<Range<usize>> {
+    let bytes = text.as_bytes();
+    let mut spans = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] != b'`' {
+            i += 1;
+            continue;
+        }
+        let run = bytes[i..].iter().take_while(|b| **b == b'`').count();
+        let mut j = i + run;
+        let mut closed = None;
+        while j < bytes.len() {
+            if bytes[j] == b'`' {
+                let close = bytes[j..].iter().take_while(|b| **b == b'`').count();
+                if close == run {
+                    closed = Some(j + close);
+                    break;
+                }
+                j += close;
+            } else {
+                j += 1;
+            }
+        }
+        match closed {
+            Some(end) => {
+                spans.push(i..end);
+                i = end;
+            }
+            None => i += run,
+        }
+    }
+    spans
+}
+
+/// The link with up to `CONTEXT_CHARS` characters on each side, whitespace collapsed.
+fn context(text: &str, span: &Range<usize>) -> String {
+    let before: String = text[..span.start].chars().rev().take(CONTEXT_CHARS).collect::<Vec<_>>().into_iter().rev().collect();
+    let after: String = text[span.end..].chars().take(CONTEXT_CHARS).collect();
+    let mut snippet = format!("{}{}{}", before, &text[span.clone()], after);
+    snippet = snippet.split_whitespace().collect::<Vec<_>>().join(" ");
+    if before.len() < span.start {
+        snippet.insert(0, '…');
+    }
+    if span.end + after.len() < text.len() {
+        snippet.push('…');
+    }
+    snippet
+}
@@ -1,5 +1,13 @@
 pub mod models;
 pub mod registry;
 pub mod parser;
+pub mod render;
+pub mod html;
 pub mod schemas;
 pub mod strategies;
+pub mod editing;
+pub mod links;
+#[cfg(test)]
+pub mod testing;
+
+mod tests;
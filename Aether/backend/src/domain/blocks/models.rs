@@ -29,3 +29,50 @@ pub struct BlockDefinition {
 pub trait SearchableBlock {
     fn to_search_text(&self, payload: &Value) -> String;
 }
+
+/// `((uuid))` embeds the target block, `[[uuid]]` links to it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReferenceKind {
+    Embed,
+    Link,
+}
+
+impl ReferenceKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReferenceKind::Embed => "embed",
+            ReferenceKind::Link => "link",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "embed" => Some(ReferenceKind::Embed),
+            "link" => Some(ReferenceKind::Link),
+            _ => None,
+        }
+    }
+}
+
+/// A row of the reference index: block `source_block_id` of `source_document_id` points at `target_block_id`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BlockReference {
+    pub source_document_id: Uuid,
+    pub source_block_id: Uuid,
+    pub target_block_id: Uuid,
+    pub kind: ReferenceKind,
+}
+
+/// A stored `[[Title]]` link between articles. `target_id` stays empty while
+/// no article in the knowledge base has that title: a "wanted page".
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArticleLink {
+    pub source_id: Uuid,
+    pub source_block_id: Option<Uuid>,
+    pub target_id: Option<Uuid>,
+    pub target_title: String,
+    pub alias: Option<String>,
+    pub context: String,
+    pub knowledge_base_id: Option<Uuid>,
+}
@@ -1,105 +1,637 @@
 use super::models::Block;
+use super::strategies::apply_searchable_trait;
 use uuid::Uuid;
-use serde_json::json;
+use serde_json::{json, Value};
 use chrono::Utc;
 
+// CommonMark block structure plus the GFM extensions (tables, task lists)
+// and the front matter / `:::` directive conventions. Inline content stays
+// raw Markdown inside the payloads. Nested blocks live in their parent's
+// `children` payload as `{ "type": ..., "payload": ... }`.
+
+struct Node {
+    type_name: &'static str,
+    payload: Value,
+}
+
+impl Node {
+    fn new(type_name: &'static str, payload: Value) -> Self {
+        Self { type_name, payload }
+    }
+
+    fn into_json(self) -> Value {
+        json!({ "type": self.type_name, "payload": self.payload })
+    }
+}
+
 pub fn parse_markdown_to_blocks(document_id: Uuid, markdown: &str) -> Vec<Block> {
-    let mut blocks = Vec::new();
-    let lines: Vec<&str> = markdown.lines().collect();
+    let lines: Vec<String> = markdown.lines().map(expand_tabs).collect();
+
+    let mut nodes = Vec::new();
+    let mut start = 0;
+    if let Some((front_matter, end)) = front_matter(&lines) {
+        nodes.push(front_matter);
+        start = end;
+    }
+    nodes.extend(parse_nodes(&lines[start..]).into_iter().map(|(node, _)| node));
+
+    nodes.into_iter().enumerate().map(|(ordinal, node)| {
+        let mut block = create_block(document_id, node.type_name, ordinal as i32, node.payload);
+        apply_searchable_trait(&mut block);
+        block
+    }).collect()
+}
+
+/// Parses a run of lines (container prefixes already stripped). Each node
+/// comes with whether a blank line preceded it, which decides list looseness.
+fn parse_nodes(lines: &[String]) -> Vec<(Node, bool)> {
+    let mut nodes = Vec::new();
     let mut i = 0;
-    
-    let mut ordinal = 0;
+    let mut blank_before = false;
 
     while i < lines.len() {
-        let line = lines[i];
-        
-        // 1. Heading
-        if line.starts_with("#") {
-            let level = line.chars().take_while(|c| *c == '#').count();
-            let text = line[level..].trim();
-            blocks.push(create_block(document_id, "heading", ordinal, json!({
-                "level": level,
-                "text": text,
-                "text_mirror": text // Searchable trait fallback
-            })));
-            ordinal += 1;
+        let line = lines[i].as_str();
+        if is_blank(line) {
+            blank_before = true;
             i += 1;
             continue;
         }
 
-        // 2. Math Block ($$ ... $$)
-        if line.trim() == "$$" {
-            let mut j = i + 1;
-            let mut content = String::new();
-            while j < lines.len() {
-                 if lines[j].trim() == "$$" {
-                     break;
-                 }
-                 content.push_str(lines[j]);
-                 content.push('\n');
-                 j += 1;
-            }
-            // If strictly $$ ... $$
-            blocks.push(create_block(document_id, "math", ordinal, json!({
-                "latex": content.trim(),
-                "text_mirror": content.trim()
-            })));
-            ordinal += 1;
-            i = j + 1;
-            continue;
+        let (node, next) = parse_node(lines, i);
+        nodes.push((node, blank_before));
+        blank_before = false;
+        i = next;
+    }
+
+    nodes
+}
+
+/// Parses the block starting at non-blank line `i`; returns it with the index of the line after it.
+fn parse_node(lines: &[String], i: usize) -> (Node, usize) {
+    let line = lines[i].as_str();
+    let indent = indent_of(line);
+
+    // 1. Indented code
+    if indent >= 4 {
+        let mut j = i;
+        let mut code = Vec::new();
+        while j < lines.len() && (is_blank(&lines[j]) || indent_of(&lines[j]) >= 4) {
+            code.push(lines[j].get(4..).unwrap_or(""));
+            j += 1;
         }
+        while code.last().is_some_and(|l| is_blank(l)) {
+            code.pop();
+            j -= 1;
+        }
+        return (Node::new("code", json!({ "language": "", "code": code.join("\n") })), j);
+    }
+
+    let text = &line[indent..];
+
+    // 2. Heading
+    if let Some((level, title)) = atx_heading(text) {
+        return (Node::new("heading", json!({ "level": level, "text": title })), i + 1);
+    }
+
+    // 3. Thematic break (before lists: `* * *` is not a list item)
+    if is_thematic_break(text) {
+        return (Node::new("thematic_break", json!({})), i + 1);
+    }
+
+    // 4. Code Block (``` ... ``` or ~~~ ... ~~~)
+    if let Some((fence, info)) = code_fence(text) {
+        let mut j = i + 1;
+        let mut code = Vec::new();
+        while j < lines.len() && !closes_fence(&lines[j], fence) {
+            code.push(strip_indent(&lines[j], indent));
+            j += 1;
+        }
+        return (Node::new("code", json!({ "language": info, "code": code.join("\n") })), (j + 1).min(lines.len()));
+    }
 
-        // 3. Code Block (``` ... ```)
-        if line.trim().starts_with("```") {
-            let lang = line.trim().trim_start_matches("```").trim();
-            let mut j = i + 1;
-            let mut content = String::new();
-            while j < lines.len() {
-                if lines[j].trim().starts_with("```") {
-                    break;
+    // 5. Math Block ($$ ... $$)
+    if text.trim_end() == "$$" {
+        let mut j = i + 1;
+        let mut content = Vec::new();
+        while j < lines.len() && lines[j].trim() != "$$" {
+            content.push(lines[j].as_str());
+            j += 1;
+        }
+        let latex = content.join("\n");
+        return (Node::new("math", json!({ "latex": latex.trim() })), (j + 1).min(lines.len()));
+    }
+
+    // 6. Directive (:::name info ... :::)
+    if let Some((colons, name, info)) = directive_open(text) {
+        let mut j = i + 1;
+        let mut fence = None;
+        let mut nested: Vec<usize> = Vec::new();
+        while j < lines.len() {
+            let inner = lines[j].as_str();
+            match fence {
+                // Colons inside a code block do not close the directive
+                Some(f) if closes_fence(inner, f) => fence = None,
+                Some(_) => {}
+                None if nested.last().is_some_and(|n| directive_close(inner, *n)) => {
+                    nested.pop();
+                }
+                None if directive_close(inner, colons) => break,
+                None => {
+                    let t = inner.trim_start();
+                    fence = code_fence(t).map(|(f, _)| f);
+                    nested.extend(directive_open(t).map(|(n, _, _)| n));
                 }
-                content.push_str(lines[j]);
-                content.push('\n');
-                j += 1;
             }
-            blocks.push(create_block(document_id, "code", ordinal, json!({
-                "language": lang,
-                "code": content.trim(),
-                "text_mirror": content.trim() 
-            })));
-            ordinal += 1;
-            i = j + 1;
-            continue;
+            j += 1;
         }
+        let children = children_json(parse_nodes(&lines[i + 1..j]));
+        return (
+            Node::new("directive", json!({ "name": name, "info": info, "children": children })),
+            (j + 1).min(lines.len()),
+        );
+    }
 
-        // 4. Paragraph (Empty lines separate paragraphs)
-        if line.trim().is_empty() {
-            i += 1;
-            continue;
+    // 7. Blockquote
+    if text.starts_with('>') {
+        let mut j = i;
+        let mut inner: Vec<String> = Vec::new();
+        while j < lines.len() {
+            let l = lines[j].as_str();
+            let t = l.trim_start();
+            if indent_of(l) < 4 && t.starts_with('>') {
+                let rest = &t[1..];
+                inner.push(rest.strip_prefix(' ').unwrap_or(rest).to_string());
+            } else if !is_blank(l) && inner.last().is_some_and(|p| !is_blank(p)) && !interrupts(l) {
+                inner.push(l.to_string()); // Lazy continuation of a paragraph
+            } else {
+                break;
+            }
+            j += 1;
         }
-        
-        // Accumulate paragraph
+        let children = children_json(parse_nodes(&inner));
+        return (Node::new("blockquote", json!({ "children": children })), j);
+    }
+
+    // 8. List
+    if let Some(marker) = list_marker(text) {
+        return parse_list(lines, i, marker);
+    }
+
+    // 9. HTML block
+    if let Some(end) = html_start(text, true) {
         let mut j = i;
-        let mut content = String::new();
+        let mut html = Vec::new();
         while j < lines.len() {
-            let l = lines[j];
-            if l.trim().is_empty() || l.starts_with("#") || l.trim() == "$$" || l.trim().starts_with("```") {
+            let l = lines[j].as_str();
+            if end.is_none() && is_blank(l) {
+                break;
+            }
+            html.push(l);
+            j += 1;
+            if end.is_some_and(|e| l.to_ascii_lowercase().contains(e)) {
                 break;
             }
-            content.push_str(l);
-            content.push('\n');
+        }
+        return (Node::new("html", json!({ "html": html.join("\n") })), j);
+    }
+
+    // 10. Table (header row, then a delimiter row with as many cells)
+    if let Some(align) = lines.get(i + 1).and_then(|l| table_delimiter(l, text)) {
+        let header = table_cells(text.trim());
+        let mut j = i + 2;
+        let mut rows = Vec::new();
+        while j < lines.len() && !is_blank(&lines[j]) && !interrupts(&lines[j]) {
+            let mut cells = table_cells(lines[j].trim());
+            cells.resize(header.len(), String::new());
+            rows.push(cells);
             j += 1;
         }
-        
-        blocks.push(create_block(document_id, "paragraph", ordinal, json!({
-            "markdown": content.trim(),
-            "text_mirror": content.trim()
-        })));
-        ordinal += 1;
-        i = j;
+        return (Node::new("table", json!({ "align": align, "header": header, "rows": rows })), j);
     }
 
-    blocks
+    // 11. Paragraph (or setext heading, or a lone image)
+    let mut j = i;
+    let mut content: Vec<&str> = Vec::new();
+    while j < lines.len() {
+        let l = lines[j].as_str();
+        if is_blank(l) {
+            break;
+        }
+        if !content.is_empty() {
+            if let Some(level) = setext_underline(l) {
+                let title = content.iter().map(|c| c.trim()).collect::<Vec<_>>().join(" ");
+                return (Node::new("heading", json!({ "level": level, "text": title })), j + 1);
+            }
+            if interrupts(l) {
+                break;
+            }
+        }
+        content.push(l.trim_start());
+        j += 1;
+    }
+    let markdown = content.join("\n");
+    let markdown = markdown.trim();
+    match lone_image(markdown) {
+        Some(image) => (Node::new("image", image), j),
+        None => (Node::new("paragraph", json!({ "markdown": markdown })), j),
+    }
+}
+
+#[derive(Clone, Copy)]
+struct ListMarker {
+    ordered: bool,
+    delimiter: char, // Bullet character, or `.`/`)` after the number
+    number: u64,
+    width: usize,   // Marker plus the spaces before the content
+    empty: bool,    // Nothing follows the marker
+}
+
+impl ListMarker {
+    fn continues(&self, other: &ListMarker) -> bool {
+        self.ordered == other.ordered && self.delimiter == other.delimiter
+    }
+}
+
+fn list_marker(text: &str) -> Option<ListMarker> {
+    let bytes = text.as_bytes();
+    let (ordered, delimiter, number, marker_len) = match bytes.first()? {
+        b'-' | b'*' | b'+' => (false, bytes[0] as char, 0, 1),
+        b'0'..=b'9' => {
+            let digits = bytes.iter().take_while(|b| b.is_ascii_digit()).count();
+            if digits > 9 {
+                return None;
+            }
+            let delimiter = *bytes.get(digits)?;
+            if delimiter != b'.' && delimiter != b')' {
+                return None;
+            }
+            (true, delimiter as char, text[..digits].parse().ok()?, digits + 1)
+        }
+        _ => return None,
+    };
+
+    let rest = &text[marker_len..];
+    if !rest.is_empty() && !rest.starts_with([' ', '\t']) {
+        return None;
+    }
+    let empty = is_blank(rest);
+    let spaces = rest.len() - rest.trim_start().len();
+    // Content indented 5+ spaces past the marker is indented code: only one space belongs to the marker
+    let padding = if empty || spaces > 4 { 1 } else { spaces };
+    Some(ListMarker { ordered, delimiter, number, width: marker_len + padding, empty })
+}
+
+fn parse_list(lines: &[String], start: usize, first: ListMarker) -> (Node, usize) {
+    let mut items = Vec::new();
+    let mut loose = false;
+    let mut i = start;
+    let mut marker = first;
+
+    loop {
+        let line = lines[i].as_str();
+        let width = indent_of(line) + marker.width;
+        let mut content = vec![line.get(width..).unwrap_or("").to_string()];
+        let mut j = i + 1;
+        while j < lines.len() {
+            let l = lines[j].as_str();
+            if is_blank(l) {
+                content.push(l.get(width..).unwrap_or("").to_string()); // Keeps whitespace lines in code
+            } else if indent_of(l) >= width {
+                content.push(l[width..].to_string());
+            } else if content.last().is_some_and(|p| !is_blank(p))
+                && !interrupts(l)
+                && !list_marker(l.trim_start()).is_some_and(|m| m.continues(&marker))
+            {
+                content.push(l.to_string()); // Lazy continuation of a paragraph
+            } else {
+                break;
+            }
+            j += 1;
+        }
+        let mut blank_after = false;
+        while content.last().is_some_and(|l| is_blank(l)) {
+            content.pop();
+            blank_after = true;
+        }
+
+        let children = parse_nodes(&content);
+        loose |= children.iter().skip(1).any(|(_, blank_before)| *blank_before);
+        items.push(list_item(children));
+
+        let next = lines.get(j)
+            .filter(|l| indent_of(l) < 4 && !is_thematic_break(l.trim_start()))
+            .and_then(|l| list_marker(l.trim_start()))
+            .filter(|m| m.continues(&marker));
+        match next {
+            Some(next) => {
+                loose |= blank_after;
+                marker = next;
+                i = j;
+            }
+            None => {
+                i = j;
+                break;
+            }
+        }
+    }
+
+    let mut payload = json!({
+        "ordered": first.ordered,
+        "marker": first.delimiter.to_string(),
+        "tight": !loose,
+        "children": items,
+    });
+    if first.ordered {
+        payload["start"] = json!(first.number);
+    }
+    (Node::new("list", payload), i)
+}
+
+/// A list item; a leading `[ ]`/`[x]` on its first paragraph makes it a task.
+fn list_item(children: Vec<(Node, bool)>) -> Value {
+    let mut children: Vec<Node> = children.into_iter().map(|(node, _)| node).collect();
+    let mut checked = None;
+    if let Some(first) = children.first_mut().filter(|n| n.type_name == "paragraph") {
+        let markdown = first.payload["markdown"].as_str().unwrap_or("").to_string();
+        let task = ["[ ] ", "[x] ", "[X] "].iter()
+            .find_map(|p| markdown.strip_prefix(p).map(|rest| (*p != "[ ] ", rest.trim_start())))
+            .filter(|(_, rest)| !rest.is_empty());
+        if let Some((done, rest)) = task {
+            checked = Some(done);
+            first.payload["markdown"] = json!(rest);
+        }
+    }
+
+    let mut payload = json!({ "children": children.into_iter().map(Node::into_json).collect::<Vec<_>>() });
+    if let Some(checked) = checked {
+        payload["checked"] = json!(checked);
+    }
+    json!({ "type": "list_item", "payload": payload })
+}
+
+fn children_json(nodes: Vec<(Node, bool)>) -> Vec<Value> {
+    nodes.into_iter().map(|(node, _)| node.into_json()).collect()
+}
+
+/// YAML (`---`) or TOML (`+++`) front matter on the very first line.
+fn front_matter(lines: &[String]) -> Option<(Node, usize)> {
+    let (fence, format) = match lines.first()?.trim_end() {
+        "---" => ("---", "yaml"),
+        "+++" => ("+++", "toml"),
+        _ => return None,
+    };
+    let end = lines.iter().skip(1).position(|l| {
+        let l = l.trim_end();
+        l == fence || (format == "yaml" && l == "...")
+    })? + 1;
+    let raw = lines[1..end].join("\n");
+    Some((Node::new("front_matter", json!({ "format": format, "raw": raw })), end + 1))
+}
+
+/// Whether `line` ends a paragraph by starting another block.
+fn interrupts(line: &str) -> bool {
+    if indent_of(line) >= 4 {
+        return false;
+    }
+    let text = line.trim_start();
+    atx_heading(text).is_some()
+        || is_thematic_break(text)
+        || code_fence(text).is_some()
+        || text.trim_end() == "$$"
+        || directive_open(text).is_some()
+        || text.starts_with('>')
+        || html_start(text, false).is_some()
+        // Only non-empty lists, and ordered ones when they start at 1
+        || list_marker(text).is_some_and(|m| !m.empty && (!m.ordered || m.number == 1))
+}
+
+fn atx_heading(text: &str) -> Option<(usize, String)> {
+    let level = text.chars().take_while(|c| *c == '#').count();
+    if level == 0 || level > 6 {
+        return None;
+    }
+    let rest = &text[level..];
+    if !rest.is_empty() && !rest.starts_with(' ') {
+        return None;
+    }
+    // Optional closing sequence: `## Title ##`
+    let mut title = rest.trim();
+    let stripped = title.trim_end_matches('#');
+    if stripped.is_empty() || stripped.ends_with(' ') {
+        title = stripped.trim_end();
+    }
+    Some((level, title.to_string()))
+}
+
+fn setext_underline(line: &str) -> Option<u64> {
+    if indent_of(line) >= 4 {
+        return None;
+    }
+    let text = line.trim();
+    if !text.is_empty() && text.chars().all(|c| c == '=') {
+        Some(1)
+    } else if !text.is_empty() && text.chars().all(|c| c == '-') {
+        Some(2)
+    } else {
+        None
+    }
+}
+
+fn is_thematic_break(text: &str) -> bool {
+    let marks: Vec<char> = text.chars().filter(|c| !c.is_whitespace()).collect();
+    marks.len() >= 3 && matches!(marks[0], '-' | '*' | '_') && marks.iter().all(|c| *c == marks[0])
+}
+
+/// An opening code fence: its (character, length) and info string.
+fn code_fence(text: &str) -> Option<((char, usize), String)> {
+    let ch = text.chars().next().filter(|c| *c == '`' || *c == '~')?;
+    let len = text.chars().take_while(|c| *c == ch).count();
+    if len < 3 {
+        return None;
+    }
+    let info = text[len..].trim();
+    if ch == '`' && info.contains('`') {
+        return None;
+    }
+    Some(((ch, len), info.to_string()))
+}
+
+fn closes_fence(line: &str, (ch, len): (char, usize)) -> bool {
+    let text = line.trim();
+    indent_of(line) < 4 && text.len() >= len && text.chars().all(|c| c == ch)
+}
+
+fn directive_open(text: &str) -> Option<(usize, String, String)> {
+    let colons = text.chars().take_while(|c| *c == ':').count();
+    if colons < 3 {
+        return None;
+    }
+    let rest = text[colons..].trim_start();
+    let name: String = rest.chars().take_while(|c| c.is_alphanumeric() || *c == '-' || *c == '_').collect();
+    if name.is_empty() {
+        return None;
+    }
+    Some((colons, name.clone(), rest[name.len()..].trim().to_string()))
+}
+
+fn directive_close(line: &str, colons: usize) -> bool {
+    let text = line.trim();
+    indent_of(line) < 4 && text.len() >= colons && text.chars().all(|c| c == ':')
+}
+
+const HTML_BLOCK_TAGS: &[&str] = &[
+    "address", "article", "aside", "base", "blockquote", "body", "caption", "center", "col", "colgroup",
+    "dd", "details", "dialog", "dir", "div", "dl", "dt", "fieldset", "figcaption", "figure", "footer",
+    "form", "frame", "frameset", "h1", "h2", "h3", "h4", "h5", "h6", "head", "header", "hr", "html",
+    "iframe", "legend", "li", "link", "main", "menu", "menuitem", "nav", "noframes", "ol", "optgroup",
+    "option", "p", "param", "section", "summary", "table", "tbody", "td", "tfoot", "th", "thead",
+    "title", "tr", "track", "ul",
+];
+
+/// Detects the start of an HTML block. Returns the (lowercase) text that ends
+/// it, or `None` when it runs to the next blank line. A lone tag of any name
+/// starts a block too, but cannot interrupt a paragraph.
+fn html_start(text: &str, standalone: bool) -> Option<Option<&'static str>> {
+    let lower = text.to_ascii_lowercase();
+    for (tag, end) in [("<script", "</script>"), ("<pre", "</pre>"), ("<style", "</style>"), ("<textarea", "</textarea>")] {
+        if let Some(rest) = lower.strip_prefix(tag) {
+            if rest.is_empty() || rest.starts_with([' ', '>']) {
+                return Some(Some(end));
+            }
+        }
+    }
+    if lower.starts_with("<!--") {
+        return Some(Some("-->"));
+    }
+    if lower.starts_with("<?") {
+        return Some(Some("?>"));
+    }
+    if lower.starts_with("<![cdata[") {
+        return Some(Some("]]>"));
+    }
+    if lower.starts_with("<!") && lower[2..].starts_with(|c: char| c.is_ascii_alphabetic()) {
+        return Some(Some(">"));
+    }
+
+    let name_start = lower.strip_prefix("</").or_else(|| lower.strip_prefix('<'))?;
+    let name: String = name_start.chars().take_while(|c| c.is_ascii_alphanumeric() || *c == '-').collect();
+    if name.is_empty() || !name.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        return None;
+    }
+    let after = &name_start[name.len()..];
+    if !(after.is_empty() || after.starts_with([' ', '>']) || after.starts_with("/>")) {
+        return None;
+    }
+    if HTML_BLOCK_TAGS.contains(&name.as_str()) {
+        return Some(None);
+    }
+    let trimmed = text.trim_end();
+    if standalone && trimmed.ends_with('>') && trimmed[1..].find(['<', '>']) == Some(trimmed.len() - 2) {
+        return Some(None);
+    }
+    None
+}
+
+/// Alignments of a table delimiter row, if `line` is one for a header `header`.
+fn table_delimiter(line: &str, header: &str) -> Option<Vec<Value>> {
+    if indent_of(line) >= 4 || !header.contains('|') {
+        return None;
+    }
+    let cells = table_cells(line.trim());
+    if cells.len() != table_cells(header.trim()).len() || !line.contains('|') {
+        return None;
+    }
+    cells.iter().map(|cell| {
+        let dashes = cell.trim_start_matches(':').trim_end_matches(':');
+        if dashes.is_empty() || !dashes.chars().all(|c| c == '-') {
+            return None;
+        }
+        Some(match (cell.starts_with(':'), cell.ends_with(':')) {
+            (true, true) => json!("center"),
+            (true, false) => json!("left"),
+            (false, true) => json!("right"),
+            (false, false) => Value::Null,
+        })
+    }).collect()
+}
+
+/// Splits a table row on unescaped pipes. Cells keep their inline Markdown (and escapes).
+fn table_cells(row: &str) -> Vec<String> {
+    let row = row.strip_prefix('|').unwrap_or(row);
+    let row = match row.strip_suffix('|') {
+        Some(inner) if !inner.ends_with('\\') => inner,
+        _ => row,
+    };
+    let mut cells = Vec::new();
+    let mut cell = String::new();
+    let mut escaped = false;
+    for c in row.chars() {
+        if c == '|' && !escaped {
+            cells.push(cell.trim().to_string());
+            cell.clear();
+        } else {
+            cell.push(c);
+        }
+        escaped = c == '\\' && !escaped;
+    }
+    cells.push(cell.trim().to_string());
+    cells
+}
+
+/// `![alt](src "title")` standing alone as a paragraph.
+fn lone_image(markdown: &str) -> Option<Value> {
+    let inner = markdown.strip_prefix("![")?.strip_suffix(')')?;
+    let (alt, destination) = inner.split_once("](")?;
+    if alt.contains(['[', ']']) || markdown.contains('\n') {
+        return None;
+    }
+    let (src, title) = match destination.split_once(' ') {
+        Some((src, title)) => {
+            let title = title.trim().strip_prefix('"')?.strip_suffix('"')?;
+            (src, Some(title))
+        }
+        None => (destination, None),
+    };
+    if src.is_empty() || src.contains(['(', ')', '<', '>']) {
+        return None;
+    }
+    let mut image = json!({ "alt": alt, "src": src });
+    if let Some(title) = title {
+        image["title"] = json!(title);
+    }
+    Some(image)
+}
+
+fn is_blank(line: &str) -> bool {
+    line.trim().is_empty()
+}
+
+fn indent_of(line: &str) -> usize {
+    line.len() - line.trim_start_matches(' ').len()
+}
+
+/// Removes up to `n` leading spaces (a fenced block's content is dedented by the fence's indent).
+fn strip_indent(line: &str, n: usize) -> &str {
+    &line[indent_of(line).min(n)..]
+}
+
+/// Tabs in the indentation count as spaces up to the next multiple of 4.
+fn expand_tabs(line: &str) -> String {
+    if !line.starts_with([' ', '\t']) || !line.contains('\t') {
+        return line.to_string();
+    }
+    let mut out = String::new();
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next_if(|c| *c == ' ' || *c == '\t') {
+        if c == '\t' {
+            let width = 4 - out.len() % 4;
+            out.push_str(&" ".repeat(width));
+        } else {
+            out.push(' ');
+        }
+    }
+    out.extend(chars);
+    out
 }
 
 fn create_block(doc_id: Uuid, type_name: &str, ordinal: i32, payload: serde_json::Value) -> Block {
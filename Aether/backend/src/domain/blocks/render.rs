@@ -0,0 +1,166 @@
+use serde_json::Value;
+use super::models::Block;
+
+// The inverse of `parser`: every block renders to Markdown that parses back
+// into the same block. Where CommonMark offers several spellings the
+// renderer picks one (ATX headings, fenced code, `***` breaks).
+
+/// Assembles the article body from its blocks.
+pub fn render_markdown(blocks: &[Block]) -> String {
+    let parts: Vec<String> = blocks.iter().map(|b| render_block(&b.type_name, &b.payload)).collect();
+    let mut body = parts.join("\n\n");
+    if !body.is_empty() {
+        body.push('\n');
+    }
+    body
+}
+
+fn render_block(type_name: &str, payload: &Value) -> String {
+    let field = |name: &str| payload.get(name).and_then(|v| v.as_str()).unwrap_or("").to_string();
+    match type_name {
+        "heading" => {
+            let level = payload.get("level").and_then(|v| v.as_u64()).unwrap_or(1).clamp(1, 6) as usize;
+            let text = field("text");
+            let mut line = format!("{} {}", "#".repeat(level), text);
+            // Trailing `#`s would be read as a closing sequence: add a real one
+            let stripped = text.trim_end_matches('#');
+            if text.ends_with('#') && (stripped.is_empty() || stripped.ends_with(' ')) {
+                line.push_str(" #");
+            }
+            line.trim_end().to_string()
+        }
+        "code" => {
+            let code = field("code");
+            let language = field("language");
+            let ch = if language.contains('`') { '~' } else { '`' };
+            let longest = code.lines()
+                .map(|l| l.trim_start().chars().take_while(|c| *c == ch).count())
+                .max()
+                .unwrap_or(0);
+            let fence = ch.to_string().repeat(longest.max(2) + 1);
+            if code.is_empty() {
+                format!("{}{}\n{}", fence, language, fence)
+            } else {
+                format!("{}{}\n{}\n{}", fence, language, code, fence)
+            }
+        }
+        "math" => format!("$$\n{}\n$$", field("latex")),
+        "paragraph" => field("markdown"),
+        // `---` could become front matter or a setext underline, `- ---` a break instead of a list
+        "thematic_break" => "***".to_string(),
+        "image" => match payload.get("title").and_then(|v| v.as_str()) {
+            Some(title) => format!("![{}]({} \"{}\")", field("alt"), field("src"), title),
+            None => format!("![{}]({})", field("alt"), field("src")),
+        },
+        "html" => field("html"),
+        "front_matter" => {
+            let fence = if field("format") == "toml" { "+++" } else { "---" };
+            let raw = field("raw");
+            if raw.is_empty() {
+                format!("{}\n{}", fence, fence)
+            } else {
+                format!("{}\n{}\n{}", fence, raw, fence)
+            }
+        }
+        "table" => render_table(payload),
+        "blockquote" => {
+            let inner = render_children(payload, false);
+            if inner.is_empty() {
+                return ">".to_string();
+            }
+            inner.lines()
+                .map(|l| if l.is_empty() { ">".to_string() } else { format!("> {}", l) })
+                .collect::<Vec<_>>()
+                .join("\n")
+        }
+        "directive" => {
+            // Nested directives need shorter fences than their parent
+            let fence = ":".repeat(3 + directive_depth(payload));
+            let open = format!("{}{} {}", fence, field("name"), field("info"));
+            let inner = render_children(payload, false);
+            if inner.is_empty() {
+                format!("{}\n{}", open.trim_end(), fence)
+            } else {
+                format!("{}\n{}\n{}", open.trim_end(), inner, fence)
+            }
+        }
+        "list" => render_list(payload),
+        _ => field("text_mirror"),
+    }
+}
+
+fn children(payload: &Value) -> &[Value] {
+    payload.get("children").and_then(|v| v.as_array()).map(Vec::as_slice).unwrap_or(&[])
+}
+
+/// Renders nested blocks; in a tight list item they are not separated by blank lines.
+fn render_children(payload: &Value, tight: bool) -> String {
+    children(payload).iter()
+        .map(|child| render_block(child["type"].as_str().unwrap_or(""), &child["payload"]))
+        .collect::<Vec<_>>()
+        .join(if tight { "\n" } else { "\n\n" })
+}
+
+fn directive_depth(payload: &Value) -> usize {
+    children(payload).iter()
+        .filter(|child| child["type"] == "directive")
+        .map(|child| 1 + directive_depth(&child["payload"]))
+        .max()
+        .unwrap_or(0)
+}
+
+fn render_list(payload: &Value) -> String {
+    let ordered = payload.get("ordered").and_then(|v| v.as_bool()).unwrap_or(false);
+    let tight = payload.get("tight").and_then(|v| v.as_bool()).unwrap_or(true);
+    let start = payload.get("start").and_then(|v| v.as_u64()).unwrap_or(1);
+    let delimiter = payload.get("marker").and_then(|v| v.as_str())
+        .unwrap_or(if ordered { "." } else { "-" });
+
+    let items: Vec<String> = children(payload).iter().enumerate().map(|(i, item)| {
+        let marker = if ordered { format!("{}{}", start + i as u64, delimiter) } else { delimiter.to_string() };
+        let item = &item["payload"];
+        let mut body = render_children(item, tight);
+        match item.get("checked").and_then(|v| v.as_bool()) {
+            Some(true) => body.insert_str(0, "[x] "),
+            Some(false) => body.insert_str(0, "[ ] "),
+            None => {}
+        }
+        if body.is_empty() {
+            return marker;
+        }
+        // Continuation lines are indented to the item's content column
+        let indent = " ".repeat(marker.len() + 1);
+        body.lines().enumerate().map(|(n, line)| match n {
+            0 => format!("{} {}", marker, line),
+            _ if line.is_empty() => String::new(),
+            _ => format!("{}{}", indent, line),
+        }).collect::<Vec<_>>().join("\n")
+    }).collect();
+
+    items.join(if tight { "\n" } else { "\n\n" })
+}
+
+fn render_table(payload: &Value) -> String {
+    let cells = |v: &Value| -> Vec<String> {
+        v.as_array().map(|a| a.iter().map(|c| c.as_str().unwrap_or("").to_string()).collect()).unwrap_or_default()
+    };
+    let row = |cells: Vec<String>| format!("| {} |", cells.join(" | "));
+
+    let header = cells(&payload["header"]);
+    let align: Vec<String> = (0..header.len()).map(|i| {
+        match payload["align"].get(i).and_then(|v| v.as_str()) {
+            Some("left") => ":---",
+            Some("center") => ":---:",
+            Some("right") => "---:",
+            _ => "---",
+        }.to_string()
+    }).collect();
+
+    let mut lines = vec![row(header.clone()), row(align)];
+    for r in payload["rows"].as_array().map(Vec::as_slice).unwrap_or(&[]) {
+        let mut r = cells(r);
+        r.resize(header.len(), String::new());
+        lines.push(row(r));
+    }
+    lines.join("\n")
+}
@@ -1,4 +1,4 @@
-use super::models::Block;
+use super::models::{Block, ReferenceKind};
 use serde_json::Value;
 
 pub fn apply_searchable_trait(block: &mut Block) {
@@ -14,13 +14,37 @@ pub fn apply_searchable_trait(block: &mut Block) {
     block.payload = Value::Object(payload_obj);
 }
 
-fn extract_text(type_name: &str, payload: &Value) -> String {
+pub fn extract_text(type_name: &str, payload: &Value) -> String {
     match type_name {
         // Standard Types
         "paragraph" => payload["markdown"].as_str().unwrap_or("").to_string(),
         "heading" => payload["text"].as_str().unwrap_or("").to_string(),
         "math" => payload["latex"].as_str().unwrap_or("").to_string(),
         "code" => payload["code"].as_str().unwrap_or("").to_string(),
+        "image" => payload["alt"].as_str().unwrap_or("").to_string(),
+        "html" => payload["html"].as_str().unwrap_or("").to_string(),
+        "front_matter" => payload["raw"].as_str().unwrap_or("").to_string(),
+        "thematic_break" => "".to_string(),
+        "table" => {
+            let row = |r: &Value| r.as_array().map(|cells| {
+                cells.iter().filter_map(|c| c.as_str()).collect::<Vec<_>>().join(" ")
+            }).unwrap_or_default();
+            let mut lines = vec![row(&payload["header"])];
+            if let Some(rows) = payload["rows"].as_array() {
+                lines.extend(rows.iter().map(row));
+            }
+            lines.join("\n")
+        },
+        // Containers: the text of their nested blocks
+        "list" | "list_item" | "blockquote" | "directive" => {
+            payload["children"].as_array().map(|children| {
+                children.iter()
+                    .map(|c| extract_text(c["type"].as_str().unwrap_or(""), &c["payload"]))
+                    .filter(|t| !t.is_empty())
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            }).unwrap_or_default()
+        },
         
         // Math KB Types
         "axiom" => {
@@ -78,9 +102,69 @@ pub fn extract_references(type_name: &str, payload: &Value) -> Vec<uuid::Uuid> {
         _ => {}
     }
 
-    // 2. Content Scanning (Regex for [[uuid]])
-    // For now, simple strict UUID scanning in string fields
-    // Implement stricter regex if needed
-    
+    // 2. Content Scanning: ((uuid)) embeds and [[uuid]] links
+    for (id, _) in scan_block_references(type_name, payload) {
+        if !refs.contains(&id) {
+            refs.push(id);
+        }
+    }
+
     refs
 }
+
+/// Block types whose text is literal: a `((uuid))` in a code sample is not a reference.
+const LITERAL_TYPES: &[&str] = &["code", "math", "html", "front_matter"];
+
+/// Finds `((uuid))` and `[[uuid]]` in a block's text fields, including nested blocks.
+pub fn scan_block_references(type_name: &str, payload: &Value) -> Vec<(uuid::Uuid, ReferenceKind)> {
+    let mut found = Vec::new();
+    visit_text(type_name, payload, &mut |text| scan_text(text, &mut found));
+    found
+}
+
+/// Calls `f` on every authored text field of a block and its nested blocks,
+/// skipping literal blocks (code, math...) and the derived `text_mirror`.
+pub fn visit_text(type_name: &str, payload: &Value, f: &mut dyn FnMut(&str)) {
+    if !LITERAL_TYPES.contains(&type_name) {
+        visit_value(payload, f);
+    }
+}
+
+fn visit_value(value: &Value, f: &mut dyn FnMut(&str)) {
+    match value {
+        Value::String(text) => f(text),
+        Value::Array(items) => items.iter().for_each(|v| visit_value(v, f)),
+        Value::Object(map) => {
+            // A nested block
+            if let (Some(t), Some(payload)) = (map.get("type").and_then(|t| t.as_str()), map.get("payload")) {
+                visit_text(t, payload, f);
+                return;
+            }
+            for (key, v) in map {
+                // The mirror repeats the text of the other fields
+                if key != "text_mirror" {
+                    visit_value(v, f);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+fn scan_text(text: &str, found: &mut Vec<(uuid::Uuid, ReferenceKind)>) {
+    const UUID_LEN: usize = 36;
+    for (open, close, kind) in [("((", "))", ReferenceKind::Embed), ("[[", "]]", ReferenceKind::Link)] {
+        for (start, _) in text.match_indices(open) {
+            let inner = start + open.len();
+            let Some(candidate) = text.get(inner..inner + UUID_LEN) else { continue };
+            if !text[inner + UUID_LEN..].starts_with(close) {
+                continue;
+            }
+            if let Ok(id) = uuid::Uuid::parse_str(candidate) {
+                if !found.contains(&(id, kind)) {
+                    found.push((id, kind));
+                }
+            }
+        }
+    }
+}
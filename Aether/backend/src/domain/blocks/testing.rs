@@ -0,0 +1,17 @@
+use serde_json::Value;
+use uuid::Uuid;
+use super::models::Block;
+
+/// A fresh block for tests; the id is random.
+pub fn block(document_id: Uuid, ordinal: i32, type_name: &str, payload: Value) -> Block {
+    Block {
+        id: Uuid::new_v4(),
+        document_id,
+        type_name: type_name.to_string(),
+        ordinal,
+        revision: 1,
+        payload,
+        created_at: chrono::Utc::now(),
+        updated_at: chrono::Utc::now(),
+    }
+}
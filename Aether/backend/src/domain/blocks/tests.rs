@@ -0,0 +1,274 @@
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use uuid::Uuid;
+    use crate::domain::blocks::editing::{self, BlockEditError};
+    use crate::domain::blocks::parser::parse_markdown_to_blocks;
+
+    const BODY: &str = "# Title\n\nFirst paragraph.\n\n```rust\nfn main() {}\n```\n\n$$\nE = mc^2\n$$\n";
+
+    #[test]
+    fn test_render_round_trip() {
+        let doc = Uuid::new_v4();
+        let blocks = parse_markdown_to_blocks(doc, BODY);
+        assert_eq!(blocks.len(), 4);
+        assert_eq!(editing::render_markdown(&blocks), BODY);
+    }
+
+    #[test]
+    fn test_block_edits() {
+        let doc = Uuid::new_v4();
+        let mut blocks = parse_markdown_to_blocks(doc, BODY);
+        let first = blocks[1].id;
+
+        let added = editing::new_block(doc, "paragraph", json!({ "markdown": "  Second paragraph.  " })).unwrap();
+        let added_id = added.id;
+        editing::insert(&mut blocks, added, 2);
+        editing::update(&mut blocks, first, json!({ "markdown": "Edited." })).unwrap();
+        assert_eq!(blocks[1].revision, 2);
+
+        let body = editing::normalize(doc, &mut blocks).unwrap();
+        assert_eq!(body, "# Title\n\nEdited.\n\nSecond paragraph.\n\n```rust\nfn main() {}\n```\n\n$$\nE = mc^2\n$$\n");
+        assert_eq!(blocks[2].payload["markdown"], "Second paragraph.");
+        assert_eq!(blocks[1].payload["text_mirror"], "Edited.");
+
+        editing::move_to(&mut blocks, added_id, 0).unwrap();
+        assert_eq!(blocks[0].id, added_id);
+        assert_eq!(blocks.iter().map(|b| b.ordinal).collect::<Vec<_>>(), vec![0, 1, 2, 3, 4]);
+
+        let removed = editing::remove(&mut blocks, added_id).unwrap();
+        assert_eq!(removed.id, added_id);
+        assert!(matches!(editing::remove(&mut blocks, added_id), Err(BlockEditError::NotFound(id)) if id == added_id));
+        assert_eq!(editing::check_revision(&blocks[1], Some(1)), Err(BlockEditError::StaleRevision { expected: 1, current: 2 }));
+        assert!(editing::new_block(doc, "paper", json!({})).is_err());
+    }
+
+    #[test]
+    fn test_normalize_rejects_splitting_blocks() {
+        let doc = Uuid::new_v4();
+        let mut blocks = parse_markdown_to_blocks(doc, BODY);
+        let id = blocks[1].id;
+        editing::update(&mut blocks, id, json!({ "markdown": "One.\n\nTwo." })).unwrap();
+        assert!(matches!(editing::normalize(doc, &mut blocks), Err(BlockEditError::NotRoundTrip(_))));
+
+        editing::update(&mut blocks, id, json!({ "markdown": "# Not a paragraph" })).unwrap();
+        assert!(matches!(editing::normalize(doc, &mut blocks), Err(BlockEditError::NotRoundTrip(_))));
+    }
+
+    #[test]
+    fn test_reconcile_keeps_identities() {
+        let doc = Uuid::new_v4();
+        let existing = parse_markdown_to_blocks(doc, BODY);
+        let edited = "# Title\n\nNew intro.\n\nFirst paragraph, edited.\n\n```rust\nfn main() {}\n```\n\n$$\nE = mc^2\n$$\n";
+        let blocks = editing::reconcile(&existing, parse_markdown_to_blocks(doc, edited));
+
+        assert_eq!(blocks.len(), 5);
+        assert_eq!(blocks[0].id, existing[0].id); // Unchanged heading
+        assert_eq!(blocks[0].revision, 1);
+        assert_eq!(blocks[1].id, existing[1].id); // Same type and position: an edit
+        assert_eq!(blocks[1].revision, 2);
+        assert!(existing.iter().all(|e| e.id != blocks[2].id)); // New paragraph
+        assert_eq!(blocks[3].id, existing[2].id); // Moved down, same content
+        assert_eq!(blocks[4].id, existing[3].id);
+    }
+
+    #[test]
+    fn test_unstored_blocks_keep_ids_between_requests() {
+        let doc = Uuid::new_v4();
+        // Listing, then an update that loads the blocks again from the same body
+        let listed = editing::split_unstored(doc, BODY);
+        let mut loaded = editing::split_unstored(doc, BODY);
+        assert_eq!(listed.iter().map(|b| b.id).collect::<Vec<_>>(), loaded.iter().map(|b| b.id).collect::<Vec<_>>());
+
+        editing::update(&mut loaded, listed[1].id, json!({ "markdown": "Edited." })).unwrap();
+        let body = editing::normalize(doc, &mut loaded).unwrap();
+        assert!(body.contains("Edited."));
+        assert_ne!(editing::split_unstored(Uuid::new_v4(), BODY)[0].id, listed[0].id);
+    }
+
+    // Round-trip corpus: each document must come back as the same blocks
+    // after rendering, and render identically the second time.
+    const CORPUS: &[&str] = &[
+        "# H1\n\n## Closed ##\n\nSetext\n===\n\nAlso setext\nover two lines\n---\n\n#NotAHeading\n\n####### Seven\n",
+        "- one\n- two\n  - nested\n  - nested too\n- three\n",
+        "3. first\n\n4. second\n\n   continued paragraph\n\n* other list\n",
+        "1) tight\n2) ordered\n\nText between.\n\n+ plus\n+ list\n",
+        "- [ ] todo\n- [x] done\n  - [X] nested done\n- [ ]not a task\n",
+        "| Name | Value |\n|:-----|------:|\n| a | 1 |\n| b \\| c | 2 |\n| short |\n\nName | Centered\n--- | :---:\nx | y\n",
+        "> quote\n> more\n>\n> - item\n> - item 2\n\n> lazy\ncontinuation\n\n> > nested\n> > quote\n",
+        "```rust\nfn main() {}\n```\n\n~~~~\n```\nnested fence\n```\n~~~~\n\n    indented code\n      more\n\n``` \n\n  whitespace\n```\n",
+        "---\ntitle: Post\ntags: [a, b]\n---\n\n# Body\n\n---\n\nText\n",
+        "+++\ntitle = \"Toml\"\n+++\nParagraph right after.\n",
+        ":::note Heads up\nSome *text*.\n\n::::warning\nNested.\n::::\n\n```\n:::\n```\n:::\n\n:::tip\n:::\n",
+        "<div class=\"note\">\n<p>raw</p>\n</div>\n\n![Diagram](img/d.png \"The title\")\n\n![inline](a.png) and text\n\n***\n\n- - -\n\n<!-- comment\n\nstill comment -->\n\n<custom-element>\n",
+        "1. Step\n\n   $$\n   x^2\n   $$\n\n2. Next\n\n$$\nE = mc^2\n$$\n",
+        "- item\n\n  > quoted\n\n  ```js\n  let a = 1;\n\n  let b = 2;\n  ```\n- \n- after empty\n",
+        "Paragraph\n- interrupted by a list\n\nParagraph\n2. not a list\n\nA line with trailing #\n\n# C#\n\n# ends with #\n",
+        "\tTab indented code\n\n-\ttab after marker\n\n10. ten\n11. eleven\n",
+    ];
+
+    #[test]
+    fn test_corpus_round_trip() {
+        let doc = Uuid::new_v4();
+        for source in CORPUS {
+            let blocks = parse_markdown_to_blocks(doc, source);
+            let body = editing::render_markdown(&blocks);
+            let reparsed = parse_markdown_to_blocks(doc, &body);
+
+            let summary = |bs: &[crate::domain::blocks::models::Block]| {
+                bs.iter().map(|b| (b.type_name.clone(), b.payload.clone())).collect::<Vec<_>>()
+            };
+            assert_eq!(summary(&reparsed), summary(&blocks), "source:\n{}\nrendered:\n{}", source, body);
+            assert_eq!(editing::render_markdown(&reparsed), body, "source:\n{}", source);
+        }
+    }
+
+    #[test]
+    fn test_parse_block_structure() {
+        let doc = Uuid::new_v4();
+        let types = |source: &str| {
+            parse_markdown_to_blocks(doc, source).into_iter().map(|b| b.type_name).collect::<Vec<_>>()
+        };
+        assert_eq!(types(CORPUS[0]), vec!["heading", "heading", "heading", "heading", "paragraph", "paragraph"]);
+        assert_eq!(types(CORPUS[8]), vec!["front_matter", "heading", "thematic_break", "paragraph"]);
+        assert_eq!(types(CORPUS[11]), vec!["html", "image", "paragraph", "thematic_break", "thematic_break", "html", "html"]);
+        assert_eq!(types(CORPUS[14]), vec!["paragraph", "list", "paragraph", "paragraph", "heading", "heading"]);
+
+        let blocks = parse_markdown_to_blocks(doc, CORPUS[0]);
+        assert_eq!(blocks[1].payload["text"], "Closed");
+        assert_eq!(blocks[3].payload["text"], "Also setext over two lines");
+        assert_eq!(blocks[3].payload["level"], 2);
+
+        // Nested lists hang off their item
+        let list = &parse_markdown_to_blocks(doc, CORPUS[1])[0].payload;
+        assert_eq!(list["tight"], true);
+        let second = &list["children"][1]["payload"]["children"];
+        assert_eq!(second[0]["payload"]["markdown"], "two");
+        assert_eq!(second[1]["type"], "list");
+        assert_eq!(second[1]["payload"]["children"].as_array().unwrap().len(), 2);
+
+        let blocks = parse_markdown_to_blocks(doc, CORPUS[2]);
+        assert_eq!(blocks[0].payload["start"], 3);
+        assert_eq!(blocks[0].payload["tight"], false);
+        assert_eq!(blocks[1].payload["marker"], "*");
+
+        let items = parse_markdown_to_blocks(doc, CORPUS[4])[0].payload["children"].clone();
+        assert_eq!(items[0]["payload"]["checked"], false);
+        assert_eq!(items[0]["payload"]["children"][0]["payload"]["markdown"], "todo");
+        assert_eq!(items[1]["payload"]["checked"], true);
+        assert_eq!(items[1]["payload"]["children"][1]["payload"]["children"][0]["payload"]["checked"], true);
+        assert!(items[2]["payload"].get("checked").is_none());
+
+        let table = &parse_markdown_to_blocks(doc, CORPUS[5])[0].payload;
+        assert_eq!(table["align"], json!(["left", "right"]));
+        assert_eq!(table["rows"][1], json!(["b \\| c", "2"]));
+        assert_eq!(table["rows"][2], json!(["short", ""]));
+        assert_eq!(table["text_mirror"], "Name Value\na 1\nb \\| c 2\nshort ");
+
+        let quotes = parse_markdown_to_blocks(doc, CORPUS[6]);
+        assert_eq!(quotes.len(), 3);
+        assert_eq!(quotes[0].payload["children"][1]["type"], "list");
+        assert_eq!(quotes[1].payload["children"][0]["payload"]["markdown"], "lazy\ncontinuation");
+        assert_eq!(quotes[2].payload["children"][0]["type"], "blockquote");
+
+        let code = parse_markdown_to_blocks(doc, CORPUS[7]);
+        assert_eq!(code[1].payload["code"], "```\nnested fence\n```");
+        assert_eq!(code[2].payload["code"], "indented code\n  more");
+        assert_eq!(code[3].payload["code"], "\n  whitespace");
+
+        let directive = &parse_markdown_to_blocks(doc, CORPUS[10])[0].payload;
+        assert_eq!(directive["name"], "note");
+        assert_eq!(directive["info"], "Heads up");
+        assert_eq!(directive["children"][1]["payload"]["name"], "warning");
+        assert_eq!(directive["children"][2]["payload"]["code"], ":::");
+
+        let image = &parse_markdown_to_blocks(doc, CORPUS[11])[1].payload;
+        assert_eq!(image["src"], "img/d.png");
+        assert_eq!(image["title"], "The title");
+        assert_eq!(image["text_mirror"], "Diagram");
+    }
+
+    #[test]
+    fn test_scan_block_references() {
+        use crate::domain::blocks::models::ReferenceKind;
+        use crate::domain::blocks::strategies::{extract_references, scan_block_references};
+
+        let a = Uuid::new_v4();
+        let b = Uuid::new_v4();
+        let c = Uuid::new_v4();
+        let source = format!(
+            "See (({a})) and [[{b}]], again (({a})).\n\n- nested [[{c}]]\n\n```\n(({c}))\n```\n\n[[not-a-uuid]] (({a})\n"
+        );
+        let blocks = parse_markdown_to_blocks(Uuid::new_v4(), &source);
+        let found: Vec<_> = blocks.iter().flat_map(|b| scan_block_references(&b.type_name, &b.payload)).collect();
+        assert_eq!(found, vec![(a, ReferenceKind::Embed), (b, ReferenceKind::Link), (c, ReferenceKind::Link)]);
+
+        // Dependency ordering sees both kinds
+        assert_eq!(extract_references(&blocks[0].type_name, &blocks[0].payload), vec![a, b]);
+        assert!(scan_block_references("code", &json!({ "code": format!("(({a}))") })).is_empty());
+    }
+
+    #[test]
+    fn test_wiki_links() {
+        use crate::domain::blocks::links::{parse_wiki_links, rewrite_wiki_links, scan_wiki_links};
+
+        let block = Uuid::new_v4();
+        let text = format!("See [[Rust]] and [[ Ownership | borrowing ]], not `[[Code]]` or [[{block}]] or [[]].");
+        let links = parse_wiki_links(&text);
+        assert_eq!(links.len(), 2);
+        assert_eq!(links[0].target, "Rust");
+        assert_eq!(&text[links[0].span.clone()], "[[Rust]]");
+        assert_eq!(links[1].target, "Ownership");
+        assert_eq!(links[1].alias.as_deref(), Some("borrowing"));
+
+        let doc = "# About [[Rust]]\n\n- item with [[Cargo|the tool]]\n\n```\n[[Rust]]\n```\n";
+        let found = scan_wiki_links(&parse_markdown_to_blocks(Uuid::new_v4(), doc));
+        let targets: Vec<_> = found.iter().map(|l| l.target.as_str()).collect();
+        assert_eq!(targets, vec!["Rust", "Cargo"]);
+        assert_eq!(found[1].context, "item with [[Cargo|the tool]]");
+
+        let long = format!("{} [[Rust]] {}", "a".repeat(100), "b".repeat(100));
+        let found = scan_wiki_links(&parse_markdown_to_blocks(Uuid::new_v4(), &long));
+        assert!(found[0].context.starts_with('…') && found[0].context.ends_with('…'));
+
+        let body = "[[rust]] and [[Rust|the language]] and `[[Rust]]`\n\n```\n[[Rust]]\n```\n\n$$\n[[Rust]]\n$$\n[[Rusty]]\n";
+        let (rewritten, count) = rewrite_wiki_links(body, "Rust", "Rust Language");
+        assert_eq!(count, 2);
+        assert_eq!(
+            rewritten,
+            "[[Rust Language]] and [[Rust Language|the language]] and `[[Rust]]`\n\n```\n[[Rust]]\n```\n\n$$\n[[Rust]]\n$$\n[[Rusty]]\n"
+        );
+    }
+
+    #[test]
+    fn test_html_rendering() {
+        use crate::domain::blocks::html::{render_html, HtmlResolver, PlainResolver};
+
+        let doc = "# A <b>\n\nSome **bold**, *em*, `a<b>` and $x_1 < y$ in snake_case_name.\n\n- [x] done\n- [link](javascript:alert(1)) and [ok](https://example.com)\n\n<script>alert(1)</script>\n\n$$\n\\int f\n$$\n";
+        let html = render_html(&parse_markdown_to_blocks(Uuid::new_v4(), doc), &mut PlainResolver);
+
+        assert!(html.contains(">A &lt;b&gt;</h1>"));
+        assert!(html.contains("<strong>bold</strong>, <em>em</em>, <code>a&lt;b&gt;</code>"));
+        assert!(html.contains("<span class=\"math inline\">\\(x_1 &lt; y\\)</span> in snake_case_name."));
+        assert!(html.contains("<li><input type=\"checkbox\" checked=\"checked\" disabled=\"disabled\" /> done</li>"));
+        assert!(html.contains("<li>link and <a href=\"https://example.com\">ok</a></li>"));
+        assert!(html.contains("<!-- raw HTML omitted -->") && !html.contains("<script>"));
+        assert!(html.contains("class=\"math display\">\\[\\int f\\]</div>"));
+
+        struct Site;
+        impl HtmlResolver for Site {
+            fn image_src(&mut self, src: &str) -> Option<String> {
+                Some(src.replace("/api/assets/", "assets/"))
+            }
+            fn wiki_href(&self, title: &str) -> Option<String> {
+                (title == "Rust").then(|| "rust.html".to_string())
+            }
+        }
+        let asset = Uuid::new_v4();
+        let doc = format!("See [[Rust|the language]], [[Go]] and ![chart](/api/assets/{})\n", asset);
+        let html = render_html(&parse_markdown_to_blocks(Uuid::new_v4(), &doc), &mut Site);
+        assert!(html.contains("<a class=\"wiki-link\" href=\"rust.html\">the language</a>"));
+        assert!(html.contains("<span class=\"wiki-link wanted\" title=\"Go\">Go</span>"));
+        assert!(html.contains(&format!("<img src=\"assets/{}\" alt=\"chart\" />", asset)));
+    }
+}
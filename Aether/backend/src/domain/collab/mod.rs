@@ -0,0 +1,84 @@
+pub mod ot;
+
+mod tests;
+
+use serde::{Deserialize, Serialize};
+use self::ot::{OtError, TextOperation};
+
+/// Operations kept for transforming late clients. A client further behind
+/// than this has to reload the document.
+pub const MAX_HISTORY: usize = 1000;
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum CollabError {
+    #[error("Revision {0} is ahead of the document")]
+    FutureRevision(u64),
+    #[error("Revision {0} is too old, reload the document")]
+    StaleRevision(u64),
+    #[error(transparent)]
+    Operation(#[from] OtError),
+}
+
+/// A cursor or selection; `anchor == head` for a plain caret.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Selection {
+    pub anchor: usize,
+    pub head: usize,
+}
+
+impl Selection {
+    pub fn transform(&self, op: &TextOperation) -> Selection {
+        Selection {
+            anchor: op.transform_index(self.anchor),
+            head: op.transform_index(self.head),
+        }
+    }
+}
+
+/// Server copy of a collaboratively edited text. Clients send operations
+/// against the revision they last saw; the document transforms them past
+/// everything applied since, so all clients converge (central-server OT).
+#[derive(Debug, Clone)]
+pub struct CollabDocument {
+    text: String,
+    revision: u64,
+    history: Vec<TextOperation>, // Last operations; history[0] produced revision `revision - history.len() + 1`
+}
+
+impl CollabDocument {
+    pub fn new(text: String) -> Self {
+        Self { text, revision: 0, history: Vec::new() }
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    /// Applies `op`, written against `revision`, and returns it as applied
+    /// (transformed) so it can be broadcast to the other clients.
+    pub fn apply_client(&mut self, revision: u64, op: TextOperation) -> Result<TextOperation, CollabError> {
+        if revision > self.revision {
+            return Err(CollabError::FutureRevision(revision));
+        }
+        let behind = (self.revision - revision) as usize;
+        if behind > self.history.len() {
+            return Err(CollabError::StaleRevision(revision));
+        }
+
+        let mut op = op;
+        for concurrent in &self.history[self.history.len() - behind..] {
+            op = TextOperation::transform(&op, concurrent)?.0;
+        }
+        self.text = op.apply(&self.text)?;
+        self.revision += 1;
+        self.history.push(op.clone());
+        if self.history.len() > MAX_HISTORY {
+            self.history.drain(..self.history.len() - MAX_HISTORY);
+        }
+        Ok(op)
+    }
+}
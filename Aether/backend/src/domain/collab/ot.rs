@@ -0,0 +1,275 @@
+use serde::{Deserialize, Serialize};
+
+/// One component of a text operation. Lengths count Unicode code points.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Component {
+    Retain(usize),
+    Insert(String),
+    Delete(usize),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum OtError {
+    #[error("Operation expects a document of length {expected}, got {actual}")]
+    LengthMismatch { expected: usize, actual: usize },
+    #[error("Operations are based on different document lengths")]
+    IncompatibleOperations,
+}
+
+/// A text change as a sequence of retain/insert/delete components covering the
+/// whole document. On the wire it uses the ot.js format: positive numbers
+/// retain, negative numbers delete, strings insert (`[3, "abc", -2, 5]`).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "Vec<WireComponent>", into = "Vec<WireComponent>")]
+pub struct TextOperation {
+    components: Vec<Component>,
+    base_len: usize,   // Length of the document it applies to
+    target_len: usize, // Length after applying it
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum WireComponent {
+    Count(i64),
+    Insert(String),
+}
+
+impl TryFrom<Vec<WireComponent>> for TextOperation {
+    type Error = String;
+
+    fn try_from(wire: Vec<WireComponent>) -> Result<Self, Self::Error> {
+        let mut op = TextOperation::new();
+        // Counts come from the client: lengths that would overflow are rejected
+        let too_long = || "Operation is longer than any document".to_string();
+        for c in wire {
+            match c {
+                WireComponent::Count(n) if n > 0 => {
+                    let n = n as usize;
+                    if op.base_len.checked_add(n).is_none() || op.target_len.checked_add(n).is_none() {
+                        return Err(too_long());
+                    }
+                    op.retain(n)
+                }
+                WireComponent::Count(n) if n < 0 => {
+                    let n = n.unsigned_abs() as usize;
+                    op.base_len.checked_add(n).ok_or_else(too_long)?;
+                    op.delete(n)
+                }
+                WireComponent::Count(_) => return Err("Zero-length component".to_string()),
+                WireComponent::Insert(s) => op.insert(&s),
+            };
+        }
+        Ok(op)
+    }
+}
+
+impl From<TextOperation> for Vec<WireComponent> {
+    fn from(op: TextOperation) -> Self {
+        op.components.into_iter().map(|c| match c {
+            Component::Retain(n) => WireComponent::Count(n as i64),
+            Component::Delete(n) => WireComponent::Count(-(n as i64)),
+            Component::Insert(s) => WireComponent::Insert(s),
+        }).collect()
+    }
+}
+
+fn char_len(s: &str) -> usize {
+    s.chars().count()
+}
+
+impl TextOperation {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn components(&self) -> &[Component] {
+        &self.components
+    }
+
+    pub fn base_len(&self) -> usize {
+        self.base_len
+    }
+
+    pub fn target_len(&self) -> usize {
+        self.target_len
+    }
+
+    /// True when applying it changes nothing.
+    pub fn is_noop(&self) -> bool {
+        self.components.iter().all(|c| matches!(c, Component::Retain(_)))
+    }
+
+    pub fn retain(&mut self, n: usize) -> &mut Self {
+        if n == 0 {
+            return self;
+        }
+        self.base_len += n;
+        self.target_len += n;
+        if let Some(Component::Retain(last)) = self.components.last_mut() {
+            *last += n;
+        } else {
+            self.components.push(Component::Retain(n));
+        }
+        self
+    }
+
+    /// Inserts go before an adjacent delete, so equal edits have one representation.
+    pub fn insert(&mut self, s: &str) -> &mut Self {
+        if s.is_empty() {
+            return self;
+        }
+        self.target_len += char_len(s);
+        let len = self.components.len();
+        if let Some(Component::Insert(last)) = self.components.last_mut() {
+            last.push_str(s);
+        } else if let Some(Component::Delete(_)) = self.components.last() {
+            match self.components.get_mut(len.wrapping_sub(2)) {
+                Some(Component::Insert(before)) => before.push_str(s),
+                _ => self.components.insert(len - 1, Component::Insert(s.to_string())),
+            }
+        } else {
+            self.components.push(Component::Insert(s.to_string()));
+        }
+        self
+    }
+
+    pub fn delete(&mut self, n: usize) -> &mut Self {
+        if n == 0 {
+            return self;
+        }
+        self.base_len += n;
+        if let Some(Component::Delete(last)) = self.components.last_mut() {
+            *last += n;
+        } else {
+            self.components.push(Component::Delete(n));
+        }
+        self
+    }
+
+    pub fn apply(&self, text: &str) -> Result<String, OtError> {
+        let actual = char_len(text);
+        if actual != self.base_len {
+            return Err(OtError::LengthMismatch { expected: self.base_len, actual });
+        }
+        let mut out = String::with_capacity(text.len());
+        let mut chars = text.chars();
+        for c in &self.components {
+            match c {
+                Component::Retain(n) => out.extend(chars.by_ref().take(*n)),
+                Component::Insert(s) => out.push_str(s),
+                Component::Delete(n) => {
+                    chars.by_ref().take(*n).for_each(drop);
+                }
+            }
+        }
+        Ok(out)
+    }
+
+    /// Transforms two concurrent operations on the same document into
+    /// `(a', b')` such that `b'` after `a` equals `a'` after `b`. When both
+    /// insert at the same position, `a`'s text comes first.
+    pub fn transform(a: &TextOperation, b: &TextOperation) -> Result<(TextOperation, TextOperation), OtError> {
+        if a.base_len != b.base_len {
+            return Err(OtError::IncompatibleOperations);
+        }
+        let mut a_prime = TextOperation::new();
+        let mut b_prime = TextOperation::new();
+        let mut ops1 = a.components.iter().cloned();
+        let mut ops2 = b.components.iter().cloned();
+        let mut op1 = ops1.next();
+        let mut op2 = ops2.next();
+
+        loop {
+            match (&op1, &op2) {
+                (None, None) => break,
+                (Some(Component::Insert(s)), _) => {
+                    a_prime.insert(s);
+                    b_prime.retain(char_len(s));
+                    op1 = ops1.next();
+                }
+                (_, Some(Component::Insert(s))) => {
+                    a_prime.retain(char_len(s));
+                    b_prime.insert(s);
+                    op2 = ops2.next();
+                }
+                (None, _) | (_, None) => return Err(OtError::IncompatibleOperations),
+                (Some(c1), Some(c2)) => {
+                    let (x, y) = (span(c1), span(c2));
+                    let m = x.min(y);
+                    match (c1, c2) {
+                        (Component::Retain(_), Component::Retain(_)) => {
+                            a_prime.retain(m);
+                            b_prime.retain(m);
+                        }
+                        // Both deleted the same text: nothing left to do
+                        (Component::Delete(_), Component::Delete(_)) => {}
+                        (Component::Delete(_), Component::Retain(_)) => {
+                            a_prime.delete(m);
+                        }
+                        (Component::Retain(_), Component::Delete(_)) => {
+                            b_prime.delete(m);
+                        }
+                        _ => unreachable!("inserts handled above"),
+                    }
+                    op1 = shorten(c1, m).or_else(|| ops1.next());
+                    op2 = shorten(c2, m).or_else(|| ops2.next());
+                }
+            }
+        }
+        Ok((a_prime, b_prime))
+    }
+
+    /// The operation turning `old` into `new`: their common start and end
+    /// are retained, the differing middle is replaced.
+    pub fn diff(old: &str, new: &str) -> TextOperation {
+        let (old_chars, new_chars): (Vec<char>, Vec<char>) = (old.chars().collect(), new.chars().collect());
+        let prefix = old_chars.iter().zip(&new_chars).take_while(|(a, b)| a == b).count();
+        let suffix = old_chars[prefix..].iter().rev()
+            .zip(new_chars[prefix..].iter().rev())
+            .take_while(|(a, b)| a == b)
+            .count();
+
+        let mut op = TextOperation::new();
+        op.retain(prefix)
+            .delete(old_chars.len() - prefix - suffix)
+            .insert(&new_chars[prefix..new_chars.len() - suffix].iter().collect::<String>())
+            .retain(suffix);
+        op
+    }
+
+    /// Where a cursor at `index` ends up after this operation.
+    pub fn transform_index(&self, index: usize) -> usize {
+        let mut remaining = index as i64;
+        let mut new_index = index as i64;
+        for c in &self.components {
+            match c {
+                Component::Retain(n) => remaining -= *n as i64,
+                Component::Insert(s) => new_index += char_len(s) as i64,
+                Component::Delete(n) => {
+                    new_index -= remaining.min(*n as i64);
+                    remaining -= *n as i64;
+                }
+            }
+            if remaining < 0 {
+                break;
+            }
+        }
+        new_index.max(0) as usize
+    }
+}
+
+fn span(c: &Component) -> usize {
+    match c {
+        Component::Retain(n) | Component::Delete(n) => *n,
+        Component::Insert(s) => char_len(s),
+    }
+}
+
+/// The rest of a retain/delete after consuming `n` of it, if any.
+fn shorten(c: &Component, n: usize) -> Option<Component> {
+    match c {
+        Component::Retain(x) if *x > n => Some(Component::Retain(x - n)),
+        Component::Delete(x) if *x > n => Some(Component::Delete(x - n)),
+        _ => None,
+    }
+}
@@ -0,0 +1,103 @@
+#[cfg(test)]
+mod tests {
+    use crate::domain::collab::ot::TextOperation;
+    use crate::domain::collab::{CollabDocument, CollabError, Selection};
+
+    fn op(json: &str) -> TextOperation {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn test_apply_and_wire_format() {
+        let o = op(r#"[2, "X", -1, 2]"#);
+        assert_eq!(o.base_len(), 5);
+        assert_eq!(o.target_len(), 5);
+        assert_eq!(o.apply("hello").unwrap(), "heXlo");
+        assert_eq!(serde_json::to_string(&o).unwrap(), r#"[2,"X",-1,2]"#);
+        assert!(o.apply("hell").is_err());
+
+        // Lengths count code points, not bytes
+        assert_eq!(op(r#"[1, -1, "é"]"#).apply("日本").unwrap(), "日é");
+        assert!(serde_json::from_str::<TextOperation>("[0]").is_err());
+
+        // Counts that overflow the lengths are rejected, not wrapped
+        let max = i64::MAX;
+        assert!(serde_json::from_str::<TextOperation>(&format!("[{max}, {max}, {max}]")).is_err());
+        assert!(serde_json::from_str::<TextOperation>(&format!("[-{max}, -{max}, -{max}]")).is_err());
+    }
+
+    #[test]
+    fn test_insert_is_normalized_before_delete() {
+        let mut a = TextOperation::new();
+        a.retain(1).delete(2).insert("x");
+        let mut b = TextOperation::new();
+        b.retain(1).insert("x").delete(2);
+        assert_eq!(a, b);
+        assert!(!a.is_noop());
+    }
+
+    #[test]
+    fn test_transform_converges() {
+        let doc = "The quick fox";
+        let cases = [
+            (r#"[4, "very ", 9]"#, r#"[10, "brown ", 3]"#),
+            (r#"[4, -6, 3]"#, r#"[10, "brown ", 3]"#),
+            (r#"[4, -6, 3]"#, r#"[2, -4, 7]"#),
+            (r#"[13, "!"]"#, r#"[13, "?"]"#),
+            (r#"[-13]"#, r#"[4, "very ", 9]"#),
+        ];
+        for (a, b) in cases {
+            let (a, b) = (op(a), op(b));
+            let (a_prime, b_prime) = TextOperation::transform(&a, &b).unwrap();
+            let left = b_prime.apply(&a.apply(doc).unwrap()).unwrap();
+            let right = a_prime.apply(&b.apply(doc).unwrap()).unwrap();
+            assert_eq!(left, right, "{:?} / {:?}", a, b);
+        }
+
+        // Same-position inserts: the first operation's text goes first
+        let (a_prime, _) = TextOperation::transform(&op(r#"[13, "!"]"#), &op(r#"[13, "?"]"#)).unwrap();
+        assert_eq!(a_prime.apply("The quick fox?").unwrap(), "The quick fox!?");
+    }
+
+    #[test]
+    fn test_document_transforms_late_operations() {
+        let mut doc = CollabDocument::new("hello world".to_string());
+
+        // Two clients edit revision 0 concurrently
+        let applied = doc.apply_client(0, op(r#"[5, ",", 6]"#)).unwrap();
+        assert_eq!(applied, op(r#"[5, ",", 6]"#));
+        let applied = doc.apply_client(0, op(r#"[11, "!"]"#)).unwrap();
+        assert_eq!(applied, op(r#"[12, "!"]"#));
+        assert_eq!(doc.text(), "hello, world!");
+        assert_eq!(doc.revision(), 2);
+
+        assert_eq!(doc.apply_client(3, op("[13]")), Err(CollabError::FutureRevision(3)));
+        assert!(doc.apply_client(1, op("[5]")).is_err());
+    }
+
+    #[test]
+    fn test_diff_merges_outside_edit() {
+        for (old, new) in [("hello world", "hello brave world"), ("abc", ""), ("", "日本"), ("aaa", "aa"), ("same", "same")] {
+            let diff = TextOperation::diff(old, new);
+            assert_eq!(diff.apply(old).unwrap(), new);
+        }
+        assert!(TextOperation::diff("same", "same").is_noop());
+
+        // A change saved elsewhere, merged into a room that edited since
+        let mut doc = CollabDocument::new("hello world".to_string());
+        doc.apply_client(0, op(r#"[11, "!"]"#)).unwrap();
+        doc.apply_client(0, TextOperation::diff("hello world", "hello there world")).unwrap();
+        assert_eq!(doc.text(), "hello there world!");
+    }
+
+    #[test]
+    fn test_selection_follows_edits() {
+        let sel = Selection { anchor: 6, head: 11 };
+        // Insert before the selection shifts it
+        assert_eq!(sel.transform(&op(r#"["Oh, ", 11]"#)), Selection { anchor: 10, head: 15 });
+        // Deleting text around the anchor collapses into the deletion point
+        assert_eq!(sel.transform(&op(r#"[4, -4, 3]"#)), Selection { anchor: 4, head: 7 });
+        // Edits after the selection leave it alone
+        assert_eq!(sel.transform(&op(r#"[12, "!", 1]"#)), sel);
+    }
+}
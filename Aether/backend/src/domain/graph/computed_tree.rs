@@ -22,12 +22,18 @@ impl ComputedTreeService {
         // 2. Build Graph
         for block in &blocks {
             let refs = extract_references(&block.type_name, &block.payload);
+            // A theorem's proof comes after it, not before
+            let proof_id = match block.type_name.as_str() {
+                "theorem" => block.payload.get("proof_id").and_then(|v| v.as_str()).and_then(|s| Uuid::parse_str(s).ok()),
+                _ => None,
+            };
             for target_id in refs {
                 if block_map.contains_key(&target_id) {
                     // target_id must come BEFORE block.id (Dependency)
                     // Edge: target -> block
-                    adj_list.entry(target_id).or_default().push(block.id);
-                    *in_degree.entry(block.id).or_insert(0) += 1;
+                    let (from, to) = if Some(target_id) == proof_id { (block.id, target_id) } else { (target_id, block.id) };
+                    adj_list.entry(from).or_default().push(to);
+                    *in_degree.entry(to).or_insert(0) += 1;
                 }
             }
         }
@@ -78,8 +84,8 @@ impl ComputedTreeService {
             }
         }
         
-        // TODO: Detect cycles? If sorted_blocks.len() != blocks.len(), cycle exists.
-        // For now, append remaining blocks (cycle participants) at the end.
+        // Cycle participants go last, in their original order; `dependencies::analyze`
+        // reports the chains (GET /api/kb/:id/structure/diagnostics)
         if sorted_blocks.len() != blocks.len() {
              let processed: HashSet<Uuid> = sorted_blocks.iter().map(|b| b.id).collect();
              for block in blocks {
@@ -0,0 +1,204 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use serde::Serialize;
+use uuid::Uuid;
+use crate::domain::blocks::models::Block;
+use crate::domain::blocks::strategies::scan_block_references;
+
+/// A `proof_id`/`theorem_id` (or, for `field: "content"`, a `((uuid))`/`[[uuid]]`)
+/// that points nowhere useful.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DanglingReference {
+    pub block_id: Uuid,
+    pub field: &'static str,
+    pub target_id: Uuid,
+    pub reason: &'static str, // "missing": not in the knowledge base; "wrong_type": not a proof/theorem
+}
+
+/// A reference from the knowledge base to a block in another document set.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ExternalReference {
+    pub block_id: Uuid,
+    pub target_id: Uuid,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AxiomDependencies {
+    pub theorem_id: Uuid,
+    pub axioms: Vec<Uuid>,
+}
+
+/// Dependency analysis of a math knowledge base. A theorem relies on the
+/// blocks its statement references and on its proofs; a proof relies on
+/// the blocks it references other than the theorem it proves.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct DependencyReport {
+    pub cycles: Vec<Vec<Uuid>>, // Each chain starts and ends with the same block
+    pub dangling: Vec<DanglingReference>,
+    pub external: Vec<ExternalReference>,
+    pub unproven_theorems: Vec<Uuid>,
+    pub unused_axioms: Vec<Uuid>,
+    pub axiom_dependencies: Vec<AxiomDependencies>,
+}
+
+impl DependencyReport {
+    /// Moves the external references whose target does not exist to `dangling`.
+    pub fn resolve_external(&mut self, existing: &HashSet<Uuid>) {
+        let (found, missing): (Vec<_>, Vec<_>) = self.external.drain(..)
+            .partition(|r| existing.contains(&r.target_id));
+        self.external = found;
+        self.dangling.extend(missing.into_iter().map(|r| DanglingReference {
+            block_id: r.block_id,
+            field: "content",
+            target_id: r.target_id,
+            reason: "missing",
+        }));
+    }
+}
+
+fn uuid_field(block: &Block, field: &str) -> Option<Uuid> {
+    block.payload.get(field).and_then(|v| v.as_str()).and_then(|s| Uuid::parse_str(s).ok())
+}
+
+pub fn analyze(blocks: &[Block]) -> DependencyReport {
+    let mut report = DependencyReport::default();
+
+    // Ordinal order keeps the report (and which cycle member comes first) stable
+    let mut blocks: Vec<&Block> = blocks.iter().collect();
+    blocks.sort_by_key(|b| (b.document_id, b.ordinal, b.id));
+    let by_id: HashMap<Uuid, &Block> = blocks.iter().map(|b| (b.id, *b)).collect();
+    let is = |id: Uuid, type_name: &str| by_id.get(&id).is_some_and(|b| b.type_name == type_name);
+
+    // 1. Pair theorems and proofs, from either side
+    let mut proofs_of: HashMap<Uuid, Vec<Uuid>> = HashMap::new();
+    let mut proves: HashMap<Uuid, Vec<Uuid>> = HashMap::new();
+    let mut pair = |theorem: Uuid, proof: Uuid| {
+        let proofs = proofs_of.entry(theorem).or_default();
+        if !proofs.contains(&proof) {
+            proofs.push(proof);
+            proves.entry(proof).or_default().push(theorem);
+        }
+    };
+    for block in &blocks {
+        let (field, expected) = match block.type_name.as_str() {
+            "theorem" => ("proof_id", "proof"),
+            "proof" => ("theorem_id", "theorem"),
+            _ => continue,
+        };
+        let Some(target) = uuid_field(block, field) else { continue };
+        match by_id.get(&target) {
+            None => report.dangling.push(DanglingReference { block_id: block.id, field, target_id: target, reason: "missing" }),
+            Some(t) if t.type_name != expected => {
+                report.dangling.push(DanglingReference { block_id: block.id, field, target_id: target, reason: "wrong_type" })
+            }
+            Some(_) if expected == "proof" => pair(block.id, target),
+            Some(_) => pair(target, block.id),
+        }
+    }
+
+    // 2. What each block relies on
+    let mut relies_on: HashMap<Uuid, Vec<Uuid>> = HashMap::new();
+    for block in &blocks {
+        let own_theorems = proves.get(&block.id).map(Vec::as_slice).unwrap_or(&[]);
+        let mut targets: Vec<Uuid> = Vec::new();
+        for (target, _) in scan_block_references(&block.type_name, &block.payload) {
+            if !by_id.contains_key(&target) {
+                let external = ExternalReference { block_id: block.id, target_id: target };
+                if !report.external.contains(&external) {
+                    report.external.push(external);
+                }
+                continue;
+            }
+            // A proof naming the theorem it proves is not circular
+            if !own_theorems.contains(&target) && !targets.contains(&target) {
+                targets.push(target);
+            }
+        }
+        if block.type_name == "theorem" {
+            for proof in proofs_of.get(&block.id).into_iter().flatten() {
+                if !targets.contains(proof) {
+                    targets.push(*proof);
+                }
+            }
+        }
+        relies_on.insert(block.id, targets);
+    }
+
+    report.cycles = find_cycles(&blocks, &relies_on);
+
+    // 3. Theorems and axioms
+    let used: HashSet<Uuid> = relies_on.values().flatten().copied().collect();
+    for block in &blocks {
+        match block.type_name.as_str() {
+            "axiom" if !used.contains(&block.id) => report.unused_axioms.push(block.id),
+            "theorem" => {
+                if !proofs_of.contains_key(&block.id) {
+                    report.unproven_theorems.push(block.id);
+                }
+                let mut axioms: Vec<&Block> = reachable(block.id, &relies_on).into_iter()
+                    .filter(|id| is(*id, "axiom"))
+                    .map(|id| by_id[&id])
+                    .collect();
+                axioms.sort_by_key(|b| (b.document_id, b.ordinal, b.id));
+                report.axiom_dependencies.push(AxiomDependencies {
+                    theorem_id: block.id,
+                    axioms: axioms.into_iter().map(|b| b.id).collect(),
+                });
+            }
+            _ => {}
+        }
+    }
+
+    report
+}
+
+/// Blocks reachable from `start`, not counting `start` itself unless it is on a cycle.
+fn reachable(start: Uuid, relies_on: &HashMap<Uuid, Vec<Uuid>>) -> HashSet<Uuid> {
+    let mut seen = HashSet::new();
+    let mut queue: VecDeque<Uuid> = relies_on.get(&start).into_iter().flatten().copied().collect();
+    while let Some(id) = queue.pop_front() {
+        if seen.insert(id) {
+            queue.extend(relies_on.get(&id).into_iter().flatten().copied());
+        }
+    }
+    seen
+}
+
+/// One chain per back edge of a depth-first search, skipping chains whose
+/// blocks all belong to a cycle already reported.
+fn find_cycles(blocks: &[&Block], relies_on: &HashMap<Uuid, Vec<Uuid>>) -> Vec<Vec<Uuid>> {
+    let mut cycles: Vec<Vec<Uuid>> = Vec::new();
+    let mut in_cycle: HashSet<Uuid> = HashSet::new();
+    let mut done: HashSet<Uuid> = HashSet::new();
+
+    for root in blocks.iter().map(|b| b.id) {
+        if done.contains(&root) {
+            continue;
+        }
+        // Iterative: chains of lemmas can be deeper than the stack allows
+        let mut path: Vec<(Uuid, usize)> = vec![(root, 0)];
+        let mut on_path: HashMap<Uuid, usize> = HashMap::from([(root, 0)]);
+        while let Some((node, next)) = path.last_mut() {
+            let node = *node;
+            let neighbors = relies_on.get(&node).map(Vec::as_slice).unwrap_or(&[]);
+            let Some(&target) = neighbors.get(*next) else {
+                path.pop();
+                on_path.remove(&node);
+                done.insert(node);
+                continue;
+            };
+            *next += 1;
+            if let Some(&position) = on_path.get(&target) {
+                let mut chain: Vec<Uuid> = path[position..].iter().map(|(id, _)| *id).collect();
+                if chain.iter().any(|id| !in_cycle.contains(id)) {
+                    in_cycle.extend(chain.iter().copied());
+                    chain.push(target);
+                    cycles.push(chain);
+                }
+            } else if !done.contains(&target) {
+                on_path.insert(target, path.len());
+                path.push((target, 0));
+            }
+        }
+    }
+    cycles
+}
@@ -1 +1,4 @@
 pub mod computed_tree;
+pub mod dependencies;
+
+mod tests;
@@ -0,0 +1,81 @@
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use std::collections::HashSet;
+    use uuid::Uuid;
+    use crate::domain::blocks::testing::block;
+    use crate::domain::graph::computed_tree::ComputedTreeService;
+    use crate::domain::graph::dependencies::{analyze, DanglingReference, ExternalReference};
+
+    #[test]
+    fn test_topological_sort_orders_proofs_after_theorems() {
+        let doc = Uuid::new_v4();
+        let axiom = block(doc, 0, "axiom", json!({ "content": "A" }));
+        let mut theorem = block(doc, 1, "theorem", json!({ "content": "T" }));
+        let proof = block(doc, 2, "proof", json!({ "theorem_id": theorem.id.to_string(), "steps": format!("By (({}))", axiom.id) }));
+        theorem.payload["proof_id"] = json!(proof.id.to_string());
+
+        let sorted = ComputedTreeService::compute_topological_sort(vec![proof.clone(), theorem.clone(), axiom.clone()]);
+        let order: Vec<Uuid> = sorted.iter().map(|b| b.id).collect();
+        assert_eq!(order, vec![axiom.id, theorem.id, proof.id]);
+    }
+
+    #[test]
+    fn test_dependency_diagnostics() {
+        let doc = Uuid::new_v4();
+        let a1 = block(doc, 0, "axiom", json!({ "content": "A1" }));
+        let a2 = block(doc, 1, "axiom", json!({ "content": "A2" }));
+        let unused = block(doc, 2, "axiom", json!({ "content": "A3" }));
+        let lemma = block(doc, 3, "theorem", json!({ "content": format!("Given (({}))", a1.id) }));
+        let lemma_proof = block(doc, 4, "proof", json!({ "theorem_id": lemma.id.to_string(), "steps": format!("[[{}]] and (({}))", lemma.id, a2.id) }));
+        let mut main = block(doc, 5, "theorem", json!({ "content": "Main" }));
+        let main_proof = block(doc, 6, "proof", json!({ "theorem_id": main.id.to_string(), "steps": format!("Apply (({}))", lemma.id) }));
+        main.payload["proof_id"] = json!(main_proof.id.to_string());
+        let missing = Uuid::new_v4();
+        let unproven = block(doc, 7, "theorem", json!({ "content": "Open", "proof_id": missing.to_string() }));
+        let stray = block(doc, 8, "proof", json!({ "theorem_id": a1.id.to_string(), "steps": "?" }));
+        let outside = Uuid::new_v4();
+        let note = block(doc, 9, "paragraph", json!({ "markdown": format!("See (({}))", outside) }));
+
+        let blocks = vec![a1.clone(), a2.clone(), unused.clone(), lemma.clone(), lemma_proof, main.clone(), main_proof, unproven.clone(), stray.clone(), note.clone()];
+        let mut report = analyze(&blocks);
+
+        assert!(report.cycles.is_empty());
+        assert_eq!(report.unused_axioms, vec![unused.id]);
+        assert_eq!(report.unproven_theorems, vec![unproven.id]);
+        assert_eq!(report.dangling, vec![
+            DanglingReference { block_id: unproven.id, field: "proof_id", target_id: missing, reason: "missing" },
+            DanglingReference { block_id: stray.id, field: "theorem_id", target_id: a1.id, reason: "wrong_type" },
+        ]);
+        assert_eq!(report.external, vec![ExternalReference { block_id: note.id, target_id: outside }]);
+
+        let axioms_of = |id: Uuid| report.axiom_dependencies.iter().find(|d| d.theorem_id == id).unwrap().axioms.clone();
+        assert_eq!(axioms_of(lemma.id), vec![a1.id, a2.id]);
+        assert_eq!(axioms_of(main.id), vec![a1.id, a2.id]);
+        assert!(axioms_of(unproven.id).is_empty());
+
+        report.resolve_external(&HashSet::new());
+        assert!(report.external.is_empty());
+        assert_eq!(report.dangling.last().unwrap().field, "content");
+    }
+
+    #[test]
+    fn test_dependency_cycles() {
+        let doc = Uuid::new_v4();
+        let mut t1 = block(doc, 0, "theorem", json!({ "content": "T1" }));
+        let mut t2 = block(doc, 1, "theorem", json!({ "content": "T2" }));
+        let p1 = block(doc, 2, "proof", json!({ "theorem_id": t1.id.to_string(), "steps": format!("By (({}))", t2.id) }));
+        let p2 = block(doc, 3, "proof", json!({ "theorem_id": t2.id.to_string(), "steps": format!("By (({}))", t1.id) }));
+        t1.payload["proof_id"] = json!(p1.id.to_string());
+        t2.payload["proof_id"] = json!(p2.id.to_string());
+        let mut selfish = block(doc, 4, "definition", json!({ "term": "x", "content": "" }));
+        selfish.payload["content"] = json!(format!("x is (({}))", selfish.id));
+
+        let report = analyze(&[t1.clone(), t2.clone(), p1.clone(), p2.clone(), selfish.clone()]);
+        assert_eq!(report.cycles, vec![
+            vec![t1.id, p1.id, t2.id, p2.id, t1.id],
+            vec![selfish.id, selfish.id],
+        ]);
+        assert!(report.unproven_theorems.is_empty());
+    }
+}
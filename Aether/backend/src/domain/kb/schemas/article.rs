@@ -0,0 +1,246 @@
+use crate::domain::kb::registry::{BlockSchema, SchemaError};
+use serde_json::Value;
+
+// Block types an article body is split into (see `blocks::parser`)
+
+fn required_str<'a>(payload: &'a Value, field: &str, block: &str) -> Result<&'a str, SchemaError> {
+    payload.get(field).and_then(|v| v.as_str())
+        .ok_or_else(|| SchemaError::ValidationFailed(format!("Missing or invalid '{}' field in {} block", field, block)))
+}
+
+pub struct ParagraphSchema;
+
+impl BlockSchema for ParagraphSchema {
+    fn validate(&self, payload: &Value) -> Result<(), SchemaError> {
+        if required_str(payload, "markdown", "paragraph")?.trim().is_empty() {
+            return Err(SchemaError::ValidationFailed("Paragraph block is empty".into()));
+        }
+        Ok(())
+    }
+
+    fn to_searchable_text(&self, payload: &Value) -> String {
+        payload["markdown"].as_str().unwrap_or("").to_string()
+    }
+}
+
+pub struct HeadingSchema;
+
+impl BlockSchema for HeadingSchema {
+    fn validate(&self, payload: &Value) -> Result<(), SchemaError> {
+        match payload.get("level").and_then(|v| v.as_u64()) {
+            Some(1..=6) => {},
+            _ => return Err(SchemaError::ValidationFailed("Heading 'level' must be 1 to 6".into())),
+        }
+        if required_str(payload, "text", "heading")?.contains('\n') {
+            return Err(SchemaError::ValidationFailed("Heading text must be a single line".into()));
+        }
+        Ok(())
+    }
+
+    fn to_searchable_text(&self, payload: &Value) -> String {
+        payload["text"].as_str().unwrap_or("").to_string()
+    }
+}
+
+pub struct CodeSchema;
+
+impl BlockSchema for CodeSchema {
+    fn validate(&self, payload: &Value) -> Result<(), SchemaError> {
+        required_str(payload, "code", "code")?;
+        if payload.get("language").is_some_and(|v| !v.is_string()) {
+            return Err(SchemaError::ValidationFailed("Invalid 'language' field in code block".into()));
+        }
+        Ok(())
+    }
+
+    fn to_searchable_text(&self, payload: &Value) -> String {
+        payload["code"].as_str().unwrap_or("").to_string()
+    }
+}
+
+/// Display math (`$$ ... $$`) in an article, unlike the KB's `math_block`.
+pub struct DisplayMathSchema;
+
+impl BlockSchema for DisplayMathSchema {
+    fn validate(&self, payload: &Value) -> Result<(), SchemaError> {
+        required_str(payload, "latex", "math")?;
+        Ok(())
+    }
+
+    fn to_searchable_text(&self, payload: &Value) -> String {
+        payload["latex"].as_str().unwrap_or("").to_string()
+    }
+}
+
+/// Nested blocks of a container: `[{ "type": ..., "payload": {...} }]`, checked down the tree.
+fn check_children(payload: &Value, block: &str) -> Result<(), SchemaError> {
+    let children = payload.get("children").and_then(|v| v.as_array())
+        .ok_or_else(|| SchemaError::ValidationFailed(format!("Missing or invalid 'children' field in {} block", block)))?;
+    for child in children {
+        let child_type = child.get("type").and_then(|v| v.as_str());
+        let child_payload = child.get("payload").filter(|v| v.is_object());
+        match (child_type, child_payload) {
+            (Some(t), Some(p)) if p.get("children").is_some() => check_children(p, t)?,
+            (Some(_), Some(_)) => {},
+            _ => return Err(SchemaError::ValidationFailed(format!("Invalid nested block in {} block", block))),
+        }
+    }
+    Ok(())
+}
+
+fn nested_text(type_name: &str, payload: &Value) -> String {
+    crate::domain::blocks::strategies::extract_text(type_name, payload)
+}
+
+/// Bullet, ordered and task lists; items are `list_item` children.
+pub struct ListSchema;
+
+impl BlockSchema for ListSchema {
+    fn validate(&self, payload: &Value) -> Result<(), SchemaError> {
+        check_children(payload, "list")?;
+        let ordered = payload.get("ordered").and_then(|v| v.as_bool()).unwrap_or(false);
+        let marker = payload.get("marker").and_then(|v| v.as_str());
+        let valid_marker = match (ordered, marker) {
+            (_, None) => true,
+            (true, Some(m)) => m == "." || m == ")",
+            (false, Some(m)) => m == "-" || m == "*" || m == "+",
+        };
+        if !valid_marker {
+            return Err(SchemaError::ValidationFailed("Invalid 'marker' field in list block".into()));
+        }
+        for item in payload["children"].as_array().into_iter().flatten() {
+            if item["type"] != "list_item" {
+                return Err(SchemaError::ValidationFailed("List children must be list_item blocks".into()));
+            }
+            if item["payload"].get("checked").is_some_and(|v| !v.is_boolean()) {
+                return Err(SchemaError::ValidationFailed("Invalid 'checked' field in list item".into()));
+            }
+        }
+        Ok(())
+    }
+
+    fn to_searchable_text(&self, payload: &Value) -> String {
+        nested_text("list", payload)
+    }
+}
+
+pub struct BlockquoteSchema;
+
+impl BlockSchema for BlockquoteSchema {
+    fn validate(&self, payload: &Value) -> Result<(), SchemaError> {
+        check_children(payload, "blockquote")
+    }
+
+    fn to_searchable_text(&self, payload: &Value) -> String {
+        nested_text("blockquote", payload)
+    }
+}
+
+/// Container directive: `:::name info` ... `:::`.
+pub struct DirectiveSchema;
+
+impl BlockSchema for DirectiveSchema {
+    fn validate(&self, payload: &Value) -> Result<(), SchemaError> {
+        let name = required_str(payload, "name", "directive")?;
+        if name.is_empty() || !name.chars().all(|c| c.is_alphanumeric() || c == '-' || c == '_') {
+            return Err(SchemaError::ValidationFailed("Directive 'name' must be a single word".into()));
+        }
+        if payload.get("info").and_then(|v| v.as_str()).is_some_and(|i| i.contains('\n')) {
+            return Err(SchemaError::ValidationFailed("Directive 'info' must be a single line".into()));
+        }
+        check_children(payload, "directive")
+    }
+
+    fn to_searchable_text(&self, payload: &Value) -> String {
+        nested_text("directive", payload)
+    }
+}
+
+/// GFM table: `header` cells, `rows` of cells and an optional `align` per column.
+pub struct TableSchema;
+
+impl BlockSchema for TableSchema {
+    fn validate(&self, payload: &Value) -> Result<(), SchemaError> {
+        let is_row = |v: &Value| v.as_array().is_some_and(|cells| cells.iter().all(|c| c.as_str().is_some_and(|s| !s.contains('\n'))));
+        let header = payload.get("header").filter(|v| is_row(v) && !v.as_array().unwrap().is_empty());
+        if header.is_none() {
+            return Err(SchemaError::ValidationFailed("Table 'header' must be a non-empty list of single-line cells".into()));
+        }
+        if !payload.get("rows").and_then(|v| v.as_array()).is_some_and(|rows| rows.iter().all(is_row)) {
+            return Err(SchemaError::ValidationFailed("Table 'rows' must be lists of single-line cells".into()));
+        }
+        let valid_align = |v: &Value| v.is_null() || matches!(v.as_str(), Some("left" | "center" | "right"));
+        if payload.get("align").is_some_and(|a| !a.as_array().is_some_and(|a| a.iter().all(valid_align))) {
+            return Err(SchemaError::ValidationFailed("Invalid 'align' field in table block".into()));
+        }
+        Ok(())
+    }
+
+    fn to_searchable_text(&self, payload: &Value) -> String {
+        nested_text("table", payload)
+    }
+}
+
+pub struct ImageSchema;
+
+impl BlockSchema for ImageSchema {
+    fn validate(&self, payload: &Value) -> Result<(), SchemaError> {
+        let src = required_str(payload, "src", "image")?;
+        if src.is_empty() || src.contains(char::is_whitespace) {
+            return Err(SchemaError::ValidationFailed("Image 'src' must be a URL without spaces".into()));
+        }
+        if payload.get("alt").is_some_and(|v| !v.is_string()) {
+            return Err(SchemaError::ValidationFailed("Invalid 'alt' field in image block".into()));
+        }
+        Ok(())
+    }
+
+    fn to_searchable_text(&self, payload: &Value) -> String {
+        payload["alt"].as_str().unwrap_or("").to_string()
+    }
+}
+
+pub struct ThematicBreakSchema;
+
+impl BlockSchema for ThematicBreakSchema {
+    fn validate(&self, _payload: &Value) -> Result<(), SchemaError> {
+        Ok(())
+    }
+
+    fn to_searchable_text(&self, _payload: &Value) -> String {
+        String::new()
+    }
+}
+
+pub struct HtmlSchema;
+
+impl BlockSchema for HtmlSchema {
+    fn validate(&self, payload: &Value) -> Result<(), SchemaError> {
+        if !required_str(payload, "html", "html")?.trim_start().starts_with('<') {
+            return Err(SchemaError::ValidationFailed("HTML block must start with a tag".into()));
+        }
+        Ok(())
+    }
+
+    fn to_searchable_text(&self, payload: &Value) -> String {
+        payload["html"].as_str().unwrap_or("").to_string()
+    }
+}
+
+/// YAML or TOML metadata at the top of the article.
+pub struct FrontMatterSchema;
+
+impl BlockSchema for FrontMatterSchema {
+    fn validate(&self, payload: &Value) -> Result<(), SchemaError> {
+        match payload.get("format").and_then(|v| v.as_str()) {
+            Some("yaml" | "toml") => {},
+            _ => return Err(SchemaError::ValidationFailed("Front matter 'format' must be yaml or toml".into())),
+        }
+        required_str(payload, "raw", "front_matter")?;
+        Ok(())
+    }
+
+    fn to_searchable_text(&self, payload: &Value) -> String {
+        payload["raw"].as_str().unwrap_or("").to_string()
+    }
+}
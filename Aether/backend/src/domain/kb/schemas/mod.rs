@@ -2,4 +2,5 @@ pub mod markdown;
 pub mod math;
 pub mod paper_v1;
 pub mod assets;
+pub mod article;
 mod tests;
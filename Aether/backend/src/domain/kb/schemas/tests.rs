@@ -100,4 +100,49 @@ mod tests {
         assert!(text.contains("LeCun Bengio Hinton"));
         assert!(text.contains("Nature"));
     }
+
+    #[test]
+    fn test_article_block_validation() {
+        use crate::domain::kb::schemas::article::{CodeSchema, DisplayMathSchema, HeadingSchema, ParagraphSchema};
+
+        assert!(ParagraphSchema.validate(&json!({ "markdown": "Some *text*" })).is_ok());
+        assert!(ParagraphSchema.validate(&json!({ "markdown": "  " })).is_err());
+        assert!(ParagraphSchema.validate(&json!({ "text": "Wrong Key" })).is_err());
+
+        assert!(HeadingSchema.validate(&json!({ "level": 2, "text": "Intro" })).is_ok());
+        assert!(HeadingSchema.validate(&json!({ "level": 7, "text": "Intro" })).is_err());
+        assert!(HeadingSchema.validate(&json!({ "level": 1, "text": "Two\nlines" })).is_err());
+
+        assert!(CodeSchema.validate(&json!({ "code": "fn main() {}", "language": "rust" })).is_ok());
+        assert!(CodeSchema.validate(&json!({ "code": "x", "language": 3 })).is_err());
+
+        assert!(DisplayMathSchema.validate(&json!({ "latex": "E=mc^2" })).is_ok());
+        assert!(DisplayMathSchema.validate(&json!({})).is_err());
+    }
+
+    #[test]
+    fn test_article_container_validation() {
+        use crate::domain::kb::schemas::article::{DirectiveSchema, FrontMatterSchema, ImageSchema, ListSchema, TableSchema};
+
+        let item = |text: &str| json!({ "type": "list_item", "payload": { "children": [{ "type": "paragraph", "payload": { "markdown": text } }] } });
+        assert!(ListSchema.validate(&json!({ "ordered": false, "marker": "-", "children": [item("a"), item("b")] })).is_ok());
+        assert!(ListSchema.validate(&json!({ "ordered": true, "marker": "-", "children": [] })).is_err());
+        assert!(ListSchema.validate(&json!({ "children": [{ "type": "paragraph", "payload": { "markdown": "x" } }] })).is_err());
+        assert!(ListSchema.validate(&json!({ "children": [{ "type": "list_item", "payload": { "children": [{ "type": "paragraph" }] } }] })).is_err());
+        assert_eq!(ListSchema.to_searchable_text(&json!({ "children": [item("a"), item("b")] })), "a\nb");
+
+        assert!(TableSchema.validate(&json!({ "header": ["a", "b"], "rows": [["1", "2"]], "align": [null, "right"] })).is_ok());
+        assert!(TableSchema.validate(&json!({ "header": [], "rows": [] })).is_err());
+        assert!(TableSchema.validate(&json!({ "header": ["a"], "rows": [["two\nlines"]] })).is_err());
+        assert!(TableSchema.validate(&json!({ "header": ["a"], "rows": [], "align": ["middle"] })).is_err());
+
+        assert!(DirectiveSchema.validate(&json!({ "name": "note", "info": "Heads up", "children": [] })).is_ok());
+        assert!(DirectiveSchema.validate(&json!({ "name": "two words", "children": [] })).is_err());
+
+        assert!(ImageSchema.validate(&json!({ "src": "img/a.png", "alt": "A" })).is_ok());
+        assert!(ImageSchema.validate(&json!({ "src": "has space.png" })).is_err());
+
+        assert!(FrontMatterSchema.validate(&json!({ "format": "yaml", "raw": "title: x" })).is_ok());
+        assert!(FrontMatterSchema.validate(&json!({ "format": "json", "raw": "{}" })).is_err());
+    }
 }
@@ -1,14 +1,17 @@
+pub mod assets;
 pub mod kb;
 pub mod prkb;
 pub mod ports;
 pub mod models;
 pub mod portability;
 pub mod blocks;
+pub mod collab;
 pub mod diff_service;
 pub mod graph;
 pub mod graph_service;
 pub mod indexer_service;
 pub mod memos;
+pub mod morphology;
 pub mod permission_service;
 pub mod sentence_parser;
 pub mod dtos;
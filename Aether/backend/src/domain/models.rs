@@ -96,6 +96,8 @@ pub struct Vocabulary {
     
     // New Fields
     pub root: Option<String>, // The actual root string, e.g. "spec"
+    #[serde(default)]
+    pub lemma: Option<String>, // Dictionary form, e.g. "run" for "running"
     pub examples: Vec<VocabularyExample>,
     #[serde(default)]
     pub query_count: i32,
@@ -103,6 +105,13 @@ pub struct Vocabulary {
     pub is_important: bool,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VocabularyRoot {
+    pub id: Uuid,
+    pub root: String,
+    pub meaning: Option<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Memo {
     #[serde(flatten)]
@@ -117,6 +126,14 @@ pub struct Memo {
     pub tags: Vec<String>,
 }
 
+impl Memo {
+    /// Version token for optimistic concurrency: the last update time in
+    /// microseconds, the precision the database keeps.
+    pub fn revision(&self) -> i64 {
+        self.node.updated_at.timestamp_micros()
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DiffChange {
     pub tag: String, // "Equal", "Insert", "Delete"
@@ -130,6 +147,18 @@ pub struct ContentDiff {
     pub changes: Vec<DiffChange>,
 }
 
+/// A node of a KB with its latest content version (0 before the first), without its body.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NodeVersion {
+    pub id: Uuid,
+    pub parent_id: Option<Uuid>,
+    pub r#type: NodeType,
+    pub title: String,
+    pub version: i64,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ContentVersionSnapshot {
     pub id: String,
@@ -319,6 +348,28 @@ pub struct VrkbAsset {
     pub created_at: DateTime<Utc>,
 }
 
+/// A stored file seen from the asset layer: an asset article (`kind` "article")
+/// or a VRKB asset ("vrkb"), with the hash addressing its content.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssetRecord {
+    pub id: Uuid,
+    pub kind: String,
+    pub hash: String,
+    pub storage_path: String,
+    pub owner_id: Option<Uuid>,
+    pub size_bytes: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+/// One use of an asset: referenced from an article, finding or VRKB doc,
+/// linked to a project, or the cover of a knowledge base.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct AssetUsage {
+    pub asset_id: Uuid,
+    pub source_kind: String,
+    pub source_id: Uuid,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VrkbMember {
     pub project_id: Uuid,
@@ -352,6 +403,13 @@ pub struct VrkbDoc {
     pub deleted_at: Option<DateTime<Utc>>,
 }
 
+impl VrkbDoc {
+    /// Version token for optimistic concurrency, like `Memo::revision`.
+    pub fn revision(&self) -> i64 {
+        self.updated_at.timestamp_micros()
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VrkbStats {
     pub metrics: VrkbMetrics,
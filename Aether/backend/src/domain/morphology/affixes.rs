@@ -0,0 +1,215 @@
+// Affix and root tables for English word-family analysis.
+// Roots are mostly Latin/Greek; the first variant is the canonical form
+// stored in `vocab_roots.root`.
+
+pub struct RootEntry {
+    pub variants: &'static [&'static str],
+    pub meaning: &'static str,
+}
+
+pub const PREFIXES: &[(&str, &str)] = &[
+    ("anti", "against"),
+    ("auto", "self"),
+    ("bene", "good, well"),
+    ("circum", "around"),
+    ("com", "with, together"),
+    ("con", "with, together"),
+    ("contra", "against"),
+    ("counter", "against, opposite"),
+    ("de", "down, away, reverse"),
+    ("dis", "not, apart"),
+    ("em", "into, cause to"),
+    ("en", "into, cause to"),
+    ("ex", "out, former"),
+    ("extra", "beyond"),
+    ("fore", "before"),
+    ("hyper", "over, excessive"),
+    ("il", "not"),
+    ("im", "not, into"),
+    ("in", "not, into"),
+    ("inter", "between"),
+    ("intro", "inward"),
+    ("ir", "not"),
+    ("mal", "bad"),
+    ("micro", "small"),
+    ("mid", "middle"),
+    ("mis", "wrongly"),
+    ("mono", "one"),
+    ("multi", "many"),
+    ("non", "not"),
+    ("ob", "against, toward"),
+    ("over", "too much"),
+    ("per", "through"),
+    ("poly", "many"),
+    ("post", "after"),
+    ("pre", "before"),
+    ("pro", "forward, for"),
+    ("re", "again, back"),
+    ("retro", "backward"),
+    ("semi", "half"),
+    ("sub", "under"),
+    ("super", "above"),
+    ("sur", "over, above"),
+    ("sym", "together"),
+    ("syn", "together"),
+    ("tele", "far"),
+    ("trans", "across"),
+    ("tri", "three"),
+    ("un", "not, reverse"),
+    ("under", "below"),
+    ("uni", "one"),
+];
+
+pub const SUFFIXES: &[(&str, &str)] = &[
+    ("ability", "capacity (noun)"),
+    ("ibility", "capacity (noun)"),
+    ("ation", "action, state (noun)"),
+    ("ition", "action, state (noun)"),
+    ("tion", "action, state (noun)"),
+    ("sion", "action, state (noun)"),
+    ("ment", "result, action (noun)"),
+    ("ness", "state, quality (noun)"),
+    ("ance", "state, quality (noun)"),
+    ("ence", "state, quality (noun)"),
+    ("ancy", "state, quality (noun)"),
+    ("ency", "state, quality (noun)"),
+    ("ship", "condition (noun)"),
+    ("hood", "state (noun)"),
+    ("dom", "state, realm (noun)"),
+    ("ism", "doctrine (noun)"),
+    ("ist", "one who (noun)"),
+    ("ity", "state, quality (noun)"),
+    ("ty", "state, quality (noun)"),
+    ("ure", "act, result (noun)"),
+    ("age", "action, collection (noun)"),
+    ("ery", "place, practice (noun)"),
+    ("ory", "place for; relating to"),
+    ("ary", "relating to"),
+    ("logy", "study of (noun)"),
+    ("er", "one who (noun)"),
+    ("or", "one who (noun)"),
+    ("ant", "one who; having quality"),
+    ("ent", "one who; having quality"),
+    ("able", "can be done (adjective)"),
+    ("ible", "can be done (adjective)"),
+    ("ful", "full of (adjective)"),
+    ("less", "without (adjective)"),
+    ("ous", "having (adjective)"),
+    ("ious", "having (adjective)"),
+    ("ive", "tending to (adjective)"),
+    ("ative", "tending to (adjective)"),
+    ("ical", "relating to (adjective)"),
+    ("ial", "relating to (adjective)"),
+    ("al", "relating to (adjective)"),
+    ("ic", "relating to (adjective)"),
+    ("ish", "somewhat (adjective)"),
+    ("ize", "make, become (verb)"),
+    ("ise", "make, become (verb)"),
+    ("ify", "make (verb)"),
+    ("fy", "make (verb)"),
+    ("ate", "make, act (verb)"),
+    ("en", "make, become (verb)"),
+    ("ly", "in the manner of (adverb)"),
+];
+
+pub const ROOTS: &[RootEntry] = &[
+    RootEntry { variants: &["spec", "spect", "spic"], meaning: "look, see" },
+    RootEntry { variants: &["dict", "dic"], meaning: "say, speak" },
+    RootEntry { variants: &["port"], meaning: "carry" },
+    RootEntry { variants: &["duc", "duct", "duce"], meaning: "lead" },
+    RootEntry { variants: &["scrib", "script"], meaning: "write" },
+    RootEntry { variants: &["rupt"], meaning: "break" },
+    RootEntry { variants: &["ject"], meaning: "throw" },
+    RootEntry { variants: &["struct", "stru"], meaning: "build" },
+    RootEntry { variants: &["tract"], meaning: "pull, drag" },
+    RootEntry { variants: &["vert", "vers"], meaning: "turn" },
+    RootEntry { variants: &["vid", "vis"], meaning: "see" },
+    RootEntry { variants: &["aud"], meaning: "hear" },
+    RootEntry { variants: &["cred"], meaning: "believe" },
+    RootEntry { variants: &["fac", "fact", "fect", "fic"], meaning: "make, do" },
+    RootEntry { variants: &["mit", "miss"], meaning: "send" },
+    RootEntry { variants: &["pon", "pos", "pound"], meaning: "put, place" },
+    RootEntry { variants: &["ced", "cess", "ceed"], meaning: "go, yield" },
+    RootEntry { variants: &["graph", "gram"], meaning: "write, draw" },
+    RootEntry { variants: &["log", "logue"], meaning: "word, reason, study" },
+    RootEntry { variants: &["phon"], meaning: "sound" },
+    RootEntry { variants: &["bio"], meaning: "life" },
+    RootEntry { variants: &["geo"], meaning: "earth" },
+    RootEntry { variants: &["chron"], meaning: "time" },
+    RootEntry { variants: &["morph"], meaning: "form, shape" },
+    RootEntry { variants: &["path"], meaning: "feeling, suffering" },
+    RootEntry { variants: &["cap", "cept", "ceiv", "cip"], meaning: "take, seize" },
+    RootEntry { variants: &["mov", "mot", "mob"], meaning: "move" },
+    RootEntry { variants: &["pel", "puls"], meaning: "drive, push" },
+    RootEntry { variants: &["pend", "pens"], meaning: "hang, weigh" },
+    RootEntry { variants: &["sequ", "secut"], meaning: "follow" },
+    RootEntry { variants: &["ten", "tain", "tin"], meaning: "hold" },
+    RootEntry { variants: &["ven", "vent"], meaning: "come" },
+    RootEntry { variants: &["voc", "vok"], meaning: "call, voice" },
+    RootEntry { variants: &["flect", "flex"], meaning: "bend" },
+    RootEntry { variants: &["form"], meaning: "shape" },
+    RootEntry { variants: &["fract", "frag"], meaning: "break" },
+    RootEntry { variants: &["jud", "jur", "jus"], meaning: "law, judge" },
+    RootEntry { variants: &["leg", "lect"], meaning: "choose, read" },
+    RootEntry { variants: &["loc"], meaning: "place" },
+    RootEntry { variants: &["manu", "man"], meaning: "hand" },
+    RootEntry { variants: &["min"], meaning: "small" },
+    RootEntry { variants: &["mort"], meaning: "death" },
+    RootEntry { variants: &["nov"], meaning: "new" },
+    RootEntry { variants: &["ped", "pod"], meaning: "foot" },
+    RootEntry { variants: &["plic", "plex", "ply"], meaning: "fold" },
+    RootEntry { variants: &["scop"], meaning: "look at, examine" },
+    RootEntry { variants: &["sens", "sent"], meaning: "feel" },
+    RootEntry { variants: &["son"], meaning: "sound" },
+    RootEntry { variants: &["tact", "tang"], meaning: "touch" },
+    RootEntry { variants: &["terr"], meaning: "earth, land" },
+    RootEntry { variants: &["therm"], meaning: "heat" },
+    RootEntry { variants: &["vac"], meaning: "empty" },
+    RootEntry { variants: &["viv", "vit"], meaning: "life, live" },
+    RootEntry { variants: &["volv", "volu"], meaning: "roll, turn" },
+    RootEntry { variants: &["labor", "lab"], meaning: "work" },
+    RootEntry { variants: &["mem"], meaning: "remember" },
+    RootEntry { variants: &["cogn", "gnos"], meaning: "know" },
+    RootEntry { variants: &["corp"], meaning: "body" },
+    RootEntry { variants: &["cur", "curs", "cour"], meaning: "run" },
+    RootEntry { variants: &["dem"], meaning: "people" },
+    RootEntry { variants: &["dur"], meaning: "hard, lasting" },
+    RootEntry { variants: &["gen"], meaning: "birth, kind" },
+    RootEntry { variants: &["grad", "gress"], meaning: "step, go" },
+    RootEntry { variants: &["hydr"], meaning: "water" },
+    RootEntry { variants: &["photo"], meaning: "light" },
+    RootEntry { variants: &["meter", "metr"], meaning: "measure" },
+    RootEntry { variants: &["nym", "onym"], meaning: "name" },
+    RootEntry { variants: &["psych"], meaning: "mind, soul" },
+    RootEntry { variants: &["soph"], meaning: "wise" },
+    RootEntry { variants: &["anthrop"], meaning: "human" },
+    RootEntry { variants: &["astr", "aster"], meaning: "star" },
+    RootEntry { variants: &["bibl"], meaning: "book" },
+    RootEntry { variants: &["cycl"], meaning: "circle, wheel" },
+    RootEntry { variants: &["dyn"], meaning: "power" },
+    RootEntry { variants: &["lumin", "luc"], meaning: "light" },
+    RootEntry { variants: &["magn"], meaning: "great" },
+    RootEntry { variants: &["mand", "mend"], meaning: "order, entrust" },
+    RootEntry { variants: &["migr"], meaning: "move, wander" },
+    RootEntry { variants: &["mut"], meaning: "change" },
+    RootEntry { variants: &["nat", "nasc"], meaning: "born" },
+    RootEntry { variants: &["pac"], meaning: "peace" },
+    RootEntry { variants: &["pat", "pater"], meaning: "father" },
+    RootEntry { variants: &["mater", "matr"], meaning: "mother" },
+    RootEntry { variants: &["rect", "reg"], meaning: "straight, rule" },
+    RootEntry { variants: &["sci"], meaning: "know" },
+    RootEntry { variants: &["sign"], meaning: "mark, sign" },
+    RootEntry { variants: &["simil", "simul"], meaning: "like, same" },
+    RootEntry { variants: &["solv", "solu"], meaning: "loosen, release" },
+    RootEntry { variants: &["spir"], meaning: "breathe" },
+    RootEntry { variants: &["sta", "stat", "sist"], meaning: "stand" },
+    RootEntry { variants: &["tempor"], meaning: "time" },
+    RootEntry { variants: &["tend", "tens"], meaning: "stretch" },
+    RootEntry { variants: &["vinc", "vict"], meaning: "conquer" },
+    RootEntry { variants: &["vol"], meaning: "wish, will" },
+];
+
+/// Canonical root for a variant or canonical spelling, if it is in the table.
+pub fn find_root(root: &str) -> Option<&'static RootEntry> {
+    ROOTS.iter().find(|r| r.variants.contains(&root))
+}
@@ -0,0 +1,158 @@
+// Regular English inflection rules (plural, 3rd person, past, participle,
+// comparative/superlative). Each rule proposes lemma candidates; the caller
+// decides which one wins (first candidate = most likely).
+
+fn is_vowel(c: char) -> bool {
+    matches!(c, 'a' | 'e' | 'i' | 'o' | 'u')
+}
+
+fn vowel_groups(s: &str) -> usize {
+    let mut groups = 0;
+    let mut prev_vowel = false;
+    for c in s.chars() {
+        let v = is_vowel(c) || c == 'y';
+        if v && !prev_vowel {
+            groups += 1;
+        }
+        prev_vowel = v;
+    }
+    groups
+}
+
+/// consonant-vowel-consonant ending, excluding w/x/y (e.g. "hop", "bak").
+fn ends_cvc(s: &str) -> bool {
+    let chars: Vec<char> = s.chars().collect();
+    if chars.len() < 3 {
+        return false;
+    }
+    let (a, b, c) = (chars[chars.len() - 3], chars[chars.len() - 2], chars[chars.len() - 1]);
+    !is_vowel(a) && is_vowel(b) && !is_vowel(c) && !matches!(c, 'w' | 'x' | 'y')
+}
+
+fn ends_double_consonant(s: &str) -> bool {
+    let chars: Vec<char> = s.chars().collect();
+    if chars.len() < 3 {
+        return false;
+    }
+    let (a, b) = (chars[chars.len() - 2], chars[chars.len() - 1]);
+    a == b && !is_vowel(a) && !matches!(a, 'l' | 's' | 'z' | 'f')
+}
+
+/// Stem left after stripping "-ed"/"-ing"/"-er"/"-est": decide whether the
+/// lemma had a silent "e" that was dropped.
+fn restore_stem(stem: &str) -> Vec<String> {
+    let mut out = Vec::new();
+
+    // stopped -> stop, bigger -> big
+    if ends_double_consonant(stem) {
+        out.push(stem[..stem.len() - 1].to_string());
+        out.push(stem.to_string());
+        return out;
+    }
+
+    let needs_e = stem.ends_with('v')
+        || stem.ends_with('u')
+        || stem.ends_with('c')
+        || stem.ends_with("dg")
+        || stem.ends_with("rg")
+        || (stem.ends_with("ls") && stem.len() > 3)
+        || stem.ends_with("rs")
+        || stem.ends_with("ns")
+        || (stem.ends_with("at") && vowel_groups(stem) > 1)
+        || stem.ends_with("iz")
+        || stem.ends_with("yz")
+        || (ends_cvc(stem) && vowel_groups(stem) == 1);
+
+    if needs_e {
+        out.push(format!("{}e", stem));
+        out.push(stem.to_string());
+    } else {
+        out.push(stem.to_string());
+        out.push(format!("{}e", stem));
+    }
+    out
+}
+
+/// Candidate lemmas produced by regular suffix rules, most likely first.
+/// Does not include the word itself.
+pub fn candidates(word: &str) -> Vec<String> {
+    let w = word;
+    let mut out: Vec<String> = Vec::new();
+    let len = w.len();
+
+    // --- -s / -es / -ies (plural, 3rd person singular) ---
+    if len > 4 && w.ends_with("ies") {
+        out.push(format!("{}y", &w[..len - 3]));
+    } else if len > 4 && w.ends_with("ves") {
+        out.push(format!("{}f", &w[..len - 3]));
+        out.push(format!("{}fe", &w[..len - 3]));
+        out.push(w[..len - 1].to_string());
+    } else if len > 3 && w.ends_with("es") {
+        let stem = &w[..len - 2];
+        if stem.ends_with("ss") || stem.ends_with('x') || stem.ends_with('z')
+            || stem.ends_with("ch") || stem.ends_with("sh") || stem.ends_with('o')
+        {
+            out.push(stem.to_string());
+            out.push(w[..len - 1].to_string());
+        } else {
+            // "makes" -> "make", "uses" -> "use"
+            out.push(w[..len - 1].to_string());
+            out.push(stem.to_string());
+        }
+    } else if len > 2 && w.ends_with('s') && !w.ends_with("ss") && !w.ends_with("us") && !w.ends_with("is") {
+        out.push(w[..len - 1].to_string());
+    }
+
+    // --- -ied / -ed ---
+    if len > 4 && w.ends_with("ied") {
+        out.push(format!("{}y", &w[..len - 3]));
+    } else if len > 3 && w.ends_with("eed") {
+        // agreed -> agree
+        out.push(w[..len - 1].to_string());
+    } else if len > 3 && w.ends_with("ed") {
+        out.extend(restore_stem(&w[..len - 2]));
+    }
+
+    // --- -ing ---
+    if len > 4 && w.ends_with("ying") && vowel_groups(&w[..len - 4]) == 0 {
+        // dying -> die, lying -> lie
+        out.push(format!("{}ie", &w[..len - 4]));
+    }
+    if len > 4 && w.ends_with("ing") {
+        let stem = &w[..len - 3];
+        if vowel_groups(stem) > 0 {
+            out.extend(restore_stem(stem));
+        }
+    }
+
+    // --- -ier / -iest / -er / -est (comparatives) ---
+    if len > 4 && w.ends_with("iest") {
+        out.push(format!("{}y", &w[..len - 4]));
+    } else if len > 4 && w.ends_with("est") {
+        out.extend(restore_stem(&w[..len - 3]));
+    }
+    if len > 3 && w.ends_with("ier") {
+        out.push(format!("{}y", &w[..len - 3]));
+    } else if len > 4 && w.ends_with("er") {
+        out.extend(restore_stem(&w[..len - 2]));
+    }
+
+    // --- -ly (adverbs -> adjective) ---
+    if len > 4 && w.ends_with("ily") {
+        out.push(format!("{}y", &w[..len - 3]));
+    } else if len > 4 && w.ends_with("ly") {
+        out.push(w[..len - 2].to_string());
+    }
+
+    let mut seen = std::collections::HashSet::new();
+    out.retain(|c| c.len() >= 2 && c != word && seen.insert(c.clone()));
+    out
+}
+
+/// Whether the word carries a suffix the rules above consider inflectional.
+pub fn looks_inflected(word: &str) -> bool {
+    let len = word.len();
+    (len > 3 && (word.ends_with("ed") || word.ends_with("ing")))
+        || (len > 2 && word.ends_with('s') && !word.ends_with("ss") && !word.ends_with("us") && !word.ends_with("is"))
+        || (len > 4 && (word.ends_with("ier") || word.ends_with("iest")))
+}
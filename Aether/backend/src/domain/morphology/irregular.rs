@@ -0,0 +1,191 @@
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+// Inflected form -> lemma. Covers the irregular verbs, nouns and comparatives
+// that the suffix rules in `inflection` cannot recover.
+const IRREGULAR_FORMS: &[(&str, &str)] = &[
+    // --- Verbs ---
+    ("am", "be"), ("is", "be"), ("are", "be"), ("was", "be"), ("were", "be"), ("been", "be"), ("being", "be"),
+    ("has", "have"), ("had", "have"), ("having", "have"),
+    ("does", "do"), ("did", "do"), ("done", "do"),
+    ("goes", "go"), ("went", "go"), ("gone", "go"),
+    ("arose", "arise"), ("arisen", "arise"),
+    ("awoke", "awake"), ("awoken", "awake"),
+    ("bore", "bear"), ("borne", "bear"),
+    ("beat", "beat"), ("beaten", "beat"),
+    ("became", "become"),
+    ("began", "begin"), ("begun", "begin"),
+    ("bent", "bend"),
+    ("bet", "bet"),
+    ("bound", "bind"),
+    ("bit", "bite"), ("bitten", "bite"),
+    ("bled", "bleed"),
+    ("blew", "blow"), ("blown", "blow"),
+    ("broke", "break"), ("broken", "break"),
+    ("bred", "breed"),
+    ("brought", "bring"),
+    ("built", "build"),
+    ("burnt", "burn"),
+    ("bought", "buy"),
+    ("caught", "catch"),
+    ("chose", "choose"), ("chosen", "choose"),
+    ("clung", "cling"),
+    ("came", "come"),
+    ("crept", "creep"),
+    ("dealt", "deal"),
+    ("dug", "dig"),
+    ("drew", "draw"), ("drawn", "draw"),
+    ("dreamt", "dream"),
+    ("drank", "drink"), ("drunk", "drink"),
+    ("drove", "drive"), ("driven", "drive"),
+    ("ate", "eat"), ("eaten", "eat"),
+    ("fell", "fall"), ("fallen", "fall"),
+    ("fed", "feed"),
+    ("felt", "feel"),
+    ("fought", "fight"),
+    ("found", "find"),
+    ("fled", "flee"),
+    ("flung", "fling"),
+    ("flew", "fly"), ("flown", "fly"),
+    ("forbade", "forbid"), ("forbidden", "forbid"),
+    ("forgot", "forget"), ("forgotten", "forget"),
+    ("forgave", "forgive"), ("forgiven", "forgive"),
+    ("froze", "freeze"), ("frozen", "freeze"),
+    ("got", "get"), ("gotten", "get"),
+    ("gave", "give"), ("given", "give"),
+    ("ground", "grind"),
+    ("grew", "grow"), ("grown", "grow"),
+    ("hung", "hang"),
+    ("heard", "hear"),
+    ("hid", "hide"), ("hidden", "hide"),
+    ("held", "hold"),
+    ("kept", "keep"),
+    ("knelt", "kneel"),
+    ("knew", "know"), ("known", "know"),
+    ("laid", "lay"),
+    ("led", "lead"),
+    ("leapt", "leap"),
+    ("learnt", "learn"),
+    ("left", "leave"),
+    ("lent", "lend"),
+    ("lay", "lie"), ("lain", "lie"),
+    ("lit", "light"),
+    ("lost", "lose"),
+    ("made", "make"),
+    ("meant", "mean"),
+    ("met", "meet"),
+    ("mistook", "mistake"), ("mistaken", "mistake"),
+    ("overcame", "overcome"),
+    ("paid", "pay"),
+    ("proven", "prove"),
+    ("rode", "ride"), ("ridden", "ride"),
+    ("rang", "ring"), ("rung", "ring"),
+    ("rose", "rise"), ("risen", "rise"),
+    ("ran", "run"),
+    ("said", "say"),
+    ("saw", "see"), ("seen", "see"),
+    ("sought", "seek"),
+    ("sold", "sell"),
+    ("sent", "send"),
+    ("shook", "shake"), ("shaken", "shake"),
+    ("shone", "shine"),
+    ("shot", "shoot"),
+    ("shown", "show"),
+    ("shrank", "shrink"), ("shrunk", "shrink"),
+    ("sang", "sing"), ("sung", "sing"),
+    ("sank", "sink"), ("sunk", "sink"),
+    ("sat", "sit"),
+    ("slept", "sleep"),
+    ("slid", "slide"),
+    ("spoke", "speak"), ("spoken", "speak"),
+    ("sped", "speed"),
+    ("spent", "spend"),
+    ("spun", "spin"),
+    ("sprang", "spring"), ("sprung", "spring"),
+    ("stood", "stand"),
+    ("stole", "steal"), ("stolen", "steal"),
+    ("stuck", "stick"),
+    ("stung", "sting"),
+    ("strove", "strive"), ("striven", "strive"),
+    ("struck", "strike"), ("stricken", "strike"),
+    ("swore", "swear"), ("sworn", "swear"),
+    ("swept", "sweep"),
+    ("swam", "swim"), ("swum", "swim"),
+    ("swung", "swing"),
+    ("took", "take"), ("taken", "take"),
+    ("taught", "teach"),
+    ("tore", "tear"), ("torn", "tear"),
+    ("told", "tell"),
+    ("thought", "think"),
+    ("threw", "throw"), ("thrown", "throw"),
+    ("understood", "understand"),
+    ("woke", "wake"), ("woken", "wake"),
+    ("wore", "wear"), ("worn", "wear"),
+    ("wove", "weave"), ("woven", "weave"),
+    ("wept", "weep"),
+    ("won", "win"),
+    ("wound", "wind"),
+    ("withdrew", "withdraw"), ("withdrawn", "withdraw"),
+    ("wrote", "write"), ("written", "write"),
+    // --- Nouns ---
+    ("men", "man"), ("women", "woman"), ("children", "child"),
+    ("feet", "foot"), ("teeth", "tooth"), ("geese", "goose"),
+    ("mice", "mouse"), ("lice", "louse"), ("oxen", "ox"), ("people", "person"),
+    ("dice", "die"), ("pennies", "penny"),
+    ("knives", "knife"), ("wives", "wife"), ("lives", "life"),
+    ("leaves", "leaf"), ("halves", "half"), ("wolves", "wolf"),
+    ("shelves", "shelf"), ("thieves", "thief"), ("loaves", "loaf"),
+    ("selves", "self"), ("calves", "calf"), ("elves", "elf"),
+    ("analyses", "analysis"), ("bases", "basis"), ("crises", "crisis"),
+    ("diagnoses", "diagnosis"), ("hypotheses", "hypothesis"), ("theses", "thesis"),
+    ("parentheses", "parenthesis"), ("axes", "axis"),
+    ("criteria", "criterion"), ("phenomena", "phenomenon"),
+    ("data", "datum"), ("media", "medium"), ("curricula", "curriculum"),
+    ("bacteria", "bacterium"), ("memoranda", "memorandum"),
+    ("cacti", "cactus"), ("fungi", "fungus"), ("nuclei", "nucleus"),
+    ("radii", "radius"), ("stimuli", "stimulus"), ("syllabi", "syllabus"),
+    ("alumni", "alumnus"),
+    ("appendices", "appendix"), ("indices", "index"), ("matrices", "matrix"),
+    ("vertices", "vertex"), ("formulae", "formula"), ("larvae", "larva"),
+    // --- Adjectives & adverbs ---
+    ("better", "good"), ("best", "good"),
+    ("worse", "bad"), ("worst", "bad"),
+    ("more", "much"), ("most", "much"),
+    ("less", "little"), ("least", "little"),
+    ("further", "far"), ("furthest", "far"), ("farther", "far"), ("farthest", "far"),
+    ("elder", "old"), ("eldest", "old"),
+];
+
+// Words ending in an inflection-looking suffix that are already lemmas.
+const INVARIANT_WORDS: &[&str] = &[
+    "always", "perhaps", "yes", "news", "series", "species", "means", "physics",
+    "mathematics", "economics", "politics", "ethics", "linguistics", "statistics",
+    "thus", "this", "his", "hers", "its", "ours", "yours", "theirs",
+    "gas", "bus", "plus", "minus", "bonus", "virus", "campus", "status", "focus",
+    "basis", "analysis", "crisis", "thesis", "axis", "lens", "chaos", "canvas",
+    "bed", "red", "need", "seed", "feed", "speed", "shed", "sled", "wed", "bleed",
+    "breed", "creed", "greed", "weed", "deed", "hundred", "sacred", "naked", "wicked",
+    "wretched", "rugged", "ragged", "crooked", "beloved", "learned", "aged",
+    "thing", "king", "ring", "sing", "spring", "string", "wing", "swing", "sting",
+    "bring", "cling", "fling", "sling", "wring", "morning", "evening", "nothing",
+    "something", "anything", "everything", "ceiling", "during", "pudding",
+    "her", "never", "ever", "over", "under", "after", "water", "paper", "number",
+    "other", "either", "neither", "whether", "rather", "together", "however",
+    "forest", "interest", "honest", "modest", "harvest", "chest", "guest", "quest",
+    "test", "rest", "nest", "west", "request", "contest", "protest",
+];
+
+fn irregular_map() -> &'static HashMap<&'static str, &'static str> {
+    static MAP: OnceLock<HashMap<&'static str, &'static str>> = OnceLock::new();
+    MAP.get_or_init(|| IRREGULAR_FORMS.iter().copied().collect())
+}
+
+/// Returns the lemma for an irregular inflected form, if known.
+pub fn lookup(word: &str) -> Option<&'static str> {
+    irregular_map().get(word).copied()
+}
+
+/// True for words whose apparent inflection suffix is part of the lemma.
+pub fn is_invariant(word: &str) -> bool {
+    INVARIANT_WORDS.contains(&word)
+}
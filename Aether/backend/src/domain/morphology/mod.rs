@@ -0,0 +1,178 @@
+pub mod affixes;
+pub mod inflection;
+pub mod irregular;
+
+mod tests;
+
+use serde::Serialize;
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct Affix {
+    pub form: String,
+    pub meaning: String,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct RootSuggestion {
+    pub root: String,    // Canonical root, e.g. "spec"
+    pub variant: String, // Spelling found in the word, e.g. "spect"
+    pub meaning: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WordAnalysis {
+    pub word: String,
+    pub lemma: String,
+    pub prefixes: Vec<Affix>,
+    pub stem: String,
+    pub suffixes: Vec<Affix>,
+    pub root: Option<RootSuggestion>,
+}
+
+/// Local English morphology: inflection rules, irregular forms and
+/// affix/root decomposition. No dictionary lookups, purely rule-based.
+pub struct Morphology;
+
+impl Morphology {
+    pub fn normalize(word: &str) -> String {
+        word.trim().to_lowercase()
+    }
+
+    fn is_analyzable(word: &str) -> bool {
+        !word.is_empty() && word.chars().all(|c| c.is_ascii_alphabetic() || c == '-' || c == '\'')
+    }
+
+    /// Best-guess dictionary form: "running" -> "run", "ran" -> "run", "studies" -> "study".
+    pub fn lemmatize(word: &str) -> String {
+        let w = Self::normalize(word);
+        if !Self::is_analyzable(&w) || irregular::is_invariant(&w) {
+            return w;
+        }
+        if let Some(lemma) = irregular::lookup(&w) {
+            return lemma.to_string();
+        }
+        if inflection::looks_inflected(&w) {
+            if let Some(first) = inflection::candidates(&w).into_iter().next() {
+                return first;
+            }
+        }
+        w
+    }
+
+    /// Every plausible lemma for `word`, starting with the word itself.
+    /// Used for lookups where an existing entry decides which guess is right.
+    pub fn lemma_candidates(word: &str) -> Vec<String> {
+        let w = Self::normalize(word);
+        let mut out = vec![w.clone()];
+        if !Self::is_analyzable(&w) {
+            return out;
+        }
+        if let Some(lemma) = irregular::lookup(&w) {
+            out.push(lemma.to_string());
+        }
+        if !irregular::is_invariant(&w) {
+            for c in inflection::candidates(&w) {
+                if !out.contains(&c) {
+                    out.push(c);
+                }
+            }
+        }
+        out
+    }
+
+    /// Splits the lemma of `word` into prefixes, stem and suffixes and
+    /// matches the stem against the root table.
+    pub fn decompose(word: &str) -> WordAnalysis {
+        let lemma = Self::lemmatize(word);
+        let mut stem: &str = &lemma;
+        let mut prefixes = Vec::new();
+        let mut suffixes = Vec::new();
+
+        if Self::is_analyzable(&lemma) {
+            // At most two prefixes, longest match first, keeping a stem of >= 3 chars
+            for _ in 0..2 {
+                let found = affixes::PREFIXES.iter()
+                    .filter(|(p, _)| stem.starts_with(p) && stem.len() >= p.len() + 3)
+                    .max_by_key(|(p, _)| p.len());
+                match found {
+                    Some((p, m)) => {
+                        prefixes.push(Affix { form: p.to_string(), meaning: m.to_string() });
+                        stem = &stem[p.len()..];
+                    }
+                    None => break,
+                }
+            }
+
+            for _ in 0..2 {
+                let found = affixes::SUFFIXES.iter()
+                    .filter(|(s, _)| stem.ends_with(s) && stem.len() >= s.len() + 3)
+                    .max_by_key(|(s, _)| s.len());
+                match found {
+                    Some((s, m)) => {
+                        suffixes.insert(0, Affix { form: s.to_string(), meaning: m.to_string() });
+                        stem = &stem[..stem.len() - s.len()];
+                    }
+                    None => break,
+                }
+            }
+        }
+
+        let stem = stem.to_string();
+        let root = Self::match_root(&lemma, &stem);
+
+        WordAnalysis {
+            word: Self::normalize(word),
+            lemma,
+            prefixes,
+            stem,
+            suffixes,
+            root,
+        }
+    }
+
+    /// Root to store on `vocab_roots` for a new word, if one can be derived.
+    pub fn suggest_root(word: &str) -> Option<RootSuggestion> {
+        Self::decompose(word).root
+    }
+
+    /// Meaning of a root (canonical or variant spelling) from the root table.
+    pub fn root_meaning(root: &str) -> Option<&'static str> {
+        affixes::find_root(&Self::normalize(root)).map(|r| r.meaning)
+    }
+
+    /// Canonical spelling for a root variant ("spect" -> "spec").
+    pub fn canonical_root(root: &str) -> String {
+        let r = Self::normalize(root);
+        affixes::find_root(&r)
+            .map(|e| e.variants[0].to_string())
+            .unwrap_or(r)
+    }
+
+    fn match_root(lemma: &str, stem: &str) -> Option<RootSuggestion> {
+        let mut best: Option<(usize, &affixes::RootEntry, &str)> = None;
+
+        for entry in affixes::ROOTS {
+            for variant in entry.variants {
+                // Three-letter variants ("cap", "ten") are too ambiguous to
+                // accept outside the stripped stem.
+                let in_stem = stem.contains(variant);
+                if variant.len() < 3 || (variant.len() == 3 && !in_stem) || !lemma.contains(variant) {
+                    continue;
+                }
+                let mut score = variant.len() * 2;
+                if in_stem { score += 2; }
+                if stem.starts_with(variant) { score += 2; }
+
+                if best.is_none_or(|(s, _, _)| score > s) {
+                    best = Some((score, entry, variant));
+                }
+            }
+        }
+
+        best.map(|(_, entry, variant)| RootSuggestion {
+            root: entry.variants[0].to_string(),
+            variant: variant.to_string(),
+            meaning: entry.meaning.to_string(),
+        })
+    }
+}
@@ -0,0 +1,74 @@
+#[cfg(test)]
+mod tests {
+    use crate::domain::morphology::Morphology;
+
+    #[test]
+    fn test_regular_inflections() {
+        assert_eq!(Morphology::lemmatize("running"), "run");
+        assert_eq!(Morphology::lemmatize("walked"), "walk");
+        assert_eq!(Morphology::lemmatize("baked"), "bake");
+        assert_eq!(Morphology::lemmatize("studies"), "study");
+        assert_eq!(Morphology::lemmatize("studied"), "study");
+        assert_eq!(Morphology::lemmatize("boxes"), "box");
+        assert_eq!(Morphology::lemmatize("makes"), "make");
+        assert_eq!(Morphology::lemmatize("stopped"), "stop");
+        assert_eq!(Morphology::lemmatize("dying"), "die");
+        assert_eq!(Morphology::lemmatize("Judged"), "judge");
+    }
+
+    #[test]
+    fn test_irregular_forms() {
+        assert_eq!(Morphology::lemmatize("ran"), "run");
+        assert_eq!(Morphology::lemmatize("went"), "go");
+        assert_eq!(Morphology::lemmatize("children"), "child");
+        assert_eq!(Morphology::lemmatize("better"), "good");
+        assert_eq!(Morphology::lemmatize("criteria"), "criterion");
+    }
+
+    #[test]
+    fn test_invariant_words_untouched() {
+        assert_eq!(Morphology::lemmatize("news"), "news");
+        assert_eq!(Morphology::lemmatize("thing"), "thing");
+        assert_eq!(Morphology::lemmatize("status"), "status");
+        assert_eq!(Morphology::lemmatize("apple"), "apple");
+        // Non-latin input is only normalized
+        assert_eq!(Morphology::lemmatize("苹果"), "苹果");
+    }
+
+    #[test]
+    fn test_candidates_cover_alternatives() {
+        let c = Morphology::lemma_candidates("created");
+        assert_eq!(c[0], "created");
+        assert!(c.contains(&"create".to_string()));
+
+        let c = Morphology::lemma_candidates("bigger");
+        assert!(c.contains(&"big".to_string()));
+    }
+
+    #[test]
+    fn test_decomposition() {
+        let a = Morphology::decompose("inspection");
+        assert_eq!(a.prefixes.first().map(|p| p.form.as_str()), Some("in"));
+        assert_eq!(a.suffixes.last().map(|s| s.form.as_str()), Some("tion"));
+        assert_eq!(a.stem, "spec");
+        assert_eq!(a.root.map(|r| r.root), Some("spec".to_string()));
+
+        let a = Morphology::decompose("retrospective");
+        let root = a.root.expect("root for retrospective");
+        assert_eq!(root.root, "spec");
+        assert_eq!(root.variant, "spect");
+
+        let a = Morphology::decompose("transported");
+        assert_eq!(a.lemma, "transport");
+        assert_eq!(a.root.map(|r| r.root), Some("port".to_string()));
+    }
+
+    #[test]
+    fn test_root_lookup() {
+        assert_eq!(Morphology::root_meaning("spec"), Some("look, see"));
+        assert_eq!(Morphology::root_meaning("SPECT"), Some("look, see"));
+        assert_eq!(Morphology::canonical_root("spect"), "spec");
+        assert_eq!(Morphology::canonical_root("xyz"), "xyz");
+        assert!(Morphology::suggest_root("apple").is_none());
+    }
+}
@@ -0,0 +1,254 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::OnceLock;
+use regex::{Captures, Regex};
+use serde_json::Value;
+use uuid::Uuid;
+use crate::domain::blocks::models::Block;
+use crate::domain::graph::computed_tree::ComputedTreeService;
+
+// A math knowledge base as a LaTeX project: `main.tex` pulls in the
+// `amsthm` environments from `preamble.tex` and the blocks from `body.tex`,
+// in dependency order. Images are expected next to them under `images/`.
+
+const PREAMBLE: &str = r"\usepackage[utf8]{inputenc}
+\usepackage[T1]{fontenc}
+\usepackage{amsmath,amssymb,amsthm}
+\usepackage{graphicx}
+\usepackage{hyperref}
+
+\theoremstyle{plain}
+\newtheorem{axiom}{Axiom}
+\newtheorem{theorem}{Theorem}
+
+\theoremstyle{definition}
+\newtheorem{definition}{Definition}
+";
+
+pub struct LatexProject {
+    /// Zip entry name -> contents.
+    pub files: Vec<(String, String)>,
+    /// Assets the body includes as `images/<uuid>`, in order of first use.
+    pub assets: Vec<Uuid>,
+}
+
+/// The `\label` of a block; `((uuid))`/`[[uuid]]` references become `\ref`s to it.
+pub fn block_label(id: Uuid) -> String {
+    format!("blk:{}", id)
+}
+
+pub fn render_project(title: &str, blocks: Vec<Block>) -> LatexProject {
+    let sorted = ComputedTreeService::compute_topological_sort(blocks);
+    let known: HashSet<Uuid> = sorted.iter().map(|b| b.id).collect();
+
+    // A proof names its theorem, a theorem its proof: either side pairs them
+    let mut theorem_of: HashMap<Uuid, Uuid> = HashMap::new();
+    for block in &sorted {
+        match math_type(block) {
+            "theorem" => if let Some(proof) = uuid_field(&block.payload, "proof_id") {
+                theorem_of.entry(proof).or_insert(block.id);
+            },
+            "proof" => if let Some(theorem) = uuid_field(&block.payload, "theorem_id") {
+                theorem_of.insert(block.id, theorem);
+            },
+            _ => {}
+        }
+    }
+
+    let mut ctx = RenderContext { known, assets: Vec::new() };
+    let parts: Vec<String> = sorted.iter()
+        .map(|b| render_block(b, theorem_of.get(&b.id).copied(), &mut ctx))
+        .filter(|s| !s.is_empty())
+        .collect();
+    let mut body = parts.join("\n\n");
+    body.push('\n');
+
+    let main = format!(
+        "\\documentclass[11pt]{{article}}\n\\input{{preamble}}\n\n\\title{{{}}}\n\\date{{}}\n\n\\begin{{document}}\n\\maketitle\n\n\\input{{body}}\n\n\\end{{document}}\n",
+        escape_text(title)
+    );
+
+    LatexProject {
+        files: vec![
+            ("main.tex".to_string(), main),
+            ("preamble.tex".to_string(), PREAMBLE.to_string()),
+            ("body.tex".to_string(), body),
+        ],
+        assets: ctx.assets,
+    }
+}
+
+struct RenderContext {
+    known: HashSet<Uuid>,
+    assets: Vec<Uuid>,
+}
+
+impl RenderContext {
+    fn use_asset(&mut self, id: Uuid) -> String {
+        if !self.assets.contains(&id) {
+            self.assets.push(id);
+        }
+        format!("images/{}", id)
+    }
+
+    fn reference(&self, id: Uuid) -> String {
+        // Outside the export: LaTeX would print `??` for an unknown label anyway
+        if self.known.contains(&id) {
+            format!("\\ref{{{}}}", block_label(id))
+        } else {
+            "??".to_string()
+        }
+    }
+}
+
+/// `math_block` blocks carry their kind in `math_type`.
+fn math_type(block: &Block) -> &str {
+    match block.type_name.as_str() {
+        "math_block" => block.payload.get("math_type").and_then(|v| v.as_str()).unwrap_or(""),
+        other => other,
+    }
+}
+
+fn uuid_field(payload: &Value, field: &str) -> Option<Uuid> {
+    payload.get(field).and_then(|v| v.as_str()).and_then(|s| Uuid::parse_str(s).ok())
+}
+
+fn render_block(block: &Block, theorem: Option<Uuid>, ctx: &mut RenderContext) -> String {
+    let payload = &block.payload;
+    let field = |name: &str| payload.get(name).and_then(|v| v.as_str()).unwrap_or("").to_string();
+    let label = format!("\\label{{{}}}", block_label(block.id));
+    // `math_block` keeps its LaTeX in `latex`, the typed blocks in `content`/`steps`
+    let latex = |name: &str| if block.type_name == "math_block" { field("latex") } else { field(name) };
+
+    match math_type(block) {
+        "axiom" | "theorem" | "definition" => {
+            let env = math_type(block);
+            let note = if env == "definition" { field("term") } else { field("label") };
+            let note = if note.is_empty() { String::new() } else { format!("[{}]", escape_text(&note)) };
+            format!(
+                "\\begin{{{env}}}{note}{label}\n{}\n\\end{{{env}}}",
+                render_latex(latex("content").trim(), ctx),
+            )
+        }
+        "proof" => {
+            let heading = match theorem {
+                Some(id) => format!("[Proof of Theorem~{}]", ctx.reference(id)),
+                None => String::new(),
+            };
+            // A `\ref` to the proof resolves to the theorem it follows
+            format!(
+                "\\begin{{proof}}{heading}{label}\n{}\n\\end{{proof}}",
+                render_latex(latex("steps").trim(), ctx),
+            )
+        }
+        "heading" => {
+            let level = payload.get("level").and_then(|v| v.as_u64()).unwrap_or(1);
+            let command = match level {
+                0 | 1 => "section",
+                2 => "subsection",
+                3 => "subsubsection",
+                _ => "paragraph",
+            };
+            format!("\\{}{{{}}}{}", command, render_text(&field("text"), ctx), label)
+        }
+        "paragraph" => render_text(&field("markdown"), ctx),
+        "math" => format!("\\begin{{equation*}}\n{}\n\\end{{equation*}}", field("latex").trim()),
+        "code" => format!("\\begin{{verbatim}}\n{}\n\\end{{verbatim}}", field("code")),
+        "image" => {
+            let Some(id) = asset_id(&field("src")) else {
+                return format!("[{}]", escape_text(&field("alt")));
+            };
+            let path = ctx.use_asset(id);
+            let caption = field("alt");
+            let caption = if caption.is_empty() { String::new() } else { format!("\\caption{{{}}}", escape_text(&caption)) };
+            format!(
+                "\\begin{{figure}}[h]\n\\centering\n\\includegraphics[width=0.8\\linewidth]{{{}}}\n{}{}\n\\end{{figure}}",
+                path, caption, label
+            )
+        }
+        "thematic_break" => "\\bigskip".to_string(),
+        "front_matter" | "html" => String::new(),
+        _ => render_text(&field("text_mirror"), ctx),
+    }
+}
+
+fn reference_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(
+        r"!\[([^\]]*)\]\(([^)\s]*)\)|\[\[asset:([0-9a-fA-F-]{36})\]\]|\(\(([0-9a-fA-F-]{36})\)\)|\[\[([0-9a-fA-F-]{36})\]\]"
+    ).unwrap())
+}
+
+/// The asset behind an image source: `[[asset:uuid]]` or an `/api/assets/uuid` URL.
+fn asset_id(src: &str) -> Option<Uuid> {
+    let id = src.strip_prefix("[[asset:").and_then(|s| s.strip_suffix("]]"))
+        .or_else(|| src.split("/api/assets/").nth(1).map(|s| s.split(['?', '#', '/']).next().unwrap_or("")))?;
+    Uuid::parse_str(id).ok()
+}
+
+/// Substitutes block references and embedded images in authored LaTeX.
+fn render_latex(text: &str, ctx: &mut RenderContext) -> String {
+    reference_regex().replace_all(text, |caps: &Captures| replace_reference(caps, ctx)).into_owned()
+}
+
+fn replace_reference(caps: &Captures, ctx: &mut RenderContext) -> String {
+    let image = |id: Uuid, ctx: &mut RenderContext| format!("\\includegraphics[width=0.8\\linewidth]{{{}}}", ctx.use_asset(id));
+    if let Some(src) = caps.get(2) {
+        return match asset_id(src.as_str()) {
+            Some(id) => image(id, ctx),
+            None => escape_text(&caps[1]),
+        };
+    }
+    if let Some(id) = caps.get(3).and_then(|m| Uuid::parse_str(m.as_str()).ok()) {
+        return image(id, ctx);
+    }
+    match caps.get(4).or(caps.get(5)).and_then(|m| Uuid::parse_str(m.as_str()).ok()) {
+        Some(id) => ctx.reference(id),
+        None => caps[0].to_string(),
+    }
+}
+
+/// Markdown prose: `$...$` and `$$...$$` pass through, the rest is escaped.
+fn render_text(text: &str, ctx: &mut RenderContext) -> String {
+    let mut out = String::new();
+    let mut rest = text;
+    while let Some(start) = rest.find('$') {
+        let delimiter = if rest[start..].starts_with("$$") { "$$" } else { "$" };
+        let inner = start + delimiter.len();
+        let Some(len) = rest[inner..].find(delimiter) else { break };
+        out.push_str(&render_prose(&rest[..start], ctx));
+        out.push_str(&rest[start..inner + len + delimiter.len()]);
+        rest = &rest[inner + len + delimiter.len()..];
+    }
+    out.push_str(&render_prose(rest, ctx));
+    out
+}
+
+fn render_prose(text: &str, ctx: &mut RenderContext) -> String {
+    let mut out = String::new();
+    let mut last = 0;
+    for caps in reference_regex().captures_iter(text) {
+        let m = caps.get(0).unwrap();
+        out.push_str(&escape_text(&text[last..m.start()]));
+        out.push_str(&replace_reference(&caps, ctx));
+        last = m.end();
+    }
+    out.push_str(&escape_text(&text[last..]));
+    out
+}
+
+pub fn escape_text(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' => out.push_str("\\textbackslash{}"),
+            '&' | '%' | '$' | '#' | '_' | '{' | '}' => {
+                out.push('\\');
+                out.push(c);
+            }
+            '~' => out.push_str("\\textasciitilde{}"),
+            '^' => out.push_str("\\textasciicircum{}"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
@@ -1,2 +1,5 @@
 pub mod ports;
 pub mod models;
+pub mod latex;
+
+mod tests;
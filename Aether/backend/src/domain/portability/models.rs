@@ -20,6 +20,16 @@ pub struct ImportSummary {
     pub total_items: usize,
     pub sections: Vec<ImportSection>,
     pub conflicts: Vec<String>,
+    /// Links of the imported content that point at nothing in the archive.
+    #[serde(default)]
+    pub unresolved_links: Vec<UnresolvedLink>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct UnresolvedLink {
+    /// Path of the file containing the link, inside the archive.
+    pub source: String,
+    pub target: String,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
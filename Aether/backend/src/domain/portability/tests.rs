@@ -0,0 +1,41 @@
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use uuid::Uuid;
+    use crate::domain::blocks::testing::block;
+    use crate::domain::portability::latex::{render_project, escape_text};
+
+    #[test]
+    fn test_latex_project() {
+        let doc = Uuid::new_v4();
+        let image = Uuid::new_v4();
+        let axiom = block(doc, 0, "axiom", json!({ "label": "Extensionality", "content": "$\\forall x (x \\in A \\iff x \\in B)$" }));
+        let mut theorem = block(doc, 1, "theorem", json!({ "label": "Uniqueness", "content": format!("Follows from (({})).", axiom.id) }));
+        let proof = block(doc, 2, "proof", json!({ "theorem_id": theorem.id.to_string(), "steps": format!("Apply [[{}]]. ![diagram](/api/assets/{})", axiom.id, image) }));
+        theorem.payload["proof_id"] = json!(proof.id.to_string());
+        let note = block(doc, 3, "paragraph", json!({ "markdown": "Costs 5% of $x_1$ & more" }));
+
+        let project = render_project("Sets & Classes", vec![proof.clone(), note, theorem.clone(), axiom.clone()]);
+        let file = |name: &str| project.files.iter().find(|(n, _)| n == name).unwrap().1.clone();
+
+        assert!(file("main.tex").contains("\\title{Sets \\& Classes}"));
+        assert!(file("preamble.tex").contains("\\newtheorem{axiom}{Axiom}"));
+
+        let body = file("body.tex");
+        let at = |needle: String| body.find(&needle).unwrap_or_else(|| panic!("missing {}", needle));
+        let axiom_at = at(format!("\\begin{{axiom}}[Extensionality]\\label{{blk:{}}}", axiom.id));
+        let theorem_at = at(format!("\\begin{{theorem}}[Uniqueness]\\label{{blk:{}}}", theorem.id));
+        let proof_at = at(format!("\\begin{{proof}}[Proof of Theorem~\\ref{{blk:{}}}]\\label{{blk:{}}}", theorem.id, proof.id));
+        assert!(axiom_at < theorem_at && theorem_at < proof_at);
+
+        assert!(body.contains(&format!("Follows from \\ref{{blk:{}}}.", axiom.id)));
+        assert!(body.contains(&format!("\\includegraphics[width=0.8\\linewidth]{{images/{}}}", image)));
+        assert!(body.contains("Costs 5\\% of $x_1$ \\& more"));
+        assert_eq!(project.assets, vec![image]);
+    }
+
+    #[test]
+    fn test_latex_escape() {
+        assert_eq!(escape_text("a_b {c} ~ ^ \\"), "a\\_b \\{c\\} \\textasciitilde{} \\textasciicircum{} \\textbackslash{}");
+    }
+}
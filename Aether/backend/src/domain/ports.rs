@@ -3,8 +3,8 @@ use serde::Serialize;
 use uuid::Uuid;
 use thiserror::Error; // Added back
 use crate::domain::models::{
-    Article, Vocabulary, Memo, User, UserId, AuthClaims, Comment, CommentId,
-    ContentVersionSnapshot, Node, KnowledgeBase, KnowledgeBaseId, ContentItem, ContentDiff,
+    Article, Vocabulary, VocabularyRoot, Memo, User, UserId, AuthClaims, Comment, CommentId,
+    ContentVersionSnapshot, NodeVersion, AssetRecord, AssetUsage, Node, KnowledgeBase, KnowledgeBaseId, ContentItem, ContentDiff,
     // VrkbProject removed
 };
 // use crate::infrastructure::persistence::entities::audit_log; // Removed unused import if I had one. 
@@ -37,6 +37,8 @@ pub enum RepositoryError {
     Unknown(String),
     #[error("Duplicate title: {0}")]
     DuplicateTitle(String),
+    #[error("Revision conflict: expected {expected}, current is {current}")]
+    RevisionConflict { expected: i64, current: i64 },
 }
 
 #[async_trait]
@@ -71,6 +73,8 @@ pub trait UserRepository: Send + Sync {
 pub trait NodeRepository: Send + Sync {
     async fn find_by_id(&self, id: &Uuid) -> Result<Option<Node>, RepositoryError>;
     async fn save(&self, node: Node, user_id: UserId) -> Result<Uuid, RepositoryError>;
+    // Optimistic Concurrency: updates the node only while its `updated_at` (in microseconds) is `expected_revision`
+    async fn save_if_revision(&self, node: Node, expected_revision: i64) -> Result<i64, RepositoryError>;
     async fn list_by_parent(&self, parent_id: Option<Uuid>) -> Result<Vec<Node>, RepositoryError>;
     async fn delete(&self, id: &Uuid) -> Result<(), RepositoryError>;
 }
@@ -78,10 +82,18 @@ pub trait NodeRepository: Send + Sync {
 #[async_trait]
 pub trait ArticleRepository: Send + Sync {
     async fn save(&self, article: Article, user_id: UserId, change_reason: Option<String>) -> Result<Uuid, RepositoryError>;
+    // Optimistic Concurrency: the revision is the latest version number (0 before the first save).
+    // Returns the new revision; without `expected_revision` the save is unconditional.
+    async fn save_if_revision(&self, article: Article, user_id: UserId, change_reason: Option<String>, expected_revision: Option<i64>) -> Result<i64, RepositoryError>;
+    async fn current_revision(&self, id: &Uuid) -> Result<i64, RepositoryError>;
+    // Block API: `article.body` must be the blocks rendered; they are stored with their ids and revisions
+    async fn save_with_blocks(&self, article: Article, blocks: Vec<crate::domain::blocks::models::Block>, user_id: UserId, change_reason: Option<String>, expected_revision: i64) -> Result<i64, RepositoryError>;
     async fn find_by_id(&self, id: &Uuid) -> Result<Option<ContentItem>, RepositoryError>;
     async fn find_by_title(&self, title: &str) -> Result<Option<Article>, RepositoryError>;
     async fn find_by_slug(&self, slug: &str) -> Result<Option<Article>, RepositoryError>;
     async fn list(&self, viewer_id: Option<UserId>, author_id: Option<UserId>, knowledge_base_id: Option<Uuid>, tag: Option<String>, category: Option<String>, limit: u64, offset: u64) -> Result<Vec<ContentItem>, RepositoryError>;
+    // Incremental backups: every node of a KB with its latest version, bodies left out
+    async fn list_versions(&self, knowledge_base_id: Uuid) -> Result<Vec<NodeVersion>, RepositoryError>;
     async fn delete(&self, id: &Uuid) -> Result<(), RepositoryError>;
     async fn get_version(&self, id: &Uuid, version: &str) -> Result<Option<ContentVersionSnapshot>, RepositoryError>;
     async fn get_history(&self, id: &Uuid) -> Result<Vec<ContentVersionSnapshot>, RepositoryError>;
@@ -109,11 +121,18 @@ pub trait VocabularyRepository: Send + Sync {
     async fn count(&self, user_id: &UserId, knowledge_base_id: Option<Uuid>) -> Result<u64, RepositoryError>;
     // Shared Sentences
     async fn search_global_sentences(&self, query: &str) -> Result<Vec<(Uuid, String, Option<String>)>, RepositoryError>;
+    // Word Families
+    async fn find_root(&self, root: &str) -> Result<Option<VocabularyRoot>, RepositoryError>;
+    async fn list_by_root(&self, user_id: &UserId, root: &str) -> Result<Vec<Vocabulary>, RepositoryError>;
+    // Reverse Lookup: (word, definition, translation) of all the user's words
+    async fn list_definitions(&self, user_id: &UserId) -> Result<Vec<(String, String, Option<String>)>, RepositoryError>;
 }
 
 #[async_trait]
 pub trait MemoRepository: Send + Sync {
     async fn save(&self, memo: Memo) -> Result<Uuid, RepositoryError>;
+    // Optimistic Concurrency: fails unless the stored memo is still at `expected_revision` (see `Memo::revision`)
+    async fn save_if_revision(&self, memo: Memo, expected_revision: i64) -> Result<i64, RepositoryError>;
     async fn find_by_id(&self, id: &Uuid) -> Result<Option<Memo>, RepositoryError>;
     async fn list(&self, viewer_id: Option<UserId>, author_id: Option<UserId>) -> Result<Vec<Memo>, RepositoryError>;
     async fn find_by_date_range(&self, author_id: UserId, start: chrono::DateTime<chrono::Utc>, end: chrono::DateTime<chrono::Utc>) -> Result<Vec<Memo>, RepositoryError>;
@@ -188,6 +207,13 @@ pub trait ExportService: Send + Sync {
         format: ExportFormat,
         requester: Option<UserId>
     ) -> Result<Vec<u8>, RepositoryError>;
+
+    /// A zipped static HTML site: an index page, one page per article and the images they use.
+    async fn export_knowledge_base_site(
+        &self,
+        kb_id: &Uuid,
+        requester: Option<UserId>
+    ) -> Result<Vec<u8>, RepositoryError>;
 }
 
 #[async_trait]
@@ -235,7 +261,8 @@ pub trait VrkbRepository: Send + Sync {
     // Docs
     async fn create_doc(&self, doc: crate::domain::models::VrkbDoc) -> Result<Uuid, RepositoryError>;
     async fn get_doc(&self, id: &Uuid) -> Result<Option<crate::domain::models::VrkbDoc>, RepositoryError>;
-    async fn update_doc(&self, doc: crate::domain::models::VrkbDoc) -> Result<(), RepositoryError>;
+    // With `expected_revision`, fails unless the stored doc is still at that revision (see `VrkbDoc::revision`)
+    async fn update_doc(&self, doc: crate::domain::models::VrkbDoc, expected_revision: Option<i64>) -> Result<(), RepositoryError>;
     async fn delete_doc(&self, id: &Uuid) -> Result<(), RepositoryError>;
     async fn list_docs(&self, project_id: &Uuid) -> Result<Vec<crate::domain::models::VrkbDoc>, RepositoryError>;
     
@@ -250,6 +277,16 @@ pub trait VrkbRepository: Send + Sync {
 
 }
 
+#[async_trait]
+pub trait AssetRepository: Send + Sync {
+    /// Every asset article and VRKB asset.
+    async fn list_asset_records(&self) -> Result<Vec<AssetRecord>, RepositoryError>;
+    /// Every usage of every asset, or of one.
+    async fn list_asset_usages(&self, asset_id: Option<Uuid>) -> Result<Vec<AssetUsage>, RepositoryError>;
+    /// Points a record at where its file now is.
+    async fn set_asset_path(&self, record: &AssetRecord, storage_path: &str) -> Result<(), RepositoryError>;
+}
+
 #[async_trait]
 pub trait AuditRepository: Send + Sync {
     async fn log_event(&self, action: &str, actor_id: Uuid, target: &str, details: serde_json::Value) -> Result<(), RepositoryError>;
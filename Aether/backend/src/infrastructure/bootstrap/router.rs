@@ -4,8 +4,8 @@ use utoipa::OpenApi;
 use utoipa_swagger_ui::SwaggerUi;
 use crate::interface::state::AppState;
 use crate::interface::api::{
-    auth, content, comment, memo, knowledge_base, export, upload, 
-    tags, vocabulary, dictionary, permission, user, system, template, group, prkb, graph, vrkb, assets, backup, portability, user_settings,
+    auth, content, blocks, links, collab, comment, memo, knowledge_base, export, feeds, upload, 
+    tags, vocabulary, dictionary, permission, user, system, template, group, prkb, graph, vrkb, assets, backup, portability, publish, user_settings,
     openapi::ApiDoc
 };
 
@@ -13,10 +13,14 @@ pub fn build_router(state: AppState) -> Router {
     let api_routes = Router::new()
         .merge(auth::router())
         .merge(content::router())
+        .merge(blocks::router())
+        .merge(links::router())
+        .merge(collab::router())
         .merge(comment::router())
         .merge(memo::router())
         .merge(knowledge_base::router())
         .merge(export::router())
+        .merge(feeds::router())
         .merge(upload::router())
         .merge(tags::router())
         .merge(vocabulary::router())
@@ -33,11 +37,13 @@ pub fn build_router(state: AppState) -> Router {
         .nest("/api/assets", assets::router())
         .nest("/api/backups", backup::router())
         .nest("/api/portability", portability::router())
+        .nest("/api/publish", publish::router())
         .with_state(state);
 
     Router::new()
         .route("/", axum::routing::get(health_check))
         .nest_service("/uploads", tower_http::services::ServeDir::new("uploads"))
+        .nest_service("/sites", tower_http::services::ServeDir::new("public_sites"))
         .merge(SwaggerUi::new("/swagger-ui").url("/api-docs/openapi.json", ApiDoc::openapi()))
         .merge(api_routes)
         .layer(axum::extract::DefaultBodyLimit::max(500 * 1024 * 1024)) // 500MB Dynamic Ceiling
@@ -4,19 +4,28 @@ use std::env;
 
 use crate::interface::state::AppState;
 use crate::infrastructure::persistence::postgres::PostgresRepository;
-use crate::domain::ports::{UserRepository, ArticleRepository, MemoRepository, CommentRepository, VrkbRepository, GraphRepository, NodeRepository, KnowledgeBaseRepository};
+use crate::domain::ports::{UserRepository, ArticleRepository, MemoRepository, CommentRepository, VrkbRepository, GraphRepository, NodeRepository, KnowledgeBaseRepository, AuditRepository, AssetRepository};
 use crate::infrastructure::auth::jwt_service::Arg2JwtAuthService;
 use crate::infrastructure::services::export_service::DataExportService;
 use crate::infrastructure::services::asset_manager::AssetManager;
+use crate::infrastructure::services::site::SiteGenerator;
+use crate::infrastructure::services::feeds::FeedService;
 use crate::infrastructure::services::backup_service::BackupService;
 use crate::infrastructure::services::portability_service::PortabilityService;
 use crate::infrastructure::services::portability::english::EnglishPortabilityProvider;
 use crate::infrastructure::services::portability::default::DefaultPortabilityProvider;
+use crate::infrastructure::services::portability::math::MathPortabilityProvider;
+use crate::infrastructure::services::portability::epub::EpubPortabilityProvider;
+use crate::infrastructure::services::portability::obsidian::ObsidianPortabilityProvider;
+use crate::infrastructure::services::portability::markdown::MarkdownPortabilityProvider;
+use crate::infrastructure::persistence::repositories::block_repository::BlockRepository;
 use crate::infrastructure::dictionary::loader::DictionaryLoader;
 use crate::domain::permission_service::PermissionService;
 use crate::domain::indexer_service::IndexerService;
 use crate::domain::graph_service::GraphService;
 use crate::infrastructure::storage::service::AssetStorageService;
+use crate::infrastructure::storage::blob_store::BlobStore;
+use crate::infrastructure::storage::gc::AssetGcService;
 use crate::infrastructure::services::arxiv::ArxivService;
 use crate::infrastructure::services::rss::RssService;
 use crate::domain::kb::SchemaRegistry;
@@ -34,12 +43,6 @@ pub async fn init_app_state(db: DatabaseConnection) -> AppState {
         env::var("JWT_SECRET").unwrap_or_else(|_| "secret".to_string())
     ));
 
-    let export_service = Arc::new(DataExportService::new(
-        repo.clone() as Arc<dyn ArticleRepository>,
-        repo.clone() as Arc<dyn MemoRepository>,
-        repo.clone() as Arc<dyn CommentRepository>,
-    ));
-
     let permission_service = PermissionService::new(repo.clone());
     
     let indexer_service = Arc::new(IndexerService::new(db.clone()));
@@ -48,9 +51,17 @@ pub async fn init_app_state(db: DatabaseConnection) -> AppState {
         repo.clone() as Arc<dyn GraphRepository>
     ));
 
+    // One content-addressed store behind both upload paths
+    let blob_store = Arc::new(BlobStore::new("uploads"));
+
     let asset_storage = Arc::new(AssetStorageService::new(
         repo.clone() as Arc<dyn VrkbRepository>, 
-        "uploads".to_string()
+        blob_store.clone()
+    ));
+
+    let asset_gc = Arc::new(AssetGcService::new(
+        repo.clone() as Arc<dyn AssetRepository>,
+        blob_store.clone()
     ));
 
     let asset_manager = Arc::new(AssetManager::new(
@@ -58,7 +69,16 @@ pub async fn init_app_state(db: DatabaseConnection) -> AppState {
         repo.clone() as Arc<dyn ArticleRepository>,
         repo.clone() as Arc<dyn KnowledgeBaseRepository>,
         Arc::new(permission_service.clone()),
-        ".".to_string()
+        ".".to_string(),
+        blob_store.clone()
+    ));
+
+    let export_service = Arc::new(DataExportService::new(
+        repo.clone() as Arc<dyn ArticleRepository>,
+        repo.clone() as Arc<dyn MemoRepository>,
+        repo.clone() as Arc<dyn CommentRepository>,
+        repo.clone() as Arc<dyn KnowledgeBaseRepository>,
+        asset_manager.clone(),
     ));
 
     let backup_service = Arc::new(BackupService::new(
@@ -66,10 +86,30 @@ pub async fn init_app_state(db: DatabaseConnection) -> AppState {
         repo.clone() as Arc<dyn crate::domain::ports::KnowledgeBaseRepository>,
         repo.clone() as Arc<dyn NodeRepository>,
         asset_manager.clone(),
-        ".".to_string()
+        repo.clone() as Arc<dyn AuditRepository>,
+        ".".to_string(),
+        env::var("BACKUP_SIGNING_KEY").or_else(|_| env::var("JWT_SECRET")).unwrap_or_else(|_| "secret".to_string())
+    ));
+
+    // Static sites of public KBs; absolute feed links need the public URL
+    let site_generator = Arc::new(SiteGenerator::new(
+        repo.clone() as Arc<dyn ArticleRepository>,
+        repo.clone() as Arc<dyn KnowledgeBaseRepository>,
+        asset_manager.clone(),
+        ".".to_string(),
+        env::var("PUBLIC_SITE_URL").ok(),
+    ));
+
+    // Feed links point into the app; without a configured URL they follow the request's host
+    let feed_service = Arc::new(FeedService::new(
+        repo.clone() as Arc<dyn ArticleRepository>,
+        repo.clone() as Arc<dyn KnowledgeBaseRepository>,
+        repo.clone() as Arc<dyn UserRepository>,
+        env::var("PUBLIC_APP_URL").ok(),
     ));
 
-    let mut portability_service = PortabilityService::new();
+    // Finished imports refresh the target KB's published site
+    let mut portability_service = PortabilityService::new(site_generator.clone());
     
     // Register English Provider (Standard)
     portability_service.register_provider(Arc::new(EnglishPortabilityProvider::new(
@@ -92,6 +132,41 @@ pub async fn init_app_state(db: DatabaseConnection) -> AppState {
         repo.clone() as Arc<dyn crate::domain::ports::KnowledgeBaseRepository>,
     ).with_id("english".to_string())));
 
+    // Register Math Provider (LaTeX project), for both math layouts
+    for id in ["math_v1", "math_v3"] {
+        portability_service.register_provider(Arc::new(MathPortabilityProvider::new(
+            BlockRepository::new(db.clone()),
+            repo.clone() as Arc<dyn crate::domain::ports::KnowledgeBaseRepository>,
+            asset_manager.clone(),
+        ).with_id(id.to_string())));
+    }
+
+    // Register EPUB Provider (any KB type, requested with ?format=epub)
+    portability_service.register_provider(Arc::new(EpubPortabilityProvider::new(
+        repo.clone() as Arc<dyn ArticleRepository>,
+        repo.clone() as Arc<dyn crate::domain::ports::KnowledgeBaseRepository>,
+        repo.clone() as Arc<dyn UserRepository>,
+        asset_manager.clone(),
+    )));
+
+    // Register Obsidian Provider (import only, requested with ?format=obsidian)
+    portability_service.register_provider(Arc::new(ObsidianPortabilityProvider::new(
+        repo.clone() as Arc<dyn ArticleRepository>,
+        repo.clone() as Arc<dyn NodeRepository>,
+        repo.clone() as Arc<dyn crate::domain::ports::KnowledgeBaseRepository>,
+        asset_manager.clone(),
+    )));
+
+    // Register Markdown Provider (import only: Markdown trees, and Notion exports under ?format=notion)
+    for id in ["markdown", "notion"] {
+        portability_service.register_provider(Arc::new(MarkdownPortabilityProvider::new(
+            repo.clone() as Arc<dyn ArticleRepository>,
+            repo.clone() as Arc<dyn NodeRepository>,
+            repo.clone() as Arc<dyn crate::domain::ports::KnowledgeBaseRepository>,
+            asset_manager.clone(),
+        ).with_id(id.to_string())));
+    }
+
     // Register Default Provider
     portability_service.register_provider(Arc::new(DefaultPortabilityProvider::new(
         backup_service.clone()
@@ -114,6 +189,10 @@ pub async fn init_app_state(db: DatabaseConnection) -> AppState {
         .time_to_live(std::time::Duration::from_secs(3600))
         .build();
 
+    // Entry parsers: picked per entry from the dictionary's declared markup and content
+    let entry_parsers = crate::infrastructure::dictionary::parser::EntryParserRegistry::with_defaults();
+    tracing::info!("Dictionary entry parsers initialized (plain, oxford, html, xdxf)");
+
     // Schema Registry
     let schema_registry = SchemaRegistry::new();
     schema_registry.register("markdown", crate::domain::kb::schemas::markdown::MarkdownSchema);
@@ -125,7 +204,28 @@ pub async fn init_app_state(db: DatabaseConnection) -> AppState {
     schema_registry.register("ip_asset", crate::domain::kb::schemas::assets::IpAssetSchema);
     schema_registry.register("credential_stub", crate::domain::kb::schemas::assets::CredentialStubSchema);
 
-    tracing::info!("KB Schema Registry initialized (types: markdown, math_block, paper, assets)");
+    // Register Article Body Schemas (block API)
+    schema_registry.register("paragraph", crate::domain::kb::schemas::article::ParagraphSchema);
+    schema_registry.register("heading", crate::domain::kb::schemas::article::HeadingSchema);
+    schema_registry.register("code", crate::domain::kb::schemas::article::CodeSchema);
+    schema_registry.register("math", crate::domain::kb::schemas::article::DisplayMathSchema);
+    schema_registry.register("list", crate::domain::kb::schemas::article::ListSchema);
+    schema_registry.register("table", crate::domain::kb::schemas::article::TableSchema);
+    schema_registry.register("blockquote", crate::domain::kb::schemas::article::BlockquoteSchema);
+    schema_registry.register("image", crate::domain::kb::schemas::article::ImageSchema);
+    schema_registry.register("thematic_break", crate::domain::kb::schemas::article::ThematicBreakSchema);
+    schema_registry.register("html", crate::domain::kb::schemas::article::HtmlSchema);
+    schema_registry.register("front_matter", crate::domain::kb::schemas::article::FrontMatterSchema);
+    schema_registry.register("directive", crate::domain::kb::schemas::article::DirectiveSchema);
+
+    tracing::info!("KB Schema Registry initialized (types: markdown, math_block, paper, assets, article blocks)");
+
+    // Live editing rooms persist through the article repository, then reindex and refresh published sites
+    let collab_service = Arc::new(crate::infrastructure::services::collab_service::CollaborationService::new(
+        repo.clone() as Arc<dyn ArticleRepository>,
+        indexer_service.clone(),
+        site_generator.clone(),
+    ));
 
     let system_settings_repository = Arc::new(SystemSettingsRepository::new(Arc::new(db.clone())));
 
@@ -136,12 +236,17 @@ pub async fn init_app_state(db: DatabaseConnection) -> AppState {
         permission_service,
         dictionary,
         dictionary_cache,
+        entry_parsers,
         indexer_service,
         graph_service,
         asset_storage,
+        asset_gc,
         asset_manager,
         backup_service,
+        collab_service,
         portability_service,
+        site_generator,
+        feed_service,
         schema_registry,
         arxiv_service,
         rss_service,
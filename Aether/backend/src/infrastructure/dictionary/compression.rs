@@ -0,0 +1,186 @@
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::Path;
+use flate2::read::{GzDecoder, ZlibDecoder};
+
+#[derive(Debug, thiserror::Error)]
+pub enum CompressionError {
+    #[error("Unknown block compression type: {0}")]
+    UnknownType(u32),
+    #[error("Block too short")]
+    Truncated,
+    #[error("zlib: {0}")]
+    Zlib(#[from] io::Error),
+    #[error("LZO stream corrupted at input offset {0}")]
+    Lzo(usize),
+    #[error("Block decompresses past its declared size of {0} bytes")]
+    Oversized(usize),
+}
+
+/// Most memory reserved up front for a block; sizes come from the file.
+const MAX_PREALLOC: usize = 1 << 20;
+
+/// Decodes an MDict block: `[type: u32 LE][adler32: u32 BE][payload]`.
+/// Type 0 = stored, 1 = LZO1X, 2 = zlib. Output is limited to `decomp_size`.
+pub fn decompress_block(block: &[u8], decomp_size: usize) -> Result<Vec<u8>, CompressionError> {
+    if block.len() < 8 {
+        return Err(CompressionError::Truncated);
+    }
+    let kind = u32::from_le_bytes([block[0], block[1], block[2], block[3]]);
+    let payload = &block[8..];
+    match kind {
+        0 => Ok(payload.to_vec()),
+        1 => lzo1x_decompress(payload, decomp_size),
+        2 => zlib_decompress(payload, decomp_size),
+        other => Err(CompressionError::UnknownType(other)),
+    }
+}
+
+pub fn zlib_decompress(data: &[u8], decomp_size: usize) -> Result<Vec<u8>, CompressionError> {
+    let mut out = Vec::with_capacity(decomp_size.min(MAX_PREALLOC));
+    ZlibDecoder::new(data).take(decomp_size as u64 + 1).read_to_end(&mut out)?;
+    if out.len() > decomp_size {
+        return Err(CompressionError::Oversized(decomp_size));
+    }
+    Ok(out)
+}
+
+/// Decompresses a dictzip (`.dict.dz`) file next to itself, replacing the
+/// previous `gzip -dk -S .dz` subprocess. Dictzip is gzip-compatible.
+pub fn gunzip_file(src: &Path, dst: &Path) -> io::Result<()> {
+    let input = File::open(src)?;
+    let mut decoder = GzDecoder::new(io::BufReader::new(input));
+    let tmp = dst.with_extension("dict.part");
+    {
+        let mut output = File::create(&tmp)?;
+        io::copy(&mut decoder, &mut output)?;
+    }
+    std::fs::rename(&tmp, dst)
+}
+
+/// Pure LZO1X decompressor (the variant used by MDict record blocks).
+/// Follows the reference `lzo1x_decompress_safe` state machine with bounds checks.
+pub fn lzo1x_decompress(input: &[u8], decomp_size: usize) -> Result<Vec<u8>, CompressionError> {
+    let mut out: Vec<u8> = Vec::with_capacity(decomp_size.min(MAX_PREALLOC));
+    let mut ip = 0usize;
+    let mut state = 0usize;
+
+    let byte = |ip: usize| -> Result<usize, CompressionError> {
+        input.get(ip).map(|b| *b as usize).ok_or(CompressionError::Lzo(ip))
+    };
+
+    // Long lengths are encoded as a run of zero bytes (255 each) plus a final byte.
+    let extended_len = |ip: &mut usize, base: usize| -> Result<usize, CompressionError> {
+        let start = *ip;
+        while byte(*ip)? == 0 {
+            *ip += 1;
+        }
+        let zeros = *ip - start;
+        let last = byte(*ip)?;
+        *ip += 1;
+        Ok(zeros * 255 + base + last)
+    };
+
+    let copy_literals = |out: &mut Vec<u8>, ip: &mut usize, n: usize| -> Result<(), CompressionError> {
+        let end = ip.checked_add(n).filter(|e| *e <= input.len()).ok_or(CompressionError::Lzo(*ip))?;
+        if out.len() + n > decomp_size {
+            return Err(CompressionError::Oversized(decomp_size));
+        }
+        out.extend_from_slice(&input[*ip..end]);
+        *ip = end;
+        Ok(())
+    };
+
+    let copy_match = |out: &mut Vec<u8>, distance: usize, len: usize, at: usize| -> Result<(), CompressionError> {
+        if distance == 0 || distance > out.len() {
+            return Err(CompressionError::Lzo(at));
+        }
+        if len > decomp_size - out.len() {
+            return Err(CompressionError::Oversized(decomp_size));
+        }
+        let start = out.len() - distance;
+        // Byte-by-byte: matches may overlap their own output
+        for i in 0..len {
+            let b = out[start + i];
+            out.push(b);
+        }
+        Ok(())
+    };
+
+    // First byte > 17 encodes an initial literal run
+    if byte(0)? > 17 {
+        let t = byte(0)? - 17;
+        ip = 1;
+        copy_literals(&mut out, &mut ip, t)?;
+        state = if t < 4 { t } else { 4 };
+    }
+
+    loop {
+        let mut t = byte(ip)?;
+        ip += 1;
+        let next;
+
+        if t < 16 {
+            if state == 0 {
+                // Literal run
+                if t == 0 {
+                    t = extended_len(&mut ip, 15)?;
+                }
+                copy_literals(&mut out, &mut ip, t + 3)?;
+                state = 4;
+                continue;
+            } else if state != 4 {
+                // 2-byte match right after a short literal run
+                next = t & 3;
+                let distance = 1 + (t >> 2) + (byte(ip)? << 2);
+                ip += 1;
+                copy_match(&mut out, distance, 2, ip)?;
+            } else {
+                // 3-byte match after a long literal run
+                next = t & 3;
+                let distance = 1 + 0x0800 + (t >> 2) + (byte(ip)? << 2);
+                ip += 1;
+                copy_match(&mut out, distance, 3, ip)?;
+            }
+        } else if t >= 64 {
+            // M2: length 3..8, distance up to 2 KiB
+            next = t & 3;
+            let distance = 1 + ((t >> 2) & 7) + (byte(ip)? << 3);
+            ip += 1;
+            let len = (t >> 5) + 1;
+            copy_match(&mut out, distance, len, ip)?;
+        } else if t >= 32 {
+            // M3: distance up to 16 KiB
+            let mut len = (t & 31) + 2;
+            if len == 2 {
+                len = extended_len(&mut ip, 31)? + 2;
+            }
+            let word = byte(ip)? | (byte(ip + 1)? << 8);
+            ip += 2;
+            next = word & 3;
+            let distance = 1 + (word >> 2);
+            copy_match(&mut out, distance, len, ip)?;
+        } else {
+            // M4: distance 16..48 KiB, or end-of-stream marker
+            let high = (t & 8) << 11;
+            let mut len = (t & 7) + 2;
+            if len == 2 {
+                len = extended_len(&mut ip, 7)? + 2;
+            }
+            let word = byte(ip)? | (byte(ip + 1)? << 8);
+            ip += 2;
+            next = word & 3;
+            let distance = high + (word >> 2);
+            if distance == 0 {
+                break;
+            }
+            copy_match(&mut out, distance + 0x4000, len, ip)?;
+        }
+
+        // Trailing literals encoded in the low bits of the match instruction
+        state = next;
+        copy_literals(&mut out, &mut ip, next)?;
+    }
+
+    Ok(out)
+}
@@ -1,20 +1,140 @@
+use std::collections::BTreeSet;
 use std::path::{Path, PathBuf};
-use std::sync::{Arc, Mutex};
+use std::sync::{Arc, Mutex, OnceLock, RwLock};
 use std::fs::File;
 use std::io::{Read, Seek, SeekFrom};
 use fst::{Set, IntoStreamer, Streamer};
 use fst::automaton::Levenshtein;
-use std::process::Command;
+use serde::{Deserialize, Serialize};
+use super::compression::gunzip_file;
+use super::mdict::MdictFile;
+use super::parser::EntryMarkup;
+use super::parser::markup::text_content;
+use super::reverse::{ReverseHit, ReverseIndex};
+
+/// Enabled state and priority of each dictionary. Kept next to the files
+/// (not in the database) so the loader can start before the DB is up.
+const MANIFEST_FILE: &str = "dictionaries.json";
+
+/// File types accepted by `install`, directly or inside a zip.
+const ALLOWED_EXTENSIONS: &[&str] = &["ifo", "idx", "dict", "dz", "syn", "mdx", "mdd", "css", "js"];
+
+/// How large the zips of one upload may expand to, all entries together.
+const MAX_EXPANDED_BYTES: u64 = 1 << 30;
+
+#[derive(Debug, thiserror::Error)]
+pub enum DictionaryAdminError {
+    #[error("Dictionary not found: {0}")]
+    NotFound(String),
+    #[error("{0}")]
+    InvalidInput(String),
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DictionaryConfig {
+    pub id: String,
+    pub enabled: bool,
+    pub priority: i32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DictionaryInfo {
+    pub id: String,
+    pub name: String,
+    pub format: String, // "stardict" | "mdict"
+    pub enabled: bool,
+    pub priority: i32,
+    pub entry_count: Option<usize>, // None when disabled (not loaded)
+    pub has_resources: bool,
+}
+
+/// One dictionary's raw definition for a headword.
+#[derive(Debug, Clone, Serialize)]
+pub struct SourceDefinition {
+    pub dictionary_id: String,
+    pub name: String,
+    pub text: String,
+    pub markup: EntryMarkup,
+}
 
 #[derive(Clone)]
 pub struct DictionaryLoader {
-    dicts: Arc<Vec<Mutex<CustomDict>>>,
-    index: Arc<Set<Vec<u8>>>,
+    base_path: PathBuf,
+    current: Arc<RwLock<Arc<DictionarySet>>>,
+    admin_lock: Arc<tokio::sync::Mutex<()>>, // Serializes manifest writes and reloads
+}
+
+/// Snapshot of the loaded dictionaries. Reloads build a new one and swap it
+/// in, so lookups in flight keep using the old files.
+struct DictionarySet {
+    dicts: Vec<LoadedDict>,       // Enabled only, by priority
+    catalog: Vec<DictionaryInfo>, // Everything on disk, by priority
+    resources: Vec<Mutex<MdictFile>>, // .mdd files (audio, images)
+    index: Set<Vec<u8>>,
+    reverse: OnceLock<ReverseIndex>, // Definition full-text index, filled in the background
+}
+
+struct LoadedDict {
+    info: DictionaryInfo,
+    source: Mutex<Box<dyn DictSource>>,
+}
+
+/// A dictionary file found on disk, not opened yet.
+struct Candidate {
+    id: String,
+    path: PathBuf,
+    format: &'static str,
+}
+
+/// A loaded dictionary file that can answer headword lookups.
+trait DictSource: Send {
+    fn name(&self) -> String;
+    fn markup(&self) -> EntryMarkup;
+    fn headwords(&self) -> Vec<String>;
+    fn get(&mut self, word: &str) -> Option<String>;
+}
+
+impl DictSource for CustomDict {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn markup(&self) -> EntryMarkup {
+        self.markup
+    }
+
+    fn headwords(&self) -> Vec<String> {
+        self.index_map.keys().cloned().collect()
+    }
+
+    fn get(&mut self, word: &str) -> Option<String> {
+        CustomDict::get(self, word)
+    }
+}
+
+impl DictSource for MdictFile {
+    fn name(&self) -> String {
+        self.title.clone()
+    }
+
+    fn markup(&self) -> EntryMarkup {
+        EntryMarkup::Html
+    }
+
+    fn headwords(&self) -> Vec<String> {
+        MdictFile::headwords(self).cloned().collect()
+    }
+
+    fn get(&mut self, word: &str) -> Option<String> {
+        self.lookup(word)
+    }
 }
 
 struct CustomDict {
-    #[allow(dead_code)]
     name: String,
+    markup: EntryMarkup,
     file_path: PathBuf,
     // Map word -> (offset, size)
     index_map: std::collections::HashMap<String, (u64, u64)>, 
@@ -23,11 +143,21 @@ struct CustomDict {
 
 impl CustomDict {
     fn new(base_path: PathBuf) -> Option<Self> {
-        let name = base_path.file_stem()?.to_string_lossy().to_string();
+        let stem = base_path.file_stem()?.to_string_lossy().to_string();
         let idx_path = base_path.with_extension("idx");
         let dict_path = base_path.with_extension("dict");
         let dz_path = base_path.with_extension("dict.dz");
 
+        // Display name ("bookname=") and entry markup ("sametypesequence=") from the .ifo
+        let ifo = std::fs::read_to_string(base_path.with_extension("ifo")).unwrap_or_default();
+        let ifo_value = |key: &str| ifo.lines()
+            .find_map(|l| l.strip_prefix(key).map(|v| v.trim().to_string()))
+            .filter(|v| !v.is_empty());
+        let name = ifo_value("bookname=").unwrap_or(stem);
+        let markup = ifo_value("sametypesequence=")
+            .map(|seq| EntryMarkup::from_sametypesequence(&seq))
+            .unwrap_or(EntryMarkup::Unknown);
+
         // 1. Parse Index
         if !idx_path.exists() { return None; }
         
@@ -45,18 +175,10 @@ impl CustomDict {
         let final_dict_path = if dict_path.exists() {
             dict_path
         } else if dz_path.exists() {
-            // Try to decompress
+            // Decompress in-process (dictzip is gzip-compatible), keeping the original
             tracing::info!("Decompressing {}...", dz_path.display());
-            // gzip -dk -S .dz (keep original, specify suffix)
-            let status = Command::new("gzip")
-                .arg("-dk")
-                .arg("-S")
-                .arg(".dz")
-                .arg(&dz_path)
-                .status();
-            
-            if status.is_err() || !status.unwrap().success() {
-                tracing::error!("Failed to decompress {}", dz_path.display());
+            if let Err(e) = gunzip_file(&dz_path, &dict_path) {
+                tracing::error!("Failed to decompress {}: {}", dz_path.display(), e);
                 // We can't use this dict
                 return None;
             }
@@ -67,6 +189,7 @@ impl CustomDict {
 
         Some(Self {
             name,
+            markup,
             file_path: final_dict_path,
             index_map,
             file: None, // Lazy open
@@ -140,67 +263,461 @@ fn parse_idx_entries(idx_path: &Path) -> Result<Vec<(String, u64, u64)>, std::io
 }
 
 
+
+fn read_manifest(base: &Path) -> Vec<DictionaryConfig> {
+    std::fs::read_to_string(base.join(MANIFEST_FILE)).ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default()
+}
+
+fn write_manifest(base: &Path, catalog: &[DictionaryInfo]) -> std::io::Result<()> {
+    let configs: Vec<DictionaryConfig> = catalog.iter()
+        .map(|d| DictionaryConfig { id: d.id.clone(), enabled: d.enabled, priority: d.priority })
+        .collect();
+    let json = serde_json::to_string_pretty(&configs).map_err(std::io::Error::other)?;
+    std::fs::create_dir_all(base)?;
+    let tmp = base.join(format!("{}.part", MANIFEST_FILE));
+    std::fs::write(&tmp, json)?;
+    std::fs::rename(tmp, base.join(MANIFEST_FILE))
+}
+
+/// Each subdirectory holds one dictionary (id = directory name). Directories
+/// with several `.ifo`/`.mdx` files get one id per file: `dir/stem`.
+fn discover(base: &Path) -> Vec<Candidate> {
+    let mut out = Vec::new();
+    let mut dirs: Vec<PathBuf> = match std::fs::read_dir(base) {
+        Ok(entries) => entries.flatten().map(|e| e.path()).filter(|p| p.is_dir()).collect(),
+        Err(_) => return out,
+    };
+    dirs.sort();
+
+    for dir in dirs {
+        let dir_name = dir.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+        let mut files: Vec<PathBuf> = match std::fs::read_dir(&dir) {
+            Ok(entries) => entries.flatten().map(|e| e.path()).collect(),
+            Err(_) => continue,
+        };
+        files.sort();
+
+        let mains: Vec<(PathBuf, &'static str)> = files.into_iter()
+            .filter_map(|p| {
+                let ext = p.extension().map(|e| e.to_string_lossy().to_lowercase()).unwrap_or_default();
+                match ext.as_str() {
+                    "ifo" => Some((p, "stardict")),
+                    "mdx" => Some((p, "mdict")),
+                    _ => None,
+                }
+            })
+            .collect();
+
+        let single = mains.len() == 1;
+        for (path, format) in mains {
+            let id = if single {
+                dir_name.clone()
+            } else {
+                let stem = path.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+                format!("{}/{}", dir_name, stem)
+            };
+            out.push(Candidate { id, path, format });
+        }
+    }
+    out
+}
+
+fn open_source(candidate: &Candidate) -> Option<Box<dyn DictSource>> {
+    match candidate.format {
+        "stardict" => CustomDict::new(candidate.path.with_extension(""))
+            .map(|d| Box::new(d) as Box<dyn DictSource>),
+        _ => match MdictFile::open(&candidate.path) {
+            Ok(d) => {
+                tracing::info!("Loaded MDict '{}' ({} entries)", d.title, d.entry_count());
+                Some(Box::new(d) as Box<dyn DictSource>)
+            }
+            Err(e) => {
+                tracing::error!("Failed to load {}: {}", candidate.path.display(), e);
+                None
+            }
+        },
+    }
+}
+
+fn build_set(base: &Path) -> DictionarySet {
+    let manifest = read_manifest(base);
+    let candidates = discover(base);
+
+    let mut catalog: Vec<DictionaryInfo> = candidates.iter().enumerate().map(|(i, c)| {
+        let config = manifest.iter().find(|m| m.id == c.id);
+        DictionaryInfo {
+            id: c.id.clone(),
+            name: c.path.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default(),
+            format: c.format.to_string(),
+            // Newly found dictionaries are enabled and rank after configured ones
+            enabled: config.map(|m| m.enabled).unwrap_or(true),
+            priority: config.map(|m| m.priority).unwrap_or(1000 + i as i32),
+            entry_count: None,
+            has_resources: c.format == "mdict" && c.path.with_extension("mdd").exists(),
+        }
+    }).collect();
+    catalog.sort_by(|a, b| a.priority.cmp(&b.priority).then_with(|| a.id.cmp(&b.id)));
+
+    let mut dicts = Vec::new();
+    let mut resources = Vec::new();
+    let mut all_words = BTreeSet::new();
+
+    for info in catalog.iter_mut().filter(|d| d.enabled) {
+        let candidate = match candidates.iter().find(|c| c.id == info.id) {
+            Some(c) => c,
+            None => continue,
+        };
+        let source = match open_source(candidate) {
+            Some(s) => s,
+            None => continue,
+        };
+
+        // Collect words for FST
+        let words = source.headwords();
+        info.name = source.name();
+        info.entry_count = Some(words.len());
+        for word in words {
+            all_words.insert(word.into_bytes());
+        }
+
+        if info.has_resources {
+            let mdd = candidate.path.with_extension("mdd");
+            match MdictFile::open(&mdd) {
+                Ok(d) => resources.push(Mutex::new(d)),
+                Err(e) => tracing::error!("Failed to load {}: {}", mdd.display(), e),
+            }
+        }
+
+        dicts.push(LoadedDict { info: info.clone(), source: Mutex::new(source) });
+    }
+
+    tracing::info!("Building FST Index with {} words from {} dictionaries...", all_words.len(), dicts.len());
+    let index = Set::from_iter(all_words).unwrap_or_else(|_| Set::from_iter(Vec::<Vec<u8>>::new()).unwrap());
+
+    DictionarySet { dicts, catalog, resources, index, reverse: OnceLock::new() }
+}
+
+/// Indexes every definition of `set` on a background thread. Reading all
+/// entries takes a while for large dictionaries, so lookups start right away
+/// and reverse search reports "indexing" until this finishes. Gives up early
+/// when the set is replaced by a reload.
+fn spawn_reverse_index(set: Arc<DictionarySet>) {
+    std::thread::spawn(move || {
+        let started = std::time::Instant::now();
+        let mut index = ReverseIndex::new();
+        for dict in &set.dicts {
+            let (words, markup) = match dict.source.lock() {
+                Ok(source) => (source.headwords(), source.markup()),
+                Err(_) => continue,
+            };
+            for word in words {
+                // Only this thread holds the set: it was swapped out
+                if Arc::strong_count(&set) == 1 {
+                    return;
+                }
+                // Lock per entry so lookups are not blocked for the whole build
+                let text = match dict.source.lock() {
+                    Ok(mut source) => source.get(&word),
+                    Err(_) => break,
+                };
+                if let Some(text) = text {
+                    index.add(&dict.info.id, &word, &plain_definition(&text, markup));
+                }
+            }
+        }
+        tracing::info!("Reverse dictionary index: {} definitions in {:?}", index.len(), started.elapsed());
+        let _ = set.reverse.set(index);
+    });
+}
+
+fn plain_definition(text: &str, markup: EntryMarkup) -> String {
+    match markup {
+        EntryMarkup::Plain => text.to_string(),
+        _ => text_content(text),
+    }
+}
+
+/// First `max_chars` of a definition, cut at a word boundary.
+fn snippet(text: &str, max_chars: usize) -> String {
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if text.chars().count() <= max_chars {
+        return text;
+    }
+    let cut: String = text.chars().take(max_chars).collect();
+    let cut = match cut.rfind(' ') {
+        Some(i) if i > max_chars / 2 => &cut[..i],
+        _ => cut.as_str(),
+    };
+    format!("{}…", cut.trim_end_matches([',', ';', ':', '.']))
+}
+
+fn validate_id(id: &str) -> Result<(), DictionaryAdminError> {
+    let valid = !id.is_empty() && id.len() <= 64
+        && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(DictionaryAdminError::InvalidInput("Dictionary id may only contain letters, digits, '-' and '_' (max 64)".into()))
+    }
+}
+
+/// Writes uploaded files (zip archives are unpacked, directories flattened)
+/// into `dir`. Requires at least one `.ifo` or `.mdx`.
+fn write_dictionary_files(dir: &Path, files: Vec<(String, Vec<u8>)>) -> Result<(), DictionaryAdminError> {
+    let mut flat: Vec<(String, Vec<u8>)> = Vec::new();
+    let mut budget = MAX_EXPANDED_BYTES;
+    let too_large = || DictionaryAdminError::InvalidInput(format!("The upload expands to more than {} MiB", MAX_EXPANDED_BYTES >> 20));
+    for (name, bytes) in files {
+        if name.to_lowercase().ends_with(".zip") {
+            let mut archive = zip::ZipArchive::new(std::io::Cursor::new(bytes))
+                .map_err(|e| DictionaryAdminError::InvalidInput(format!("Invalid zip {}: {}", name, e)))?;
+            for i in 0..archive.len() {
+                let mut entry = archive.by_index(i)
+                    .map_err(|e| DictionaryAdminError::InvalidInput(e.to_string()))?;
+                if entry.is_dir() {
+                    continue;
+                }
+                let entry_name = entry.name().to_string();
+                // Declared sizes can lie: the read is cut off past what is left as well
+                if entry.size() > budget {
+                    return Err(too_large());
+                }
+                let mut buf = Vec::new();
+                entry.by_ref().take(budget + 1).read_to_end(&mut buf)?;
+                if buf.len() as u64 > budget {
+                    return Err(too_large());
+                }
+                budget -= buf.len() as u64;
+                flat.push((entry_name, buf));
+            }
+        } else {
+            flat.push((name, bytes));
+        }
+    }
+
+    let mut prepared = Vec::new();
+    for (name, bytes) in flat {
+        let file_name = Path::new(&name).file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        if file_name.is_empty() || file_name.starts_with('.') {
+            continue;
+        }
+        let ext = Path::new(&file_name).extension()
+            .map(|e| e.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        if !ALLOWED_EXTENSIONS.contains(&ext.as_str()) {
+            return Err(DictionaryAdminError::InvalidInput(format!("Unsupported file type: {}", file_name)));
+        }
+        prepared.push((file_name, ext, bytes));
+    }
+
+    if !prepared.iter().any(|(_, ext, _)| ext == "ifo" || ext == "mdx") {
+        return Err(DictionaryAdminError::InvalidInput("Upload must contain a StarDict .ifo or an MDict .mdx file".into()));
+    }
+
+    std::fs::create_dir_all(dir)?;
+    for (file_name, _, bytes) in prepared {
+        std::fs::write(dir.join(file_name), bytes)?;
+    }
+    Ok(())
+}
+
 impl DictionaryLoader {
     pub fn new(base_path: &str) -> Self {
         tracing::info!("Initializing DictionaryLoader from: {}", base_path);
-        let mut dicts = Vec::new();
-        let mut all_words = std::collections::BTreeSet::new();
-
-        let path = Path::new(base_path);
-        if let Ok(entries) = std::fs::read_dir(path) {
-            for entry in entries.flatten() {
-                let path = entry.path();
-                if path.is_dir() {
-                    if let Ok(sub_entries) = std::fs::read_dir(&path) {
-                        for sub_entry in sub_entries.flatten() {
-                             let sub_path = sub_entry.path();
-                             if let Some(ext) = sub_path.extension() {
-                                 if ext == "ifo" {
-                                     let base = sub_path.with_extension("");
-                                     if let Some(d) = CustomDict::new(base) {
-                                         // Collect words for FST
-                                         for word in d.index_map.keys() {
-                                             all_words.insert(word.as_bytes().to_vec());
-                                         }
-                                         dicts.push(Mutex::new(d));
-                                     }
-                                 }
-                             }
-                        }
+        let base_path = PathBuf::from(base_path);
+        let set = Arc::new(build_set(&base_path));
+        spawn_reverse_index(set.clone());
+
+        Self {
+            base_path,
+            current: Arc::new(RwLock::new(set)),
+            admin_lock: Arc::new(tokio::sync::Mutex::new(())),
+        }
+    }
+
+    fn snapshot(&self) -> Arc<DictionarySet> {
+        match self.current.read() {
+            Ok(set) => set.clone(),
+            Err(poisoned) => poisoned.into_inner().clone(),
+        }
+    }
+
+    /// Rescans the dictionary directory and swaps in the new set and fst index.
+    pub async fn reload(&self) -> Result<(), DictionaryAdminError> {
+        let _guard = self.admin_lock.lock().await;
+        self.reload_locked().await
+    }
+
+    async fn reload_locked(&self) -> Result<(), DictionaryAdminError> {
+        let base = self.base_path.clone();
+        let set = tokio::task::spawn_blocking(move || build_set(&base)).await
+            .map_err(std::io::Error::other)?;
+        let set = Arc::new(set);
+        match self.current.write() {
+            Ok(mut current) => *current = set.clone(),
+            Err(poisoned) => *poisoned.into_inner() = set.clone(),
+        }
+        spawn_reverse_index(set);
+        Ok(())
+    }
+
+    /// All dictionaries on disk, enabled or not, by priority.
+    pub fn list(&self) -> Vec<DictionaryInfo> {
+        self.snapshot().catalog.clone()
+    }
+
+    pub fn exists(&self, id: &str) -> bool {
+        self.snapshot().catalog.iter().any(|d| d.id == id)
+    }
+
+    pub async fn configure(&self, id: &str, enabled: Option<bool>, priority: Option<i32>) -> Result<DictionaryInfo, DictionaryAdminError> {
+        let _guard = self.admin_lock.lock().await;
+        let mut catalog = self.list();
+        let entry = catalog.iter_mut().find(|d| d.id == id)
+            .ok_or_else(|| DictionaryAdminError::NotFound(id.to_string()))?;
+        if let Some(enabled) = enabled {
+            entry.enabled = enabled;
+        }
+        if let Some(priority) = priority {
+            entry.priority = priority;
+        }
+        write_manifest(&self.base_path, &catalog)?;
+        self.reload_locked().await?;
+
+        self.list().into_iter().find(|d| d.id == id)
+            .ok_or_else(|| DictionaryAdminError::NotFound(id.to_string()))
+    }
+
+    /// Puts `ids` first, in the given order; unlisted dictionaries keep their
+    /// relative order after them.
+    pub async fn reorder(&self, ids: &[String]) -> Result<Vec<DictionaryInfo>, DictionaryAdminError> {
+        let _guard = self.admin_lock.lock().await;
+        let mut catalog = self.list();
+        if let Some(missing) = ids.iter().find(|id| !catalog.iter().any(|d| &d.id == *id)) {
+            return Err(DictionaryAdminError::NotFound(missing.clone()));
+        }
+
+        let mut unlisted = ids.len() as i32;
+        for entry in catalog.iter_mut() {
+            entry.priority = match ids.iter().position(|id| *id == entry.id) {
+                Some(pos) => pos as i32,
+                None => {
+                    unlisted += 1;
+                    unlisted
+                }
+            };
+        }
+        write_manifest(&self.base_path, &catalog)?;
+        self.reload_locked().await?;
+        Ok(self.list())
+    }
+
+    /// Stores uploaded dictionary files under `<base>/<id>/` and reloads.
+    pub async fn install(&self, id: &str, files: Vec<(String, Vec<u8>)>) -> Result<Vec<DictionaryInfo>, DictionaryAdminError> {
+        validate_id(id)?;
+        let _guard = self.admin_lock.lock().await;
+        let dir = self.base_path.join(id);
+        if dir.exists() {
+            return Err(DictionaryAdminError::InvalidInput(format!("Dictionary '{}' already exists", id)));
+        }
+
+        let target = dir.clone();
+        let result = tokio::task::spawn_blocking(move || write_dictionary_files(&target, files)).await
+            .map_err(std::io::Error::other)?;
+        if let Err(e) = result {
+            let _ = std::fs::remove_dir_all(&dir);
+            return Err(e);
+        }
+
+        self.reload_locked().await?;
+        Ok(self.list().into_iter().filter(|d| d.id == id || d.id.starts_with(&format!("{}/", id))).collect())
+    }
+
+    /// Deletes a dictionary's files and its manifest entry.
+    pub async fn remove(&self, id: &str) -> Result<(), DictionaryAdminError> {
+        let _guard = self.admin_lock.lock().await;
+        let catalog = self.list();
+        if !catalog.iter().any(|d| d.id == id) {
+            return Err(DictionaryAdminError::NotFound(id.to_string()));
+        }
+
+        match id.split_once('/') {
+            // One of several dictionaries in a directory: remove only its files
+            Some((dir, stem)) => {
+                let dir = self.base_path.join(dir);
+                for entry in std::fs::read_dir(&dir)?.flatten() {
+                    let name = entry.file_name().to_string_lossy().to_string();
+                    if is_file_of(&name, stem) {
+                        std::fs::remove_file(entry.path())?;
                     }
                 }
             }
+            None => std::fs::remove_dir_all(self.base_path.join(id))?,
         }
 
-        tracing::info!("Building FST Index with {} words...", all_words.len());
-        let index = Set::from_iter(all_words).unwrap_or_else(|_| Set::from_iter(Vec::<Vec<u8>>::new()).unwrap());
+        let remaining: Vec<DictionaryInfo> = catalog.into_iter().filter(|d| d.id != id).collect();
+        write_manifest(&self.base_path, &remaining)?;
+        self.reload_locked().await
+    }
 
-        Self {
-            dicts: Arc::new(dicts),
-            index: Arc::new(index),
+    /// Definitions from every enabled dictionary, by priority.
+    pub fn lookup(&self, word: &str) -> Option<Vec<String>> {
+        let results: Vec<String> = self.lookup_sources(word, None).into_iter().map(|d| d.text).collect();
+
+        if results.is_empty() {
+             None
+        } else {
+             Some(results)
         }
     }
 
-    pub fn lookup(&self, word: &str) -> Option<Vec<String>> {
+    /// Definitions labeled by dictionary. `selection` restricts and orders the
+    /// dictionaries (a user's set); `None` uses the global priority order.
+    pub fn lookup_sources(&self, word: &str, selection: Option<&[String]>) -> Vec<SourceDefinition> {
+        let set = self.snapshot();
+        let ordered: Vec<&LoadedDict> = match selection {
+            Some(ids) => ids.iter()
+                .filter_map(|id| set.dicts.iter().find(|d| &d.info.id == id))
+                .collect(),
+            None => set.dicts.iter().collect(),
+        };
+
         let mut results = Vec::new();
-        
-        for dict_mutex in self.dicts.iter() {
-             if let Ok(mut dict) = dict_mutex.lock() {
-                 if let Some(def) = dict.get(word) {
-                     results.push(def);
+        for dict in ordered {
+             if let Ok(mut source) = dict.source.lock() {
+                 if let Some(text) = source.get(word) {
+                     results.push(SourceDefinition {
+                         dictionary_id: dict.info.id.clone(),
+                         name: dict.info.name.clone(),
+                         text,
+                         markup: source.markup(),
+                     });
                  }
              }
         }
-        
-        if results.is_empty() {
-             None
-        } else {
-             Some(results)
+        results
+    }
+
+    /// Resource (audio, image, stylesheet) from the loaded `.mdd` files,
+    /// e.g. `sound/hello.mp3` as referenced by `sound://hello.mp3` in entries.
+    pub fn resource(&self, path: &str) -> Option<Vec<u8>> {
+        let set = self.snapshot();
+        for mdd in set.resources.iter() {
+            if let Ok(mut mdd) = mdd.lock() {
+                if let Some(bytes) = mdd.resource(path) {
+                    return Some(bytes);
+                }
+            }
         }
+        None
     }
 
     pub async fn fuzzy_search(&self, word: &str) -> Vec<String> {
-        let index = self.index.clone();
+        let set = self.snapshot();
         let query = word.to_string();
 
         tokio::task::spawn_blocking(move || {
@@ -208,7 +725,7 @@ impl DictionaryLoader {
             
             // 1. Always check for exact match first
             let mut matches = Vec::new();
-            if index.contains(query.as_bytes()) {
+            if set.index.contains(query.as_bytes()) {
                  matches.push(query.clone());
             }
 
@@ -217,7 +734,7 @@ impl DictionaryLoader {
                  Err(_) => return matches
             };
 
-            let mut stream = index.search(lev).into_stream();
+            let mut stream = set.index.search(lev).into_stream();
             
             // Collect more candidates (e.g. 100) to allow for sorting
             while let Some(key) = stream.next() {
@@ -252,4 +769,42 @@ impl DictionaryLoader {
             matches.into_iter().take(20).collect()
         }).await.unwrap_or_default()
     }
+
+    /// False while the definition index of the current set is still being built.
+    pub fn reverse_ready(&self) -> bool {
+        self.snapshot().reverse.get().is_some()
+    }
+
+    /// Headwords found by meaning ("fear of heights" -> "acrophobia"), each
+    /// with a short definition from the first dictionary that matched.
+    /// `None` while the index is still being built.
+    pub async fn reverse_search(&self, query: &str, selection: Option<&[String]>, limit: usize) -> Option<Vec<ReverseHit>> {
+        let set = self.snapshot();
+        set.reverse.get()?;
+        let query = query.to_string();
+        let selection = selection.map(|s| s.to_vec());
+
+        tokio::task::spawn_blocking(move || {
+            let index = set.reverse.get()?;
+            let mut hits = index.search(&query, selection.as_deref(), limit);
+            for hit in hits.iter_mut() {
+                let Some(dict) = hit.sources.first().and_then(|id| set.dicts.iter().find(|d| &d.info.id == id)) else {
+                    continue;
+                };
+                if let Ok(mut source) = dict.source.lock() {
+                    let markup = source.markup();
+                    hit.snippet = source.get(&hit.word).map(|t| snippet(&plain_definition(&t, markup), 160));
+                }
+            }
+            Some(hits)
+        }).await.ok().flatten()
+    }
+}
+
+/// `oxford.v2.dict.dz` belongs to `oxford.v2`, but `oxford.v2.mdx` does not
+/// belong to `oxford`: what follows the stem must be known extensions.
+fn is_file_of(name: &str, stem: &str) -> bool {
+    name.strip_prefix(stem)
+        .and_then(|rest| rest.strip_prefix('.'))
+        .is_some_and(|exts| exts.split('.').all(|ext| ALLOWED_EXTENSIONS.contains(&ext.to_lowercase().as_str())))
 }
@@ -0,0 +1,416 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use ripemd::{Digest, Ripemd128};
+
+use super::compression::{decompress_block, zlib_decompress, CompressionError};
+
+#[derive(Debug, thiserror::Error)]
+pub enum MdictError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Compression error: {0}")]
+    Compression(#[from] CompressionError),
+    #[error("Invalid MDict file: {0}")]
+    Format(String),
+    #[error("Unsupported MDict feature: {0}")]
+    Unsupported(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum TextEncoding {
+    Utf8,
+    Utf16,
+    Legacy(&'static encoding_rs::Encoding), // GBK / GB18030 / Big5
+}
+
+impl TextEncoding {
+    fn from_header(name: &str, is_mdd: bool) -> Self {
+        if is_mdd {
+            return TextEncoding::Utf16;
+        }
+        match name.to_uppercase().as_str() {
+            "" | "UTF-8" | "UTF8" => TextEncoding::Utf8,
+            "UTF-16" | "UTF16" | "UTF-16LE" => TextEncoding::Utf16,
+            "GBK" | "GB2312" => TextEncoding::Legacy(encoding_rs::GB18030),
+            other => encoding_rs::Encoding::for_label(other.as_bytes())
+                .map(TextEncoding::Legacy)
+                .unwrap_or(TextEncoding::Utf8),
+        }
+    }
+
+    /// Width of one code unit, which is also the width of the key terminator.
+    fn unit(&self) -> usize {
+        if *self == TextEncoding::Utf16 { 2 } else { 1 }
+    }
+
+    fn decode(&self, bytes: &[u8]) -> String {
+        match self {
+            TextEncoding::Utf8 => String::from_utf8_lossy(bytes).into_owned(),
+            TextEncoding::Utf16 => {
+                let units: Vec<u16> = bytes.chunks_exact(2).map(|c| u16::from_le_bytes([c[0], c[1]])).collect();
+                String::from_utf16_lossy(&units)
+            }
+            TextEncoding::Legacy(enc) => enc.decode(bytes).0.into_owned(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct RecordBlock {
+    file_offset: u64,   // Absolute position of the compressed block
+    comp_size: u64,
+    decomp_offset: u64, // Position in the concatenated (decompressed) record stream
+    decomp_size: u64,
+}
+
+/// A parsed `.mdx` (definitions) or `.mdd` (resources) file.
+/// Keys are kept in memory; record blocks are read and decompressed on demand.
+pub struct MdictFile {
+    pub title: String,
+    pub attributes: HashMap<String, String>,
+    file_path: PathBuf,
+    encoding: TextEncoding,
+    // key -> (start, end) in the decompressed record stream
+    keys: HashMap<String, (u64, u64)>,
+    record_blocks: Vec<RecordBlock>,
+    file: Option<File>,
+    cached_block: Option<(usize, Vec<u8>)>,
+}
+
+/// Cursor over a byte slice reading big-endian numbers of the file's width.
+struct Reader<'a> {
+    buf: &'a [u8],
+    pos: usize,
+    wide: bool, // v2.0+: 8-byte numbers, 2-byte key sizes
+}
+
+impl<'a> Reader<'a> {
+    fn new(buf: &'a [u8], wide: bool) -> Self {
+        Self { buf, pos: 0, wide }
+    }
+
+    fn take(&mut self, n: usize) -> Result<&'a [u8], MdictError> {
+        let end = self.pos.checked_add(n).filter(|e| *e <= self.buf.len())
+            .ok_or_else(|| MdictError::Format("unexpected end of data".into()))?;
+        let slice = &self.buf[self.pos..end];
+        self.pos = end;
+        Ok(slice)
+    }
+
+    fn number(&mut self) -> Result<u64, MdictError> {
+        if self.wide {
+            let b = self.take(8)?;
+            Ok(u64::from_be_bytes(b.try_into().unwrap()))
+        } else {
+            let b = self.take(4)?;
+            Ok(u32::from_be_bytes(b.try_into().unwrap()) as u64)
+        }
+    }
+
+    fn key_size(&mut self) -> Result<usize, MdictError> {
+        if self.wide {
+            let b = self.take(2)?;
+            Ok(u16::from_be_bytes([b[0], b[1]]) as usize)
+        } else {
+            Ok(self.take(1)?[0] as usize)
+        }
+    }
+
+    fn done(&self) -> bool {
+        self.pos >= self.buf.len()
+    }
+}
+
+/// Largest header, index section or block read into memory at once. Real
+/// dictionaries stay far below; a larger size means a damaged file.
+const MAX_SECTION_SIZE: u64 = 64 << 20;
+
+fn read_exact_at(file: &mut File, offset: u64, len: usize) -> Result<Vec<u8>, MdictError> {
+    file.seek(SeekFrom::Start(offset))?;
+    let mut buf = vec![0u8; len];
+    file.read_exact(&mut buf)?;
+    Ok(buf)
+}
+
+/// Reads sections at offsets taken from the file, after checking that the
+/// file can hold them: sizes in a malformed file are not trusted.
+struct Sections {
+    file: File,
+    len: u64,
+}
+
+impl Sections {
+    fn read(&mut self, offset: u64, size: u64, what: &str) -> Result<Vec<u8>, MdictError> {
+        offset.checked_add(size).filter(|end| *end <= self.len)
+            .ok_or_else(|| MdictError::Format(format!("{} out of range", what)))?;
+        if size > MAX_SECTION_SIZE {
+            return Err(MdictError::Format(format!("{} too large ({} bytes)", what, size)));
+        }
+        read_exact_at(&mut self.file, offset, size as usize)
+    }
+}
+
+fn advance(pos: u64, by: u64) -> Result<u64, MdictError> {
+    pos.checked_add(by).ok_or_else(|| MdictError::Format("offset overflow".into()))
+}
+
+fn declared_size(size: u64, what: &str) -> Result<usize, MdictError> {
+    if size > MAX_SECTION_SIZE {
+        return Err(MdictError::Format(format!("{} too large ({} bytes)", what, size)));
+    }
+    Ok(size as usize)
+}
+
+/// Header attributes look like `<Dictionary Title="..." Encoding="UTF-8" .../>`.
+fn parse_header_attributes(header: &str) -> HashMap<String, String> {
+    let re = regex::Regex::new(r#"(\w+)="((?s).*?)""#).unwrap();
+    re.captures_iter(header)
+        .map(|c| (c[1].to_string(), unescape_xml(&c[2])))
+        .collect()
+}
+
+fn unescape_xml(s: &str) -> String {
+    s.replace("&lt;", "<").replace("&gt;", ">").replace("&quot;", "\"").replace("&apos;", "'").replace("&amp;", "&")
+}
+
+/// Key-block-info decryption (`Encrypted="2"`): the key is RIPEMD-128 of the
+/// block checksum followed by the constant 0x3695.
+fn decrypt_key_block_info(block: &[u8]) -> Vec<u8> {
+    let mut hasher = Ripemd128::new();
+    hasher.update(&block[4..8]);
+    hasher.update(0x3695u32.to_le_bytes());
+    let key = hasher.finalize();
+
+    let mut out = block[..8].to_vec();
+    let mut previous: u8 = 0x36;
+    for (i, &b) in block[8..].iter().enumerate() {
+        let t = b.rotate_left(4) ^ previous ^ (i as u8) ^ key[i % key.len()];
+        previous = b;
+        out.push(t);
+    }
+    out
+}
+
+impl MdictFile {
+    /// Reads the header and the key index; record blocks stay on disk.
+    pub fn open(path: &Path) -> Result<Self, MdictError> {
+        let is_mdd = path.extension().is_some_and(|e| e.eq_ignore_ascii_case("mdd"));
+        let file = File::open(path)?;
+        let len = file.metadata()?.len();
+        Self::parse(Sections { file, len }, path.to_path_buf(), is_mdd)
+    }
+
+    fn parse(mut sections: Sections, file_path: PathBuf, is_mdd: bool) -> Result<Self, MdictError> {
+        // 1. Header: [len: u32 BE][UTF-16LE XML][adler32: u32 LE]
+        let header_len = Reader::new(&sections.read(0, 4, "header")?, false).number()?;
+        let header_bytes = sections.read(4, header_len, "header")?;
+        let header = TextEncoding::Utf16.decode(&header_bytes);
+        let attributes = parse_header_attributes(&header);
+
+        let version: f32 = attributes.get("GeneratedByEngineVersion")
+            .and_then(|v| v.trim().parse().ok())
+            .unwrap_or(2.0);
+        let wide = version >= 2.0;
+        let encrypted: u32 = attributes.get("Encrypted")
+            .and_then(|v| v.trim().parse().ok())
+            .unwrap_or(0);
+        if encrypted & 1 != 0 {
+            return Err(MdictError::Unsupported("keyword header is encrypted (requires registration key)".into()));
+        }
+        let encoding = TextEncoding::from_header(attributes.get("Encoding").map(|s| s.as_str()).unwrap_or(""), is_mdd);
+        let title = attributes.get("Title").cloned().filter(|t| !t.trim().is_empty())
+            .unwrap_or_else(|| file_path.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default());
+
+        // 2. Keyword section header: 5 numbers + adler32 (v2.0), or 4 numbers
+        let mut pos = advance(header_len, 8)?;
+        let section_len = if wide { 5 * 8 + 4 } else { 4 * 4 };
+        let section = sections.read(pos, section_len, "keyword section header")?;
+        let mut kr = Reader::new(&section, wide);
+        let num_key_blocks = kr.number()?;
+        let _num_entries = kr.number()?;
+        let key_info_decomp_size = if wide { Some(kr.number()?) } else { None };
+        let key_info_size = kr.number()?;
+        let key_blocks_size = kr.number()?;
+        pos = advance(pos, section_len)?;
+
+        // 3. Key block info
+        let info_raw = sections.read(pos, key_info_size, "key block info")?;
+        pos = advance(pos, key_info_size)?;
+        let info = if wide {
+            if info_raw.len() < 8 {
+                return Err(MdictError::Format("key block info too short".into()));
+            }
+            let raw = if encrypted & 2 != 0 { decrypt_key_block_info(&info_raw) } else { info_raw };
+            if raw[..4] != [2, 0, 0, 0] {
+                return Err(MdictError::Format("key block info is not zlib compressed".into()));
+            }
+            let size = declared_size(key_info_decomp_size.unwrap_or(0), "key block info")?;
+            zlib_decompress(&raw[8..], size)?
+        } else {
+            info_raw
+        };
+
+        let unit = encoding.unit();
+        let terminator = if wide { unit } else { 0 };
+        let mut ir = Reader::new(&info, wide);
+        let mut key_block_sizes = Vec::new();
+        while !ir.done() && (key_block_sizes.len() as u64) < num_key_blocks {
+            let _entries = ir.number()?;
+            let first = ir.key_size()?;
+            ir.take(first * unit + terminator)?;
+            let last = ir.key_size()?;
+            ir.take(last * unit + terminator)?;
+            let comp = ir.number()?;
+            let decomp = declared_size(ir.number()?, "key block")?;
+            key_block_sizes.push((comp, decomp));
+        }
+
+        // 4. Key blocks: [key_id: number][key text + terminator]...
+        let mut entries: Vec<(u64, String)> = Vec::new();
+        let mut block_pos = pos;
+        for (comp, decomp) in key_block_sizes {
+            let block = sections.read(block_pos, comp, "key block")?;
+            block_pos = advance(block_pos, comp)?;
+            let keys = decompress_block(&block, decomp)?;
+            let mut kr = Reader::new(&keys, wide);
+            while !kr.done() {
+                let key_id = kr.number()?;
+                let start = kr.pos;
+                let mut end = start;
+                while end + unit <= keys.len() && keys[end..end + unit].iter().any(|b| *b != 0) {
+                    end += unit;
+                }
+                let text = encoding.decode(&keys[start..end]);
+                kr.pos = (end + unit).min(keys.len());
+                entries.push((key_id, text));
+            }
+        }
+        pos = advance(pos, key_blocks_size)?;
+
+        // 5. Record section header + block info
+        let numbers = if wide { 4 * 8 } else { 4 * 4 };
+        let section = sections.read(pos, numbers, "record section header")?;
+        let mut rr = Reader::new(&section, wide);
+        let num_record_blocks = rr.number()?;
+        let _num_record_entries = rr.number()?;
+        let record_info_size = rr.number()?;
+        let _record_blocks_size = rr.number()?;
+        pos = advance(pos, numbers)?;
+
+        let record_info = sections.read(pos, record_info_size, "record block info")?;
+        let mut rr = Reader::new(&record_info, wide);
+        let mut record_blocks = Vec::new();
+        let mut file_offset = advance(pos, record_info_size)?;
+        let mut decomp_offset = 0u64;
+        while !rr.done() && (record_blocks.len() as u64) < num_record_blocks {
+            let comp_size = rr.number()?;
+            let decomp_size = rr.number()?;
+            let end = advance(file_offset, comp_size)?;
+            if end > sections.len || comp_size > MAX_SECTION_SIZE || decomp_size > MAX_SECTION_SIZE {
+                return Err(MdictError::Format("record block out of range".into()));
+            }
+            record_blocks.push(RecordBlock { file_offset, comp_size, decomp_offset, decomp_size });
+            file_offset = end;
+            decomp_offset = advance(decomp_offset, decomp_size)?;
+        }
+
+        // 6. Key -> record span. A record ends where the next key's record starts.
+        entries.sort_by_key(|(id, _)| *id);
+        let mut keys = HashMap::with_capacity(entries.len());
+        for i in 0..entries.len() {
+            let start = entries[i].0;
+            let end = entries.get(i + 1).map(|(id, _)| *id).unwrap_or(decomp_offset);
+            let key = if is_mdd { normalize_resource_path(&entries[i].1) } else { entries[i].1.clone() };
+            keys.entry(key).or_insert((start, end));
+        }
+
+        Ok(Self {
+            title,
+            attributes,
+            file_path,
+            encoding,
+            keys,
+            record_blocks,
+            file: None, // Lazy open
+            cached_block: None,
+        })
+    }
+
+    pub fn headwords(&self) -> impl Iterator<Item = &String> {
+        self.keys.keys()
+    }
+
+    pub fn entry_count(&self) -> usize {
+        self.keys.len()
+    }
+
+    fn read_span(&mut self, start: u64, end: u64) -> Result<Vec<u8>, MdictError> {
+        let idx = match self.record_blocks.binary_search_by(|b| {
+            if start < b.decomp_offset {
+                std::cmp::Ordering::Greater
+            } else if start >= b.decomp_offset + b.decomp_size {
+                std::cmp::Ordering::Less
+            } else {
+                std::cmp::Ordering::Equal
+            }
+        }) {
+            Ok(i) => i,
+            Err(_) => return Err(MdictError::Format(format!("record offset {} out of range", start))),
+        };
+
+        if self.cached_block.as_ref().map(|(i, _)| *i) != Some(idx) {
+            if self.file.is_none() {
+                self.file = Some(File::open(&self.file_path)?);
+            }
+            let block = self.record_blocks[idx].clone();
+            let file = self.file.as_mut().unwrap();
+            let raw = read_exact_at(file, block.file_offset, block.comp_size as usize)?;
+            let decoded = decompress_block(&raw, block.decomp_size as usize)?;
+            self.cached_block = Some((idx, decoded));
+        }
+
+        let block = &self.record_blocks[idx];
+        let (_, bytes) = self.cached_block.as_ref().unwrap();
+        let from = (start - block.decomp_offset) as usize;
+        let to = ((end - block.decomp_offset) as usize).min(bytes.len());
+        Ok(bytes[from.min(to)..to].to_vec())
+    }
+
+    fn find_span(&self, key: &str) -> Option<(u64, u64)> {
+        self.keys.get(key).copied()
+            .or_else(|| self.keys.get(&key.to_lowercase()).copied())
+    }
+
+    /// Definition text for a headword. Follows `@@@LINK=other` redirects once.
+    pub fn lookup(&mut self, word: &str) -> Option<String> {
+        let (start, end) = self.find_span(word)?;
+        let bytes = self.read_span(start, end).ok()?;
+        let text = self.encoding.decode(&bytes);
+        let text = text.trim_end_matches(['\0', '\r', '\n']).to_string();
+
+        if let Some(target) = text.strip_prefix("@@@LINK=") {
+            let target = target.trim().to_string();
+            if target != word {
+                let (s, e) = self.find_span(&target)?;
+                let bytes = self.read_span(s, e).ok()?;
+                return Some(self.encoding.decode(&bytes).trim_end_matches(['\0', '\r', '\n']).to_string());
+            }
+        }
+        Some(text)
+    }
+
+    /// Raw resource bytes from an `.mdd` file (audio, images, CSS).
+    pub fn resource(&mut self, path: &str) -> Option<Vec<u8>> {
+        let (start, end) = self.find_span(&normalize_resource_path(path))?;
+        self.read_span(start, end).ok()
+    }
+}
+
+/// MDD keys look like `\sound\hello.mp3`; normalize to that form.
+pub fn normalize_resource_path(path: &str) -> String {
+    let p = path.trim().replace('/', "\\");
+    let p = if p.starts_with('\\') { p } else { format!("\\{}", p) };
+    p.to_lowercase()
+}
@@ -1 +1,7 @@
 pub mod loader;
+pub mod compression;
+pub mod mdict;
+pub mod parser;
+pub mod reverse;
+
+mod tests;
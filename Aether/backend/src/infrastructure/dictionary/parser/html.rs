@@ -0,0 +1,223 @@
+use super::markup::{attribute, decode_entities, tokenize, Node};
+use super::plain::feed_line;
+use super::sanitize::sanitize_html;
+use super::{normalize_pos, EntryBuilder, EntryMarkup, EntryParser, ParsedEntry};
+
+/// HTML entries (MDict, StarDict `h`). Structure comes from common class
+/// names (`pos`, `phon`, `ex`, `xr`, `reg`, `sense`...) with line-based
+/// fallbacks for unclassed markup.
+pub struct HtmlParser;
+
+impl EntryParser for HtmlParser {
+    fn name(&self) -> &'static str {
+        "html"
+    }
+
+    fn score(&self, raw: &str, markup: EntryMarkup) -> u8 {
+        match markup {
+            EntryMarkup::Html => 90,
+            _ if looks_like_html(raw) => 50,
+            _ => 0,
+        }
+    }
+
+    fn parse(&self, raw: &str) -> ParsedEntry {
+        let mut entry = walk(raw, html_role, false);
+        entry.html = Some(sanitize_html(raw));
+        entry
+    }
+}
+
+pub(crate) fn looks_like_html(raw: &str) -> bool {
+    const TAGS: &[&str] = &["<br", "<div", "<span", "<p>", "<p ", "<b>", "<i>", "<li", "<font", "<a ", "<table", "<ol", "<ul"];
+    let lower = raw.to_lowercase();
+    TAGS.iter().any(|t| lower.contains(t))
+}
+
+/// What an element contributes to the entry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Role {
+    Text,
+    Skip,  // Headwords, scripts, etymology
+    Block, // Line break
+    Sense, // Container of one definition
+    Pos,
+    Phonetic,
+    Example,
+    Reference,
+    Label,
+}
+
+fn html_role(name: &str, attrs: &str) -> Role {
+    if matches!(name, "script" | "style" | "head" | "title" | "h1" | "h2") {
+        return Role::Skip;
+    }
+
+    if let Some(class) = attribute(attrs, "class") {
+        for token in class.split_whitespace() {
+            let role = match token.to_lowercase().as_str() {
+                "hw" | "hwd" | "headword" => Role::Skip,
+                "pos" | "gram" | "wordtype" | "part-of-speech" | "partofspeech" => Role::Pos,
+                "phon" | "phonetic" | "phonetics" | "pron" | "ipa" => Role::Phonetic,
+                "ex" | "exa" | "eg" | "x" | "example" | "examples" | "sentence" => Role::Example,
+                "xr" | "xref" | "xrefs" | "crossref" | "cross-ref" | "see" => Role::Reference,
+                "reg" | "register" | "label" | "lab" | "usage" | "domain" | "subj" => Role::Label,
+                "sense" | "sn" | "sn-g" | "meaning" => Role::Sense,
+                _ => continue,
+            };
+            return role;
+        }
+    }
+
+    match name {
+        "li" | "dd" => Role::Sense,
+        "br" | "hr" | "p" | "div" | "ul" | "ol" | "dl" | "dt" | "tr" | "table" | "blockquote" | "h3" | "h4" | "h5" | "h6" => Role::Block,
+        "a" => match attribute(attrs, "href") {
+            Some(h) if h.starts_with("entry://") => Role::Reference,
+            Some(h) if h.starts_with("sound://") => Role::Skip,
+            _ => Role::Text,
+        },
+        _ => Role::Text,
+    }
+}
+
+/// Walks markup, using `role_of(tag, attrs)` to decide what each element
+/// means. With `newline_breaks`, newlines in text end a line (XDXF).
+pub(crate) fn walk(raw: &str, role_of: fn(&str, &str) -> Role, newline_breaks: bool) -> ParsedEntry {
+    let mut builder = EntryBuilder::default();
+    let mut stack: Vec<(String, Role)> = Vec::new();
+    let mut line = String::new();
+    let mut capture: Option<(Role, String, usize)> = None; // role, text, stack index
+
+    for node in tokenize(raw) {
+        let skipping = stack.iter().any(|(_, r)| *r == Role::Skip);
+        match node {
+            Node::Text(text) => {
+                if skipping {
+                    continue;
+                }
+                let text = decode_entities(text);
+                match capture.as_mut() {
+                    Some((_, buf, _)) => buf.push_str(&text),
+                    None if newline_breaks => {
+                        let mut parts = text.split('\n').peekable();
+                        while let Some(part) = parts.next() {
+                            line.push_str(part);
+                            if parts.peek().is_some() {
+                                flush_line(&mut builder, &mut line, in_sense(&stack));
+                            }
+                        }
+                    }
+                    None => line.push_str(&text),
+                }
+            }
+            Node::Open { name, attrs, self_closing } => {
+                if skipping || capture.is_some() {
+                    // Nested markup inside a captured span only separates words
+                    if let Some((_, buf, _)) = capture.as_mut() {
+                        buf.push(' ');
+                    }
+                    if !self_closing {
+                        stack.push((name, Role::Text));
+                    }
+                    continue;
+                }
+
+                let role = role_of(&name, attrs);
+                match role {
+                    Role::Block | Role::Pos | Role::Example | Role::Reference => {
+                        flush_line(&mut builder, &mut line, in_sense(&stack));
+                    }
+                    Role::Sense => {
+                        flush_line(&mut builder, &mut line, in_sense(&stack));
+                        builder.start_definition();
+                    }
+                    // Images and other void elements still separate words
+                    Role::Text if self_closing => line.push(' '),
+                    _ => {}
+                }
+                if self_closing {
+                    continue;
+                }
+                if matches!(role, Role::Pos | Role::Phonetic | Role::Example | Role::Reference | Role::Label) {
+                    capture = Some((role, String::new(), stack.len()));
+                }
+                stack.push((name, role));
+            }
+            Node::Close { name } => {
+                let idx = match stack.iter().rposition(|(n, _)| *n == name) {
+                    Some(idx) => idx,
+                    None => continue,
+                };
+                let role = stack[idx].1;
+                stack.truncate(idx);
+
+                if let Some((captured, buf, depth)) = capture.take() {
+                    if idx <= depth {
+                        emit(&mut builder, &mut line, captured, &buf, in_sense(&stack));
+                    } else {
+                        capture = Some((captured, buf, depth));
+                        continue;
+                    }
+                }
+                match role {
+                    Role::Block => flush_line(&mut builder, &mut line, in_sense(&stack)),
+                    Role::Sense => flush_line(&mut builder, &mut line, true),
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    if let Some((role, buf, _)) = capture.take() {
+        emit(&mut builder, &mut line, role, &buf, in_sense(&stack));
+    }
+    flush_line(&mut builder, &mut line, in_sense(&stack));
+    builder.finish()
+}
+
+fn in_sense(stack: &[(String, Role)]) -> bool {
+    stack.iter().any(|(_, r)| *r == Role::Sense)
+}
+
+/// Text collected since the last break: inside a sense it extends the current
+/// definition, otherwise it is classified like a plain-text line.
+fn flush_line(builder: &mut EntryBuilder, line: &mut String, in_sense: bool) {
+    let text = std::mem::take(line);
+    if text.trim().is_empty() {
+        return;
+    }
+    if in_sense {
+        builder.text(&text);
+    } else {
+        feed_line(builder, &text);
+    }
+}
+
+fn emit(builder: &mut EntryBuilder, line: &mut String, role: Role, text: &str, in_sense: bool) {
+    let text = text.trim();
+    if text.is_empty() {
+        return;
+    }
+    match role {
+        Role::Pos => {
+            let first = text.split_whitespace().next().unwrap_or(text);
+            match normalize_pos(text).or_else(|| normalize_pos(first)) {
+                Some(pos) => builder.start_meaning(pos),
+                // Grammar notes that are not a part of speech stay with the text
+                None => line.push_str(&format!(" ({}) ", text)),
+            }
+        }
+        Role::Phonetic => builder.phonetic(text),
+        Role::Example => builder.example(text),
+        Role::Reference => builder.cross_reference(text),
+        // XDXF marks parts of speech with <abr> too
+        Role::Label if normalize_pos(text).is_some() => {
+            flush_line(builder, line, in_sense);
+            builder.start_meaning(text);
+        }
+        // Inline so the definition's label extraction picks it up
+        Role::Label => line.push_str(&format!(" ({}) ", text)),
+        _ => line.push_str(text),
+    }
+}
@@ -0,0 +1,200 @@
+// Minimal, forgiving tag scanner shared by the HTML and XDXF parsers and the
+// sanitizer. Dictionary markup is rarely well-formed, so this never fails:
+// unknown constructs degrade to text.
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Node<'a> {
+    Open { name: String, attrs: &'a str, self_closing: bool },
+    Close { name: String },
+    Text(&'a str),
+}
+
+const VOID_ELEMENTS: &[&str] = &["br", "img", "hr", "meta", "link", "input", "source", "wbr", "col", "area", "base"];
+
+pub fn is_void(name: &str) -> bool {
+    VOID_ELEMENTS.contains(&name)
+}
+
+pub fn tokenize(input: &str) -> Vec<Node<'_>> {
+    let mut nodes = Vec::new();
+    let mut pos = 0;
+    let mut text_start = 0;
+
+    while let Some(offset) = input[pos..].find('<') {
+        let lt = pos + offset;
+        let rest = &input[lt..];
+
+        // Comments, doctype, CDATA: skipped entirely
+        if let Some(body) = rest.strip_prefix("<!--") {
+            push_text(&mut nodes, &input[text_start..lt]);
+            let end = body.find("-->").map(|e| lt + 4 + e + 3).unwrap_or(input.len());
+            pos = end;
+            text_start = end;
+            continue;
+        }
+        if rest.starts_with("<!") || rest.starts_with("<?") {
+            push_text(&mut nodes, &input[text_start..lt]);
+            let end = rest.find('>').map(|e| lt + e + 1).unwrap_or(input.len());
+            pos = end;
+            text_start = end;
+            continue;
+        }
+
+        let closing = rest.starts_with("</");
+        let name_start = lt + if closing { 2 } else { 1 };
+        let starts_with_letter = input[name_start..].chars().next().is_some_and(|c| c.is_ascii_alphabetic());
+        let gt = match find_tag_end(&input[lt..]) {
+            Some(e) if starts_with_letter => lt + e,
+            // A stray '<' ("a < b"): keep it as text
+            _ => {
+                pos = lt + 1;
+                continue;
+            }
+        };
+
+        push_text(&mut nodes, &input[text_start..lt]);
+        let inner = &input[name_start..gt];
+        let name_end = inner.find(|c: char| c.is_whitespace() || c == '/' || c == '>').unwrap_or(inner.len());
+        let name = inner[..name_end].to_lowercase();
+
+        if closing {
+            nodes.push(Node::Close { name });
+        } else {
+            let attrs = inner[name_end..].trim();
+            let self_closing = attrs.ends_with('/') || is_void(&name);
+            let attrs = attrs.trim_end_matches('/').trim_end();
+            nodes.push(Node::Open { name, attrs, self_closing });
+        }
+        pos = gt + 1;
+        text_start = pos;
+    }
+    push_text(&mut nodes, &input[text_start..]);
+    nodes
+}
+
+fn push_text<'a>(nodes: &mut Vec<Node<'a>>, text: &'a str) {
+    if !text.is_empty() {
+        nodes.push(Node::Text(text));
+    }
+}
+
+/// Index of the `>` ending the tag at the start of `s`, skipping quoted values.
+fn find_tag_end(s: &str) -> Option<usize> {
+    let mut quote: Option<char> = None;
+    for (i, c) in s.char_indices().skip(1) {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None if c == '"' || c == '\'' => quote = Some(c),
+            None if c == '>' => return Some(i),
+            None if c == '<' => return None,
+            None => {}
+        }
+    }
+    None
+}
+
+/// Attributes of a tag as lowercase name/value pairs (values entity-decoded).
+pub fn attributes(attrs: &str) -> Vec<(String, String)> {
+    let mut out = Vec::new();
+    let mut rest = attrs.trim();
+    while !rest.is_empty() {
+        let name_end = rest.find(|c: char| c.is_whitespace() || c == '=').unwrap_or(rest.len());
+        let name = rest[..name_end].to_lowercase();
+        rest = rest[name_end..].trim_start();
+
+        let mut value = String::new();
+        if let Some(after) = rest.strip_prefix('=') {
+            let after = after.trim_start();
+            match after.chars().next() {
+                Some(q @ ('"' | '\'')) => {
+                    let end = after[1..].find(q).map(|e| e + 1).unwrap_or(after.len());
+                    value = decode_entities(&after[1..end]);
+                    rest = after.get(end + 1..).unwrap_or("").trim_start();
+                }
+                _ => {
+                    let end = after.find(char::is_whitespace).unwrap_or(after.len());
+                    value = decode_entities(&after[..end]);
+                    rest = after[end..].trim_start();
+                }
+            }
+        }
+        if !name.is_empty() {
+            out.push((name, value));
+        }
+    }
+    out
+}
+
+pub fn attribute(attrs: &str, name: &str) -> Option<String> {
+    attributes(attrs).into_iter().find(|(n, _)| n == name).map(|(_, v)| v)
+}
+
+pub fn decode_entities(s: &str) -> String {
+    if !s.contains('&') {
+        return s.to_string();
+    }
+    let mut out = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(amp) = rest.find('&') {
+        out.push_str(&rest[..amp]);
+        let after = &rest[amp + 1..];
+        let decoded = after.find(';').filter(|e| *e <= 10).and_then(|end| {
+            let entity = &after[..end];
+            let c = match entity {
+                "amp" => Some('&'),
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                "nbsp" => Some(' '),
+                _ => entity.strip_prefix('#').and_then(|n| {
+                    match n.strip_prefix(['x', 'X']) {
+                        Some(hex) => u32::from_str_radix(hex, 16).ok(),
+                        None => n.parse().ok(),
+                    }
+                    .and_then(char::from_u32)
+                }),
+            };
+            c.map(|c| (c, end))
+        });
+        match decoded {
+            Some((c, end)) => {
+                out.push(c);
+                rest = &after[end + 1..];
+            }
+            None => {
+                out.push('&');
+                rest = after;
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+pub fn escape_text(s: &str) -> String {
+    s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+pub fn escape_attribute(s: &str) -> String {
+    escape_text(s).replace('"', "&quot;")
+}
+
+/// Visible text of markup, tags replaced by spaces (script/style content dropped).
+pub fn text_content(input: &str) -> String {
+    let mut out = String::with_capacity(input.len());
+    let mut hidden: Option<String> = None;
+    for node in tokenize(input) {
+        match node {
+            Node::Open { name, self_closing: false, .. } if hidden.is_none() && matches!(name.as_str(), "script" | "style" | "head") => {
+                hidden = Some(name);
+            }
+            Node::Close { name } if hidden.as_deref() == Some(name.as_str()) => hidden = None,
+            _ if hidden.is_some() => {}
+            Node::Text(text) => out.push_str(&decode_entities(text)),
+            _ => out.push(' '),
+        }
+    }
+    out
+}
@@ -0,0 +1,387 @@
+pub mod html;
+pub mod markup;
+pub mod oxford;
+pub mod plain;
+pub mod sanitize;
+pub mod xdxf;
+
+mod tests;
+
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, RwLock};
+
+/// Part of speech used when an entry never names one.
+pub const DEFAULT_POS: &str = "Definition";
+
+/// Markup a dictionary declares for its entries (StarDict `sametypesequence`;
+/// MDict is always HTML). Parsers treat it as a hint, not a guarantee.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EntryMarkup {
+    Plain,
+    Html,
+    Xdxf,
+    Unknown,
+}
+
+impl EntryMarkup {
+    /// `m`/`l`/`t`/`y` are plain text, `h`/`g` HTML and Pango, `x` XDXF.
+    pub fn from_sametypesequence(seq: &str) -> Self {
+        match seq.trim().chars().next() {
+            Some('m') | Some('l') | Some('t') | Some('y') => EntryMarkup::Plain,
+            Some('h') | Some('g') => EntryMarkup::Html,
+            Some('x') => EntryMarkup::Xdxf,
+            _ => EntryMarkup::Unknown,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct Definition {
+    pub definition: String,
+    pub example: Option<String>, // First of `examples`, kept for older clients
+    #[serde(default)]
+    pub examples: Vec<String>,
+    #[serde(default)]
+    pub labels: Vec<String>, // Register/usage labels: "informal", "US", ...
+    #[serde(default)]
+    pub cross_references: Vec<String>,
+}
+
+impl Definition {
+    pub fn new(definition: impl Into<String>) -> Self {
+        Self { definition: definition.into(), ..Default::default() }
+    }
+
+    pub fn with_example(mut self, example: Option<String>) -> Self {
+        if let Some(e) = example.filter(|e| !e.trim().is_empty()) {
+            self.examples.push(e.clone());
+            self.example = Some(e);
+        }
+        self
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Meaning {
+    #[serde(rename = "partOfSpeech")]
+    pub part_of_speech: String,
+    pub definitions: Vec<Definition>,
+}
+
+#[derive(Serialize, Debug, Clone, Default, PartialEq)]
+pub struct ParsedEntry {
+    pub phonetic: Option<String>,
+    pub meanings: Vec<Meaning>,
+    /// Sanitized original markup, for HTML dictionaries whose layout is worth keeping.
+    pub html: Option<String>,
+}
+
+/// Turns one raw dictionary entry into normalized meanings.
+pub trait EntryParser: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    /// How well this parser fits `raw` (0 = not at all). The registry uses the best fit.
+    fn score(&self, raw: &str, markup: EntryMarkup) -> u8;
+
+    fn parse(&self, raw: &str) -> ParsedEntry;
+}
+
+/// Registered entry parsers, picked per entry by `EntryParser::score`.
+#[derive(Clone)]
+pub struct EntryParserRegistry {
+    parsers: Arc<RwLock<Vec<Box<dyn EntryParser>>>>,
+}
+
+impl Default for EntryParserRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EntryParserRegistry {
+    pub fn new() -> Self {
+        Self {
+            parsers: Arc::new(RwLock::new(Vec::new())),
+        }
+    }
+
+    /// Registry with the built-in plain, Oxford, HTML and XDXF parsers.
+    pub fn with_defaults() -> Self {
+        let registry = Self::new();
+        registry.register(plain::PlainTextParser);
+        registry.register(oxford::OxfordParser);
+        registry.register(html::HtmlParser);
+        registry.register(xdxf::XdxfParser);
+        registry
+    }
+
+    pub fn register<P: EntryParser + 'static>(&self, parser: P) {
+        let mut parsers = self.parsers.write().expect("Parser registry lock poisoned");
+        parsers.push(Box::new(parser));
+    }
+
+    /// Parses with the best-scoring parser; earlier registrations win ties.
+    /// Falls back to plain text when nothing claims the entry.
+    pub fn parse(&self, raw: &str, markup: EntryMarkup) -> ParsedEntry {
+        let parsers = self.parsers.read().expect("Parser registry lock poisoned");
+        let mut best: Option<(u8, &dyn EntryParser)> = None;
+        for parser in parsers.iter() {
+            let score = parser.score(raw, markup);
+            if score > 0 && best.is_none_or(|(s, _)| score > s) {
+                best = Some((score, parser.as_ref()));
+            }
+        }
+        match best {
+            Some((_, parser)) => parser.parse(raw),
+            None => plain::PlainTextParser.parse(raw),
+        }
+    }
+}
+
+/// Collects meanings while a parser walks an entry. Definitions are finalized
+/// (labels and cross-references pulled out of the text) when the next one starts.
+#[derive(Default)]
+pub(crate) struct EntryBuilder {
+    phonetic: Option<String>,
+    meanings: Vec<Meaning>,
+    draft: Option<Definition>,
+}
+
+impl EntryBuilder {
+    pub fn phonetic(&mut self, text: &str) {
+        let p = collapse_whitespace(text);
+        if self.phonetic.is_none() && !p.is_empty() {
+            self.phonetic = Some(p);
+        }
+    }
+
+    pub fn has_phonetic(&self) -> bool {
+        self.phonetic.is_some()
+    }
+
+    pub fn start_meaning(&mut self, pos: &str) {
+        self.flush();
+        let pos = normalize_pos(pos).map(str::to_string).unwrap_or_else(|| collapse_whitespace(pos));
+        if pos.is_empty() {
+            return;
+        }
+        // Repeated markers for the same part of speech share one block
+        if self.meanings.last().is_some_and(|m| m.part_of_speech == pos) {
+            return;
+        }
+        self.meanings.push(Meaning { part_of_speech: pos, definitions: Vec::new() });
+    }
+
+    pub fn start_definition(&mut self) {
+        self.flush();
+        self.draft = Some(Definition::default());
+    }
+
+    pub fn text(&mut self, text: &str) {
+        let text = collapse_whitespace(text);
+        if text.is_empty() {
+            return;
+        }
+        let draft = self.draft.get_or_insert_with(Definition::default);
+        if !draft.definition.is_empty() {
+            draft.definition.push(' ');
+        }
+        draft.definition.push_str(&text);
+    }
+
+    pub fn example(&mut self, text: &str) {
+        let text = collapse_whitespace(text);
+        let text = text.trim_matches(|c: char| c == '*' || c == '•' || c.is_whitespace());
+        if !text.is_empty() {
+            self.draft.get_or_insert_with(Definition::default).examples.push(text.to_string());
+        }
+    }
+
+    pub fn label(&mut self, text: &str) {
+        let label = register_label(text).map(str::to_string).unwrap_or_else(|| collapse_whitespace(text));
+        if label.is_empty() {
+            return;
+        }
+        let draft = self.draft.get_or_insert_with(Definition::default);
+        if !draft.labels.contains(&label) {
+            draft.labels.push(label);
+        }
+    }
+
+    pub fn cross_reference(&mut self, text: &str) {
+        let target = clean_reference(text);
+        if target.is_empty() {
+            return;
+        }
+        let draft = self.draft.get_or_insert_with(Definition::default);
+        if !draft.cross_references.contains(&target) {
+            draft.cross_references.push(target);
+        }
+    }
+
+    fn flush(&mut self) {
+        if let Some(mut d) = self.draft.take() {
+            finalize_definition(&mut d);
+            if d.definition.is_empty() && d.examples.is_empty() && d.cross_references.is_empty() {
+                return;
+            }
+            if self.meanings.is_empty() {
+                self.meanings.push(Meaning { part_of_speech: DEFAULT_POS.to_string(), definitions: Vec::new() });
+            }
+            if let Some(m) = self.meanings.last_mut() {
+                m.definitions.push(d);
+            }
+        }
+    }
+
+    pub fn finish(mut self) -> ParsedEntry {
+        self.flush();
+        self.meanings.retain(|m| !m.definitions.is_empty());
+        ParsedEntry { phonetic: self.phonetic, meanings: self.meanings, html: None }
+    }
+}
+
+pub(crate) fn collapse_whitespace(s: &str) -> String {
+    s.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Full part-of-speech name for a marker like `n.`, `vt` or `adj`.
+pub fn normalize_pos(marker: &str) -> Option<&'static str> {
+    let m = marker.trim().trim_matches(|c| c == '(' || c == ')').to_lowercase();
+    let pos = match m.as_str() {
+        "n" | "n." | "noun" | "nouns" => "noun",
+        "v" | "v." | "vt" | "vt." | "vi" | "vi." | "v.t." | "v.i." | "verb" => "verb",
+        "a." | "adj" | "adj." | "adjective" => "adjective",
+        "ad." | "adv" | "adv." | "adverb" => "adverb",
+        "prep" | "prep." | "preposition" => "preposition",
+        "conj" | "conj." | "conjunction" => "conjunction",
+        "pron" | "pron." | "pronoun" => "pronoun",
+        "int." | "interj" | "interj." | "interjection" | "exclamation" => "interjection",
+        "det" | "det." | "determiner" => "determiner",
+        "art." | "article" => "article",
+        "abbr" | "abbr." | "abbreviation" => "abbreviation",
+        "num" | "num." | "numeral" => "numeral",
+        "aux" | "aux." | "auxiliary" => "auxiliary verb",
+        "modal" | "modal v" => "modal verb",
+        "pref" | "pref." | "prefix" => "prefix",
+        "suff" | "suff." | "suffix" => "suffix",
+        "idm" | "idiom" => "idiom",
+        "phr v" | "phrasal verb" => "phrasal verb",
+        _ => return None,
+    };
+    Some(pos)
+}
+
+/// Canonical register/usage label for `(infml)`, `[formal]`, `esp US` and the like.
+pub fn register_label(text: &str) -> Option<&'static str> {
+    let t = text.trim().trim_matches(|c| matches!(c, '(' | ')' | '[' | ']' | '.' | ',')).trim();
+    let t = t.strip_prefix("esp ").or_else(|| t.strip_prefix("esp. ")).unwrap_or(t);
+    let label = match t.to_lowercase().as_str() {
+        "infml" | "informal" | "inf" | "口" => "informal",
+        "fml" | "formal" => "formal",
+        "sl" | "slang" => "slang",
+        "colloq" | "colloquial" => "colloquial",
+        "arch" | "archaic" | "obs" | "obsolete" => "archaic",
+        "dated" | "old-fashioned" | "old use" => "dated",
+        "derog" | "derogatory" | "pej" | "pejorative" => "derogatory",
+        "offens" | "offensive" => "offensive",
+        "vulg" | "vulgar" | "taboo" | "△" => "vulgar",
+        "joc" | "jocular" | "hum" | "humorous" => "humorous",
+        "euph" | "euphemistic" => "euphemistic",
+        "fig" | "figurative" => "figurative",
+        "lit" | "literary" => "literary",
+        "poet" | "poetic" => "poetic",
+        "rhet" | "rhetorical" => "rhetorical",
+        "ironic" | "ironical" => "ironic",
+        "techn" | "technical" => "technical",
+        "rare" => "rare",
+        "dial" | "dialect" => "dialect",
+        "us" | "ame" | "name" | "american" => "US",
+        "brit" | "bre" | "british" => "British",
+        _ => return None,
+    };
+    Some(label)
+}
+
+fn clean_reference(text: &str) -> String {
+    let t = collapse_whitespace(text);
+    let t = t.trim_start_matches(|c: char| matches!(c, '=' | '>' | '⇒' | '→') || c.is_whitespace());
+    let lower = t.to_lowercase();
+    let mut t = t;
+    for prefix in ["illus at ", "see also ", "see ", "cf. ", "cf ", "compare "] {
+        if lower.starts_with(prefix) {
+            t = &t[prefix.len()..];
+            break;
+        }
+    }
+    // Targets are headwords: stop at a translation or note that follows them
+    let end = t.find(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, ' ' | '-' | '\'' | '/' | '.')))
+        .unwrap_or(t.len());
+    t[..end].trim().trim_end_matches(['.', ',', ';', ':', ')']).trim().to_string()
+}
+
+/// Pulls `(informal)`-style labels and `see X` / `=> X` references out of the
+/// definition text and fills `example` from `examples`.
+fn finalize_definition(d: &mut Definition) {
+    let mut text = collapse_whitespace(&d.definition);
+
+    // Parenthesized/bracketed groups that are entirely register labels
+    let mut kept = String::with_capacity(text.len());
+    let mut rest = text.as_str();
+    while let Some(open) = rest.find(['(', '[']) {
+        let close_char = if rest[open..].starts_with('(') { ')' } else { ']' };
+        let Some(close) = rest[open..].find(close_char).map(|c| open + c) else { break };
+        let inner = &rest[open + 1..close];
+        let labels: Vec<&'static str> = inner.split([',', ';']).filter_map(register_label).collect();
+        let all_labels = !labels.is_empty() && labels.len() == inner.split([',', ';']).count();
+
+        let reference = inner.trim_start().to_lowercase();
+        if all_labels {
+            kept.push_str(&rest[..open]);
+            for l in labels {
+                if !d.labels.iter().any(|x| x == l) {
+                    d.labels.push(l.to_string());
+                }
+            }
+        } else if reference.starts_with("see ") || reference.starts_with("cf") || reference.starts_with("compare ") {
+            kept.push_str(&rest[..open]);
+            let target = clean_reference(inner);
+            if !target.is_empty() && !d.cross_references.contains(&target) {
+                d.cross_references.push(target);
+            }
+        } else {
+            kept.push_str(&rest[..=close]);
+        }
+        rest = &rest[close + 1..];
+    }
+    kept.push_str(rest);
+    text = collapse_whitespace(&kept);
+
+    // Arrow references: "=> upset", "→ apple-cart"
+    while let Some(pos) = text.find("=>").or_else(|| text.find('⇒')).or_else(|| text.find('→')) {
+        let after = &text[pos..];
+        let end = after.find(['.', ';', ',']).unwrap_or(after.len());
+        let target = clean_reference(&after[..end]);
+        if !target.is_empty() && !d.cross_references.contains(&target) {
+            d.cross_references.push(target);
+        }
+        let skip = (pos + end + 1).min(text.len());
+        text = collapse_whitespace(&format!("{} {}", &text[..pos], &text[skip..]));
+    }
+
+    // A definition that is only a reference: "See apple."
+    let lower = text.to_lowercase();
+    if lower.starts_with("see ") || lower.starts_with("cf. ") {
+        let target = clean_reference(&text);
+        if !target.is_empty() && !d.cross_references.contains(&target) {
+            d.cross_references.push(target);
+        }
+        text.clear();
+    }
+
+    d.definition = text.trim_matches(|c: char| c == ';' || c == ',' || c == ':' || c.is_whitespace()).to_string();
+    if d.example.is_none() {
+        d.example = d.examples.first().cloned();
+    }
+}
@@ -0,0 +1,186 @@
+use std::sync::OnceLock;
+use regex::Regex;
+use super::{collapse_whitespace, normalize_pos, register_label, EntryBuilder, EntryMarkup, EntryParser, ParsedEntry};
+
+/// Oxford learner's style run-on text, as in the bundled 牛津现代英汉双解词典:
+///
+/// ```text
+/// / 5Apl; `æpl/ n 1 (a) round fruit ... 苹果. =>illus at fruit. (b) (also apple
+/// tree) tree bearing this fruit. * apple blossom 2 (idm) the apple of sb's eye
+/// person or thing that is loved: She is the apple of her father's eye.
+/// ```
+///
+/// Bare part-of-speech markers, numbered senses with `(a)` sub-senses,
+/// `*` examples, `(infml)` labels, `[C]` grammar codes and `=>` references.
+pub struct OxfordParser;
+
+impl EntryParser for OxfordParser {
+    fn name(&self) -> &'static str {
+        "oxford"
+    }
+
+    fn score(&self, raw: &str, markup: EntryMarkup) -> u8 {
+        match markup {
+            EntryMarkup::Plain | EntryMarkup::Unknown if looks_like_oxford(raw) => 60,
+            _ => 0,
+        }
+    }
+
+    fn parse(&self, raw: &str) -> ParsedEntry {
+        let text = collapse_whitespace(raw);
+        let mut builder = EntryBuilder::default();
+
+        // Pronunciation: the first /.../ near the start (after the headword, if any)
+        let mut rest = text.as_str();
+        if let Some(start) = text.find('/').filter(|s| *s < 60) {
+            if let Some(len) = text[start + 1..].find('/') {
+                builder.phonetic(&text[start..start + len + 2]);
+                rest = &text[start + len + 2..];
+            }
+        }
+
+        let tokens: Vec<&str> = rest.split_whitespace().collect();
+        let mut example = String::new();
+        let mut in_example = false;
+        let mut prev: Option<&str> = None;
+        let mut i = 0;
+
+        while i < tokens.len() {
+            let t = tokens[i];
+            let next = tokens.get(i + 1).copied();
+
+            // Parenthesized groups may span tokens: "(esp US)", "(phr v)"
+            if t.starts_with('(') {
+                let (group, used) = join_group(&tokens[i..], ')');
+                let inner = group.trim_start_matches('(').trim_end_matches([')', '.', ',']);
+                let labels: Vec<&str> = inner.split(',').map(str::trim).collect();
+
+                if matches!(inner, "idm" | "phr v") {
+                    finish_example(&mut builder, &mut example);
+                    in_example = false;
+                    builder.start_meaning(inner);
+                } else if inner.len() == 1 && inner.chars().all(|c| ('a'..='h').contains(&c)) {
+                    finish_example(&mut builder, &mut example);
+                    in_example = false;
+                    builder.start_definition();
+                } else if labels.iter().all(|l| register_label(l).is_some()) {
+                    for l in labels {
+                        builder.label(l);
+                    }
+                } else if in_example {
+                    push_word(&mut example, &group);
+                } else {
+                    builder.text(&group);
+                }
+                prev = Some(tokens[i + used - 1]);
+                i += used;
+                continue;
+            }
+
+            // Grammar codes: [C], [U], [Tn], [sing v]
+            if t.starts_with('[') {
+                let (group, used) = join_group(&tokens[i..], ']');
+                if group.len() > 20 {
+                    builder.text(&group);
+                }
+                prev = Some(tokens[i + used - 1]);
+                i += used;
+                continue;
+            }
+
+            if normalize_pos(t).is_some() && !t.contains('.') && at_boundary(prev) && next.is_some_and(starts_sense) {
+                finish_example(&mut builder, &mut example);
+                in_example = false;
+                builder.start_meaning(t);
+            } else if is_sense_number(t) && at_boundary(prev) {
+                finish_example(&mut builder, &mut example);
+                in_example = false;
+                builder.start_definition();
+            } else if t == "*" || t == "•" {
+                finish_example(&mut builder, &mut example);
+                in_example = true;
+            } else if t.starts_with("=>") || t.starts_with('⇒') {
+                // Reference runs to the end of the sentence
+                let mut target = String::new();
+                let mut used = 0;
+                for word in &tokens[i..] {
+                    push_word(&mut target, word);
+                    used += 1;
+                    if word.ends_with(['.', ';', ',']) {
+                        break;
+                    }
+                }
+                builder.cross_reference(&target);
+                prev = Some(tokens[i + used - 1]);
+                i += used;
+                continue;
+            } else if in_example {
+                push_word(&mut example, t);
+            } else if let Some(head) = t.strip_suffix(':') {
+                // "definition: Example sentence."
+                builder.text(head);
+                in_example = true;
+            } else {
+                builder.text(t);
+            }
+
+            prev = Some(t);
+            i += 1;
+        }
+
+        finish_example(&mut builder, &mut example);
+        builder.finish()
+    }
+}
+
+fn looks_like_oxford(raw: &str) -> bool {
+    static POS: OnceLock<Regex> = OnceLock::new();
+    static STRUCTURE: OnceLock<Regex> = OnceLock::new();
+    let pos = POS.get_or_init(|| {
+        Regex::new(r"(?:^|[\s/.])(?:n|v|adj|adv|prep|conj|pron|interj)\s+(?:\d|\[|\()").unwrap()
+    });
+    let structure = STRUCTURE.get_or_init(|| {
+        Regex::new(r"\s\d\s|\s\*\s|=>|\((?:infml|fml|derog|idm|joc|sl|esp US|US|Brit|phr v)\)").unwrap()
+    });
+    pos.is_match(raw) && structure.is_match(raw)
+}
+
+/// Joins tokens from the start of `tokens` until one closes the group (at most 5).
+fn join_group(tokens: &[&str], close: char) -> (String, usize) {
+    let mut group = String::new();
+    for (n, t) in tokens.iter().take(5).enumerate() {
+        push_word(&mut group, t);
+        if t.trim_end_matches(['.', ',', ';', ':']).ends_with(close) {
+            return (group, n + 1);
+        }
+    }
+    (tokens[0].to_string(), 1)
+}
+
+fn push_word(buf: &mut String, word: &str) {
+    if !buf.is_empty() {
+        buf.push(' ');
+    }
+    buf.push_str(word);
+}
+
+fn finish_example(builder: &mut EntryBuilder, example: &mut String) {
+    let text = std::mem::take(example);
+    builder.example(&text);
+}
+
+fn is_sense_number(t: &str) -> bool {
+    (1..=2).contains(&t.len()) && t.chars().all(|c| c.is_ascii_digit())
+}
+
+/// Markers only count at sentence boundaries or right after other markers.
+fn at_boundary(prev: Option<&str>) -> bool {
+    match prev {
+        None => true,
+        Some(p) => p.ends_with(['.', ';', ')', ']', ':']) || normalize_pos(p).is_some(),
+    }
+}
+
+fn starts_sense(next: &str) -> bool {
+    next.starts_with(['[', '(']) || is_sense_number(next)
+}
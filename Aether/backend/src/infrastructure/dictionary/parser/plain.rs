@@ -0,0 +1,149 @@
+use std::sync::OnceLock;
+use regex::Regex;
+use super::{normalize_pos, EntryBuilder, EntryMarkup, EntryParser, ParsedEntry};
+
+/// Line-oriented text such as 朗道英汉 or other `sametypesequence=m` dictionaries:
+///
+/// ```text
+/// *['æpl]
+/// n. 苹果, 苹果树
+/// 1. a round fruit  e.g. an apple a day
+/// 【医】 苹果
+/// ```
+pub struct PlainTextParser;
+
+impl EntryParser for PlainTextParser {
+    fn name(&self) -> &'static str {
+        "plain"
+    }
+
+    fn score(&self, _raw: &str, markup: EntryMarkup) -> u8 {
+        match markup {
+            EntryMarkup::Plain | EntryMarkup::Unknown => 10,
+            _ => 1,
+        }
+    }
+
+    fn parse(&self, raw: &str) -> ParsedEntry {
+        let mut builder = EntryBuilder::default();
+        for line in raw.lines() {
+            feed_line(&mut builder, line);
+        }
+        builder.finish()
+    }
+}
+
+fn numbering() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"^(?:\d{1,2}[.)、]|\(\d{1,2}\)|[a-h][.)]|\([a-h]\)|[①-⑳]|[-•*·])\s*").unwrap())
+}
+
+fn example_marker() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"(?i)^(?:e\.g\.|eg\.|ex\.|example:|例[:：]|例句[:：])\s*").unwrap())
+}
+
+fn inline_example() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"(?i)\s(?:e\.g\.|例[:：])\s*").unwrap())
+}
+
+fn reference_marker() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"(?i)^(?:syn(?:onyms?)?[:：]|see also[:：]?|=>|⇒|→)\s*").unwrap())
+}
+
+/// `/.../` or `[...]` at the start of a line; StarDict plain text often
+/// prefixes it with `*`.
+pub(crate) fn leading_phonetic(line: &str) -> Option<(&str, &str)> {
+    let t = line.trim_start().trim_start_matches('*');
+    let close = match t.chars().next()? {
+        '/' => '/',
+        '[' => ']',
+        _ => return None,
+    };
+    let end = t[1..].find(close)? + 1;
+    let inner = &t[1..end];
+    // Grammar codes like [C] or [U] are not pronunciations
+    if inner.trim().is_empty() || inner.len() > 60 || (close == ']' && inner.len() <= 4 && inner.chars().all(|c| c.is_ascii_alphabetic())) {
+        return None;
+    }
+    Some((&t[..=end], &t[end + 1..]))
+}
+
+/// Classifies one line of text and feeds it to the builder: part-of-speech
+/// headers, numbered senses, examples, references, domain labels.
+pub(crate) fn feed_line(builder: &mut EntryBuilder, line: &str) {
+    let mut line = line.trim();
+    if line.is_empty() {
+        return;
+    }
+
+    if !builder.has_phonetic() {
+        if let Some((phonetic, rest)) = leading_phonetic(line) {
+            builder.phonetic(phonetic);
+            line = rest.trim();
+            if line.is_empty() {
+                return;
+            }
+        }
+    }
+
+    if let Some(m) = example_marker().find(line) {
+        builder.example(&line[m.end()..]);
+        return;
+    }
+
+    if let Some(m) = reference_marker().find(line) {
+        for target in line[m.end()..].split([',', ';', '，', '；']) {
+            builder.cross_reference(target);
+        }
+        return;
+    }
+
+    // Leading part of speech: "n. 苹果", "vt. & vi. 跑"
+    let mut pos_seen = false;
+    while let Some(first) = line.split_whitespace().next() {
+        if pos_seen && first == "&" {
+            line = line[1..].trim_start();
+            continue;
+        }
+        match normalize_pos(first.trim_end_matches(',')) {
+            Some(pos) => {
+                if !pos_seen {
+                    builder.start_meaning(pos);
+                    pos_seen = true;
+                }
+                line = line[first.len()..].trim_start();
+            }
+            None => break,
+        }
+    }
+    if line.is_empty() {
+        return;
+    }
+
+    builder.start_definition();
+    if let Some(m) = numbering().find(line) {
+        line = &line[m.end()..];
+    }
+
+    // Domain labels: "【医】 苹果"
+    while let Some(rest) = line.strip_prefix('【') {
+        match rest.find('】') {
+            Some(end) => {
+                builder.label(&rest[..end]);
+                line = rest[end + '】'.len_utf8()..].trim_start();
+            }
+            None => break,
+        }
+    }
+
+    match inline_example().find(line) {
+        Some(m) => {
+            builder.text(&line[..m.start()]);
+            builder.example(&line[m.end()..]);
+        }
+        None => builder.text(line),
+    }
+}
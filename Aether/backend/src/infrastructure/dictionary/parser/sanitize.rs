@@ -0,0 +1,147 @@
+use super::markup::{attributes, decode_entities, escape_attribute, escape_text, tokenize, Node};
+
+/// Tags kept as-is. Everything else is unwrapped (content kept).
+const ALLOWED_TAGS: &[&str] = &[
+    "a", "b", "i", "u", "s", "em", "strong", "small", "big", "sup", "sub", "span", "div", "p", "br", "hr",
+    "ul", "ol", "li", "dl", "dt", "dd", "blockquote", "h1", "h2", "h3", "h4", "h5", "h6",
+    "table", "thead", "tbody", "tr", "td", "th", "img", "code", "pre", "ruby", "rt", "rp", "font",
+];
+
+/// Tags dropped together with their content.
+const DROPPED_TAGS: &[&str] = &[
+    "script", "style", "iframe", "object", "embed", "head", "title", "noscript", "template", "svg", "math", "form", "button", "textarea", "select",
+];
+
+const ALLOWED_ATTRIBUTES: &[&str] = &["class", "title", "lang", "dir", "colspan", "rowspan", "alt", "color"];
+
+const RESOURCE_ENDPOINT: &str = "/api/dictionary/resource?path=";
+
+/// Allowlist-based cleanup of dictionary HTML before it reaches the browser:
+/// no scripts, styles, event handlers or `javascript:` URLs. MDict links are
+/// rewritten: `sound://x.mp3` and relative images point at the resource
+/// endpoint, `entry://word` becomes a `data-entry` link for the frontend.
+pub fn sanitize_html(html: &str) -> String {
+    let mut out = String::with_capacity(html.len());
+    let mut open: Vec<String> = Vec::new();
+    let mut dropping: Option<(String, usize)> = None; // (tag, depth)
+
+    for node in tokenize(html) {
+        if let Some((tag, depth)) = dropping.as_mut() {
+            match &node {
+                Node::Open { name, self_closing: false, .. } if name == tag => *depth += 1,
+                Node::Close { name } if name == tag => {
+                    *depth -= 1;
+                    if *depth == 0 {
+                        dropping = None;
+                    }
+                }
+                _ => {}
+            }
+            continue;
+        }
+
+        match node {
+            Node::Text(text) => out.push_str(&escape_text(&decode_entities(text))),
+            Node::Open { name, attrs, self_closing } => {
+                if DROPPED_TAGS.contains(&name.as_str()) {
+                    if !self_closing {
+                        dropping = Some((name, 1));
+                    }
+                    continue;
+                }
+                if !ALLOWED_TAGS.contains(&name.as_str()) {
+                    continue;
+                }
+                out.push('<');
+                out.push_str(&name);
+                for (attr, value) in clean_attributes(&name, attrs) {
+                    out.push_str(&format!(" {}=\"{}\"", attr, escape_attribute(&value)));
+                }
+                if self_closing {
+                    out.push_str(" />");
+                } else {
+                    out.push('>');
+                    open.push(name);
+                }
+            }
+            Node::Close { name } => {
+                // Only close what we opened; implicitly close anything left inside it
+                if let Some(idx) = open.iter().rposition(|t| *t == name) {
+                    for tag in open.drain(idx..).rev() {
+                        out.push_str(&format!("</{}>", tag));
+                    }
+                }
+            }
+        }
+    }
+
+    for tag in open.into_iter().rev() {
+        out.push_str(&format!("</{}>", tag));
+    }
+    out
+}
+
+fn clean_attributes(tag: &str, attrs: &str) -> Vec<(String, String)> {
+    let mut out = Vec::new();
+    for (name, value) in attributes(attrs) {
+        if ALLOWED_ATTRIBUTES.contains(&name.as_str()) {
+            out.push((name, value));
+            continue;
+        }
+        match (tag, name.as_str()) {
+            ("a", "href") => out.extend(rewrite_link(&value)),
+            ("img", "src") => {
+                if let Some(src) = rewrite_image(&value) {
+                    out.push(("src".to_string(), src));
+                }
+            }
+            _ => {}
+        }
+    }
+    out
+}
+
+fn rewrite_link(href: &str) -> Vec<(String, String)> {
+    let href = href.trim();
+    let lower = href.to_lowercase();
+    if let Some(word) = href.strip_prefix("entry://") {
+        return vec![
+            ("href".to_string(), "#".to_string()),
+            ("data-entry".to_string(), word.trim_start_matches('#').to_string()),
+        ];
+    }
+    if let Some(path) = href.strip_prefix("sound://") {
+        return vec![
+            ("href".to_string(), resource_url(path)),
+            ("data-sound".to_string(), "true".to_string()),
+        ];
+    }
+    if lower.starts_with("http://") || lower.starts_with("https://") || lower.starts_with('#') {
+        return vec![("href".to_string(), href.to_string())];
+    }
+    Vec::new()
+}
+
+fn rewrite_image(src: &str) -> Option<String> {
+    let src = src.trim();
+    let lower = src.to_lowercase();
+    if lower.starts_with("http://") || lower.starts_with("https://") || lower.starts_with("data:image/") {
+        return Some(src.to_string());
+    }
+    if lower.contains(':') {
+        return None; // javascript:, file:, ...
+    }
+    Some(resource_url(src.trim_start_matches("./")))
+}
+
+fn resource_url(path: &str) -> String {
+    let mut out = String::from(RESOURCE_ENDPOINT);
+    for b in path.bytes() {
+        if b.is_ascii_alphanumeric() || matches!(b, b'-' | b'_' | b'.' | b'~' | b'/') {
+            out.push(b as char);
+        } else {
+            out.push_str(&format!("%{:02X}", b));
+        }
+    }
+    out
+}
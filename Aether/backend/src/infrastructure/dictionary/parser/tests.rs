@@ -0,0 +1,170 @@
+#[cfg(test)]
+mod tests {
+    use crate::infrastructure::dictionary::parser::html::HtmlParser;
+    use crate::infrastructure::dictionary::parser::oxford::OxfordParser;
+    use crate::infrastructure::dictionary::parser::plain::PlainTextParser;
+    use crate::infrastructure::dictionary::parser::sanitize::sanitize_html;
+    use crate::infrastructure::dictionary::parser::xdxf::XdxfParser;
+    use crate::infrastructure::dictionary::parser::{
+        normalize_pos, Definition, EntryMarkup, EntryParser, EntryParserRegistry, Meaning, ParsedEntry, DEFAULT_POS,
+    };
+
+    const PLAIN: &str = include_str!("fixtures/plain_langdao.txt");
+    const HTML: &str = include_str!("fixtures/html_mdict.html");
+    const XDXF: &str = include_str!("fixtures/xdxf.xml");
+    const OXFORD: &str = include_str!("fixtures/oxford.txt");
+
+    fn def(text: &str) -> Definition {
+        Definition::new(text)
+    }
+
+    fn examples(mut d: Definition, examples: &[&str]) -> Definition {
+        d.examples = examples.iter().map(|e| e.to_string()).collect();
+        d.example = d.examples.first().cloned();
+        d
+    }
+
+    fn labeled(mut d: Definition, labels: &[&str]) -> Definition {
+        d.labels = labels.iter().map(|l| l.to_string()).collect();
+        d
+    }
+
+    fn refs(mut d: Definition, refs: &[&str]) -> Definition {
+        d.cross_references = refs.iter().map(|r| r.to_string()).collect();
+        d
+    }
+
+    fn meaning(pos: &str, definitions: Vec<Definition>) -> Meaning {
+        Meaning { part_of_speech: pos.to_string(), definitions }
+    }
+
+    #[test]
+    fn test_plain_text_fixture() {
+        let entry = PlainTextParser.parse(PLAIN);
+        assert_eq!(entry, ParsedEntry {
+            phonetic: Some("['æpl]".to_string()),
+            meanings: vec![meaning("noun", vec![
+                def("苹果, 苹果树"),
+                refs(labeled(examples(def("苹果"), &["an apple a day keeps the doctor away"]), &["医"]), &["pome"]),
+            ])],
+            html: None,
+        });
+    }
+
+    #[test]
+    fn test_plain_text_without_structure() {
+        let entry = PlainTextParser.parse("just some words\n");
+        assert_eq!(entry.meanings, vec![meaning(DEFAULT_POS, vec![def("just some words")])]);
+        assert!(PlainTextParser.parse("  \n ").meanings.is_empty());
+    }
+
+    #[test]
+    fn test_html_fixture() {
+        let entry = HtmlParser.parse(HTML);
+        assert_eq!(entry.phonetic.as_deref(), Some("/ˈæp.əl/"));
+        assert_eq!(entry.meanings, vec![
+            meaning("noun", vec![
+                refs(labeled(examples(
+                    def("a round fruit with firm white flesh & a green or red skin"),
+                    &["She bit into a crisp apple.", "apple pie"],
+                ), &["informal"]), &["fruit"]),
+                def("the tree on which apples grow"),
+            ]),
+            meaning("verb", vec![labeled(def("to pick apples"), &["rare"])]),
+        ]);
+
+        let html = entry.html.unwrap();
+        assert!(!html.contains("script") && !html.contains("alert"));
+        assert!(!html.contains("onclick") && !html.contains("onerror") && !html.contains("javascript"));
+        assert!(html.contains(r##"<a href="#" data-entry="fruit" class="xr">fruit</a>"##));
+        assert!(html.contains(r#"<img src="/api/dictionary/resource?path=img/apple.png" />"#));
+        assert!(html.contains(r#"href="/api/dictionary/resource?path=apple.mp3" data-sound="true""#));
+    }
+
+    #[test]
+    fn test_xdxf_fixture() {
+        let entry = XdxfParser.parse(XDXF);
+        assert_eq!(entry, ParsedEntry {
+            phonetic: Some("ˈæpl".to_string()),
+            meanings: vec![
+                meaning("noun", vec![
+                    examples(def("round fruit of a tree of the rose family"), &["an apple orchard"]),
+                    refs(labeled(def("the apple of one's eye, a cherished person"), &["informal"]), &["Big Apple"]),
+                ]),
+                meaning("verb", vec![def("to pick apples")]),
+            ],
+            html: None,
+        });
+    }
+
+    #[test]
+    fn test_oxford_fixture() {
+        let entry = OxfordParser.parse(OXFORD);
+        assert_eq!(entry, ParsedEntry {
+            phonetic: Some("/ 5Apl; `æpl/".to_string()),
+            meanings: vec![
+                meaning("noun", vec![
+                    refs(def("round fruit with firm juicy flesh and green, red or yellow skin 苹果."), &["fruit"]),
+                    examples(
+                        def("(also apple tree) tree bearing this fruit 苹果树."),
+                        &["apple blossom 苹果花", "an apple orchard 苹果园."],
+                    ),
+                    refs(labeled(def("New York City 纽约市."), &["informal"]), &["Big Apple"]),
+                ]),
+                meaning("idiom", vec![examples(
+                    def("the apple of sb's eye person or thing that is loved more than any other 掌上明珠"),
+                    &["She is the apple of her father's eye. 她是她父亲的掌上明珠."],
+                )]),
+            ],
+            html: None,
+        });
+    }
+
+    #[test]
+    fn test_registry_picks_parser_by_markup_and_content() {
+        let registry = EntryParserRegistry::with_defaults();
+
+        // Declared markup wins; sniffing covers dictionaries that declare nothing
+        assert!(registry.parse(HTML, EntryMarkup::Html).html.is_some());
+        assert!(registry.parse(HTML, EntryMarkup::Unknown).html.is_some());
+        assert_eq!(registry.parse(XDXF, EntryMarkup::Xdxf), XdxfParser.parse(XDXF));
+        assert_eq!(registry.parse(XDXF, EntryMarkup::Unknown), XdxfParser.parse(XDXF));
+        assert_eq!(registry.parse(OXFORD, EntryMarkup::Plain), OxfordParser.parse(OXFORD));
+        assert_eq!(registry.parse(PLAIN, EntryMarkup::Plain), PlainTextParser.parse(PLAIN));
+
+        // An empty registry still parses as plain text
+        let empty = EntryParserRegistry::new();
+        assert_eq!(empty.parse(PLAIN, EntryMarkup::Html), PlainTextParser.parse(PLAIN));
+    }
+
+    #[test]
+    fn test_sametypesequence_markup() {
+        assert_eq!(EntryMarkup::from_sametypesequence("m"), EntryMarkup::Plain);
+        assert_eq!(EntryMarkup::from_sametypesequence("tm"), EntryMarkup::Plain);
+        assert_eq!(EntryMarkup::from_sametypesequence("h"), EntryMarkup::Html);
+        assert_eq!(EntryMarkup::from_sametypesequence("g"), EntryMarkup::Html);
+        assert_eq!(EntryMarkup::from_sametypesequence("x"), EntryMarkup::Xdxf);
+        assert_eq!(EntryMarkup::from_sametypesequence(""), EntryMarkup::Unknown);
+    }
+
+    #[test]
+    fn test_labels_and_references_in_definition_text() {
+        let entry = PlainTextParser.parse("adj. (infml, derog) very bad; awful => terrible\n");
+        assert_eq!(entry.meanings, vec![meaning("adjective", vec![
+            refs(labeled(def("very bad; awful"), &["informal", "derogatory"]), &["terrible"]),
+        ])]);
+
+        assert_eq!(normalize_pos("vt."), Some("verb"));
+        assert_eq!(normalize_pos("abbr"), Some("abbreviation"));
+        assert_eq!(normalize_pos("apple"), None);
+    }
+
+    #[test]
+    fn test_sanitizer_handles_broken_markup() {
+        assert_eq!(sanitize_html("<b>bold <i>both</b> tail"), "<b>bold <i>both</i></b> tail");
+        assert_eq!(sanitize_html("a < b & c"), "a &lt; b &amp; c");
+        assert_eq!(sanitize_html("<style>p{}</style><font color=red face=x>hi</font>"), r#"<font color="red">hi</font>"#);
+        assert_eq!(sanitize_html(r#"<img src="javascript:alert(1)">"#), "<img />");
+        assert_eq!(sanitize_html("<custom>kept</custom></p>"), "kept");
+    }
+}
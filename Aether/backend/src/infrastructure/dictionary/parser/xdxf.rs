@@ -0,0 +1,43 @@
+use super::html::{walk, Role};
+use super::{EntryMarkup, EntryParser, ParsedEntry};
+
+/// XDXF entries (StarDict `x`): `<k>` headword, `<tr>` transcription,
+/// `<gr>`/`<pos>` grammar, nested `<def>` senses, `<ex>` examples,
+/// `<kref>` cross-references, `<abr>` usage labels.
+pub struct XdxfParser;
+
+const XDXF_TAGS: &[&str] = &["<k>", "<def", "<kref", "<ex>", "<ex ", "<dtrn>", "<abr>", "<tr>", "<gr>"];
+
+impl EntryParser for XdxfParser {
+    fn name(&self) -> &'static str {
+        "xdxf"
+    }
+
+    fn score(&self, raw: &str, markup: EntryMarkup) -> u8 {
+        let tagged = XDXF_TAGS.iter().any(|t| raw.contains(t));
+        match markup {
+            EntryMarkup::Xdxf => 100,
+            EntryMarkup::Html if tagged => 40,
+            _ if tagged => 80,
+            _ => 0,
+        }
+    }
+
+    fn parse(&self, raw: &str) -> ParsedEntry {
+        walk(raw, xdxf_role, true)
+    }
+}
+
+fn xdxf_role(name: &str, _attrs: &str) -> Role {
+    match name {
+        "k" | "etm" | "rref" | "sr" | "opt" => Role::Skip,
+        "tr" => Role::Phonetic,
+        "gr" | "pos" => Role::Pos,
+        "abr" | "categ" => Role::Label,
+        "def" => Role::Sense,
+        "ex" => Role::Example,
+        "kref" | "iref" => Role::Reference,
+        "ar" | "blockquote" | "br" => Role::Block,
+        _ => Role::Text, // dtrn, deftext, co, c, i, b...
+    }
+}
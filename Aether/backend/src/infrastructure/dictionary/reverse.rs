@@ -0,0 +1,206 @@
+use std::collections::HashMap;
+use serde::Serialize;
+use crate::domain::morphology::Morphology;
+
+/// BM25 parameters (the usual defaults).
+const K1: f32 = 1.2;
+const B: f32 = 0.75;
+
+/// Function words that would otherwise match half of every dictionary.
+const STOPWORDS: &[&str] = &[
+    "a", "an", "the", "of", "to", "in", "on", "at", "by", "for", "from", "with", "as", "into", "about",
+    "and", "or", "but", "not", "no", "is", "are", "was", "were", "be", "been", "being", "it", "its",
+    "this", "that", "these", "those", "which", "who", "whom", "what", "when", "where", "how",
+    "sb", "sth", "sb's", "someone", "something", "one", "one's", "etc", "esp", "eg", "ie", "e.g", "i.e",
+];
+
+/// One headword found by meaning.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct ReverseHit {
+    pub word: String,
+    pub score: f32,
+    pub sources: Vec<String>, // Dictionary ids (or "vocabulary") whose definitions matched
+    pub snippet: Option<String>,
+}
+
+struct IndexedDoc {
+    headword: String,
+    source: u16,
+    len: u32,
+}
+
+/// In-memory inverted index over definition text, ranked with BM25.
+/// Terms are lemmatized English words and CJK bigrams, so "fears of heights"
+/// matches "fear of height" and "恐高" matches "恐高症".
+#[derive(Default)]
+pub struct ReverseIndex {
+    sources: Vec<String>,
+    docs: Vec<IndexedDoc>,
+    postings: HashMap<String, Vec<(u32, u16)>>, // term -> (doc, term frequency)
+    total_len: u64,
+}
+
+impl ReverseIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.docs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.docs.is_empty()
+    }
+
+    /// Indexes one definition of `headword` from `source`.
+    pub fn add(&mut self, source: &str, headword: &str, text: &str) {
+        let terms = tokenize(text);
+        if terms.is_empty() {
+            return;
+        }
+        let source = match self.sources.iter().position(|s| s == source) {
+            Some(i) => i,
+            None => {
+                self.sources.push(source.to_string());
+                self.sources.len() - 1
+            }
+        };
+
+        let doc = self.docs.len() as u32;
+        let mut counts: HashMap<String, u16> = HashMap::new();
+        for term in &terms {
+            let count = counts.entry(term.clone()).or_default();
+            *count = count.saturating_add(1);
+        }
+        for (term, tf) in counts {
+            self.postings.entry(term).or_default().push((doc, tf));
+        }
+        self.docs.push(IndexedDoc { headword: headword.to_string(), source: source as u16, len: terms.len() as u32 });
+        self.total_len += terms.len() as u64;
+    }
+
+    /// Headwords whose definitions best match `query`, best first. `sources`
+    /// restricts the search to some dictionaries. Entries matching every query
+    /// term rank above partial matches; headwords that are themselves query
+    /// words are left out.
+    pub fn search(&self, query: &str, sources: Option<&[String]>, limit: usize) -> Vec<ReverseHit> {
+        let mut terms = tokenize(query);
+        terms.sort();
+        terms.dedup();
+        if terms.is_empty() || self.docs.is_empty() {
+            return Vec::new();
+        }
+
+        let allowed: Option<Vec<bool>> = sources.map(|ids| {
+            self.sources.iter().map(|s| ids.contains(s)).collect()
+        });
+        let n = self.docs.len() as f32;
+        let avg_len = self.total_len as f32 / n;
+
+        let mut scores: HashMap<u32, (f32, usize)> = HashMap::new();
+        for term in &terms {
+            let Some(postings) = self.postings.get(term) else { continue };
+            let df = postings.len() as f32;
+            let idf = (1.0 + (n - df + 0.5) / (df + 0.5)).ln();
+            for &(doc, tf) in postings {
+                let d = &self.docs[doc as usize];
+                if allowed.as_ref().is_some_and(|a| !a[d.source as usize]) {
+                    continue;
+                }
+                let tf = tf as f32;
+                let norm = K1 * (1.0 - B + B * d.len as f32 / avg_len);
+                let entry = scores.entry(doc).or_default();
+                entry.0 += idf * tf * (K1 + 1.0) / (tf + norm);
+                entry.1 += 1;
+            }
+        }
+
+        // One hit per headword: its best definition, with every dictionary that
+        // matched (in indexing order, i.e. dictionary priority)
+        let mut scores: Vec<(u32, (f32, usize))> = scores.into_iter().collect();
+        scores.sort_by_key(|(doc, _)| *doc);
+        let mut hits: HashMap<String, ReverseHit> = HashMap::new();
+        for (doc, (score, matched)) in scores {
+            let d = &self.docs[doc as usize];
+            let key = d.headword.to_lowercase();
+            if terms.contains(&Morphology::lemmatize(&key)) {
+                continue;
+            }
+            let score = score * matched as f32 / terms.len() as f32;
+            let source = &self.sources[d.source as usize];
+            let hit = hits.entry(key).or_insert_with(|| ReverseHit {
+                word: d.headword.clone(),
+                score: 0.0,
+                sources: Vec::new(),
+                snippet: None,
+            });
+            hit.score = hit.score.max(score);
+            if !hit.sources.contains(source) {
+                hit.sources.push(source.clone());
+            }
+        }
+
+        let mut hits: Vec<ReverseHit> = hits.into_values().collect();
+        sort_hits(&mut hits);
+        hits.truncate(limit);
+        hits
+    }
+}
+
+/// Best score first, then alphabetical so results are stable.
+pub fn sort_hits(hits: &mut [ReverseHit]) {
+    hits.sort_by(|a, b| {
+        b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| a.word.cmp(&b.word))
+    });
+}
+
+/// Index terms: lemmatized lowercase words without stopwords, and overlapping
+/// bigrams for runs of CJK characters (single characters stay as they are).
+pub fn tokenize(text: &str) -> Vec<String> {
+    let mut terms = Vec::new();
+    let mut word = String::new();
+    let mut cjk: Vec<char> = Vec::new();
+
+    for c in text.chars().chain(std::iter::once(' ')) {
+        if is_cjk(c) {
+            push_word(&mut terms, &mut word);
+            cjk.push(c);
+        } else if c.is_alphanumeric() || (c == '\'' && !word.is_empty()) {
+            push_cjk(&mut terms, &mut cjk);
+            word.extend(c.to_lowercase());
+        } else {
+            push_word(&mut terms, &mut word);
+            push_cjk(&mut terms, &mut cjk);
+        }
+    }
+    terms
+}
+
+fn push_word(terms: &mut Vec<String>, word: &mut String) {
+    let w = std::mem::take(word);
+    let w = w.trim_end_matches('\'');
+    if w.chars().count() < 2 || STOPWORDS.contains(&w) {
+        return;
+    }
+    terms.push(Morphology::lemmatize(w));
+}
+
+fn push_cjk(terms: &mut Vec<String>, run: &mut Vec<char>) {
+    match run.len() {
+        0 => {}
+        1 => terms.push(run[0].to_string()),
+        _ => terms.extend(run.windows(2).map(|w| w.iter().collect::<String>())),
+    }
+    run.clear();
+}
+
+fn is_cjk(c: char) -> bool {
+    matches!(c as u32,
+        0x3040..=0x30FF     // Kana
+        | 0x3400..=0x4DBF   // CJK Extension A
+        | 0x4E00..=0x9FFF   // CJK Unified Ideographs
+        | 0xAC00..=0xD7AF   // Hangul
+        | 0xF900..=0xFAFF)  // Compatibility Ideographs
+}
@@ -0,0 +1,314 @@
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+    use std::path::PathBuf;
+    use flate2::{write::ZlibEncoder, Compression};
+    use ripemd::{Digest, Ripemd128};
+    use crate::infrastructure::dictionary::compression::{decompress_block, gunzip_file, lzo1x_decompress};
+    use crate::infrastructure::dictionary::loader::DictionaryLoader;
+    use crate::infrastructure::dictionary::mdict::{normalize_resource_path, MdictFile};
+    use crate::infrastructure::dictionary::parser::EntryMarkup;
+    use crate::infrastructure::dictionary::reverse::{tokenize, ReverseIndex};
+
+    fn zlib(data: &[u8]) -> Vec<u8> {
+        let mut e = ZlibEncoder::new(Vec::new(), Compression::default());
+        e.write_all(data).unwrap();
+        e.finish().unwrap()
+    }
+
+    /// `[type][adler32][payload]`; the checksum is not verified by the reader.
+    fn block(kind: u32, payload: &[u8]) -> Vec<u8> {
+        let mut out = kind.to_le_bytes().to_vec();
+        out.extend_from_slice(&[0, 0, 0, 0]);
+        out.extend_from_slice(payload);
+        out
+    }
+
+    /// Inverse of the reader's key-block-info decryption.
+    fn encrypt_key_block_info(plain: &[u8]) -> Vec<u8> {
+        let mut hasher = Ripemd128::new();
+        hasher.update(&plain[4..8]);
+        hasher.update(0x3695u32.to_le_bytes());
+        let key = hasher.finalize();
+
+        let mut out = plain[..8].to_vec();
+        let mut previous: u8 = 0x36;
+        for (i, &t) in plain[8..].iter().enumerate() {
+            let b = (t ^ previous ^ (i as u8) ^ key[i % key.len()]).rotate_right(4);
+            previous = b;
+            out.push(b);
+        }
+        out
+    }
+
+    /// Builds a minimal v2.0 UTF-8 `.mdx` with one key block and one record block.
+    fn build_mdx(entries: &[(&str, &str)], encrypted: u32) -> Vec<u8> {
+        let mut keys = Vec::new();
+        let mut records = Vec::new();
+        for (word, text) in entries {
+            keys.extend_from_slice(&(records.len() as u64).to_be_bytes());
+            keys.extend_from_slice(word.as_bytes());
+            keys.push(0);
+            records.extend_from_slice(text.as_bytes());
+            records.push(0);
+        }
+        let key_block = block(0, &keys);
+
+        let (first, last) = (entries[0].0, entries[entries.len() - 1].0);
+        let mut info = (entries.len() as u64).to_be_bytes().to_vec();
+        info.extend_from_slice(&(first.len() as u16).to_be_bytes());
+        info.extend_from_slice(first.as_bytes());
+        info.push(0);
+        info.extend_from_slice(&(last.len() as u16).to_be_bytes());
+        info.extend_from_slice(last.as_bytes());
+        info.push(0);
+        info.extend_from_slice(&(key_block.len() as u64).to_be_bytes());
+        info.extend_from_slice(&(keys.len() as u64).to_be_bytes());
+        let mut info_block = block(2, &zlib(&info));
+        info_block[4..8].copy_from_slice(&[0x12, 0x34, 0x56, 0x78]);
+        if encrypted & 2 != 0 {
+            info_block = encrypt_key_block_info(&info_block);
+        }
+
+        let header = format!(
+            r#"<Dictionary GeneratedByEngineVersion="2.0" Encrypted="{}" Encoding="UTF-8" Title="Test &amp; Dict"/>"#,
+            encrypted
+        );
+        let header: Vec<u8> = header.encode_utf16().flat_map(|u| u.to_le_bytes()).collect();
+
+        let mut out = (header.len() as u32).to_be_bytes().to_vec();
+        out.extend_from_slice(&header);
+        out.extend_from_slice(&[0, 0, 0, 0]);
+
+        for n in [1, entries.len() as u64, info.len() as u64, info_block.len() as u64, key_block.len() as u64] {
+            out.extend_from_slice(&n.to_be_bytes());
+        }
+        out.extend_from_slice(&[0, 0, 0, 0]);
+        out.extend_from_slice(&info_block);
+        out.extend_from_slice(&key_block);
+
+        let record_block = block(2, &zlib(&records));
+        for n in [1, entries.len() as u64, 16, record_block.len() as u64] {
+            out.extend_from_slice(&n.to_be_bytes());
+        }
+        out.extend_from_slice(&(record_block.len() as u64).to_be_bytes());
+        out.extend_from_slice(&(records.len() as u64).to_be_bytes());
+        out.extend_from_slice(&record_block);
+        out
+    }
+
+    fn write_temp(name: &str, data: &[u8]) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("aether_dict_test_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(name);
+        std::fs::write(&path, data).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_lzo_literals_and_overlapping_match() {
+        // "abc" literal run, then an M2 match (len 8, distance 3), then EOF
+        let stream = [20, b'a', b'b', b'c', 0xE8, 0x00, 0x11, 0x00, 0x00];
+        let out = lzo1x_decompress(&stream, 11).unwrap();
+        assert_eq!(out, b"abcabcabcab");
+
+        assert!(lzo1x_decompress(&[20, b'a'], 3).is_err());
+        // More output than declared
+        assert!(lzo1x_decompress(&stream, 10).is_err());
+    }
+
+    #[test]
+    fn test_block_types() {
+        assert_eq!(decompress_block(&block(0, b"raw"), 3).unwrap(), b"raw");
+        assert_eq!(decompress_block(&block(2, &zlib(b"zipped")), 6).unwrap(), b"zipped");
+        assert!(decompress_block(&block(9, b"?"), 1).is_err());
+        assert!(decompress_block(&[0, 0], 0).is_err());
+        assert!(decompress_block(&block(2, &zlib(b"zipped")), 5).is_err());
+    }
+
+    #[test]
+    fn test_mdx_lookup_and_link_redirect() {
+        let data = build_mdx(&[
+            ("Apples", "@@@LINK=apple"),
+            ("apple", "<b>apple</b> a round fruit"),
+            ("zebra", "striped animal"),
+        ], 0);
+        let path = write_temp("plain.mdx", &data);
+        let mut dict = MdictFile::open(&path).unwrap();
+
+        assert_eq!(dict.title, "Test & Dict");
+        assert_eq!(dict.entry_count(), 3);
+        assert_eq!(dict.lookup("apple").as_deref(), Some("<b>apple</b> a round fruit"));
+        assert_eq!(dict.lookup("Apples").as_deref(), Some("<b>apple</b> a round fruit"));
+        assert_eq!(dict.lookup("ZEBRA").as_deref(), Some("striped animal"));
+        assert!(dict.lookup("missing").is_none());
+    }
+
+    #[test]
+    fn test_mdx_encrypted_key_block_info() {
+        let data = build_mdx(&[("alpha", "first"), ("beta", "second")], 2);
+        let path = write_temp("encrypted.mdx", &data);
+        let mut dict = MdictFile::open(&path).unwrap();
+        assert_eq!(dict.lookup("beta").as_deref(), Some("second"));
+    }
+
+    #[test]
+    fn test_mdx_malformed_sizes_are_rejected() {
+        let data = build_mdx(&[("alpha", "first"), ("beta", "second")], 0);
+        let header_len = u32::from_be_bytes(data[0..4].try_into().unwrap()) as usize;
+        let section = header_len + 8;
+        let patch = |field: usize, value: u64| {
+            let mut bad = data.clone();
+            bad[section + field * 8..section + field * 8 + 8].copy_from_slice(&value.to_be_bytes());
+            bad
+        };
+
+        // Huge block count: only the blocks the index holds are read
+        let path = write_temp("many_blocks.mdx", &patch(0, u64::MAX));
+        assert_eq!(MdictFile::open(&path).unwrap().lookup("beta").as_deref(), Some("second"));
+
+        // Sizes past the end of the file or memory limits
+        for (field, value) in [(2, u64::MAX), (3, u64::MAX), (3, u64::MAX - 8), (4, u64::MAX)] {
+            let path = write_temp("bad_sizes.mdx", &patch(field, value));
+            assert!(MdictFile::open(&path).is_err(), "field {} = {}", field, value);
+        }
+        let mut bad = data.clone();
+        bad[0..4].copy_from_slice(&u32::MAX.to_be_bytes());
+        assert!(MdictFile::open(&write_temp("bad_header.mdx", &bad)).is_err());
+        assert!(MdictFile::open(&write_temp("truncated.mdx", &data[..data.len() - 10])).is_err());
+    }
+
+    #[test]
+    fn test_resource_path_normalization() {
+        assert_eq!(normalize_resource_path("sound/Hello.mp3"), "\\sound\\hello.mp3");
+        assert_eq!(normalize_resource_path("\\img\\a.png"), "\\img\\a.png");
+    }
+
+    #[test]
+    fn test_gunzip_dictzip() {
+        let mut e = flate2::write::GzEncoder::new(Vec::new(), Compression::default());
+        e.write_all(b"dictionary body").unwrap();
+        let src = write_temp("sample.dict.dz", &e.finish().unwrap());
+        let dst = src.with_extension("");
+        gunzip_file(&src, &dst).unwrap();
+        assert_eq!(std::fs::read(&dst).unwrap(), b"dictionary body");
+        assert!(src.exists());
+    }
+
+    #[tokio::test]
+    async fn test_loader_priorities_and_hot_reload() {
+        let base = std::env::temp_dir().join(format!("aether_dict_loader_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&base);
+        for (id, text) in [("alpha", "from alpha"), ("beta", "from beta")] {
+            std::fs::create_dir_all(base.join(id)).unwrap();
+            std::fs::write(base.join(id).join(format!("{}.mdx", id)), build_mdx(&[("word", text)], 0)).unwrap();
+        }
+        let loader = DictionaryLoader::new(base.to_str().unwrap());
+
+        let ids = |defs: Vec<crate::infrastructure::dictionary::loader::SourceDefinition>| -> Vec<String> {
+            defs.into_iter().map(|d| d.dictionary_id).collect()
+        };
+        assert_eq!(ids(loader.lookup_sources("word", None)), ["alpha", "beta"]);
+        assert!(loader.lookup_sources("word", None).iter().all(|d| d.markup == EntryMarkup::Html));
+
+        // Per-user selection restricts and orders
+        let selection = vec!["beta".to_string()];
+        assert_eq!(ids(loader.lookup_sources("word", Some(&selection))), ["beta"]);
+
+        // Global reorder persists in the manifest and survives a reload
+        loader.reorder(&["beta".to_string()]).await.unwrap();
+        assert_eq!(ids(loader.lookup_sources("word", None)), ["beta", "alpha"]);
+        let reloaded = DictionaryLoader::new(base.to_str().unwrap());
+        assert_eq!(ids(reloaded.lookup_sources("word", None)), ["beta", "alpha"]);
+
+        // Disabled dictionaries stay listed but are not searched
+        loader.configure("beta", Some(false), None).await.unwrap();
+        assert_eq!(loader.lookup("word"), Some(vec!["from alpha".to_string()]));
+        assert!(loader.list().iter().any(|d| d.id == "beta" && !d.enabled && d.entry_count.is_none()));
+
+        // Upload, then fuzzy index picks up the new headwords
+        loader.install("gamma", vec![("gamma.mdx".to_string(), build_mdx(&[("zygote", "cell")], 0))]).await.unwrap();
+        assert_eq!(loader.fuzzy_search("zygot").await.first().map(|s| s.as_str()), Some("zygote"));
+        assert!(loader.install("gamma", vec![("gamma.mdx".to_string(), vec![])]).await.is_err());
+        assert!(loader.install("bad", vec![("notes.txt".to_string(), vec![])]).await.is_err());
+
+        loader.remove("gamma").await.unwrap();
+        assert!(!loader.exists("gamma"));
+        assert!(!base.join("gamma").exists());
+
+        // Several dictionaries in one directory, with dots in their names
+        std::fs::create_dir_all(base.join("delta")).unwrap();
+        for name in ["oxford.mdx", "oxford.v2.mdx", "oxford.v2.mdd", "oxford.v2.css"] {
+            std::fs::write(base.join("delta").join(name), build_mdx(&[("word", name)], 0)).unwrap();
+        }
+        loader.reload().await.unwrap();
+        loader.remove("delta/oxford.v2").await.unwrap();
+        let left: Vec<String> = std::fs::read_dir(base.join("delta")).unwrap()
+            .flatten().map(|e| e.file_name().to_string_lossy().to_string()).collect();
+        assert_eq!(left, ["oxford.mdx"]);
+
+        let _ = std::fs::remove_dir_all(&base);
+    }
+
+    #[test]
+    fn test_reverse_tokenizer() {
+        assert_eq!(tokenize("The fear of Heights, running!"), ["fear", "height", "run"]);
+        assert_eq!(tokenize("害怕高处 x"), ["害怕", "怕高", "高处"]);
+        assert_eq!(tokenize("sb's fear"), ["fear"]);
+    }
+
+    #[test]
+    fn test_reverse_index_ranking() {
+        let mut index = ReverseIndex::new();
+        index.add("oxford", "acrophobia", "an abnormal fear of heights 恐高症");
+        index.add("oxford", "vertigo", "a feeling of dizziness, often caused by looking down from heights");
+        index.add("oxford", "fear", "an unpleasant emotion caused by the threat of danger");
+        index.add("oxford", "arachnophobia", "an abnormal fear of spiders");
+        index.add("collins", "acrophobia", "extreme fear of high places");
+        index.add("collins", "altitude", "the height of an object above sea level");
+
+        let hits = index.search("fear of heights", None, 10);
+        let words: Vec<&str> = hits.iter().map(|h| h.word.as_str()).collect();
+        // Full matches first; the query word itself is not an answer
+        assert_eq!(words[0], "acrophobia");
+        assert_eq!(hits[0].sources, ["oxford", "collins"]);
+        assert!(!words.contains(&"fear"));
+        assert!(words.contains(&"vertigo") && words.contains(&"arachnophobia"));
+
+        // Restricted to one dictionary
+        let hits = index.search("fear of heights", Some(&["collins".to_string()]), 10);
+        let words: Vec<&str> = hits.iter().map(|h| h.word.as_str()).collect();
+        assert_eq!(words, ["acrophobia", "altitude"]);
+
+        assert_eq!(index.search("恐高", None, 10)[0].word, "acrophobia");
+        assert!(index.search("the of", None, 10).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_loader_reverse_search() {
+        let base = std::env::temp_dir().join(format!("aether_dict_reverse_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&base);
+        std::fs::create_dir_all(base.join("med")).unwrap();
+        let entries = [
+            ("acrophobia", "<div class=\"def\">abnormal <b>fear</b> of heights</div><script>fear()</script>"),
+            ("vertigo", "dizziness caused by heights"),
+        ];
+        std::fs::write(base.join("med").join("med.mdx"), build_mdx(&entries, 0)).unwrap();
+        let loader = DictionaryLoader::new(base.to_str().unwrap());
+
+        let mut hits = None;
+        for _ in 0..100 {
+            hits = loader.reverse_search("fear of heights", None, 5).await;
+            if hits.is_some() {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        }
+        let hits = hits.expect("reverse index not built");
+        assert_eq!(hits[0].word, "acrophobia");
+        assert_eq!(hits[0].sources, ["med"]);
+        assert_eq!(hits[0].snippet.as_deref(), Some("abnormal fear of heights"));
+
+        let _ = std::fs::remove_dir_all(&base);
+    }
+}
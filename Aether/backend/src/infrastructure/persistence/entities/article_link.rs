@@ -0,0 +1,37 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "article_links")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Uuid,
+    pub source_id: Uuid, // References nodes.id
+    pub source_block_id: Option<Uuid>,
+    pub target_id: Option<Uuid>, // NULL: unresolved ("wanted page")
+    pub target_title: String, // As written in the link
+    pub alias: Option<String>,
+    pub context: String,
+    pub knowledge_base_id: Option<Uuid>, // Of the source; links resolve within it
+    pub created_at: DateTimeWithTimeZone,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::node::Entity",
+        from = "Column::SourceId",
+        to = "super::node::Column::Id",
+        on_update = "NoAction",
+        on_delete = "Cascade"
+    )]
+    Source,
+}
+
+impl Related<super::node::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Source.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
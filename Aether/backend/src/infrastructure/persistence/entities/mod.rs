@@ -22,6 +22,8 @@ pub mod graph_node;
 
 pub mod vrkb;
 pub mod blocks;
+pub mod block_reference;
+pub mod article_link;
 pub mod layout_template;
 pub mod audit_log;
 pub mod prkb_feeds;
@@ -14,6 +14,7 @@ pub struct Model {
     pub language: String,
     pub status: String,
     pub root_id: Option<Uuid>,
+    pub lemma: Option<String>,
     #[sea_orm(default_value = 0)]
     pub query_count: i32,
     #[sea_orm(default_value = false)]
@@ -2,16 +2,24 @@ use async_trait::async_trait;
 use sea_orm::*;
 use uuid::Uuid;
 use chrono::Utc;
-use crate::domain::models::{Article, ContentBody, ContentVersionSnapshot, Node, NodeType, PermissionMode, ContentItem, ContentDiff};
+use crate::domain::models::{Article, ContentBody, ContentVersionSnapshot, Node, NodeType, NodeVersion, PermissionMode, ContentItem, ContentDiff};
 use crate::domain::models::UserId;
 use crate::domain::ports::{ArticleRepository, PermissionRepository, RepositoryError};
 use crate::infrastructure::persistence::postgres::PostgresRepository;
-use crate::infrastructure::persistence::entities::{node, article_detail, content_version, user, blocks};
+use crate::infrastructure::persistence::entities::{node, article_detail, content_version, user, blocks, block_reference, article_link};
 use crate::domain::blocks::parser::parse_markdown_to_blocks;
-
-#[async_trait]
-impl ArticleRepository for PostgresRepository {
-    async fn save(&self, article: Article, editor_id: UserId, change_reason: Option<String>) -> Result<Uuid, RepositoryError> {
+use crate::domain::blocks::editing::reconcile;
+use crate::domain::blocks::models::Block;
+use crate::domain::blocks::strategies::scan_block_references;
+use crate::domain::blocks::links::scan_wiki_links;
+use sea_orm::sea_query::{Expr, Func};
+use std::collections::HashMap;
+
+impl PostgresRepository {
+    /// Saves an article and returns its revision (latest version number).
+    /// With `expected_revision`, nothing is written unless it is still current.
+    /// `blocks`, when given, are written as they are instead of re-parsing the body.
+    async fn save_article(&self, article: Article, blocks: Option<Vec<Block>>, editor_id: UserId, change_reason: Option<String>, expected_revision: Option<i64>) -> Result<i64, RepositoryError> {
         // 0. Duplicate Title Check
         // Check if another article exists with the same title but different ID
         let duplicate = node::Entity::find()
@@ -29,7 +37,39 @@ impl ArticleRepository for PostgresRepository {
          // Transactional Save: Node + ArticleDetail
          let txn = self.db.begin().await.map_err(|e| RepositoryError::ConnectionError(e.to_string()))?;
 
+        // Lock the node row so concurrent saves of the same article are serialized
+        // and agree on the latest version
+        let stored_node = node::Entity::find_by_id(article.node.id)
+            .lock_exclusive()
+            .one(&txn)
+            .await
+            .map_err(|e| RepositoryError::ConnectionError(e.to_string()))?;
+        let stored_detail = article_detail::Entity::find_by_id(article.node.id)
+            .one(&txn)
+            .await
+            .map_err(|e| RepositoryError::ConnectionError(e.to_string()))?;
+
+        let latest = content_version::Entity::find()
+            .filter(content_version::Column::NodeId.eq(article.node.id))
+            .order_by_desc(content_version::Column::Version)
+            .one(&txn)
+            .await
+            .map_err(|e| RepositoryError::ConnectionError(e.to_string()))?;
+
+        if let Some(expected) = expected_revision {
+            let current = latest.as_ref().map(|v| v.version as i64).unwrap_or(0);
+            if current != expected {
+                // Dropping the transaction rolls it back
+                return Err(RepositoryError::RevisionConflict { expected, current });
+            }
+        }
+
         // 1. Save Node
+        let permission_str = match article.node.permission_mode {
+            PermissionMode::Public => "Public",
+            PermissionMode::Private => "Private",
+            PermissionMode::Internal => "Internal",
+        }.to_string();
         let node_model = node::ActiveModel {
             id: Set(article.node.id),
             parent_id: Set(article.node.parent_id),
@@ -37,11 +77,7 @@ impl ArticleRepository for PostgresRepository {
             knowledge_base_id: Set(article.node.knowledge_base_id),
             r#type: Set("Article".to_owned()),
             title: Set(article.node.title.clone()),
-            permission_mode: Set(match article.node.permission_mode {
-                PermissionMode::Public => "Public".to_string(),
-                PermissionMode::Private => "Private".to_string(),
-                PermissionMode::Internal => "Internal".to_string(),
-            }),
+            permission_mode: Set(permission_str.clone()),
             permission_data: Set(None),
             created_at: Set(article.node.created_at.into()),
             updated_at: Set(article.node.updated_at.into()),
@@ -67,14 +103,15 @@ impl ArticleRepository for PostgresRepository {
             crate::domain::models::ContentStatus::Archived => "Archived",
             crate::domain::models::ContentStatus::Published => "Published",
         }.to_string();
+        let detail_tags = serde_json::to_string(&article.tags).unwrap_or_default();
 
         let detail_model = article_detail::ActiveModel {
             id: Set(article.node.id),
-            slug: Set(article.slug),
-            status: Set(status_str),
+            slug: Set(article.slug.clone()),
+            status: Set(status_str.clone()),
             category: Set(article.category),
             body: Set(body_json.clone()),
-            tags: Set(serde_json::to_string(&article.tags).unwrap_or_default()),
+            tags: Set(detail_tags.clone()),
             derived_data: Set(article.derived_data.clone()),
             public_version_id: Set(None),
         };
@@ -96,17 +133,23 @@ impl ArticleRepository for PostgresRepository {
         // Calculate Content Hash
         let current_hash = format!("{:x}", md5::compute(body_json.to_string()));
 
-        // Fetch max version
-        let max_ver_query = content_version::Entity::find()
-            .filter(content_version::Column::NodeId.eq(article.node.id))
-            .order_by_desc(content_version::Column::Version)
-            .one(&txn)
-            .await
-            .map_err(|e| RepositoryError::ConnectionError(e.to_string()))?;
+        // Versions only keep title and body, but tag, status, visibility and move edits
+        // still get one, so the revision changes with them
+        let metadata_changed = match (&stored_node, &stored_detail) {
+            (Some(n), Some(d)) => {
+                d.tags != detail_tags
+                    || d.status != status_str
+                    || !n.permission_mode.eq_ignore_ascii_case(&permission_str)
+                    || n.parent_id != article.node.parent_id
+                    || n.knowledge_base_id != article.node.knowledge_base_id
+            }
+            _ => false,
+        };
 
-        let (new_version, should_save_version) = match max_ver_query {
+        let (new_version, should_save_version) = match latest {
             Some(latest) => {
-                 if latest.content_hash == current_hash && change_reason.is_none() {
+                 // Renames are versioned too, so the revision covers title and body
+                 if latest.content_hash == current_hash && latest.title == article.node.title && !metadata_changed && change_reason.is_none() {
                      // No content change and no forced reason -> Skip versioning
                      (latest.version, false)
                  } else {
@@ -122,7 +165,7 @@ impl ArticleRepository for PostgresRepository {
                  id: Set(version_id),
                  node_id: Set(article.node.id),
                  version: Set(new_version),
-                 title: Set(article.node.title),
+                 title: Set(article.node.title.clone()),
                  body: Set(body_json.clone()),
                  change_reason: Set(change_reason),
                  content_hash: Set(current_hash),
@@ -146,8 +189,37 @@ impl ArticleRepository for PostgresRepository {
         // ---------------------------------------------------------
         // Parse Body and Write to Blocks Table
         if let crate::domain::models::ContentBody::Markdown(ref md_text) = article.body {
-             let blocks_vec = parse_markdown_to_blocks(article.node.id, md_text);
+             let blocks_vec = match blocks {
+                 Some(given) => given,
+                 None => {
+                     // Keep ids and revisions of blocks the edit left alone
+                     let existing = blocks::Entity::find()
+                        .filter(blocks::Column::DocumentId.eq(article.node.id))
+                        .order_by_asc(blocks::Column::Ordinal)
+                        .all(&txn)
+                        .await
+                        .map_err(|e| RepositoryError::ConnectionError(e.to_string()))?
+                        .into_iter()
+                        .map(map_block)
+                        .collect::<Vec<_>>();
+                     reconcile(&existing, parse_markdown_to_blocks(article.node.id, md_text))
+                 }
+             };
              
+             let references: Vec<block_reference::ActiveModel> = blocks_vec.iter().flat_map(|b| {
+                 scan_block_references(&b.type_name, &b.payload).into_iter().map(move |(target, kind)| {
+                     block_reference::ActiveModel {
+                         id: Set(Uuid::new_v4()),
+                         source_document_id: Set(b.document_id),
+                         source_block_id: Set(b.id),
+                         target_block_id: Set(target),
+                         kind: Set(kind.as_str().to_string()),
+                         created_at: Set(Utc::now().into()),
+                     }
+                 })
+             }).collect();
+             let wiki_links = scan_wiki_links(&blocks_vec);
+
              // 1. Delete existing blocks for this document
              blocks::Entity::delete_many()
                 .filter(blocks::Column::DocumentId.eq(article.node.id))
@@ -175,9 +247,75 @@ impl ArticleRepository for PostgresRepository {
                      .await
                      .map_err(|e| RepositoryError::ConnectionError(e.to_string()))?;
              }
+
+             // 3. Rebuild the document's reference index
+             block_reference::Entity::delete_many()
+                .filter(block_reference::Column::SourceDocumentId.eq(article.node.id))
+                .exec(&txn)
+                .await
+                .map_err(|e| RepositoryError::ConnectionError(e.to_string()))?;
+             if !references.is_empty() {
+                 block_reference::Entity::insert_many(references)
+                     .exec(&txn)
+                     .await
+                     .map_err(|e| RepositoryError::ConnectionError(e.to_string()))?;
+             }
+
+             // 4. Rebuild its wiki links, resolved within the knowledge base
+             let kb_id = article.node.knowledge_base_id;
+             let mut targets: HashMap<String, Option<Uuid>> = HashMap::new();
+             let mut links = Vec::with_capacity(wiki_links.len());
+             for link in wiki_links {
+                 let key = link.target.to_lowercase();
+                 let target_id = match targets.get(&key) {
+                     Some(target_id) => *target_id,
+                     None => {
+                         let target_id = resolve_wiki_target(&txn, &link.target, kb_id).await
+                             .map_err(|e| RepositoryError::ConnectionError(e.to_string()))?;
+                         targets.insert(key, target_id);
+                         target_id
+                     }
+                 };
+                 links.push(article_link::ActiveModel {
+                     id: Set(Uuid::new_v4()),
+                     source_id: Set(article.node.id),
+                     source_block_id: Set(Some(link.source_block_id)),
+                     target_id: Set(target_id),
+                     target_title: Set(link.target),
+                     alias: Set(link.alias),
+                     context: Set(link.context),
+                     knowledge_base_id: Set(kb_id),
+                     created_at: Set(Utc::now().into()),
+                 });
+             }
+             article_link::Entity::delete_many()
+                .filter(article_link::Column::SourceId.eq(article.node.id))
+                .exec(&txn)
+                .await
+                .map_err(|e| RepositoryError::ConnectionError(e.to_string()))?;
+             if !links.is_empty() {
+                 article_link::Entity::insert_many(links)
+                     .exec(&txn)
+                     .await
+                     .map_err(|e| RepositoryError::ConnectionError(e.to_string()))?;
+             }
         }
         // ---------------------------------------------------------
 
+        // Wanted pages with this article's title (or slug) now resolve to it
+        article_link::Entity::update_many()
+            .col_expr(article_link::Column::TargetId, Expr::value(article.node.id))
+            .filter(article_link::Column::TargetId.is_null())
+            .filter(in_knowledge_base(article_link::Column::KnowledgeBaseId, article.node.knowledge_base_id))
+            .filter(
+                Condition::any()
+                    .add(Expr::expr(Func::lower(Expr::col((article_link::Entity, article_link::Column::TargetTitle)))).eq(article.node.title.to_lowercase()))
+                    .add(article_link::Column::TargetTitle.eq(article.slug.as_str()))
+            )
+            .exec(&txn)
+            .await
+            .map_err(|e| RepositoryError::ConnectionError(e.to_string()))?;
+
         txn.commit().await.map_err(|e| RepositoryError::ConnectionError(e.to_string()))?;
 
         // 4. ReBAC Permissions (Executed after commit to ensure Node visibility)
@@ -192,7 +330,34 @@ impl ArticleRepository for PostgresRepository {
         if let Some(kb_id) = article.node.knowledge_base_id {
             let _ = self.add_relation(article.node.id, "node", "parent", kb_id, "node").await;
         }
-        Ok(article.node.id)
+        Ok(new_version as i64)
+    }
+}
+
+#[async_trait]
+impl ArticleRepository for PostgresRepository {
+    async fn save(&self, article: Article, editor_id: UserId, change_reason: Option<String>) -> Result<Uuid, RepositoryError> {
+        let id = article.node.id;
+        self.save_article(article, None, editor_id, change_reason, None).await?;
+        Ok(id)
+    }
+
+    async fn save_if_revision(&self, article: Article, editor_id: UserId, change_reason: Option<String>, expected_revision: Option<i64>) -> Result<i64, RepositoryError> {
+        self.save_article(article, None, editor_id, change_reason, expected_revision).await
+    }
+
+    async fn save_with_blocks(&self, article: Article, blocks: Vec<Block>, editor_id: UserId, change_reason: Option<String>, expected_revision: i64) -> Result<i64, RepositoryError> {
+        self.save_article(article, Some(blocks), editor_id, change_reason, Some(expected_revision)).await
+    }
+
+    async fn current_revision(&self, id: &Uuid) -> Result<i64, RepositoryError> {
+        let latest = content_version::Entity::find()
+            .filter(content_version::Column::NodeId.eq(*id))
+            .order_by_desc(content_version::Column::Version)
+            .one(&self.db)
+            .await
+            .map_err(|e| RepositoryError::ConnectionError(e.to_string()))?;
+        Ok(latest.map(|v| v.version as i64).unwrap_or(0))
     }
 
     async fn find_by_id(&self, id: &Uuid) -> Result<Option<ContentItem>, RepositoryError> {
@@ -395,6 +560,48 @@ impl ArticleRepository for PostgresRepository {
         Ok(content_items)
     }
 
+    async fn list_versions(&self, knowledge_base_id: Uuid) -> Result<Vec<NodeVersion>, RepositoryError> {
+        let nodes = node::Entity::find()
+            .filter(node::Column::KnowledgeBaseId.eq(knowledge_base_id))
+            .all(&self.db)
+            .await
+            .map_err(|e| RepositoryError::ConnectionError(e.to_string()))?;
+
+        let ids: Vec<Uuid> = nodes.iter().map(|n| n.id).collect();
+        let latest: HashMap<Uuid, i32> = if ids.is_empty() {
+            HashMap::new()
+        } else {
+            content_version::Entity::find()
+                .select_only()
+                .column(content_version::Column::NodeId)
+                .column_as(content_version::Column::Version.max(), "version")
+                .filter(content_version::Column::NodeId.is_in(ids))
+                .group_by(content_version::Column::NodeId)
+                .into_tuple::<(Uuid, i32)>()
+                .all(&self.db)
+                .await
+                .map_err(|e| RepositoryError::ConnectionError(e.to_string()))?
+                .into_iter()
+                .collect()
+        };
+
+        Ok(nodes.into_iter().map(|n| NodeVersion {
+            version: latest.get(&n.id).copied().unwrap_or(0) as i64,
+            id: n.id,
+            parent_id: n.parent_id,
+            r#type: match n.r#type.as_str() {
+                "Article" => NodeType::Article,
+                "Folder" => NodeType::Folder,
+                "Vocabulary" => NodeType::Vocabulary,
+                "Memo" => NodeType::Memo,
+                _ => NodeType::Article,
+            },
+            title: n.title,
+            created_at: n.created_at.into(),
+            updated_at: n.updated_at.into(),
+        }).collect())
+    }
+
     async fn search(&self, query: &str) -> Result<Vec<Article>, RepositoryError> {
         let term = format!("%{}%", query);
 
@@ -665,3 +872,44 @@ fn map_article(n: node::Model, d: article_detail::Model, match_user: Option<crat
         derived_data: d.derived_data,
     }
 }
+
+fn map_block(m: blocks::Model) -> Block {
+    Block {
+        id: m.id,
+        document_id: m.document_id,
+        type_name: m.r#type,
+        ordinal: m.ordinal,
+        revision: m.revision,
+        payload: m.payload,
+        created_at: m.created_at.into(),
+        updated_at: m.updated_at.into(),
+    }
+}
+
+fn in_knowledge_base(column: impl ColumnTrait, kb_id: Option<Uuid>) -> sea_orm::sea_query::SimpleExpr {
+    match kb_id {
+        Some(kb_id) => column.eq(kb_id),
+        None => column.is_null(),
+    }
+}
+
+/// The article a `[[target]]` link made in knowledge base `kb_id` points to:
+/// by title (ignoring case), then by slug.
+async fn resolve_wiki_target<C: ConnectionTrait>(conn: &C, target: &str, kb_id: Option<Uuid>) -> Result<Option<Uuid>, DbErr> {
+    let by_title = node::Entity::find()
+        .filter(node::Column::Type.eq("Article"))
+        .filter(in_knowledge_base(node::Column::KnowledgeBaseId, kb_id))
+        .filter(Expr::expr(Func::lower(Expr::col((node::Entity, node::Column::Title)))).eq(target.to_lowercase()))
+        .one(conn)
+        .await?;
+    if let Some(n) = by_title {
+        return Ok(Some(n.id));
+    }
+
+    let by_slug = article_detail::Entity::find()
+        .filter(article_detail::Column::Slug.eq(target))
+        .find_also_related(node::Entity)
+        .one(conn)
+        .await?;
+    Ok(by_slug.and_then(|(_, n)| n).filter(|n| n.knowledge_base_id == kb_id).map(|n| n.id))
+}
@@ -0,0 +1,167 @@
+use async_trait::async_trait;
+use sea_orm::*;
+use sea_orm::sea_query::{Alias, Expr, IntoColumnRef, SimpleExpr};
+use uuid::Uuid;
+use chrono::Utc;
+use crate::domain::assets::asset_ids;
+use crate::domain::models::{AssetRecord, AssetUsage};
+use crate::domain::ports::{AssetRepository, RepositoryError};
+use crate::infrastructure::persistence::postgres::PostgresRepository;
+use crate::infrastructure::persistence::entities::{article_detail, knowledge_base, node};
+use crate::infrastructure::persistence::entities::vrkb::{asset, doc, finding, project_asset};
+
+/// Rows read per page when scanning content for asset references.
+const PAGE_SIZE: u64 = 500;
+
+/// `LIKE '%<uuid>%'` on a JSON column, to narrow a scan to one asset.
+fn mentions(column: impl IntoColumnRef, asset_id: Uuid) -> SimpleExpr {
+    Expr::col(column).cast_as(Alias::new("TEXT")).like(format!("%{}%", asset_id))
+}
+
+fn usages(text: &str, source_kind: &str, source_id: Uuid) -> impl Iterator<Item = AssetUsage> {
+    let source_kind = source_kind.to_string();
+    asset_ids(text).into_iter().map(move |asset_id| AssetUsage { asset_id, source_kind: source_kind.clone(), source_id })
+}
+
+#[async_trait]
+impl AssetRepository for PostgresRepository {
+    async fn list_asset_records(&self) -> Result<Vec<AssetRecord>, RepositoryError> {
+        let articles = article_detail::Entity::find()
+            .filter(article_detail::Column::Category.eq("Asset"))
+            .find_also_related(node::Entity)
+            .all(&self.db)
+            .await
+            .map_err(|e| RepositoryError::ConnectionError(e.to_string()))?;
+
+        let mut records = Vec::new();
+        for (detail, n) in articles {
+            // Asset articles keep their file in a `Custom` body
+            let payload = &detail.body["data"];
+            records.push(AssetRecord {
+                id: detail.id,
+                kind: "article".to_string(),
+                hash: payload["hash"].as_str().unwrap_or(&detail.slug).to_string(),
+                storage_path: payload["file_path"].as_str().unwrap_or_default().to_string(),
+                owner_id: n.as_ref().map(|n| n.author_id),
+                size_bytes: payload["size_bytes"].as_i64().unwrap_or(0),
+                created_at: n.map(|n| n.created_at.with_timezone(&Utc)).unwrap_or_else(Utc::now),
+            });
+        }
+
+        let assets = asset::Entity::find()
+            .all(&self.db)
+            .await
+            .map_err(|e| RepositoryError::ConnectionError(e.to_string()))?;
+        records.extend(assets.into_iter().map(|m| AssetRecord {
+            id: m.id,
+            kind: "vrkb".to_string(),
+            hash: m.hash,
+            storage_path: m.storage_path,
+            owner_id: None,
+            size_bytes: m.size_bytes,
+            created_at: m.created_at.with_timezone(&Utc),
+        }));
+        Ok(records)
+    }
+
+    async fn list_asset_usages(&self, asset_id: Option<Uuid>) -> Result<Vec<AssetUsage>, RepositoryError> {
+        let mut found = Vec::new();
+
+        // 1. Articles (asset articles themselves only hold files)
+        let mut query = article_detail::Entity::find()
+            .filter(Condition::any()
+                .add(article_detail::Column::Category.is_null())
+                .add(article_detail::Column::Category.ne("Asset")));
+        if let Some(id) = asset_id {
+            query = query.filter(mentions((article_detail::Entity, article_detail::Column::Body), id));
+        }
+        let mut pages = query.paginate(&self.db, PAGE_SIZE);
+        while let Some(page) = pages.fetch_and_next().await.map_err(|e| RepositoryError::ConnectionError(e.to_string()))? {
+            for detail in page {
+                found.extend(usages(&detail.body.to_string(), "article", detail.id));
+            }
+        }
+
+        // 2. VRKB findings and docs
+        let mut query = finding::Entity::find().filter(finding::Column::Content.is_not_null());
+        if let Some(id) = asset_id {
+            query = query.filter(mentions((finding::Entity, finding::Column::Content), id));
+        }
+        let mut pages = query.paginate(&self.db, PAGE_SIZE);
+        while let Some(page) = pages.fetch_and_next().await.map_err(|e| RepositoryError::ConnectionError(e.to_string()))? {
+            for f in page {
+                let text = f.content.map(|c| c.to_string()).unwrap_or_default();
+                found.extend(usages(&text, "finding", f.id));
+            }
+        }
+
+        let mut query = doc::Entity::find().filter(doc::Column::Content.is_not_null());
+        if let Some(id) = asset_id {
+            query = query.filter(mentions((doc::Entity, doc::Column::Content), id));
+        }
+        let mut pages = query.paginate(&self.db, PAGE_SIZE);
+        while let Some(page) = pages.fetch_and_next().await.map_err(|e| RepositoryError::ConnectionError(e.to_string()))? {
+            for d in page {
+                let text = d.content.map(|c| c.to_string()).unwrap_or_default();
+                found.extend(usages(&text, "doc", d.id));
+            }
+        }
+
+        // 3. Project links
+        let mut query = project_asset::Entity::find();
+        if let Some(id) = asset_id {
+            query = query.filter(project_asset::Column::AssetId.eq(id));
+        }
+        let links = query.all(&self.db)
+            .await
+            .map_err(|e| RepositoryError::ConnectionError(e.to_string()))?;
+        found.extend(links.into_iter().map(|l| AssetUsage {
+            asset_id: l.asset_id,
+            source_kind: "project".to_string(),
+            source_id: l.project_id,
+        }));
+
+        // 4. Knowledge base covers
+        let kbs = knowledge_base::Entity::find()
+            .filter(knowledge_base::Column::CoverImage.is_not_null())
+            .all(&self.db)
+            .await
+            .map_err(|e| RepositoryError::ConnectionError(e.to_string()))?;
+        for kb in kbs {
+            found.extend(usages(kb.cover_image.as_deref().unwrap_or_default(), "knowledge_base", kb.id));
+        }
+
+        // The text scans may match an id that is only a substring elsewhere
+        if let Some(id) = asset_id {
+            found.retain(|u| u.asset_id == id);
+        }
+        Ok(found)
+    }
+
+    async fn set_asset_path(&self, record: &AssetRecord, storage_path: &str) -> Result<(), RepositoryError> {
+        match record.kind.as_str() {
+            "article" => {
+                let detail = article_detail::Entity::find_by_id(record.id)
+                    .one(&self.db)
+                    .await
+                    .map_err(|e| RepositoryError::ConnectionError(e.to_string()))?
+                    .ok_or(RepositoryError::NotFound(record.id.to_string()))?;
+                let mut body = detail.body.clone();
+                body["data"]["file_path"] = serde_json::Value::String(storage_path.to_string());
+                let mut active: article_detail::ActiveModel = detail.into();
+                active.body = Set(body);
+                active.update(&self.db).await.map_err(|e| RepositoryError::ConnectionError(e.to_string()))?;
+            }
+            "vrkb" => {
+                asset::Entity::update_many()
+                    .col_expr(asset::Column::StoragePath, Expr::value(storage_path))
+                    .filter(asset::Column::Id.eq(record.id))
+                    .exec(&self.db)
+                    .await
+                    .map_err(|e| RepositoryError::ConnectionError(e.to_string()))?;
+            }
+            other => return Err(RepositoryError::ValidationError(format!("Unknown asset kind: {}", other))),
+        }
+        Ok(())
+    }
+}
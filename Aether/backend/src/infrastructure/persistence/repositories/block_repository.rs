@@ -1,7 +1,7 @@
 use sea_orm::*;
 use uuid::Uuid;
-use crate::infrastructure::persistence::entities::{blocks, node};
-use crate::domain::blocks::models::Block;
+use crate::infrastructure::persistence::entities::{blocks, block_reference, node};
+use crate::domain::blocks::models::{Block, BlockReference, ReferenceKind};
 use crate::domain::blocks::strategies::apply_searchable_trait;
 
 pub struct BlockRepository {
@@ -52,7 +52,6 @@ impl BlockRepository {
         Ok(())
     }
     
-    #[allow(dead_code)]
     pub async fn find_by_document_id(&self, document_id: Uuid) -> Result<Vec<Block>, DbErr> {
         let models = blocks::Entity::find()
             .filter(blocks::Column::DocumentId.eq(document_id))
@@ -91,8 +90,56 @@ impl BlockRepository {
         }).collect())
     }
     
+    pub async fn find_by_ids(&self, ids: &[Uuid]) -> Result<Vec<Block>, DbErr> {
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        let models = blocks::Entity::find()
+            .filter(blocks::Column::Id.is_in(ids.iter().copied()))
+            .all(&self.db)
+            .await?;
+
+        Ok(models.into_iter().map(|m| Block {
+            id: m.id,
+            document_id: m.document_id,
+            type_name: m.r#type,
+            ordinal: m.ordinal,
+            revision: m.revision,
+            payload: m.payload,
+            created_at: m.created_at.into(),
+            updated_at: m.updated_at.into(),
+        }).collect())
+    }
+
+    /// References made by a document's blocks, in index order.
+    pub async fn find_references_from(&self, document_id: Uuid) -> Result<Vec<BlockReference>, DbErr> {
+        let models = block_reference::Entity::find()
+            .filter(block_reference::Column::SourceDocumentId.eq(document_id))
+            .all(&self.db)
+            .await?;
+        Ok(models.into_iter().filter_map(map_reference).collect())
+    }
+
+    /// Where a block is embedded or linked from.
+    pub async fn find_usages(&self, block_id: Uuid) -> Result<Vec<BlockReference>, DbErr> {
+        let models = block_reference::Entity::find()
+            .filter(block_reference::Column::TargetBlockId.eq(block_id))
+            .all(&self.db)
+            .await?;
+        Ok(models.into_iter().filter_map(map_reference).collect())
+    }
+
     // Feature: Search Global via Mandatory Trait is implemented via the 'plain_text' column in PG
     // But since plain_text is a generated column in DB, we don't write to it in Rust.
     // However, SeaORM ActiveModel doesn't know it's generated unless marked.
     // We didn't include `plain_text` in `ActiveModel` fields above (Set), which is correct for generated columns.
 }
+
+fn map_reference(m: block_reference::Model) -> Option<BlockReference> {
+    Some(BlockReference {
+        source_document_id: m.source_document_id,
+        source_block_id: m.source_block_id,
+        target_block_id: m.target_block_id,
+        kind: ReferenceKind::parse(&m.kind)?,
+    })
+}
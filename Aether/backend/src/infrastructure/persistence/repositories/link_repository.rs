@@ -0,0 +1,70 @@
+use sea_orm::*;
+use sea_orm::sea_query::{Expr, Func};
+use uuid::Uuid;
+use crate::infrastructure::persistence::entities::article_link;
+use crate::domain::blocks::models::ArticleLink;
+
+pub struct LinkRepository {
+    db: DatabaseConnection,
+}
+
+impl LinkRepository {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// Links pointing at an article.
+    pub async fn find_backlinks(&self, target_id: Uuid) -> Result<Vec<ArticleLink>, DbErr> {
+        let models = article_link::Entity::find()
+            .filter(article_link::Column::TargetId.eq(target_id))
+            .order_by_desc(article_link::Column::CreatedAt)
+            .all(&self.db)
+            .await?;
+        Ok(models.into_iter().map(map_link).collect())
+    }
+
+    /// Unresolved links, in one knowledge base or among articles outside any.
+    pub async fn find_wanted(&self, knowledge_base_id: Option<Uuid>) -> Result<Vec<ArticleLink>, DbErr> {
+        let in_kb = match knowledge_base_id {
+            Some(kb_id) => article_link::Column::KnowledgeBaseId.eq(kb_id),
+            None => article_link::Column::KnowledgeBaseId.is_null(),
+        };
+        let models = article_link::Entity::find()
+            .filter(article_link::Column::TargetId.is_null())
+            .filter(in_kb)
+            .all(&self.db)
+            .await?;
+        Ok(models.into_iter().map(map_link).collect())
+    }
+
+    /// Links written as `[[title]]` that point at an article, or that would
+    /// have before it was renamed (unresolved, same knowledge base).
+    pub async fn find_links_to(&self, target_id: Uuid, title: &str, knowledge_base_id: Option<Uuid>) -> Result<Vec<ArticleLink>, DbErr> {
+        let in_kb = match knowledge_base_id {
+            Some(kb_id) => article_link::Column::KnowledgeBaseId.eq(kb_id),
+            None => article_link::Column::KnowledgeBaseId.is_null(),
+        };
+        let models = article_link::Entity::find()
+            .filter(Expr::expr(Func::lower(Expr::col((article_link::Entity, article_link::Column::TargetTitle)))).eq(title.trim().to_lowercase()))
+            .filter(
+                Condition::any()
+                    .add(article_link::Column::TargetId.eq(target_id))
+                    .add(Condition::all().add(article_link::Column::TargetId.is_null()).add(in_kb))
+            )
+            .all(&self.db)
+            .await?;
+        Ok(models.into_iter().map(map_link).collect())
+    }
+}
+
+fn map_link(m: article_link::Model) -> ArticleLink {
+    ArticleLink {
+        source_id: m.source_id,
+        source_block_id: m.source_block_id,
+        target_id: m.target_id,
+        target_title: m.target_title,
+        alias: m.alias,
+        context: m.context,
+        knowledge_base_id: m.knowledge_base_id,
+    }
+}
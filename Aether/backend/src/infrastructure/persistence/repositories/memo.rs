@@ -1,18 +1,33 @@
 use async_trait::async_trait;
 use sea_orm::*;
 use uuid::Uuid;
-use chrono::Utc;
+use chrono::{SubsecRound, Utc};
 use crate::domain::models::{Memo, Node, NodeType, PermissionMode};
 use crate::domain::models::UserId;
 use crate::domain::ports::{MemoRepository, RepositoryError};
 use crate::infrastructure::persistence::postgres::PostgresRepository;
 use crate::infrastructure::persistence::entities::{node, memo_detail};
 
-#[async_trait]
-impl MemoRepository for PostgresRepository {
-    async fn save(&self, memo: Memo) -> Result<Uuid, RepositoryError> {
+impl PostgresRepository {
+    /// Saves a memo and returns its new revision. With `expected_revision`,
+    /// nothing is written unless the stored memo is still at it.
+    async fn save_memo(&self, memo: Memo, expected_revision: Option<i64>) -> Result<i64, RepositoryError> {
         let txn = self.db.begin().await.map_err(|e| RepositoryError::ConnectionError(e.to_string()))?;
 
+        if let Some(expected) = expected_revision {
+            // Locked until commit, so no other save slips in between check and write
+            let stored = node::Entity::find_by_id(memo.node.id)
+                .lock_exclusive()
+                .one(&txn)
+                .await
+                .map_err(|e| RepositoryError::ConnectionError(e.to_string()))?
+                .ok_or(RepositoryError::NotFound(format!("Memo {}", memo.node.id)))?;
+            let current = stored.updated_at.timestamp_micros();
+            if current != expected {
+                return Err(RepositoryError::RevisionConflict { expected, current });
+            }
+        }
+
         // 1. Save Node
         let node_model = node::ActiveModel {
             id: Set(memo.node.id),
@@ -28,7 +43,8 @@ impl MemoRepository for PostgresRepository {
             }),
             permission_data: Set(None),
             created_at: Set(memo.node.created_at.into()),
-            updated_at: Set(memo.node.updated_at.into()),
+            // Revisions count microseconds: nothing finer is stored for the database to round
+            updated_at: Set(memo.node.updated_at.trunc_subsecs(6).into()),
         };
         node::Entity::insert(node_model)
             .on_conflict(
@@ -106,7 +122,20 @@ impl MemoRepository for PostgresRepository {
             .exec(&txn).await.map_err(|e| RepositoryError::ConnectionError(e.to_string()))?;
 
         txn.commit().await.map_err(|e| RepositoryError::ConnectionError(e.to_string()))?;
-        Ok(memo.node.id)
+        Ok(memo.revision())
+    }
+}
+
+#[async_trait]
+impl MemoRepository for PostgresRepository {
+    async fn save(&self, memo: Memo) -> Result<Uuid, RepositoryError> {
+        let id = memo.node.id;
+        self.save_memo(memo, None).await?;
+        Ok(id)
+    }
+
+    async fn save_if_revision(&self, memo: Memo, expected_revision: i64) -> Result<i64, RepositoryError> {
+        self.save_memo(memo, Some(expected_revision)).await
     }
 
     async fn find_by_id(&self, id: &Uuid) -> Result<Option<Memo>, RepositoryError> {
@@ -10,7 +10,9 @@ pub mod permission;
 pub mod graph_repo;
 pub mod vrkb;
 pub mod block_repository;
+pub mod link_repository;
 pub mod layout_template_repository;
 pub mod audit;
+pub mod asset;
 pub mod prkb;
 pub mod system_settings_repository;
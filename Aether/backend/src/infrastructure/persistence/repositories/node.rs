@@ -1,4 +1,5 @@
 use async_trait::async_trait;
+use chrono::SubsecRound;
 use sea_orm::*;
 use uuid::Uuid;
 use crate::domain::models::{Node, NodeType, PermissionMode};
@@ -62,7 +63,7 @@ impl NodeRepository for PostgresRepository {
             }),
             permission_data: Set(None), 
             created_at: Set(node.created_at.into()),
-            updated_at: Set(node.updated_at.into()),
+            updated_at: Set(node.updated_at.trunc_subsecs(6).into()),
         };
 
         // Standard Insert. Assuming ID is unique or handling error.
@@ -93,6 +94,45 @@ impl NodeRepository for PostgresRepository {
         Ok(node.id)
     }
 
+    async fn save_if_revision(&self, node: Node, expected_revision: i64) -> Result<i64, RepositoryError> {
+        let at = chrono::DateTime::<chrono::Utc>::from_timestamp_micros(expected_revision)
+            .ok_or(RepositoryError::ValidationError(format!("Invalid revision {}", expected_revision)))?;
+        let model = node::ActiveModel {
+            title: Set(node.title.clone()),
+            parent_id: Set(node.parent_id),
+            knowledge_base_id: Set(node.knowledge_base_id),
+            permission_mode: Set(match node.permission_mode {
+                PermissionMode::Public => "public".to_string(),
+                PermissionMode::Private => "private".to_string(),
+                PermissionMode::Internal => "internal".to_string(),
+            }),
+            updated_at: Set(node.updated_at.trunc_subsecs(6).into()),
+            ..Default::default()
+        };
+
+        // Conditional update: only matches while the stored time is the one the client saw.
+        // Compared as a one-microsecond range, since revisions drop anything finer.
+        let result = node::Entity::update_many()
+            .set(model)
+            .filter(node::Column::Id.eq(node.id))
+            .filter(node::Column::UpdatedAt.gte(at))
+            .filter(node::Column::UpdatedAt.lt(at + chrono::Duration::microseconds(1)))
+            .exec(&self.db)
+            .await
+            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        if result.rows_affected == 0 {
+            return match NodeRepository::find_by_id(self, &node.id).await? {
+                Some(current) => Err(RepositoryError::RevisionConflict {
+                    expected: expected_revision,
+                    current: current.updated_at.timestamp_micros(),
+                }),
+                None => Err(RepositoryError::NotFound(format!("Node {}", node.id))),
+            };
+        }
+        Ok(node.updated_at.timestamp_micros())
+    }
+
     async fn list_by_parent(&self, parent_id: Option<Uuid>) -> Result<Vec<Node>, RepositoryError> {
         let condition = if let Some(pid) = parent_id {
             node::Column::ParentId.eq(pid)
@@ -2,8 +2,9 @@ use async_trait::async_trait;
 use sea_orm::*;
 use uuid::Uuid;
 use chrono::Utc;
-use crate::domain::models::{Vocabulary, Node, NodeType, PermissionMode};
+use crate::domain::models::{Vocabulary, VocabularyRoot, Node, NodeType, PermissionMode};
 use crate::domain::models::UserId;
+use crate::domain::morphology::Morphology;
 use crate::domain::ports::{VocabularyRepository, RepositoryError};
 use crate::infrastructure::persistence::postgres::PostgresRepository;
 use crate::infrastructure::persistence::entities::{node, vocab_detail, vocab_example, global_sentence, vocab_root};
@@ -15,10 +16,10 @@ impl VocabularyRepository for PostgresRepository {
 
         // 1. Handle Root (if present)
         let mut root_id = None;
-        if let Some(r_str) = &vocab.root {
+        if let Some(r_str) = vocab.root.as_deref().map(Morphology::canonical_root).filter(|r| !r.is_empty()) {
             // Check if root exists
             let existing = vocab_root::Entity::find()
-                .filter(vocab_root::Column::Root.eq(r_str))
+                .filter(vocab_root::Column::Root.eq(r_str.as_str()))
                 .one(&txn).await.map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
             
             if let Some(e) = existing {
@@ -27,8 +28,8 @@ impl VocabularyRepository for PostgresRepository {
                 let new_id = Uuid::new_v4();
                 let model = vocab_root::ActiveModel {
                     id: Set(new_id),
-                    root: Set(r_str.clone()),
-                    meaning: Set(None), 
+                    meaning: Set(Morphology::root_meaning(&r_str).map(|m| m.to_string())),
+                    root: Set(r_str),
                 };
                 vocab_root::Entity::insert(model)
                     .exec(&txn).await.map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
@@ -62,8 +63,10 @@ impl VocabularyRepository for PostgresRepository {
             .exec(&txn).await.map_err(|e| RepositoryError::ConnectionError(e.to_string()))?;
 
         // 3. Save Detail (with Root ID)
+        let lemma = Morphology::lemmatize(&vocab.word);
         let detail_model = vocab_detail::ActiveModel {
             id: Set(vocab.node.id),
+            lemma: Set(Some(lemma)),
             word: Set(vocab.word),
             definition: Set(vocab.definition),
             translation: Set(vocab.translation),
@@ -77,7 +80,7 @@ impl VocabularyRepository for PostgresRepository {
         vocab_detail::Entity::insert(detail_model)
             .on_conflict(
                 sea_orm::sea_query::OnConflict::column(vocab_detail::Column::Id)
-                    .update_columns([vocab_detail::Column::Definition, vocab_detail::Column::Translation, vocab_detail::Column::Status, vocab_detail::Column::RootId, vocab_detail::Column::QueryCount, vocab_detail::Column::IsImportant])
+                    .update_columns([vocab_detail::Column::Definition, vocab_detail::Column::Translation, vocab_detail::Column::Status, vocab_detail::Column::RootId, vocab_detail::Column::Lemma, vocab_detail::Column::QueryCount, vocab_detail::Column::IsImportant])
                     .to_owned()
             )
             .exec(&txn).await.map_err(|e| RepositoryError::ConnectionError(e.to_string()))?;
@@ -171,11 +174,21 @@ impl VocabularyRepository for PostgresRepository {
     }
 
     async fn find_by_word(&self, user_id: &UserId, word: &str) -> Result<Option<Vocabulary>, RepositoryError> {
-        let details = vocab_detail::Entity::find()
-            .filter(vocab_detail::Column::Word.eq(word))
+        // Match the exact word or an entry stored under its lemma ("running" -> "run").
+        // Other lemma candidates are guesses and would merge unrelated words.
+        let lemma = Morphology::lemmatize(word);
+        let mut details = vocab_detail::Entity::find()
+            .filter(
+                Condition::any()
+                    .add(vocab_detail::Column::Word.eq(word))
+                    .add(vocab_detail::Column::Lemma.eq(lemma.clone()))
+            )
             .all(&self.db).await
             .map_err(|e| RepositoryError::ConnectionError(e.to_string()))?;
 
+        // Exact spelling wins over lemma matches
+        details.sort_by_key(|d| (d.word != word, d.lemma.as_deref() != Some(lemma.as_str())));
+
         for d in details {
              let n_opt = node::Entity::find_by_id(d.id).one(&self.db).await
                 .map_err(|e| RepositoryError::ConnectionError(e.to_string()))?;
@@ -339,6 +352,48 @@ impl VocabularyRepository for PostgresRepository {
         
         Ok(results.into_iter().map(|g| (g.id, g.text, g.translation)).collect())
     }
+
+    async fn find_root(&self, root: &str) -> Result<Option<VocabularyRoot>, RepositoryError> {
+        let canonical = Morphology::canonical_root(root);
+        let model = vocab_root::Entity::find()
+            .filter(vocab_root::Column::Root.eq(canonical))
+            .one(&self.db).await.map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        Ok(model.map(|r| VocabularyRoot { id: r.id, root: r.root, meaning: r.meaning }))
+    }
+
+    async fn list_by_root(&self, user_id: &UserId, root: &str) -> Result<Vec<Vocabulary>, RepositoryError> {
+        let Some(root_model) = self.find_root(root).await? else {
+            return Ok(vec![]);
+        };
+
+        let results = node::Entity::find()
+            .filter(node::Column::Type.eq("Vocabulary"))
+            .filter(node::Column::AuthorId.eq(user_id.0))
+            .find_also_related(vocab_detail::Entity)
+            .filter(vocab_detail::Column::RootId.eq(root_model.id))
+            .order_by_asc(node::Column::Title)
+            .all(&self.db).await.map_err(|e| RepositoryError::ConnectionError(e.to_string()))?;
+
+        let mut vocabs = Vec::new();
+        for (n, d) in results {
+            if let Some(detail) = d {
+                let examples = fetch_examples_for_vocab(&self.db, detail.id).await?;
+                vocabs.push(map_vocab(n, detail, Some(root_model.root.clone()), examples));
+            }
+        }
+        Ok(vocabs)
+    }
+
+    async fn list_definitions(&self, user_id: &UserId) -> Result<Vec<(String, String, Option<String>)>, RepositoryError> {
+        let results = vocab_detail::Entity::find()
+            .inner_join(node::Entity)
+            .filter(node::Column::Type.eq("Vocabulary"))
+            .filter(node::Column::AuthorId.eq(user_id.0))
+            .all(&self.db).await.map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        Ok(results.into_iter().map(|d| (d.word, d.definition, d.translation)).collect())
+    }
 }
 
 // Helpers
@@ -416,6 +471,7 @@ fn map_vocab(n: node::Model, d: vocab_detail::Model, root: Option<String>, examp
             updated_at: n.updated_at.with_timezone(&Utc),
         },
         word: d.word,
+        lemma: d.lemma,
         definition: d.definition,
         translation: d.translation,
         phonetic: d.phonetic,
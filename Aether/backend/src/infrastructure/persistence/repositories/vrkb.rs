@@ -1,7 +1,7 @@
 use async_trait::async_trait;
 use sea_orm::*;
 use uuid::Uuid;
-use chrono::Utc;
+use chrono::{SubsecRound, Utc};
 use crate::domain::models::{VrkbProject, VrkbSection, VrkbFinding, VrkbAsset, VrkbMember, VrkbSpec, VrkbDoc};
 use crate::domain::ports::{VrkbRepository, RepositoryError};
 use crate::infrastructure::persistence::postgres::PostgresRepository;
@@ -386,7 +386,7 @@ impl VrkbRepository for PostgresRepository {
             parent_id: Set(doc_data.parent_id),
             author_id: Set(doc_data.author_id),
             created_at: Set(doc_data.created_at.into()),
-            updated_at: Set(doc_data.updated_at.into()),
+            updated_at: Set(doc_data.updated_at.trunc_subsecs(6).into()),
             deleted_at: Set(doc_data.deleted_at.map(|d| d.into())),
         };
         doc::Entity::insert(active_model)
@@ -415,7 +415,8 @@ impl VrkbRepository for PostgresRepository {
         }))
     }
 
-    async fn update_doc(&self, doc_data: VrkbDoc) -> Result<(), RepositoryError> {
+    async fn update_doc(&self, doc_data: VrkbDoc, expected_revision: Option<i64>) -> Result<(), RepositoryError> {
+        let id = doc_data.id;
         let active_model = doc::ActiveModel {
             id: Set(doc_data.id),
             project_id: Set(doc_data.project_id),
@@ -424,21 +425,37 @@ impl VrkbRepository for PostgresRepository {
             parent_id: Set(doc_data.parent_id),
             author_id: Set(doc_data.author_id),
             // created_at: Set(doc_data.created_at.into()), // Don't update created_at?
-            updated_at: Set(doc_data.updated_at.into()),
+            updated_at: Set(doc_data.updated_at.trunc_subsecs(6).into()),
             deleted_at: Set(doc_data.deleted_at.map(|d| d.into())),
             ..Default::default() // Important strictly for partial updates if we were doing find first, but here we replace all fields we set.
         };
-        
-        // Use update method which expects model to result from find
-        // Or clearer: find -> update.
-        // But for upsert-like behavior we can just do insert ... on conflict update
-        
-        // Let's stick to update logic:
-         doc::Entity::update(active_model)
+
+        // Conditional update: only matches while the stored timestamp is the one the client saw.
+        // Compared as a one-microsecond range, since revisions drop anything finer.
+        let mut update = doc::Entity::update_many()
+            .set(active_model)
+            .filter(doc::Column::Id.eq(id));
+        if let Some(expected) = expected_revision {
+            let at = chrono::DateTime::<Utc>::from_timestamp_micros(expected)
+                .ok_or(RepositoryError::ValidationError(format!("Invalid revision {}", expected)))?;
+            update = update
+                .filter(doc::Column::UpdatedAt.gte(at))
+                .filter(doc::Column::UpdatedAt.lt(at + chrono::Duration::microseconds(1)));
+        }
+        let result = update
             .exec(&self.db)
             .await
             .map_err(|e| RepositoryError::ConnectionError(e.to_string()))?;
-            
+
+        if result.rows_affected == 0 {
+            return match self.get_doc(&id).await? {
+                Some(current) => Err(RepositoryError::RevisionConflict {
+                    expected: expected_revision.unwrap_or_default(),
+                    current: current.revision(),
+                }),
+                None => Err(RepositoryError::NotFound(format!("Doc {}", id))),
+            };
+        }
         Ok(())
     }
 
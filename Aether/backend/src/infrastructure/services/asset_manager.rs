@@ -1,8 +1,6 @@
 use std::path::PathBuf;
 use std::sync::Arc;
 use uuid::Uuid;
-use sha2::{Digest, Sha256};
-use tokio::io::AsyncWriteExt;
 use serde_json::json;
 use chrono::Utc;
 
@@ -13,6 +11,7 @@ use crate::domain::models::{
 };
 use crate::domain::permission_service::PermissionService;
 use crate::infrastructure::persistence::postgres::PostgresRepository;
+use crate::infrastructure::storage::blob_store::BlobStore;
 
 #[derive(Clone)]
 pub struct AssetManager {
@@ -21,6 +20,7 @@ pub struct AssetManager {
     kb_repo: Arc<dyn KnowledgeBaseRepository>,
     perm_service: Arc<PermissionService<PostgresRepository>>,
     storage_root: PathBuf,
+    blobs: Arc<BlobStore>,
 }
 
 impl AssetManager {
@@ -30,6 +30,7 @@ impl AssetManager {
         kb_repo: Arc<dyn KnowledgeBaseRepository>,
         perm_service: Arc<PermissionService<PostgresRepository>>,
         storage_root: String,
+        blobs: Arc<BlobStore>,
     ) -> Self {
         Self {
             _node_repo: node_repo,
@@ -37,6 +38,7 @@ impl AssetManager {
             kb_repo,
             perm_service,
             storage_root: PathBuf::from(storage_root),
+            blobs,
         }
     }
 
@@ -51,27 +53,13 @@ impl AssetManager {
         // 1. Ensure "My Assets" KB exists for this user
         let kb_id = self.ensure_my_assets_kb(user_id).await.map_err(|e| e.to_string())?;
 
-        // 2. Compute Hash
-        let mut hasher = Sha256::new();
-        hasher.update(data);
-        let hash_result = hasher.finalize();
-        let hash_hex = format!("{:x}", hash_result);
+        // 2. Save File to the blob store (content addressed, stored once)
+        let hash_hex = self.blobs.put(data).await?;
 
-        // 3. Save File to Disk
-        // Structure: uploads/ab/abcdef...
-        let sharded_dir = self.storage_root.join("uploads").join(&hash_hex[0..2]);
-        let file_path = sharded_dir.join(&hash_hex);
-        
-        tokio::fs::create_dir_all(&sharded_dir).await.map_err(|e| e.to_string())?;
-        
-        // We overwrite if exists (content addressable, so it's safe)
-        let mut file = tokio::fs::File::create(&file_path).await.map_err(|e| e.to_string())?;
-        file.write_all(data).await.map_err(|e| e.to_string())?;
-
-        // 4. Create Article (Asset Entity)
+        // 3. Create Article (Asset Entity)
         
         // Relative path for storage in DB
-        let relative_path = format!("uploads/{}/{}", &hash_hex[0..2], hash_hex);
+        let relative_path = self.blobs.storage_path(&hash_hex);
 
         let payload = json!({
             "file_path": relative_path,
@@ -182,7 +170,11 @@ impl AssetManager {
             .unwrap_or("application/octet-stream")
             .to_string();
 
-        let full_path = self.storage_root.join(relative_path);
+        // The blob may have moved to the unified layout since
+        let full_path = payload.get("hash")
+            .and_then(|v| v.as_str())
+            .and_then(|hash| self.blobs.locate(hash))
+            .unwrap_or_else(|| self.storage_root.join(relative_path));
 
         Ok((full_path, mime_type))
     }
@@ -0,0 +1,148 @@
+use std::io::Read;
+use argon2::{Algorithm, Argon2, Params, Version};
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use chrono::Utc;
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+use ring::rand::{SecureRandom, SystemRandom};
+use serde::{Deserialize, Serialize};
+
+// An encrypted backup is `MAGIC`, the length of the header (u32, big endian),
+// the JSON header, then the zip sealed with AES-256-GCM. The header is the
+// associated data, so the hint and KDF parameters cannot be swapped either.
+const MAGIC: &[u8] = b"AKBENC1\n";
+const SALT_LEN: usize = 16;
+/// Header lengths come from the file: longer ones are refused, not allocated.
+const MAX_HEADER_LEN: usize = 64 << 10;
+/// Larger KDF costs in a header are refused rather than computed; a few
+/// times what `encrypt` writes (Argon2's defaults: 19 MiB, 2 passes).
+const MAX_MEMORY_KIB: u32 = 64 << 10;
+const MAX_ITERATIONS: u32 = 8;
+
+/// How to encrypt a backup; the hint is stored in clear.
+#[derive(Clone, Deserialize)]
+pub struct BackupEncryption {
+    pub passphrase: String,
+    #[serde(default)]
+    pub hint: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EncryptionHeader {
+    pub cipher: String,
+    pub kdf: String,
+    pub memory_kib: u32,
+    pub iterations: u32,
+    pub parallelism: u32,
+    pub salt: String,
+    pub nonce: String,
+    pub hint: Option<String>,
+    pub encrypted_at: String,
+}
+
+pub fn is_encrypted(data: &[u8]) -> bool {
+    data.starts_with(MAGIC)
+}
+
+/// The header of an encrypted backup, `None` for a plain one.
+pub fn peek_header<R: Read>(mut reader: R) -> Result<Option<EncryptionHeader>, String> {
+    let mut magic = [0u8; MAGIC.len()];
+    if reader.read_exact(&mut magic).is_err() || magic != MAGIC {
+        return Ok(None);
+    }
+    let mut len = [0u8; 4];
+    reader.read_exact(&mut len).map_err(|_| "Invalid encrypted backup")?;
+    let len = u32::from_be_bytes(len) as usize;
+    if len > MAX_HEADER_LEN {
+        return Err("Invalid encrypted backup".to_string());
+    }
+    let mut header = vec![0u8; len];
+    reader.read_exact(&mut header).map_err(|_| "Invalid encrypted backup")?;
+    serde_json::from_slice(&header).map(Some).map_err(|_| "Invalid encrypted backup".to_string())
+}
+
+/// Splits an encrypted backup into its header, the header bytes and the ciphertext.
+fn split(data: &[u8]) -> Result<(EncryptionHeader, &[u8], &[u8]), String> {
+    let rest = data.strip_prefix(MAGIC).ok_or("Invalid encrypted backup")?;
+    if rest.len() < 4 {
+        return Err("Invalid encrypted backup".to_string());
+    }
+    let len = u32::from_be_bytes([rest[0], rest[1], rest[2], rest[3]]) as usize;
+    if len > MAX_HEADER_LEN {
+        return Err("Invalid encrypted backup".to_string());
+    }
+    let (header_bytes, ciphertext) = rest[4..].split_at_checked(len).ok_or("Invalid encrypted backup")?;
+    let header = serde_json::from_slice(header_bytes).map_err(|_| "Invalid encrypted backup")?;
+    Ok((header, header_bytes, ciphertext))
+}
+
+fn derive_key(passphrase: &str, salt: &[u8], header: &EncryptionHeader) -> Result<LessSafeKey, String> {
+    if header.kdf != "argon2id" || header.cipher != "aes-256-gcm" {
+        return Err(format!("Unsupported encryption: {} / {}", header.kdf, header.cipher));
+    }
+    if header.memory_kib > MAX_MEMORY_KIB || header.iterations > MAX_ITERATIONS {
+        return Err("Invalid encrypted backup".to_string());
+    }
+    let params = Params::new(header.memory_kib, header.iterations, header.parallelism, Some(32)).map_err(|e| e.to_string())?;
+    let mut key = [0u8; 32];
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| e.to_string())?;
+    let key = UnboundKey::new(&AES_256_GCM, &key).map_err(|_| "Invalid key")?;
+    Ok(LessSafeKey::new(key))
+}
+
+pub fn encrypt(data: &[u8], encryption: &BackupEncryption) -> Result<Vec<u8>, String> {
+    if encryption.passphrase.is_empty() {
+        return Err("Passphrase must not be empty".to_string());
+    }
+    let rng = SystemRandom::new();
+    let mut salt = [0u8; SALT_LEN];
+    let mut nonce = [0u8; NONCE_LEN];
+    rng.fill(&mut salt).map_err(|_| "No randomness available")?;
+    rng.fill(&mut nonce).map_err(|_| "No randomness available")?;
+
+    let params = Params::default();
+    let header = EncryptionHeader {
+        cipher: "aes-256-gcm".to_string(),
+        kdf: "argon2id".to_string(),
+        memory_kib: params.m_cost(),
+        iterations: params.t_cost(),
+        parallelism: params.p_cost(),
+        salt: STANDARD.encode(salt),
+        nonce: STANDARD.encode(nonce),
+        hint: encryption.hint.clone().filter(|h| !h.trim().is_empty()),
+        encrypted_at: Utc::now().to_rfc3339(),
+    };
+    let header_bytes = serde_json::to_vec(&header).map_err(|e| e.to_string())?;
+    if header_bytes.len() > MAX_HEADER_LEN {
+        return Err("Hint is too long".to_string());
+    }
+    let key = derive_key(&encryption.passphrase, &salt, &header)?;
+
+    let mut sealed = data.to_vec();
+    key.seal_in_place_append_tag(Nonce::assume_unique_for_key(nonce), Aad::from(&header_bytes), &mut sealed)
+        .map_err(|_| "Encryption failed")?;
+
+    let mut out = Vec::with_capacity(MAGIC.len() + 4 + header_bytes.len() + sealed.len());
+    out.extend_from_slice(MAGIC);
+    out.extend_from_slice(&(header_bytes.len() as u32).to_be_bytes());
+    out.extend_from_slice(&header_bytes);
+    out.extend_from_slice(&sealed);
+    Ok(out)
+}
+
+pub fn decrypt(data: &[u8], passphrase: &str) -> Result<Vec<u8>, String> {
+    let (header, header_bytes, ciphertext) = split(data)?;
+    let salt = STANDARD.decode(&header.salt).map_err(|_| "Invalid encrypted backup")?;
+    let nonce = STANDARD.decode(&header.nonce).ok()
+        .and_then(|n| Nonce::try_assume_unique_for_key(&n).ok())
+        .ok_or("Invalid encrypted backup")?;
+    let key = derive_key(passphrase, &salt, &header)?;
+
+    let mut plain = ciphertext.to_vec();
+    let len = key.open_in_place(nonce, Aad::from(header_bytes), &mut plain)
+        .map_err(|_| "Wrong passphrase or corrupted backup")?
+        .len();
+    plain.truncate(len);
+    Ok(plain)
+}
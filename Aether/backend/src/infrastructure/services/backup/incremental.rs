@@ -0,0 +1,70 @@
+use std::collections::{HashMap, HashSet};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// The backup an incremental one builds on. The checksum of its manifest
+/// identifies it, and survives re-encryption.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BackupBase {
+    pub filename: String,
+    pub manifest_sha256: String,
+}
+
+/// Node ids whose content changed since the base (new ones included), and
+/// those the base has that are gone. States are `(version, updated_at)`.
+pub fn diff_nodes(base: &HashMap<Uuid, (i64, String)>, current: &[(Uuid, i64, String)]) -> (HashSet<Uuid>, Vec<Uuid>) {
+    let changed = current.iter()
+        .filter(|(id, version, updated_at)| base.get(id).is_none_or(|(v, u)| v != version || u != updated_at))
+        .map(|(id, _, _)| *id)
+        .collect();
+    let present: HashSet<Uuid> = current.iter().map(|(id, _, _)| *id).collect();
+    let mut removed: Vec<Uuid> = base.keys().filter(|id| !present.contains(id)).copied().collect();
+    removed.sort();
+    (changed, removed)
+}
+
+/// `assets/<sha256>` -> the hash; assets of older backups are named by id.
+pub fn asset_hash(path: &str) -> Option<&str> {
+    path.strip_prefix("assets/").filter(|h| h.len() == 64 && h.bytes().all(|b| b.is_ascii_hexdigit()))
+}
+
+/// Where the assets of a backup chain are: the archive holding each (`None`
+/// for the one being written) and its path there.
+#[derive(Debug, Clone, Default)]
+pub struct AssetIndex {
+    pub by_id: HashMap<Uuid, (Option<String>, String)>,
+    pub by_hash: HashMap<String, (Option<String>, String)>,
+}
+
+impl AssetIndex {
+    /// The assets of a base backup: those it stores itself are in `filename`.
+    pub fn from_base(filename: &str, assets_map: &HashMap<Uuid, String>, asset_sources: &HashMap<Uuid, String>) -> Self {
+        let mut index = Self::default();
+        for (id, path) in assets_map {
+            let source = asset_sources.get(id).cloned().unwrap_or_else(|| filename.to_string());
+            index.insert(*id, path, Some(source));
+        }
+        index
+    }
+
+    pub fn insert(&mut self, id: Uuid, path: &str, source: Option<String>) {
+        if let Some(hash) = asset_hash(path) {
+            self.by_hash.entry(hash.to_string()).or_insert_with(|| (source.clone(), path.to_string()));
+        }
+        self.by_id.insert(id, (source, path.to_string()));
+    }
+}
+
+/// `keep` and, transitively, the bases of the incremental backups in it.
+/// `chains` maps an incremental backup to its base.
+pub fn with_bases(mut keep: HashSet<String>, chains: &HashMap<String, String>) -> HashSet<String> {
+    let mut pending: Vec<String> = keep.iter().cloned().collect();
+    while let Some(filename) = pending.pop() {
+        if let Some(base) = chains.get(&filename) {
+            if keep.insert(base.clone()) {
+                pending.push(base.clone());
+            }
+        }
+    }
+    keep
+}
@@ -0,0 +1,167 @@
+use std::collections::HashSet;
+use std::io::{Read, Seek};
+use chrono::Utc;
+use ring::hmac;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+use zip::ZipArchive;
+
+pub const MANIFEST_PATH: &str = "manifest.json";
+pub const SIGNATURE_PATH: &str = "manifest.sig";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ManifestEntry {
+    pub path: String,
+    pub sha256: String,
+    pub size: u64,
+}
+
+/// Checksums of every file in a backup; `manifest.sig` holds its HMAC-SHA256.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupManifest {
+    pub version: String,
+    pub created_at: String,
+    pub entries: Vec<ManifestEntry>,
+}
+
+impl BackupManifest {
+    pub fn new() -> Self {
+        Self { version: "1".to_string(), created_at: Utc::now().to_rfc3339(), entries: vec![] }
+    }
+
+    pub fn add(&mut self, path: &str, data: &[u8]) {
+        self.entries.push(ManifestEntry {
+            path: path.to_string(),
+            sha256: format!("{:x}", Sha256::digest(data)),
+            size: data.len() as u64,
+        });
+    }
+}
+
+impl Default for BackupManifest {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// An asset referenced by the backed up articles that could not be included.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SkippedAsset {
+    pub id: Uuid,
+    pub reason: String,
+}
+
+pub fn sign(key: &[u8], data: &[u8]) -> String {
+    let tag = hmac::sign(&hmac::Key::new(hmac::HMAC_SHA256, key), data);
+    tag.as_ref().iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+pub fn verify_signature(key: &[u8], data: &[u8], signature: &str) -> bool {
+    let bytes: Option<Vec<u8>> = signature.trim().as_bytes().chunks(2)
+        .map(|pair| std::str::from_utf8(pair).ok().filter(|_| pair.len() == 2).and_then(|hex| u8::from_str_radix(hex, 16).ok()))
+        .collect();
+    bytes.is_some_and(|bytes| hmac::verify(&hmac::Key::new(hmac::HMAC_SHA256, key), data, &bytes).is_ok())
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct VerifyReport {
+    pub valid: bool,
+    /// False for backups made before manifests were written (version 1.0).
+    pub has_manifest: bool,
+    pub signature_valid: bool,
+    /// An unsigned 1.0 backup, accepted only because the caller allowed legacy backups.
+    pub unsigned_legacy: bool,
+    pub checked: usize,
+    /// Listed (in the manifest or `meta.json`) but not in the archive.
+    pub missing: Vec<String>,
+    /// Checksum or size mismatch.
+    pub corrupted: Vec<String>,
+    /// In the archive but not in the manifest.
+    pub unexpected: Vec<String>,
+    pub skipped_assets: Vec<SkippedAsset>,
+    pub errors: Vec<String>,
+}
+
+impl VerifyReport {
+    /// Adds the `required` paths missing from the archive and settles `valid`.
+    pub fn finish<R: Read + Seek>(mut self, archive: &ZipArchive<R>, required: &[String]) -> Self {
+        let names: HashSet<&str> = archive.file_names().collect();
+        for path in required {
+            if !names.contains(path.as_str()) && !self.missing.contains(path) {
+                self.missing.push(path.clone());
+            }
+        }
+        self.valid = (self.signature_valid || self.unsigned_legacy)
+            && self.missing.is_empty()
+            && self.corrupted.is_empty()
+            && self.unexpected.is_empty()
+            && self.errors.is_empty();
+        self
+    }
+
+    /// One line on what is wrong, for errors.
+    pub fn summary(&self) -> String {
+        let mut problems = self.errors.clone();
+        for (count, what) in [(self.missing.len(), "missing"), (self.corrupted.len(), "corrupted"), (self.unexpected.len(), "unexpected")] {
+            if count > 0 {
+                problems.push(format!("{} {} file(s)", count, what));
+            }
+        }
+        problems.join(", ")
+    }
+}
+
+/// Checks the manifest signature and every file of the archive against it.
+/// The result still needs `VerifyReport::finish`.
+pub fn verify_archive<R: Read + Seek>(archive: &mut ZipArchive<R>, key: &[u8]) -> VerifyReport {
+    let mut report = VerifyReport::default();
+    let Ok(manifest_data) = read_entry(archive, MANIFEST_PATH) else {
+        return report;
+    };
+    report.has_manifest = true;
+
+    match read_entry(archive, SIGNATURE_PATH) {
+        Ok(signature) => report.signature_valid = verify_signature(key, &manifest_data, &String::from_utf8_lossy(&signature)),
+        Err(_) => report.errors.push("Signature missing".to_string()),
+    }
+    if !report.signature_valid && report.errors.is_empty() {
+        report.errors.push("Signature does not match".to_string());
+    }
+    let manifest: BackupManifest = match serde_json::from_slice(&manifest_data) {
+        Ok(manifest) => manifest,
+        Err(e) => {
+            report.errors.push(format!("Invalid manifest: {}", e));
+            return report;
+        }
+    };
+
+    for entry in &manifest.entries {
+        match read_entry(archive, &entry.path) {
+            Ok(data) => {
+                report.checked += 1;
+                if data.len() as u64 != entry.size || format!("{:x}", Sha256::digest(&data)) != entry.sha256 {
+                    report.corrupted.push(entry.path.clone());
+                }
+            }
+            Err(_) => report.missing.push(entry.path.clone()),
+        }
+    }
+
+    let listed: HashSet<&str> = manifest.entries.iter().map(|e| e.path.as_str()).collect();
+    for i in 0..archive.len() {
+        let Ok(file) = archive.by_index(i) else { continue };
+        let name = file.name();
+        if !file.is_dir() && name != MANIFEST_PATH && name != SIGNATURE_PATH && !listed.contains(name) {
+            report.unexpected.push(name.to_string());
+        }
+    }
+    report
+}
+
+pub fn read_entry<R: Read + Seek>(archive: &mut ZipArchive<R>, path: &str) -> Result<Vec<u8>, String> {
+    let mut file = archive.by_name(path).map_err(|e| e.to_string())?;
+    let mut data = Vec::new();
+    file.read_to_end(&mut data).map_err(|e| e.to_string())?;
+    Ok(data)
+}
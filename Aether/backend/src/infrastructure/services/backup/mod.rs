@@ -0,0 +1,7 @@
+pub mod crypto;
+pub mod incremental;
+pub mod manifest;
+pub mod restore;
+pub mod schedule;
+
+mod tests;
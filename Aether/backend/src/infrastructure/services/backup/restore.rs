@@ -0,0 +1,129 @@
+use std::collections::HashMap;
+use std::sync::OnceLock;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::domain::blocks::links::rewrite_wiki_links;
+
+/// Where a backup is restored.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RestoreMode {
+    /// A new private knowledge base.
+    #[default]
+    NewKb,
+    /// Into an existing knowledge base, next to its content; folders with the
+    /// same path are reused.
+    Merge,
+    /// Into an existing knowledge base, whose content is deleted first.
+    Overwrite,
+}
+
+/// What to do with an article whose title or slug is already taken.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictPolicy {
+    /// Restore it under a free title (and slug).
+    #[default]
+    Rename,
+    /// Keep the existing article and leave the backup's out.
+    Skip,
+    /// Write the backup's article over the existing one, if it is the user's.
+    Replace,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct RestoreOptions {
+    #[serde(default)]
+    pub mode: RestoreMode,
+    /// Required by `Merge` and `Overwrite`.
+    pub target_kb_id: Option<Uuid>,
+    #[serde(default)]
+    pub conflict_policy: ConflictPolicy,
+    /// Report what would happen without writing anything.
+    #[serde(default)]
+    pub dry_run: bool,
+    /// Accept an unsigned backup made before manifests existed (version 1.0).
+    #[serde(default)]
+    pub allow_unsigned_legacy: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RestoreConflict {
+    pub backup_id: Uuid,
+    pub title: String,
+    /// "title" or "slug"
+    pub field: String,
+    pub existing_id: Uuid,
+    pub resolution: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RestoreReport {
+    pub kb_id: Uuid,
+    pub mode: RestoreMode,
+    pub dry_run: bool,
+    /// Restored from an unsigned legacy backup, whose content could not be checked.
+    pub unsigned_legacy: bool,
+    pub folders: usize,
+    pub articles: usize,
+    pub merged_folders: usize,
+    pub replaced: usize,
+    pub skipped: usize,
+    /// Nodes of the target deleted by `Overwrite`.
+    pub removed: usize,
+    pub assets: usize,
+    pub conflicts: Vec<RestoreConflict>,
+}
+
+impl RestoreReport {
+    pub fn new(kb_id: Uuid, options: &RestoreOptions) -> Self {
+        Self {
+            kb_id,
+            mode: options.mode,
+            dry_run: options.dry_run,
+            unsigned_legacy: false,
+            folders: 0,
+            articles: 0,
+            merged_folders: 0,
+            replaced: 0,
+            skipped: 0,
+            removed: 0,
+            assets: 0,
+            conflicts: vec![],
+        }
+    }
+}
+
+/// Candidate titles for a restored article whose title is taken.
+pub fn restored_title(title: &str, attempt: usize) -> String {
+    match attempt {
+        0 => title.to_string(),
+        1 => format!("{} (restored)", title),
+        n => format!("{} (restored {})", title, n),
+    }
+}
+
+/// Replaces every id of the backup (nodes and assets) found in a body by
+/// its id after the restore: asset references, article URLs and the like.
+pub fn remap_ids(body: &str, ids: &HashMap<Uuid, Uuid>) -> String {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    let re = RE.get_or_init(|| {
+        Regex::new(r"[0-9a-fA-F]{8}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{12}").unwrap()
+    });
+    re.replace_all(body, |caps: &regex::Captures| {
+        Uuid::parse_str(&caps[0]).ok()
+            .and_then(|id| ids.get(&id))
+            .map_or_else(|| caps[0].to_string(), |id| id.to_string())
+    }).into_owned()
+}
+
+/// Points `[[Title]]` links at the titles renamed by the restore.
+pub fn rename_links(body: &str, renames: &[(String, String)]) -> String {
+    let mut body = body.to_string();
+    for (from, to) in renames {
+        body = rewrite_wiki_links(&body, from, to).0;
+    }
+    body
+}
@@ -0,0 +1,127 @@
+use std::collections::HashSet;
+use chrono::{DateTime, Datelike, Duration, NaiveDateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// How many backups to keep: the newest of each of the last `daily` days,
+/// `weekly` ISO weeks and `monthly` months that have one. The newest backup
+/// is always kept.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Retention {
+    pub daily: usize,
+    pub weekly: usize,
+    pub monthly: usize,
+}
+
+impl Default for Retention {
+    fn default() -> Self {
+        Self { daily: 7, weekly: 4, monthly: 6 }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupSchedule {
+    pub kb_id: Uuid,
+    pub owner_id: Uuid,
+    pub enabled: bool,
+    pub interval_hours: u32,
+    pub retention: Retention,
+    #[serde(default)]
+    pub last_run: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub last_success: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub last_error: Option<String>,
+    #[serde(default)]
+    pub consecutive_failures: u32,
+}
+
+/// After a failure the next attempt comes sooner than the interval.
+const RETRY_HOURS: u32 = 1;
+
+impl BackupSchedule {
+    fn wait(&self) -> Duration {
+        let hours = if self.last_error.is_some() { self.interval_hours.min(RETRY_HOURS) } else { self.interval_hours };
+        Duration::hours(hours as i64)
+    }
+
+    /// `None` when disabled; a schedule that never ran is due right away.
+    pub fn next_run(&self) -> Option<DateTime<Utc>> {
+        self.enabled.then(|| self.last_run.map_or_else(Utc::now, |at| at + self.wait()))
+    }
+
+    pub fn is_due(&self, now: DateTime<Utc>) -> bool {
+        self.enabled && self.last_run.is_none_or(|at| at + self.wait() <= now)
+    }
+}
+
+/// Body of `PUT /api/backups/schedules/:kb_id`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ScheduleRequest {
+    #[serde(default = "enabled")]
+    pub enabled: bool,
+    #[serde(default = "daily")]
+    pub interval_hours: u32,
+    #[serde(default)]
+    pub retention: Retention,
+}
+
+fn enabled() -> bool {
+    true
+}
+
+fn daily() -> u32 {
+    24
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BackupEntry {
+    pub filename: String,
+    pub created_at: DateTime<Utc>,
+    pub size: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ScheduleStatus {
+    #[serde(flatten)]
+    pub schedule: BackupSchedule,
+    pub next_run: Option<DateTime<Utc>>,
+    /// Oldest first, so the sizes read as a trend.
+    pub backups: Vec<BackupEntry>,
+    pub total_size: u64,
+}
+
+/// `<kb_id>_<YYYYmmdd>_<HHMMSS>.akb` -> KB and creation time.
+pub fn parse_backup_name(filename: &str) -> Option<(Uuid, DateTime<Utc>)> {
+    let (kb_id, timestamp) = filename.strip_suffix(".akb")?.split_once('_')?;
+    let at = NaiveDateTime::parse_from_str(timestamp, "%Y%m%d_%H%M%S").ok()?;
+    Some((Uuid::parse_str(kb_id).ok()?, at.and_utc()))
+}
+
+/// The day, ISO week or month of a backup.
+type PeriodKey = fn(&DateTime<Utc>) -> (i32, u32);
+
+/// The backups `retention` keeps; the others may be pruned.
+pub fn retained(backups: &[BackupEntry], retention: &Retention) -> HashSet<String> {
+    let mut sorted: Vec<&BackupEntry> = backups.iter().collect();
+    sorted.sort_by_key(|b| std::cmp::Reverse(b.created_at));
+
+    let mut keep: HashSet<String> = sorted.first().map(|b| b.filename.clone()).into_iter().collect();
+    let periods: [(usize, PeriodKey); 3] = [
+        (retention.daily, |at| (at.year(), at.ordinal())),
+        (retention.weekly, |at| (at.iso_week().year(), at.iso_week().week())),
+        (retention.monthly, |at| (at.year(), at.month())),
+    ];
+    for (count, period) in periods {
+        let mut seen = HashSet::new();
+        for backup in &sorted {
+            if seen.len() >= count {
+                break;
+            }
+            if seen.insert(period(&backup.created_at)) {
+                keep.insert(backup.filename.clone());
+            }
+        }
+    }
+    keep
+}
@@ -0,0 +1,502 @@
+#[cfg(test)]
+mod tests {
+    use std::collections::{HashMap, HashSet};
+    use std::path::Path;
+    use std::sync::{Arc, Mutex};
+    use chrono::{Duration, TimeZone, Utc};
+    use std::io::{Cursor, Write};
+    use uuid::Uuid;
+    use zip::{write::FileOptions, ZipArchive, ZipWriter};
+    use crate::domain::models::{
+        Article, ContentBody, ContentDiff, ContentItem, ContentStatus, ContentVersionSnapshot,
+        KnowledgeBase, KnowledgeBaseId, Node, NodeType, NodeVersion, PermissionMode, UserId, Visibility,
+    };
+    use crate::domain::ports::{ArticleRepository, AuditLog, AuditRepository, KnowledgeBaseRepository, NodeRepository, RepositoryError};
+    use crate::infrastructure::services::asset_manager::AssetManager;
+    use crate::infrastructure::services::backup_service::BackupService;
+    use crate::infrastructure::services::backup::crypto::{decrypt, encrypt, is_encrypted, peek_header, BackupEncryption};
+    use crate::infrastructure::services::backup::incremental::{asset_hash, diff_nodes, with_bases, AssetIndex};
+    use crate::infrastructure::services::backup::manifest::{
+        sign, verify_archive, BackupManifest, VerifyReport, MANIFEST_PATH, SIGNATURE_PATH,
+    };
+    use crate::infrastructure::services::backup::schedule::{
+        parse_backup_name, retained, BackupEntry, BackupSchedule, Retention,
+    };
+    use crate::infrastructure::services::backup::restore::{
+        remap_ids, rename_links, restored_title, ConflictPolicy, RestoreMode, RestoreOptions,
+    };
+
+    #[test]
+    fn test_restore_remapping() {
+        let (article, asset, unknown) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let (new_article, new_asset) = (Uuid::new_v4(), Uuid::new_v4());
+        let ids = HashMap::from([(article, new_article), (asset, new_asset)]);
+
+        let body = format!(
+            "[[asset:{}]] ![x](/api/assets/{}) [see](/article/{}) [[{}]]\n",
+            asset, asset.to_string().to_uppercase(), article, unknown
+        );
+        assert_eq!(
+            remap_ids(&body, &ids),
+            format!("[[asset:{}]] ![x](/api/assets/{}) [see](/article/{}) [[{}]]\n", new_asset, new_asset, new_article, unknown)
+        );
+
+        let renames = vec![("Intro".to_string(), restored_title("Intro", 1))];
+        assert_eq!(
+            rename_links("[[intro]] and [[Intro|start]], `[[Intro]]`\n", &renames),
+            "[[Intro (restored)]] and [[Intro (restored)|start]], `[[Intro]]`\n"
+        );
+        assert_eq!(restored_title("Intro", 0), "Intro");
+        assert_eq!(restored_title("Intro", 3), "Intro (restored 3)");
+    }
+
+    #[test]
+    fn test_restore_options() {
+        let options: RestoreOptions = serde_json::from_str("{}").unwrap();
+        assert_eq!((options.mode, options.conflict_policy, options.dry_run), (RestoreMode::NewKb, ConflictPolicy::Rename, false));
+
+        let target = Uuid::new_v4();
+        let options: RestoreOptions = serde_json::from_value(serde_json::json!({
+            "mode": "overwrite", "target_kb_id": target, "conflict_policy": "replace", "dry_run": true
+        })).unwrap();
+        assert_eq!(options.mode, RestoreMode::Overwrite);
+        assert_eq!(options.target_kb_id, Some(target));
+        assert_eq!(options.conflict_policy, ConflictPolicy::Replace);
+        assert!(options.dry_run);
+    }
+
+    /// A backup with the given files; the manifest lists and signs `listed` ones only.
+    fn archive(files: &[(&str, &str)], listed: usize, key: &[u8]) -> ZipArchive<Cursor<Vec<u8>>> {
+        let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+        let options: FileOptions<'_, ()> = FileOptions::default();
+        let mut manifest = BackupManifest::new();
+        for (i, (path, data)) in files.iter().enumerate() {
+            zip.start_file(*path, options).unwrap();
+            zip.write_all(data.as_bytes()).unwrap();
+            if i < listed {
+                manifest.add(path, data.as_bytes());
+            }
+        }
+        let manifest = serde_json::to_string(&manifest).unwrap();
+        zip.start_file(MANIFEST_PATH, options).unwrap();
+        zip.write_all(manifest.as_bytes()).unwrap();
+        zip.start_file(SIGNATURE_PATH, options).unwrap();
+        zip.write_all(sign(key, manifest.as_bytes()).as_bytes()).unwrap();
+        ZipArchive::new(Cursor::new(zip.finish().unwrap().into_inner())).unwrap()
+    }
+
+    #[test]
+    fn test_backup_verification() {
+        let files = [("content/Intro.md", "# Intro"), ("meta.json", "{}")];
+        let required = vec!["content/Intro.md".to_string(), "assets/1".to_string()];
+
+        let mut valid = archive(&files, 2, b"key");
+        let report = verify_archive(&mut valid, b"key").finish(&valid, &required[..1]);
+        assert!(report.valid && report.signature_valid && report.has_manifest);
+        assert_eq!(report.checked, 2);
+
+        // Listed in meta.json but never written
+        let report = verify_archive(&mut valid, b"key").finish(&valid, &required);
+        assert!(!report.valid);
+        assert_eq!(report.missing, vec!["assets/1".to_string()]);
+
+        let report = verify_archive(&mut valid, b"other").finish(&valid, &[]);
+        assert!(!report.valid && !report.signature_valid);
+        assert_eq!(report.summary(), "Signature does not match");
+
+        // A file slipped into the archive after it was signed
+        let mut added = archive(&files, 1, b"key");
+        let report = verify_archive(&mut added, b"key").finish(&added, &[]);
+        assert!(!report.valid);
+        assert_eq!(report.unexpected, vec!["meta.json".to_string()]);
+
+        // Unsigned archives pass only as an explicitly allowed legacy backup
+        let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+        zip.start_file("meta.json", FileOptions::<'_, ()>::default()).unwrap();
+        zip.write_all(b"{}").unwrap();
+        let mut unsigned = ZipArchive::new(Cursor::new(zip.finish().unwrap().into_inner())).unwrap();
+        let report = verify_archive(&mut unsigned, b"key");
+        assert!(!report.has_manifest && !report.clone().finish(&unsigned, &[]).valid);
+        let report = VerifyReport { unsigned_legacy: true, ..report }.finish(&unsigned, &[]);
+        assert!(report.valid && report.unsigned_legacy);
+    }
+
+    #[test]
+    fn test_backup_encryption() {
+        let data = b"PK\x03\x04 a zip".to_vec();
+        let encryption = BackupEncryption { passphrase: "correct horse".to_string(), hint: Some("the usual".to_string()) };
+        let sealed = encrypt(&data, &encryption).unwrap();
+        assert!(is_encrypted(&sealed) && !is_encrypted(&data));
+        assert!(!sealed.windows(data.len()).any(|w| w == data.as_slice()));
+
+        let header = peek_header(sealed.as_slice()).unwrap().unwrap();
+        assert_eq!((header.cipher.as_str(), header.kdf.as_str()), ("aes-256-gcm", "argon2id"));
+        assert_eq!(header.hint.as_deref(), Some("the usual"));
+        assert!(peek_header(data.as_slice()).unwrap().is_none());
+
+        assert_eq!(decrypt(&sealed, "correct horse").unwrap(), data);
+        assert_eq!(decrypt(&sealed, "wrong horse").unwrap_err(), "Wrong passphrase or corrupted backup");
+
+        // The header is authenticated too
+        let at = sealed.windows(9).position(|w| w == b"the usual").unwrap();
+        let mut tampered = sealed.clone();
+        tampered[at..at + 9].copy_from_slice(b"the other");
+        assert!(decrypt(&tampered, "correct horse").is_err());
+
+        let empty = BackupEncryption { passphrase: String::new(), hint: None };
+        assert!(encrypt(&data, &empty).is_err());
+
+        // A header length past the cap is refused before anything is allocated
+        let mut huge = b"AKBENC1\n".to_vec();
+        huge.extend_from_slice(&u32::MAX.to_be_bytes());
+        assert!(peek_header(huge.as_slice()).is_err());
+        assert!(decrypt(&huge, "correct horse").is_err());
+        let long_hint = BackupEncryption { passphrase: "p".to_string(), hint: Some("h".repeat(70_000)) };
+        assert_eq!(encrypt(&data, &long_hint).unwrap_err(), "Hint is too long");
+
+        // KDF costs well past what `encrypt` writes are refused, not computed
+        let header_len = u32::from_be_bytes(sealed[8..12].try_into().unwrap()) as usize;
+        let mut costly = header.clone();
+        costly.memory_kib = 1 << 20;
+        let costly_bytes = serde_json::to_vec(&costly).unwrap();
+        let mut forged = b"AKBENC1\n".to_vec();
+        forged.extend_from_slice(&(costly_bytes.len() as u32).to_be_bytes());
+        forged.extend_from_slice(&costly_bytes);
+        forged.extend_from_slice(&sealed[12 + header_len..]);
+        assert_eq!(decrypt(&forged, "correct horse").unwrap_err(), "Invalid encrypted backup");
+    }
+
+    #[test]
+    fn test_backup_retention() {
+        let kb_id = Uuid::new_v4();
+        let name = format!("{}_20260105_093000.akb", kb_id);
+        assert_eq!(parse_backup_name(&name), Some((kb_id, Utc.with_ymd_and_hms(2026, 1, 5, 9, 30, 0).unwrap())));
+        assert_eq!(parse_backup_name("notes.akb"), None);
+
+        // Two backups a day from Jan 1 (Thursday) to Mar 31
+        let start = Utc.with_ymd_and_hms(2026, 1, 1, 6, 0, 0).unwrap();
+        let backups: Vec<BackupEntry> = (0..180).map(|i| {
+            let created_at = start + Duration::hours(12 * i);
+            BackupEntry { filename: created_at.format("%Y%m%d_%H%M%S").to_string(), created_at, size: 0 }
+        }).collect();
+
+        let mut keep: Vec<String> = retained(&backups, &Retention { daily: 3, weekly: 2, monthly: 3 }).into_iter().collect();
+        keep.sort();
+        assert_eq!(keep, vec![
+            "20260131_180000", // January
+            "20260228_180000", // February
+            "20260329_180000", // day, and week 13
+            "20260330_180000", // day
+            "20260331_180000", // newest: day, week 14 and March
+        ]);
+
+        let none = Retention { daily: 0, weekly: 0, monthly: 0 };
+        assert_eq!(retained(&backups, &none).len(), 1);
+        assert!(retained(&[], &Retention::default()).is_empty());
+    }
+
+    #[test]
+    fn test_backup_schedule_due() {
+        let now = Utc::now();
+        let mut schedule = BackupSchedule {
+            kb_id: Uuid::new_v4(),
+            owner_id: Uuid::new_v4(),
+            enabled: true,
+            interval_hours: 24,
+            retention: Retention::default(),
+            last_run: None,
+            last_success: None,
+            last_error: None,
+            consecutive_failures: 0,
+        };
+        assert!(schedule.is_due(now));
+
+        schedule.last_run = Some(now - Duration::hours(2));
+        assert!(!schedule.is_due(now));
+        assert_eq!(schedule.next_run(), Some(now + Duration::hours(22)));
+
+        // Failures are retried within the hour
+        schedule.last_error = Some("Knowledge Base not found".to_string());
+        assert!(schedule.is_due(now));
+
+        schedule.enabled = false;
+        assert!(!schedule.is_due(now) && schedule.next_run().is_none());
+    }
+
+    #[test]
+    fn test_incremental_diff() {
+        let (same, edited, touched, gone, new) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let at = "2026-03-01T10:00:00+00:00".to_string();
+        let base: HashMap<Uuid, (i64, String)> = [
+            (same, (3, at.clone())),
+            (edited, (1, at.clone())),
+            (touched, (2, at.clone())),
+            (gone, (1, at.clone())),
+        ].into_iter().collect();
+        let current = vec![
+            (same, 3, at.clone()),
+            (edited, 2, at.clone()),
+            // Moved or renamed: same content version, newer update time
+            (touched, 2, "2026-03-02T08:00:00+00:00".to_string()),
+            (new, 1, at.clone()),
+        ];
+
+        let (changed, removed) = diff_nodes(&base, &current);
+        assert_eq!(changed, HashSet::from([edited, touched, new]));
+        assert_eq!(removed, vec![gone]);
+    }
+
+    #[test]
+    fn test_incremental_assets() {
+        let hash = "ab".repeat(32);
+        assert_eq!(asset_hash(&format!("assets/{}", hash)), Some(hash.as_str()));
+        assert_eq!(asset_hash(&format!("assets/{}", Uuid::new_v4())), None);
+        assert_eq!(asset_hash(&format!("content/{}", hash)), None);
+
+        // The base stores one asset and points to its own base for another
+        let (stored, inherited, legacy, copy) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let other = "cd".repeat(32);
+        let assets_map = HashMap::from([
+            (stored, format!("assets/{}", hash)),
+            (inherited, format!("assets/{}", other)),
+            (legacy, format!("assets/{}", legacy)),
+        ]);
+        let sources = HashMap::from([(inherited, "first.akb".to_string())]);
+        let mut index = AssetIndex::from_base("second.akb", &assets_map, &sources);
+
+        assert_eq!(index.by_id[&stored], (Some("second.akb".to_string()), format!("assets/{}", hash)));
+        assert_eq!(index.by_hash[&other], (Some("first.akb".to_string()), format!("assets/{}", other)));
+        assert_eq!(index.by_hash.len(), 2);
+
+        // An upload with the same content is found by its hash
+        let new_hash = "ef".repeat(32);
+        index.insert(copy, &format!("assets/{}", new_hash), None);
+        assert_eq!(index.by_hash[&new_hash].0, None);
+        index.insert(Uuid::new_v4(), &format!("assets/{}", hash), None);
+        assert_eq!(index.by_hash[&hash].0, Some("second.akb".to_string()));
+    }
+
+    #[test]
+    fn test_incremental_chain_retention() {
+        let chains = HashMap::from([
+            ("c.akb".to_string(), "b.akb".to_string()),
+            ("b.akb".to_string(), "a.akb".to_string()),
+            ("e.akb".to_string(), "d.akb".to_string()),
+        ]);
+        let keep = with_bases(HashSet::from(["c.akb".to_string(), "f.akb".to_string()]), &chains);
+        let expected: HashSet<String> = ["a.akb", "b.akb", "c.akb", "f.akb"].iter().map(|s| s.to_string()).collect();
+        assert_eq!(keep, expected);
+    }
+
+    /// Knowledge bases, folders and articles in memory. Saving the article
+    /// titled `failing` fails, as a database error would.
+    #[derive(Default)]
+    struct MemoryStore {
+        kbs: Mutex<HashMap<Uuid, KnowledgeBase>>,
+        items: Mutex<HashMap<Uuid, ContentItem>>,
+        failing: Mutex<Option<String>>,
+    }
+
+    impl MemoryStore {
+        fn node(item: &ContentItem) -> &Node {
+            match item {
+                ContentItem::Article(a) => &a.node,
+                ContentItem::Node(n) => n,
+            }
+        }
+
+        fn article(&self, find: impl Fn(&Article) -> bool) -> Option<Article> {
+            self.items.lock().unwrap().values().find_map(|item| match item {
+                ContentItem::Article(a) if find(a) => Some(a.clone()),
+                _ => None,
+            })
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl ArticleRepository for MemoryStore {
+        async fn save(&self, article: Article, _user_id: UserId, _change_reason: Option<String>) -> Result<Uuid, RepositoryError> {
+            if self.failing.lock().unwrap().as_deref() == Some(article.node.title.as_str()) {
+                return Err(RepositoryError::DatabaseError("disk full".to_string()));
+            }
+            let id = article.node.id;
+            self.items.lock().unwrap().insert(id, ContentItem::Article(article));
+            Ok(id)
+        }
+        async fn save_if_revision(&self, _: Article, _: UserId, _: Option<String>, _: Option<i64>) -> Result<i64, RepositoryError> { unimplemented!() }
+        async fn current_revision(&self, _: &Uuid) -> Result<i64, RepositoryError> { unimplemented!() }
+        async fn save_with_blocks(&self, _: Article, _: Vec<crate::domain::blocks::models::Block>, _: UserId, _: Option<String>, _: i64) -> Result<i64, RepositoryError> { unimplemented!() }
+        async fn find_by_id(&self, id: &Uuid) -> Result<Option<ContentItem>, RepositoryError> {
+            Ok(self.items.lock().unwrap().get(id).cloned())
+        }
+        async fn find_by_title(&self, title: &str) -> Result<Option<Article>, RepositoryError> {
+            Ok(self.article(|a| a.node.title == title))
+        }
+        async fn find_by_slug(&self, slug: &str) -> Result<Option<Article>, RepositoryError> {
+            Ok(self.article(|a| a.slug == slug))
+        }
+        async fn list(&self, _: Option<UserId>, _: Option<UserId>, knowledge_base_id: Option<Uuid>, _: Option<String>, _: Option<String>, _: u64, _: u64) -> Result<Vec<ContentItem>, RepositoryError> {
+            Ok(self.items.lock().unwrap().values().filter(|item| Self::node(item).knowledge_base_id == knowledge_base_id).cloned().collect())
+        }
+        async fn list_versions(&self, _: Uuid) -> Result<Vec<NodeVersion>, RepositoryError> { Ok(vec![]) }
+        async fn delete(&self, _: &Uuid) -> Result<(), RepositoryError> { unimplemented!() }
+        async fn get_version(&self, _: &Uuid, _: &str) -> Result<Option<ContentVersionSnapshot>, RepositoryError> { unimplemented!() }
+        async fn get_history(&self, _: &Uuid) -> Result<Vec<ContentVersionSnapshot>, RepositoryError> { unimplemented!() }
+        async fn get_diff(&self, _: &Uuid, _: &str, _: &str) -> Result<ContentDiff, RepositoryError> { unimplemented!() }
+        async fn search(&self, _: &str) -> Result<Vec<Article>, RepositoryError> { unimplemented!() }
+        async fn delete_recursive(&self, id: &Uuid) -> Result<(), RepositoryError> {
+            let mut items = self.items.lock().unwrap();
+            let mut doomed = vec![*id];
+            while let Some(id) = doomed.pop() {
+                items.remove(&id);
+                doomed.extend(items.values().map(Self::node).filter(|n| n.parent_id == Some(id)).map(|n| n.id));
+            }
+            Ok(())
+        }
+        async fn find_drafts_by_article_ids(&self, _: Vec<Uuid>) -> Result<Vec<(Uuid, String, serde_json::Value, chrono::DateTime<Utc>)>, RepositoryError> { unimplemented!() }
+        async fn find_draft_by_id(&self, _: &Uuid) -> Result<Option<(String, serde_json::Value)>, RepositoryError> { unimplemented!() }
+        async fn save_draft(&self, _: Uuid, _: String, _: serde_json::Value) -> Result<(), RepositoryError> { unimplemented!() }
+        async fn count(&self, _: Option<UserId>, _: Option<Uuid>) -> Result<u64, RepositoryError> { unimplemented!() }
+    }
+
+    #[async_trait::async_trait]
+    impl NodeRepository for MemoryStore {
+        async fn find_by_id(&self, _: &Uuid) -> Result<Option<Node>, RepositoryError> { unimplemented!() }
+        async fn save(&self, node: Node, _user_id: UserId) -> Result<Uuid, RepositoryError> {
+            let id = node.id;
+            self.items.lock().unwrap().insert(id, ContentItem::Node(node));
+            Ok(id)
+        }
+        async fn save_if_revision(&self, _: Node, _: i64) -> Result<i64, RepositoryError> { unimplemented!() }
+        async fn list_by_parent(&self, _: Option<Uuid>) -> Result<Vec<Node>, RepositoryError> { unimplemented!() }
+        async fn delete(&self, _: &Uuid) -> Result<(), RepositoryError> { unimplemented!() }
+    }
+
+    #[async_trait::async_trait]
+    impl KnowledgeBaseRepository for MemoryStore {
+        async fn save(&self, kb: KnowledgeBase) -> Result<KnowledgeBaseId, RepositoryError> {
+            let id = kb.id.clone();
+            self.kbs.lock().unwrap().insert(id.0, kb);
+            Ok(id)
+        }
+        async fn find_by_id(&self, id: &KnowledgeBaseId) -> Result<Option<KnowledgeBase>, RepositoryError> {
+            Ok(self.kbs.lock().unwrap().get(&id.0).cloned())
+        }
+        async fn find_by_title(&self, _: &UserId, _: &str) -> Result<Option<KnowledgeBase>, RepositoryError> { unimplemented!() }
+        async fn list(&self, _: Option<UserId>, _: Option<UserId>) -> Result<Vec<KnowledgeBase>, RepositoryError> { unimplemented!() }
+        async fn delete(&self, _: &KnowledgeBaseId) -> Result<(), RepositoryError> { unimplemented!() }
+    }
+
+    #[async_trait::async_trait]
+    impl AuditRepository for MemoryStore {
+        async fn log_event(&self, _: &str, _: Uuid, _: &str, _: serde_json::Value) -> Result<(), RepositoryError> { Ok(()) }
+        async fn get_logs_by_target(&self, _: &str) -> Result<Vec<AuditLog>, RepositoryError> { unimplemented!() }
+        async fn get_logs_by_actor(&self, _: Uuid) -> Result<Vec<AuditLog>, RepositoryError> { unimplemented!() }
+    }
+
+    /// Never reached: the backups restored here hold no assets.
+    fn asset_manager(store: &Arc<MemoryStore>, root: &Path) -> Arc<AssetManager> {
+        use crate::domain::permission_service::PermissionService;
+        use crate::infrastructure::persistence::postgres::PostgresRepository;
+        use crate::infrastructure::storage::blob_store::BlobStore;
+        let permissions = Arc::new(PermissionService::new(Arc::new(PostgresRepository::new(sea_orm::DatabaseConnection::Disconnected))));
+        Arc::new(AssetManager::new(
+            store.clone(), store.clone(), store.clone(), permissions,
+            root.to_string_lossy().into_owned(), Arc::new(BlobStore::new(root.join("blobs"))),
+        ))
+    }
+
+    fn node(kb_id: Uuid, author_id: Uuid, parent_id: Option<Uuid>, r#type: NodeType, title: &str) -> Node {
+        Node {
+            id: Uuid::new_v4(),
+            parent_id,
+            author_id,
+            knowledge_base_id: Some(kb_id),
+            r#type,
+            title: title.to_string(),
+            permission_mode: PermissionMode::Private,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn article(node: Node, slug: &str, body: &str) -> Article {
+        Article {
+            node,
+            slug: slug.to_string(),
+            status: ContentStatus::Draft,
+            category: None,
+            body: ContentBody::Markdown(body.to_string()),
+            tags: vec![],
+            author_name: None,
+            author_avatar: None,
+            derived_data: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_overwrite_restore_failure_keeps_old_content() {
+        let root = std::env::temp_dir().join(format!("aether_restore_{}", Uuid::new_v4()));
+        let store = Arc::new(MemoryStore::default());
+        let service = BackupService::new(
+            store.clone(), store.clone(), store.clone(), asset_manager(&store, &root), store.clone(),
+            root.to_string_lossy().into_owned(), "key".to_string(),
+        );
+        let (user_id, kb_id) = (Uuid::new_v4(), Uuid::new_v4());
+        KnowledgeBaseRepository::save(&*store, KnowledgeBase {
+            id: KnowledgeBaseId(kb_id),
+            author_id: user_id,
+            title: "Notes".to_string(),
+            description: None,
+            tags: vec![],
+            cover_image: None,
+            cover_offset_y: 0,
+            renderer_id: None,
+            visibility: Visibility::Private,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }).await.unwrap();
+
+        let folder = node(kb_id, user_id, None, NodeType::Folder, "Folder");
+        let (folder_id, user) = (folder.id, UserId(user_id));
+        NodeRepository::save(&*store, folder, user.clone()).await.unwrap();
+        let a = article(node(kb_id, user_id, Some(folder_id), NodeType::Article, "A"), "a", "first");
+        let b = article(node(kb_id, user_id, None, NodeType::Article, "B"), "b", "second");
+        let (a_id, b_id) = (a.node.id, b.node.id);
+        ArticleRepository::save(&*store, a.clone(), user.clone(), None).await.unwrap();
+        ArticleRepository::save(&*store, b, user.clone(), None).await.unwrap();
+        let backup = service.create_backup(kb_id, user_id, None).await.unwrap();
+
+        // Changed after the backup
+        ArticleRepository::save(&*store, article(a.node, "a", "edited"), user.clone(), None).await.unwrap();
+        let c = article(node(kb_id, user_id, None, NodeType::Article, "C"), "c", "third");
+        let c_id = c.node.id;
+        ArticleRepository::save(&*store, c, user, None).await.unwrap();
+
+        let options = RestoreOptions { mode: RestoreMode::Overwrite, target_kb_id: Some(kb_id), ..Default::default() };
+        *store.failing.lock().unwrap() = Some("B".to_string());
+        assert!(service.restore(service.get_backup_path(&backup), user_id, options.clone(), None).await.is_err());
+        // Nothing was deleted: what was not restored yet is still there
+        for id in [folder_id, a_id, b_id, c_id] {
+            assert!(store.items.lock().unwrap().contains_key(&id));
+        }
+        assert_eq!(store.article(|a| a.node.id == c_id).unwrap().body, ContentBody::Markdown("third".to_string()));
+
+        *store.failing.lock().unwrap() = None;
+        service.restore(service.get_backup_path(&backup), user_id, options, None).await.unwrap();
+        let body = |title: &str| store.article(|a| a.node.title == title).map(|a| (a.node.id, a.body));
+        assert_eq!(body("A"), Some((a_id, ContentBody::Markdown("first".to_string()))));
+        assert_eq!(body("B"), Some((b_id, ContentBody::Markdown("second".to_string()))));
+        assert_eq!(body("C"), None);
+        // The old folder, and the one left over by the failed attempt, are gone
+        let folders: Vec<Uuid> = store.items.lock().unwrap().values().filter_map(|item| match item {
+            ContentItem::Node(n) => Some(n.id),
+            _ => None,
+        }).collect();
+        assert_eq!(folders.len(), 1);
+        assert!(!folders.contains(&folder_id));
+        assert_eq!(store.article(|a| a.node.id == a_id).unwrap().node.parent_id, folders.first().copied());
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+}
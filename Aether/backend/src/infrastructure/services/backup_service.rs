@@ -1,18 +1,31 @@
-use std::path::PathBuf;
-use std::sync::Arc;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
 use std::collections::{HashMap, HashSet};
-use std::io::{Write, Read};
+use std::io::{Cursor, Write, Read};
 use uuid::Uuid;
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use serde::{Serialize, Deserialize};
 use zip::{ZipWriter, ZipArchive, write::FileOptions, CompressionMethod};
-use regex::Regex;
+use std::io::Seek;
+use sha2::{Digest, Sha256};
 use tokio::io::AsyncReadExt; // For file reading
 
 use crate::domain::ports::{
-    ArticleRepository, KnowledgeBaseRepository, NodeRepository
+    ArticleRepository, AuditRepository, KnowledgeBaseRepository, NodeRepository
 };
 use crate::infrastructure::services::asset_manager::AssetManager;
+use crate::infrastructure::services::backup::crypto::{self, BackupEncryption, EncryptionHeader};
+use crate::infrastructure::services::backup::incremental::{diff_nodes, with_bases, AssetIndex, BackupBase};
+use crate::infrastructure::services::backup::manifest::{
+    read_entry, sign, verify_archive, BackupManifest, SkippedAsset, VerifyReport, MANIFEST_PATH, SIGNATURE_PATH,
+};
+use crate::infrastructure::services::backup::schedule::{
+    parse_backup_name, retained, BackupEntry, BackupSchedule, Retention, ScheduleRequest, ScheduleStatus,
+};
+use crate::infrastructure::services::backup::restore::{
+    remap_ids, rename_links, restored_title, ConflictPolicy, RestoreConflict, RestoreMode, RestoreOptions, RestoreReport,
+};
+use crate::infrastructure::services::export_service::asset_ids;
 use crate::domain::models::{
     Article, Node, ContentBody, ContentItem, ContentStatus, NodeType, KnowledgeBaseId, UserId, Visibility, PermissionMode, KnowledgeBase
 };
@@ -25,7 +38,17 @@ struct BackupMeta {
     exported_at: String,
     knowledge_base: BackupKbMeta,
     nodes: Vec<BackupNodeMeta>,
-    assets_map: HashMap<Uuid, String>, // Asset UUID -> Zip Path (assets/hash.png)
+    assets_map: HashMap<Uuid, String>, // Asset UUID -> Zip Path (assets/<sha256>)
+    #[serde(default)]
+    skipped_assets: Vec<SkippedAsset>,
+    // Incremental backups: the backup they build on, the nodes deleted since,
+    // and the assets stored by an earlier backup of the chain (id -> filename)
+    #[serde(default)]
+    base: Option<BackupBase>,
+    #[serde(default)]
+    removed: Vec<Uuid>,
+    #[serde(default)]
+    asset_sources: HashMap<Uuid, String>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -46,12 +69,24 @@ struct BackupNodeMeta {
     r#type: String, // "Article", "Folder"
     tags: Vec<String>,
     status: String,
+    #[serde(default)]
+    category: Option<String>,
     created_at: String,
     updated_at: String,
     // We might store extra props here
     path: String, // Human readable path in zip: "Folder/My Article.md"
+    #[serde(default)]
+    version: i64,
+    /// The backup of the chain holding the body, if not this one.
+    #[serde(default)]
+    source: Option<String>,
+    #[serde(default)]
+    assets: Vec<Uuid>,
 }
 
+/// Format of the backups written; 1.1 added the manifest, 1.2 increments.
+const META_VERSION: &str = "1.2";
+
 // --- Service ---
 
 #[derive(Clone)]
@@ -60,7 +95,78 @@ pub struct BackupService {
     kb_repo: Arc<dyn KnowledgeBaseRepository>,
     node_repo: Arc<dyn NodeRepository>,
     asset_manager: Arc<AssetManager>,
+    audit_repo: Arc<dyn AuditRepository>,
     backup_root: PathBuf,
+    signing_key: Vec<u8>,
+    // Per KB, persisted in `backups/schedules.json`
+    schedules: Arc<Mutex<HashMap<Uuid, BackupSchedule>>>,
+    // Incremental backup -> its base, persisted in `backups/chains.json`, so
+    // pruning keeps the bases (encrypted backups cannot be read for it)
+    chains: Arc<Mutex<HashMap<String, String>>>,
+}
+
+/// How often the scheduler looks for due backups.
+const SCHEDULER_TICK: std::time::Duration = std::time::Duration::from_secs(60);
+
+/// A backup being written: the zip, in memory so an encrypted backup never
+/// reaches the disk in clear, and the manifest of what went in.
+struct ArchiveWriter {
+    zip: ZipWriter<Cursor<Vec<u8>>>,
+    manifest: BackupManifest,
+}
+
+impl ArchiveWriter {
+    fn new() -> Self {
+        Self { zip: ZipWriter::new(Cursor::new(Vec::new())), manifest: BackupManifest::new() }
+    }
+
+    fn options() -> FileOptions<'static, ()> {
+        FileOptions::default()
+            .compression_method(CompressionMethod::Deflated)
+            .unix_permissions(0o755)
+    }
+
+    fn write(&mut self, path: &str, data: &[u8]) -> Result<(), String> {
+        self.zip.start_file(path, Self::options()).map_err(|e| e.to_string())?;
+        self.zip.write_all(data).map_err(|e| e.to_string())?;
+        self.manifest.add(path, data);
+        Ok(())
+    }
+
+    fn directory(&mut self, path: &str) -> Result<(), String> {
+        self.zip.add_directory(path, Self::options()).map_err(|e| e.to_string())
+    }
+
+    /// Adds `meta.json` and the signed manifest; returns the file content.
+    fn finish(mut self, meta: &BackupMeta, signing_key: &[u8], encryption: Option<&BackupEncryption>) -> Result<Vec<u8>, String> {
+        let meta_json = serde_json::to_string_pretty(meta).map_err(|e| e.to_string())?;
+        self.write("meta.json", meta_json.as_bytes())?;
+
+        let manifest_json = serde_json::to_string_pretty(&self.manifest).map_err(|e| e.to_string())?;
+        self.zip.start_file(MANIFEST_PATH, Self::options()).map_err(|e| e.to_string())?;
+        self.zip.write_all(manifest_json.as_bytes()).map_err(|e| e.to_string())?;
+        self.zip.start_file(SIGNATURE_PATH, Self::options()).map_err(|e| e.to_string())?;
+        self.zip.write_all(sign(signing_key, manifest_json.as_bytes()).as_bytes()).map_err(|e| e.to_string())?;
+
+        let data = self.zip.finish().map_err(|e| e.to_string())?.into_inner();
+        match encryption {
+            Some(encryption) => crypto::encrypt(&data, encryption),
+            None => Ok(data),
+        }
+    }
+}
+
+fn load_map<T: serde::de::DeserializeOwned + Default>(path: &Path) -> T {
+    std::fs::read(path).ok()
+        .and_then(|data| serde_json::from_slice(&data).ok())
+        .unwrap_or_default()
+}
+
+fn save_map<T: Serialize>(path: &Path, map: &T) -> Result<(), String> {
+    let data = serde_json::to_vec_pretty(map).map_err(|e| e.to_string())?;
+    let temp_path = path.with_extension("json.tmp");
+    std::fs::write(&temp_path, data).map_err(|e| e.to_string())?;
+    std::fs::rename(&temp_path, path).map_err(|e| e.to_string())
 }
 
 impl BackupService {
@@ -69,22 +175,30 @@ impl BackupService {
         kb_repo: Arc<dyn KnowledgeBaseRepository>,
         node_repo: Arc<dyn NodeRepository>,
         asset_manager: Arc<AssetManager>,
+        audit_repo: Arc<dyn AuditRepository>,
         storage_root: String,
+        signing_key: String,
     ) -> Self {
         let backup_root = PathBuf::from(storage_root).join("backups");
         std::fs::create_dir_all(&backup_root).unwrap_or_default(); // Ensure dir exists
 
+        let schedules = load_map(&backup_root.join("schedules.json"));
+        let chains = load_map(&backup_root.join("chains.json"));
+
         Self {
             article_repo,
             kb_repo,
             node_repo,
             asset_manager,
+            audit_repo,
             backup_root,
+            signing_key: signing_key.into_bytes(),
+            schedules: Arc::new(Mutex::new(schedules)),
+            chains: Arc::new(Mutex::new(chains)),
         }
     }
 
-    pub async fn create_backup(&self, kb_id: Uuid, user_id: Uuid) -> Result<String, String> {
-        // 1. Fetch KB
+    async fn owned_kb(&self, kb_id: Uuid, user_id: Uuid) -> Result<KnowledgeBase, String> {
         let kb = self.kb_repo.find_by_id(&KnowledgeBaseId(kb_id))
             .await.map_err(|e| e.to_string())?
             .ok_or("Knowledge Base not found")?;
@@ -92,6 +206,126 @@ impl BackupService {
         if kb.author_id != user_id {
             return Err("Unauthorized".to_string());
         }
+        Ok(kb)
+    }
+
+    fn new_filename(&self, kb_id: Uuid) -> Result<String, String> {
+        let timestamp = Utc::now().format("%Y%m%d_%H%M%S").to_string();
+        let filename = format!("{}_{}.akb", kb_id, timestamp);
+        if self.get_backup_path(&filename).exists() {
+            return Err("A backup of this knowledge base was just made".to_string());
+        }
+        Ok(filename)
+    }
+
+    /// Meta of a node, and its body when it is an article.
+    fn describe(&self, item: &ContentItem, node_map: &HashMap<Uuid, (Option<Uuid>, String)>, version: i64) -> (BackupNodeMeta, Option<String>) {
+        let (node, body_str) = match item {
+            ContentItem::Article(a) => {
+                let s = match &a.body {
+                    ContentBody::Markdown(t) => t.clone(),
+                    ContentBody::CodeSnippet { code, .. } => code.clone(),
+                    _ => String::new(),
+                };
+                (&a.node, s)
+            },
+            ContentItem::Node(n) => (n, String::new()),
+        };
+
+        // Generate Path
+        let path_str = self.build_path(node.id, node_map);
+        let full_entry_path = if node.r#type == NodeType::Folder {
+            format!("content/{}/", path_str)
+        } else {
+            format!("content/{}.md", path_str)
+        };
+
+        let meta = BackupNodeMeta {
+            id: node.id,
+            parent_id: node.parent_id,
+            title: node.title.clone(),
+            slug: match item { ContentItem::Article(a) => a.slug.clone(), _ => "".to_string() },
+            r#type: format!("{:?}", node.r#type),
+            tags: match item { ContentItem::Article(a) => a.tags.clone(), _ => vec![] },
+            status: format!("{:?}", match item { ContentItem::Article(a) => a.status, _ => ContentStatus::Draft }),
+            category: match item { ContentItem::Article(a) => a.category.clone(), _ => None },
+            created_at: node.created_at.to_rfc3339(),
+            updated_at: node.updated_at.to_rfc3339(),
+            path: full_entry_path,
+            version,
+            source: None,
+            assets: asset_ids(&body_str),
+        };
+        (meta, (node.r#type == NodeType::Article).then_some(body_str))
+    }
+
+    async fn read_asset(&self, asset_id: Uuid, user_id: Uuid) -> Result<Vec<u8>, String> {
+        let (path, _mime) = self.asset_manager.get_asset_file(asset_id, None, user_id).await?;
+        let mut asset_file = tokio::fs::File::open(&path).await.map_err(|e| e.to_string())?;
+        let mut buffer = Vec::new();
+        asset_file.read_to_end(&mut buffer).await.map_err(|e| e.to_string())?;
+        Ok(buffer)
+    }
+
+    /// Stores the referenced assets not in `index` yet, once per content.
+    /// Returns the assets map, the sources of those in earlier backups and
+    /// the assets that could not be read.
+    async fn pack_assets(
+        &self,
+        writer: &mut ArchiveWriter,
+        ids: Vec<Uuid>,
+        mut index: AssetIndex,
+        user_id: Uuid,
+    ) -> Result<(HashMap<Uuid, String>, HashMap<Uuid, String>, Vec<SkippedAsset>), String> {
+        let mut assets_map = HashMap::new();
+        let mut asset_sources = HashMap::new();
+        let mut skipped_assets = Vec::new();
+        for asset_id in ids {
+            let known = index.by_id.get(&asset_id).cloned();
+            let (source, path) = match known {
+                Some(known) => known,
+                None => match self.read_asset(asset_id, user_id).await {
+                    Ok(buffer) => {
+                        let hash = format!("{:x}", Sha256::digest(&buffer));
+                        match index.by_hash.get(&hash).cloned() {
+                            Some(known) => known,
+                            None => {
+                                let path = format!("assets/{}", hash);
+                                writer.write(&path, &buffer)?;
+                                (None, path)
+                            }
+                        }
+                    }
+                    Err(reason) => {
+                        tracing::warn!("Skipping asset {} in backup: {}", asset_id, reason);
+                        skipped_assets.push(SkippedAsset { id: asset_id, reason });
+                        continue;
+                    }
+                },
+            };
+            index.insert(asset_id, &path, source.clone());
+            if let Some(source) = source {
+                asset_sources.insert(asset_id, source);
+            }
+            assets_map.insert(asset_id, path);
+        }
+        Ok((assets_map, asset_sources, skipped_assets))
+    }
+
+    fn kb_meta(kb: KnowledgeBase) -> BackupKbMeta {
+        BackupKbMeta {
+            id: kb.id.0,
+            title: kb.title,
+            description: kb.description,
+            renderer_id: kb.renderer_id,
+            tags: kb.tags,
+        }
+    }
+
+    /// Writes a backup of the KB, sealed with the passphrase when `encryption` is given.
+    pub async fn create_backup(&self, kb_id: Uuid, user_id: Uuid, encryption: Option<&BackupEncryption>) -> Result<String, String> {
+        // 1. Fetch KB
+        let kb = self.owned_kb(kb_id, user_id).await?;
 
         // 2. Fetch All Nodes/Articles
         // We use list with large limit. Pagination might be needed for huge KBs, but for V1 we assume <10k items.
@@ -102,22 +336,16 @@ impl BackupService {
             None, None, 
             10000, 0
         ).await.map_err(|e| e.to_string())?;
+        let versions: HashMap<Uuid, i64> = self.article_repo.list_versions(kb_id).await.map_err(|e| e.to_string())?
+            .into_iter().map(|v| (v.id, v.version)).collect();
 
         // 3. Prepare ZIP
-        let timestamp = Utc::now().format("%Y%m%d_%H%M%S").to_string();
-        let filename = format!("{}_{}.akb", kb_id, timestamp);
-        let file_path = self.backup_root.join(&filename);
-        
-        let file = std::fs::File::create(&file_path).map_err(|e| e.to_string())?;
-        let mut zip = ZipWriter::new(file);
-        let options: FileOptions<'_, ()> = FileOptions::default()
-            .compression_method(CompressionMethod::Deflated)
-            .unix_permissions(0o755);
+        let filename = self.new_filename(kb_id)?;
+        let mut writer = ArchiveWriter::new();
 
         // 4. Analyze Assets & Build Folder Tree
         let mut nodes_meta = Vec::new();
-        let mut assets_to_include = HashSet::new(); // Set of Asset UUIDs
-        let asset_regex = Regex::new(r"\[\[asset:([0-9a-fA-F-]+)\]\]").unwrap();
+        let mut assets_to_include = Vec::new();
 
         // Build simple ID -> Title/Parent map for path generation
         let mut node_map: HashMap<Uuid, (Option<Uuid>, String)> = HashMap::new();
@@ -131,100 +359,145 @@ impl BackupService {
 
         // Process Items
         for item in &items {
-            let (node, body_str) = match item {
-                ContentItem::Article(a) => {
-                    let s = match &a.body {
-                        ContentBody::Markdown(t) => t.clone(),
-                        ContentBody::CodeSnippet { code, .. } => code.clone(),
-                        _ => String::new(),
-                    };
-                    (&a.node, s)
-                },
-                ContentItem::Node(n) => (n, String::new()),
-            };
-
-            // Detect Assets
-            for cap in asset_regex.captures_iter(&body_str) {
-                if let Some(m) = cap.get(1) {
-                    if let Ok(uuid) = Uuid::parse_str(m.as_str()) {
-                        assets_to_include.insert(uuid);
-                    }
+            let id = match item { ContentItem::Article(a) => a.node.id, ContentItem::Node(n) => n.id };
+            let (meta, body) = self.describe(item, &node_map, versions.get(&id).copied().unwrap_or(0));
+            match body {
+                Some(body) => writer.write(&meta.path, body.as_bytes())?,
+                None => writer.directory(&meta.path)?,
+            }
+            for asset in &meta.assets {
+                if !assets_to_include.contains(asset) {
+                    assets_to_include.push(*asset);
                 }
             }
+            nodes_meta.push(meta);
+        }
 
-            // Generate Path
-            let path_str = self.build_path(node.id, &node_map);
-            let full_entry_path = if node.r#type == NodeType::Folder {
-                format!("content/{}/", path_str)
-            } else {
-                format!("content/{}.md", path_str)
-            };
+        // 5. Process Assets
+        let (assets_map, _, skipped_assets) = self.pack_assets(&mut writer, assets_to_include, AssetIndex::default(), user_id).await?;
 
-            // Write Content to Zip
-            if node.r#type == NodeType::Article {
-                zip.start_file(&full_entry_path, options).map_err(|e| e.to_string())?;
-                zip.write_all(body_str.as_bytes()).map_err(|e| e.to_string())?;
-            } else {
-                 zip.add_directory(&full_entry_path, options).map_err(|e| e.to_string())?;
-            }
-
-            // Add to Meta
-            nodes_meta.push(BackupNodeMeta {
-                id: node.id,
-                parent_id: node.parent_id,
-                title: node.title.clone(),
-                slug: match item { ContentItem::Article(a) => a.slug.clone(), _ => "".to_string() },
-                r#type: format!("{:?}", node.r#type),
-                tags: match item { ContentItem::Article(a) => a.tags.clone(), _ => vec![] },
-                status: format!("{:?}", match item { ContentItem::Article(a) => a.status, _ => ContentStatus::Draft }),
-                created_at: node.created_at.to_rfc3339(),
-                updated_at: node.updated_at.to_rfc3339(),
-                path: full_entry_path,
-            });
+        // 6. Meta, manifest and signature
+        let meta = BackupMeta {
+            version: META_VERSION.to_string(),
+            exported_at: Utc::now().to_rfc3339(),
+            knowledge_base: Self::kb_meta(kb),
+            nodes: nodes_meta,
+            assets_map,
+            skipped_assets,
+            base: None,
+            removed: vec![],
+            asset_sources: HashMap::new(),
+        };
+        let data = writer.finish(&meta, &self.signing_key, encryption)?;
+        tokio::fs::write(self.get_backup_path(&filename), data).await.map_err(|e| e.to_string())?;
+
+        Ok(filename)
+    }
+
+    /// Writes a backup holding only what changed since `base` (by default the
+    /// KB's latest backup): nodes whose content version or update time moved,
+    /// and assets no backup of the chain has yet. The rest points into the chain.
+    /// An encrypted base is opened with the passphrase of `encryption`.
+    pub async fn create_incremental_backup(&self, kb_id: Uuid, user_id: Uuid, base: Option<String>, encryption: Option<&BackupEncryption>) -> Result<String, String> {
+        let kb = self.owned_kb(kb_id, user_id).await?;
+
+        // 1. Base
+        let base_name = match base {
+            Some(name) => name,
+            None => self.kb_backups(kb_id).await?.pop().map(|b| b.filename).ok_or("No base backup to build on")?,
+        };
+        if base_name.contains('/') || base_name.contains("..") || parse_backup_name(&base_name).map(|(id, _)| id) != Some(kb_id) {
+            return Err("Base backup belongs to another knowledge base".to_string());
+        }
+        let base_path = self.get_backup_path(&base_name);
+        if !base_path.exists() {
+            return Err("Backup file not found".to_string());
+        }
+        let base_data = Self::open_backup(&base_path, encryption.map(|e| e.passphrase.as_str()))?;
+        let verification = self.verify_data(&base_data, false)?;
+        if !verification.valid {
+            return Err(format!("Backup failed verification: {}", verification.summary()));
         }
+        let mut archive = ZipArchive::new(Cursor::new(&base_data[..])).map_err(|e| e.to_string())?;
+        let base_manifest = read_entry(&mut archive, MANIFEST_PATH).map_err(|_| "Base backup has no manifest")?;
+        let base_meta = Self::read_meta(&mut archive)?;
 
-        // 5. Process Assets
-        let mut assets_map = HashMap::new();
-        for asset_id in assets_to_include {
-            match self.asset_manager.get_asset_file(asset_id, None, user_id).await {
-                Ok((path, _mime)) => {
-                    let entry_name = format!("assets/{}", asset_id); // Flattened assets
-                    // Read file
-                    if let Ok(mut asset_file) = tokio::fs::File::open(&path).await {
-                        let mut buffer = Vec::new();
-                        if asset_file.read_to_end(&mut buffer).await.is_ok() {
-                            zip.start_file(&entry_name, options).map_err(|e| e.to_string())?;
-                            zip.write_all(&buffer).map_err(|e| e.to_string())?;
-                            assets_map.insert(asset_id, entry_name);
-                        }
+        // 2. What changed
+        let versions = self.article_repo.list_versions(kb_id).await.map_err(|e| e.to_string())?;
+        let base_state: HashMap<Uuid, (i64, String)> = base_meta.nodes.iter()
+            .map(|n| (n.id, (n.version, n.updated_at.clone())))
+            .collect();
+        let current: Vec<(Uuid, i64, String)> = versions.iter().map(|v| (v.id, v.version, v.updated_at.to_rfc3339())).collect();
+        let (changed, removed) = diff_nodes(&base_state, &current);
+        let base_nodes: HashMap<Uuid, &BackupNodeMeta> = base_meta.nodes.iter().map(|n| (n.id, n)).collect();
+        let node_map: HashMap<Uuid, (Option<Uuid>, String)> = versions.iter().map(|v| (v.id, (v.parent_id, v.title.clone()))).collect();
+
+        // 3. Nodes: changed ones are stored, the others keep their body where it is
+        let filename = self.new_filename(kb_id)?;
+        let mut writer = ArchiveWriter::new();
+        let mut nodes_meta = Vec::new();
+        let mut referenced = Vec::new();
+        for version in &versions {
+            let previous = base_nodes.get(&version.id).filter(|_| !changed.contains(&version.id));
+            let meta = match previous {
+                Some(previous) if version.r#type != NodeType::Folder => {
+                    let mut meta = (*previous).clone();
+                    meta.parent_id = version.parent_id;
+                    meta.title = version.title.clone();
+                    meta.source = previous.source.clone().or_else(|| Some(base_name.clone()));
+                    meta
+                }
+                _ => {
+                    let Some(item) = self.article_repo.find_by_id(&version.id).await.map_err(|e| e.to_string())? else { continue };
+                    let (meta, body) = self.describe(&item, &node_map, version.version);
+                    match body {
+                        Some(body) => writer.write(&meta.path, body.as_bytes())?,
+                        None => writer.directory(&meta.path)?,
                     }
-                },
-                Err(e) => {
-                    tracing::warn!("Skipping asset {} in backup: {}", asset_id, e);
+                    meta
+                }
+            };
+            for asset in &meta.assets {
+                if !referenced.contains(asset) {
+                    referenced.push(*asset);
                 }
             }
+            nodes_meta.push(meta);
         }
+        // Backups before 1.2 do not say which node uses which asset
+        if matches!(base_meta.version.as_str(), "1.0" | "1.1") {
+            for asset in base_meta.assets_map.keys() {
+                if !referenced.contains(asset) {
+                    referenced.push(*asset);
+                }
+            }
+        }
+
+        // 4. Assets
+        let index = AssetIndex::from_base(&base_name, &base_meta.assets_map, &base_meta.asset_sources);
+        let (assets_map, asset_sources, skipped_assets) = self.pack_assets(&mut writer, referenced, index, user_id).await?;
 
-        // 6. Write Meta.json
+        // 5. Meta, manifest and signature
         let meta = BackupMeta {
-            version: "1.0".to_string(),
+            version: META_VERSION.to_string(),
             exported_at: Utc::now().to_rfc3339(),
-            knowledge_base: BackupKbMeta {
-                id: kb.id.0,
-                title: kb.title,
-                description: kb.description,
-                renderer_id: kb.renderer_id,
-                tags: kb.tags,
-            },
+            knowledge_base: Self::kb_meta(kb),
             nodes: nodes_meta,
             assets_map,
+            skipped_assets,
+            base: Some(BackupBase {
+                filename: base_name.clone(),
+                manifest_sha256: format!("{:x}", Sha256::digest(&base_manifest)),
+            }),
+            removed,
+            asset_sources,
         };
+        let data = writer.finish(&meta, &self.signing_key, encryption)?;
+        tokio::fs::write(self.get_backup_path(&filename), data).await.map_err(|e| e.to_string())?;
 
-        let meta_json = serde_json::to_string_pretty(&meta).map_err(|e| e.to_string())?;
-        zip.start_file("meta.json", options).map_err(|e| e.to_string())?;
-        zip.write_all(meta_json.as_bytes()).map_err(|e| e.to_string())?;
-
-        zip.finish().map_err(|e| e.to_string())?;
+        let mut chains = self.chains.lock().unwrap();
+        chains.insert(filename.clone(), base_name);
+        save_map(&self.backup_root.join("chains.json"), &*chains)?;
 
         Ok(filename)
     }
@@ -272,158 +545,668 @@ impl BackupService {
         Ok(files)
     }
 
-    pub async fn restore_backup(&self, file_path: PathBuf, user_id: Uuid) -> Result<Uuid, String> {
-        // 1. Open Zip
-        let file = std::fs::File::open(&file_path).map_err(|e| e.to_string())?;
-        let mut archive = ZipArchive::new(file).map_err(|e| e.to_string())?;
+    /// The header of an encrypted backup (cipher, KDF, hint), `None` for a plain one.
+    pub fn encryption_info(&self, filename: &str) -> Result<Option<EncryptionHeader>, String> {
+        let file = std::fs::File::open(self.get_backup_path(filename)).map_err(|_| "Backup file not found")?;
+        crypto::peek_header(std::io::BufReader::new(file))
+    }
 
-        // 2. Read Meta.json
-        let mut meta_file = archive.by_name("meta.json").map_err(|_| "Invalid backup: missing meta.json")?;
-        let mut meta_content = String::new();
-        meta_file.read_to_string(&mut meta_content).map_err(|e| e.to_string())?;
-        drop(meta_file); // Release borrow
+    /// The zip of a backup file, decrypted if need be.
+    fn open_backup(file_path: &Path, passphrase: Option<&str>) -> Result<Vec<u8>, String> {
+        let data = std::fs::read(file_path).map_err(|e| e.to_string())?;
+        if !crypto::is_encrypted(&data) {
+            return Ok(data);
+        }
+        match passphrase {
+            Some(passphrase) => crypto::decrypt(&data, passphrase),
+            None => Err("Passphrase required".to_string()),
+        }
+    }
 
-        let meta: BackupMeta = serde_json::from_str(&meta_content).map_err(|e| format!("Invalid meta.json: {}", e))?;
+    fn read_meta<R: Read + Seek>(archive: &mut ZipArchive<R>) -> Result<BackupMeta, String> {
+        let data = read_entry(archive, "meta.json").map_err(|_| "Invalid backup: missing meta.json")?;
+        serde_json::from_slice(&data).map_err(|e| format!("Invalid meta.json: {}", e))
+    }
 
-        // 3. Create NEW Knowledge Base
-        let new_kb_id = Uuid::new_v4();
-        let restored_title = format!("{} (Restored {})", meta.knowledge_base.title, Utc::now().format("%Y-%m-%d %H:%M"));
-        
-        let new_kb = KnowledgeBase {
-            id: KnowledgeBaseId(new_kb_id),
-            author_id: user_id,
-            title: restored_title,
-            description: meta.knowledge_base.description,
-            tags: meta.knowledge_base.tags,
-            renderer_id: meta.knowledge_base.renderer_id,
-            visibility: Visibility::Private, // Default to private
-            cover_image: None,
-            cover_offset_y: 0,
-            created_at: Utc::now(),
-            updated_at: Utc::now(),
-        };
+    fn meta_of(data: &[u8]) -> Result<BackupMeta, String> {
+        let mut archive = ZipArchive::new(Cursor::new(data)).map_err(|e| e.to_string())?;
+        Self::read_meta(&mut archive)
+    }
 
-        self.kb_repo.save(new_kb).await.map_err(|e| e.to_string())?;
+    /// The backups an incremental one builds on, by filename, each opened with
+    /// the same passphrase, verified, and checked to be the very backup it was
+    /// made from. Empty for a full backup.
+    fn load_chain(&self, meta: &BackupMeta, passphrase: Option<&str>) -> Result<HashMap<String, Vec<u8>>, String> {
+        let mut chain = HashMap::new();
+        let mut next = meta.base.clone();
+        while let Some(base) = next {
+            if base.filename.contains('/') || base.filename.contains("..") || chain.contains_key(&base.filename) {
+                return Err(format!("Base backup {} is not a valid chain link", base.filename));
+            }
+            let path = self.get_backup_path(&base.filename);
+            if !path.exists() {
+                return Err(format!("Base backup {} is missing", base.filename));
+            }
+            let data = Self::open_backup(&path, passphrase)?;
+            let verification = self.verify_data(&data, false)?;
+            if !verification.valid {
+                return Err(format!("Base backup {} failed verification: {}", base.filename, verification.summary()));
+            }
+            let mut archive = ZipArchive::new(Cursor::new(&data[..])).map_err(|e| e.to_string())?;
+            let manifest = read_entry(&mut archive, MANIFEST_PATH)?;
+            if format!("{:x}", Sha256::digest(&manifest)) != base.manifest_sha256 {
+                return Err(format!("Base backup {} is not the one this backup was made from", base.filename));
+            }
+            next = Self::read_meta(&mut archive)?.base;
+            chain.insert(base.filename, data);
+        }
+        Ok(chain)
+    }
 
-        // 4. Map IDs (Old -> New)
-        let mut node_id_map: HashMap<Uuid, Uuid> = HashMap::new();
-        let mut asset_id_map: HashMap<Uuid, Uuid> = HashMap::new();
+    /// Checks a backup against its signed manifest, and that every article and
+    /// asset listed in `meta.json` is in it (or, for an incremental one, in its chain).
+    /// Unsigned backups fail, except 1.0 ones (from before manifests) with `allow_legacy`.
+    pub fn verify_data(&self, data: &[u8], allow_legacy: bool) -> Result<VerifyReport, String> {
+        let mut archive = ZipArchive::new(Cursor::new(data)).map_err(|e| e.to_string())?;
+        let mut report = verify_archive(&mut archive, &self.signing_key);
 
-        // 5. Restore Assets
-        for (old_asset_uuid, zip_path) in meta.assets_map {
-            // Extract file from zip to memory
-            let mut asset_file = archive.by_name(&zip_path).map_err(|_| format!("Asset missing: {}", zip_path))?;
-            let mut buffer = Vec::new();
-            asset_file.read_to_end(&mut buffer).map_err(|e| e.to_string())?;
-            drop(asset_file);
+        let mut required = vec!["meta.json".to_string()];
+        match Self::read_meta(&mut archive).ok() {
+            Some(meta) => {
+                // Only backups made before manifests existed may come without one, when allowed
+                if !report.has_manifest {
+                    if meta.version != "1.0" {
+                        report.errors.push("Manifest missing".to_string());
+                    } else if allow_legacy {
+                        report.unsigned_legacy = true;
+                    } else {
+                        report.errors.push("Unsigned legacy backup (1.0): allow legacy backups to use it".to_string());
+                    }
+                }
+                // What an incremental backup did not store is in its chain
+                required.extend(meta.nodes.iter().filter(|n| n.r#type == "Article" && n.source.is_none()).map(|n| n.path.clone()));
+                required.extend(meta.assets_map.iter().filter(|(id, _)| !meta.asset_sources.contains_key(*id)).map(|(_, path)| path.clone()));
+                report.skipped_assets = meta.skipped_assets;
+            }
+            None => report.errors.push("Invalid or missing meta.json".to_string()),
+        }
+        Ok(report.finish(&archive, &required))
+    }
 
-            // Upload via AssetManager (deduplication happens inside)
-            // Guess mime type or default? AssetManager computes hash anyway.
-            // We pass "restored_asset" as filename if unknown.
-            let mime = "application/octet-stream".to_string(); // In a real system, we'd store mime in meta.json or detect it.
-            
-            match self.asset_manager.upload_asset(user_id, "restored_asset".to_string(), mime, &buffer).await {
-                Ok(new_asset_node) => {
-                    // Map old asset ID to new asset ID (Note: upload_asset returns Article struct, its ID is the node ID)
-                    // Wait, upload_asset returns Article.
-                    asset_id_map.insert(old_asset_uuid, new_asset_node.node.id);
-                },
-                Err(e) => tracing::error!("Failed to restore asset {}: {}", old_asset_uuid, e),
+    pub async fn verify_backup(&self, filename: &str, passphrase: Option<String>, allow_legacy: bool) -> Result<VerifyReport, String> {
+        let path = self.get_backup_path(filename);
+        if !path.exists() {
+            return Err("Backup file not found".to_string());
+        }
+        let service = self.clone();
+        tokio::task::spawn_blocking(move || {
+            let data = Self::open_backup(&path, passphrase.as_deref())?;
+            let mut report = service.verify_data(&data, allow_legacy)?;
+            if report.valid {
+                let chained = Self::meta_of(&data)
+                    .and_then(|meta| service.load_chain(&meta, passphrase.as_deref()))
+                    .and_then(|chain| Self::read_backup(&data, &chain));
+                if let Err(e) = chained {
+                    report.errors.push(e);
+                    report.valid = false;
+                }
+            }
+            Ok(report)
+        }).await.map_err(|e| e.to_string())?
+    }
+
+    /// Encrypts a stored backup under a new passphrase, or stores it in clear
+    /// when `encryption` is `None`. The current passphrase opens encrypted ones.
+    pub async fn reencrypt_backup(&self, filename: &str, passphrase: Option<String>, encryption: Option<BackupEncryption>) -> Result<(), String> {
+        let path = self.get_backup_path(filename);
+        if !path.exists() {
+            return Err("Backup file not found".to_string());
+        }
+        let service = self.clone();
+        tokio::task::spawn_blocking(move || {
+            let data = Self::open_backup(&path, passphrase.as_deref())?;
+            let verification = service.verify_data(&data, false)?;
+            if !verification.valid {
+                return Err(format!("Backup failed verification: {}", verification.summary()));
             }
+            let data = match &encryption {
+                Some(encryption) => crypto::encrypt(&data, encryption)?,
+                None => data,
+            };
+            // Replaced in one step, so a failure leaves the old file intact
+            let temp_path = path.with_extension("akb.tmp");
+            std::fs::write(&temp_path, data).map_err(|e| e.to_string())?;
+            std::fs::rename(&temp_path, &path).map_err(|e| e.to_string())
+        }).await.map_err(|e| e.to_string())?
+    }
+
+    /// Reads `meta.json`, the article bodies (by node id) and the asset files,
+    /// taking from `chain` what an incremental backup refers to.
+    fn read_backup(data: &[u8], chain: &HashMap<String, Vec<u8>>) -> Result<(BackupMeta, HashMap<Uuid, String>, Vec<(Uuid, String, Vec<u8>)>), String> {
+        let mut archive = ZipArchive::new(Cursor::new(data)).map_err(|e| e.to_string())?;
+        let meta = Self::read_meta(&mut archive)?;
+
+        let mut bases = HashMap::new();
+        for (filename, data) in chain {
+            bases.insert(filename.as_str(), ZipArchive::new(Cursor::new(&data[..])).map_err(|e| e.to_string())?);
         }
+        let mut entry = |source: Option<&String>, path: &str| match source {
+            None => read_entry(&mut archive, path),
+            Some(filename) => match bases.get_mut(filename.as_str()) {
+                Some(base) => read_entry(base, path),
+                None => Err(format!("Base backup {} is missing", filename)),
+            },
+        };
 
-        // 6. Restore Nodes (First Pass: Create ID Mapping)
-        for node_meta in &meta.nodes {
-            node_id_map.insert(node_meta.id, Uuid::new_v4());
+        let mut bodies = HashMap::new();
+        for node_meta in meta.nodes.iter().filter(|n| n.r#type == "Article") {
+            let data = entry(node_meta.source.as_ref(), &node_meta.path).map_err(|_| format!("Content missing: {}", node_meta.path))?;
+            bodies.insert(node_meta.id, String::from_utf8_lossy(&data).into_owned());
         }
 
-        // 7. Restore Nodes (Second Pass: Create & Save)
-        // We need to process parents first? No, we can save with parent_id even if parent doesn't exist yet in DB?
-        // SQL Foreign Keys might complain if parent_id points to non-existent row.
-        // PostgresRepository usually handles this if we disable FK or order correctly.
-        // Or we can save all, then update parents? Or Toplogical sort?
-        // Simple approach: Use recursive creation or retry?
-        // Better: Sort by path depth (Folder/ -> Folder/Sub/). `meta.nodes` order is not guaranteed.
-        // Let's sort by slash count in path.
-        
+        let mut assets = Vec::new();
+        for (old_asset_uuid, zip_path) in &meta.assets_map {
+            let buffer = entry(meta.asset_sources.get(old_asset_uuid), zip_path).map_err(|_| format!("Asset missing: {}", zip_path))?;
+            assets.push((*old_asset_uuid, zip_path.clone(), buffer));
+        }
+        Ok((meta, bodies, assets))
+    }
+
+    /// The article a restored title or slug collides with.
+    async fn existing_article(&self, title: &str, slug: &str) -> Result<Option<(&'static str, Article)>, String> {
+        let by_title = self.article_repo.find_by_title(title).await.map_err(|e| e.to_string())?;
+        if let Some(article) = by_title {
+            return Ok(Some(("title", article)));
+        }
+        let by_slug = self.article_repo.find_by_slug(slug).await.map_err(|e| e.to_string())?;
+        Ok(by_slug.map(|a| ("slug", a)))
+    }
+
+    /// Restores a backup with new ids throughout, into a new knowledge base or
+    /// an existing one (see `RestoreMode`); titles and slugs already in use are
+    /// handled by the `ConflictPolicy`. With `dry_run`, only the report is made.
+    /// Encrypted backups need their passphrase; an incremental backup is
+    /// restored with what its chain holds.
+    pub async fn restore(&self, file_path: PathBuf, user_id: Uuid, options: RestoreOptions, passphrase: Option<String>) -> Result<RestoreReport, String> {
+        // Decrypting and verifying are CPU-bound: off the async workers, like `verify_backup`
+        let service = self.clone();
+        let allow_legacy = options.allow_unsigned_legacy;
+        let (verification, meta, bodies, assets) = tokio::task::spawn_blocking(move || {
+            let data = Self::open_backup(&file_path, passphrase.as_deref())?;
+            let verification = service.verify_data(&data, allow_legacy)?;
+            if !verification.valid {
+                return Err(format!("Backup failed verification: {}", verification.summary()));
+            }
+            let chain = service.load_chain(&Self::meta_of(&data)?, passphrase.as_deref())?;
+            let (meta, bodies, assets) = Self::read_backup(&data, &chain)?;
+            Ok((verification, meta, bodies, assets))
+        }).await.map_err(|e| e.to_string())??;
+
+        // 1. Target
+        let target = match options.mode {
+            RestoreMode::NewKb => None,
+            RestoreMode::Merge | RestoreMode::Overwrite => {
+                let kb_id = options.target_kb_id.ok_or("A target knowledge base is required")?;
+                let kb = self.kb_repo.find_by_id(&KnowledgeBaseId(kb_id))
+                    .await.map_err(|e| e.to_string())?
+                    .ok_or("Knowledge Base not found")?;
+                if kb.author_id != user_id {
+                    return Err("Unauthorized".to_string());
+                }
+                Some(kb)
+            }
+        };
+        let kb_id = target.as_ref().map(|kb| kb.id.0).unwrap_or_else(Uuid::new_v4);
+        let mut report = RestoreReport::new(kb_id, &options);
+        report.unsigned_legacy = verification.unsigned_legacy;
+
+        let existing = match &target {
+            Some(_) => self.article_repo.list(Some(UserId(user_id)), None, Some(kb_id), None, None, 10000, 0)
+                .await.map_err(|e| e.to_string())?,
+            None => vec![],
+        };
+        let existing_nodes: Vec<&Node> = existing.iter().map(|item| match item {
+            ContentItem::Article(a) => &a.node,
+            ContentItem::Node(n) => n,
+        }).collect();
+
+        let removed: HashSet<Uuid> = match options.mode {
+            RestoreMode::Overwrite => existing_nodes.iter().map(|n| n.id).collect(),
+            _ => HashSet::new(),
+        };
+
+        // Merging reuses the folders at the same place with the same title
+        let mut folders: HashMap<(Option<Uuid>, String), Uuid> = HashMap::new();
+        if options.mode == RestoreMode::Merge {
+            for node in existing_nodes.iter().filter(|n| n.r#type == NodeType::Folder) {
+                folders.insert((node.parent_id, node.title.to_lowercase()), node.id);
+            }
+        }
+
+        // 2. Plan: parents before children, every id remapped
+        // By depth in the tree: in an incremental backup, the path of an
+        // unchanged node is where an earlier backup stored it
+        let parents: HashMap<Uuid, Option<Uuid>> = meta.nodes.iter().map(|n| (n.id, n.parent_id)).collect();
+        let depth = |id: Uuid| {
+            let mut depth = 0;
+            let mut current = parents.get(&id).copied().flatten();
+            while let Some(parent) = current.filter(|_| depth < parents.len()) {
+                depth += 1;
+                current = parents.get(&parent).copied().flatten();
+            }
+            depth
+        };
         let mut sorted_nodes = meta.nodes.clone();
-        sorted_nodes.sort_by_key(|n| n.path.matches('/').count());
+        sorted_nodes.sort_by_key(|n| depth(n.id));
 
-        let asset_regex = Regex::new(r"\[\[asset:([0-9a-fA-F-]+)\]\]").unwrap();
+        let mut id_map: HashMap<Uuid, Uuid> = HashMap::new();
+        // Where the children of a backup node go (a skipped article's go to its parent)
+        let mut parent_map: HashMap<Uuid, Option<Uuid>> = HashMap::new();
+        let mut taken_titles: HashSet<String> = HashSet::new();
+        let mut taken_slugs: HashSet<String> = HashSet::new();
+        let mut replaced_ids: HashSet<Uuid> = HashSet::new();
+        let mut renames: Vec<(String, String)> = Vec::new();
+        let mut planned: Vec<(BackupNodeMeta, Node, String, Option<Article>)> = Vec::new();
 
         for node_meta in sorted_nodes {
-            let new_id = *node_id_map.get(&node_meta.id).unwrap();
-            
-            // Resolve Parent
-            let new_parent_id = node_meta.parent_id.and_then(|pid| node_id_map.get(&pid).cloned());
-
-            // Read Body from Zip
-            let body_content = if node_meta.r#type == "Article" {
-                // Remove trailing slash if any (though meta path should be file path)
-                let mut f = archive.by_name(&node_meta.path).map_err(|_| format!("Content missing: {}", node_meta.path))?;
-                let mut s = String::new();
-                f.read_to_string(&mut s).map_err(|e| e.to_string())?;
-                s
-            } else {
-                String::new()
+            let parent = node_meta.parent_id.and_then(|p| parent_map.get(&p).copied().flatten());
+            let mut node = Node {
+                id: Uuid::new_v4(),
+                parent_id: parent,
+                author_id: user_id,
+                knowledge_base_id: Some(kb_id),
+                r#type: NodeType::Article,
+                title: node_meta.title.clone(),
+                permission_mode: PermissionMode::Private,
+                created_at: Utc::now(),
+                updated_at: Utc::now(),
             };
 
-            // Rewrite Asset Links
-            let new_body_content = asset_regex.replace_all(&body_content, |caps: &regex::Captures| {
-                if let Ok(old_uuid) = Uuid::parse_str(&caps[1]) {
-                    if let Some(new_uuid) = asset_id_map.get(&old_uuid) {
-                        return format!("[[asset:{}]]", new_uuid);
+            if node_meta.r#type == "Folder" {
+                let key = (parent, node_meta.title.to_lowercase());
+                if let Some(id) = folders.get(&key) {
+                    report.merged_folders += 1;
+                    id_map.insert(node_meta.id, *id);
+                    parent_map.insert(node_meta.id, Some(*id));
+                    continue;
+                }
+                node.r#type = NodeType::Folder;
+                folders.insert(key, node.id);
+                id_map.insert(node_meta.id, node.id);
+                parent_map.insert(node_meta.id, Some(node.id));
+                report.folders += 1;
+                planned.push((node_meta, node, String::new(), None));
+                continue;
+            }
+
+            let mut slug = if node_meta.slug.is_empty() { Uuid::new_v4().to_string() } else { node_meta.slug.clone() };
+            let mut conflict = self.existing_article(&node_meta.title, &slug).await?;
+            // An article the overwrite removes is written over in place instead:
+            // the restore goes in before anything is deleted, and the old
+            // content stays in the article's history
+            if let Some((_, existing)) = conflict.take_if(|(_, a)| removed.contains(&a.node.id) && !replaced_ids.contains(&a.node.id)) {
+                node.id = existing.node.id;
+                replaced_ids.insert(existing.node.id);
+                let slug_taken = self.article_repo.find_by_slug(&slug).await.map_err(|e| e.to_string())?
+                    .is_some_and(|a| a.node.id != existing.node.id);
+                if slug_taken {
+                    slug = format!("{}-{}", slug, &Uuid::new_v4().simple().to_string()[..8]);
+                }
+            }
+            let mut replacing = None;
+            if let Some((field, existing)) = conflict {
+                let owned = existing.node.author_id == user_id && !replaced_ids.contains(&existing.node.id);
+                let action = match options.conflict_policy {
+                    ConflictPolicy::Skip => ConflictPolicy::Skip,
+                    ConflictPolicy::Replace if owned => ConflictPolicy::Replace,
+                    _ => ConflictPolicy::Rename,
+                };
+                let resolution = match action {
+                    ConflictPolicy::Skip => "skipped".to_string(),
+                    ConflictPolicy::Replace => "replaced".to_string(),
+                    ConflictPolicy::Rename => {
+                        let mut attempt = 1;
+                        loop {
+                            let title = restored_title(&node_meta.title, attempt);
+                            let free = !taken_titles.contains(&title)
+                                && self.article_repo.find_by_title(&title).await.map_err(|e| e.to_string())?.is_none();
+                            if free {
+                                node.title = title;
+                                break;
+                            }
+                            attempt += 1;
+                        }
+                        if options.conflict_policy == ConflictPolicy::Replace {
+                            format!("renamed to '{}' (the existing article is not yours to replace)", node.title)
+                        } else {
+                            format!("renamed to '{}'", node.title)
+                        }
+                    }
+                };
+                report.conflicts.push(RestoreConflict {
+                    backup_id: node_meta.id,
+                    title: node_meta.title.clone(),
+                    field: field.to_string(),
+                    existing_id: existing.node.id,
+                    resolution,
+                });
+
+                match action {
+                    ConflictPolicy::Skip => {
+                        // Links to the backup's article now lead to the existing one
+                        report.skipped += 1;
+                        id_map.insert(node_meta.id, existing.node.id);
+                        parent_map.insert(node_meta.id, parent);
+                        continue;
+                    }
+                    ConflictPolicy::Replace => {
+                        report.replaced += 1;
+                        node.id = existing.node.id;
+                        node.permission_mode = existing.node.permission_mode.clone();
+                        node.created_at = existing.node.created_at;
+                        replaced_ids.insert(existing.node.id);
+                        replacing = Some(existing);
+                    }
+                    ConflictPolicy::Rename => {
+                        renames.push((node_meta.title.clone(), node.title.clone()));
+                        let slug_taken = field == "slug"
+                            || self.article_repo.find_by_slug(&slug).await.map_err(|e| e.to_string())?.is_some();
+                        if slug_taken {
+                            slug = format!("{}-{}", slug, &Uuid::new_v4().simple().to_string()[..8]);
+                        }
                     }
                 }
-                caps[0].to_string() // Keep original if not found (shouldn't happen if map is complete)
-            }).to_string();
+            }
+            if replacing.is_none() {
+                report.articles += 1;
+            }
+            if taken_slugs.contains(&slug) {
+                slug = format!("{}-{}", slug, &Uuid::new_v4().simple().to_string()[..8]);
+            }
+            taken_titles.insert(node.title.clone());
+            taken_slugs.insert(slug.clone());
+            id_map.insert(node_meta.id, node.id);
+            parent_map.insert(node_meta.id, Some(node.id));
+            planned.push((node_meta, node, slug, replacing));
+        }
+        report.assets = assets.len();
+        report.removed = removed.difference(&replaced_ids).count();
+
+        if options.dry_run {
+            return Ok(report);
+        }
 
-            // Create Node
-            let node_type = match node_meta.r#type.as_str() {
-                "Folder" => NodeType::Folder,
-                _ => NodeType::Article,
+        // 3. Target knowledge base, if a new one
+        if target.is_none() {
+            let restored_title = format!("{} (Restored {})", meta.knowledge_base.title, Utc::now().format("%Y-%m-%d %H:%M"));
+            let new_kb = KnowledgeBase {
+                id: KnowledgeBaseId(kb_id),
+                author_id: user_id,
+                title: restored_title,
+                description: meta.knowledge_base.description.clone(),
+                tags: meta.knowledge_base.tags.clone(),
+                renderer_id: meta.knowledge_base.renderer_id.clone(),
+                visibility: Visibility::Private, // Default to private
+                cover_image: None,
+                cover_offset_y: 0,
+                created_at: Utc::now(),
+                updated_at: Utc::now(),
             };
+            self.kb_repo.save(new_kb).await.map_err(|e| e.to_string())?;
+        }
+
+        // 4. Assets: the user's copy of identical content is reused
+        let mut kept = replaced_ids;
+        for (old_asset_uuid, zip_path, buffer) in assets {
+            let hash = format!("{:x}", Sha256::digest(&buffer));
+            let reused = self.article_repo.find_by_slug(&hash).await.map_err(|e| e.to_string())?
+                .filter(|a| a.node.author_id == user_id);
+            if let Some(asset) = reused {
+                id_map.insert(old_asset_uuid, asset.node.id);
+                kept.insert(asset.node.id);
+                continue;
+            }
+            // In a real system, we'd store mime in meta.json or detect it.
+            let mime = "application/octet-stream".to_string();
+            let filename = zip_path.rsplit('/').next().unwrap_or(&zip_path).to_string();
+            match self.asset_manager.upload_asset(user_id, filename, mime, &buffer).await {
+                Ok(new_asset_node) => {
+                    id_map.insert(old_asset_uuid, new_asset_node.node.id);
+                },
+                Err(e) => tracing::error!("Failed to restore asset {}: {}", old_asset_uuid, e),
+            }
+        }
+
+        // 5. Nodes
+        for (node_meta, node, slug, replacing) in planned {
+            if node.r#type == NodeType::Folder {
+                self.node_repo.save(node, UserId(user_id)).await.map_err(|e| e.to_string())?;
+                continue;
+            }
+            let body = bodies.get(&node_meta.id).map(String::as_str).unwrap_or_default();
+            let body = rename_links(&remap_ids(body, &id_map), &renames);
 
             let status = match node_meta.status.as_str() {
                 "Published" => ContentStatus::Published,
                 "Archived" => ContentStatus::Archived,
                 _ => ContentStatus::Draft,
             };
-
-            let node = Node {
-                id: new_id,
-                parent_id: new_parent_id,
-                author_id: user_id,
-                knowledge_base_id: Some(new_kb_id),
-                r#type: node_type.clone(),
-                title: node_meta.title.clone(),
-                permission_mode: PermissionMode::Private,
-                created_at: Utc::now(),
-                updated_at: Utc::now(),
+            let reason = if replacing.is_some() { "Replaced from backup" } else { "Restored from backup" };
+            let article = Article {
+                node,
+                slug,
+                status,
+                category: node_meta.category.or_else(|| replacing.and_then(|a| a.category)),
+                body: ContentBody::Markdown(body),
+                tags: node_meta.tags,
+                author_name: None,
+                author_avatar: None,
+                derived_data: None,
             };
+            self.article_repo.save(article, UserId(user_id), Some(reason.to_string())).await.map_err(|e| e.to_string())?;
+        }
 
-            if node_type == NodeType::Article {
-                let article = Article {
-                    node,
-                    slug: Uuid::new_v4().to_string(), // Generate new slug to avoid collision
-                    status,
-                    category: None, // TODO: Store category in meta
-                    body: ContentBody::Markdown(new_body_content),
-                    tags: node_meta.tags,
-                    author_name: None,
-                    author_avatar: None,
-                    derived_data: None,
-                };
-                self.article_repo.save(article, UserId(user_id), Some("Restored from backup".to_string())).await.map_err(|e| e.to_string())?;
-            } else {
-                self.node_repo.save(node, UserId(user_id)).await.map_err(|e| e.to_string())?;
-            }
+        // 6. Overwrite: the old nodes not written over go last, so a failure
+        // above leaves them in place. Deleting the top-level ones takes their
+        // descendants along (those written over were moved out by now)
+        let stale: HashSet<Uuid> = removed.difference(&kept).copied().collect();
+        for node in existing_nodes.iter().filter(|n| stale.contains(&n.id) && n.parent_id.is_none_or(|p| !stale.contains(&p))) {
+            self.article_repo.delete_recursive(&node.id).await.map_err(|e| e.to_string())?;
         }
 
-        Ok(new_kb_id)
+        Ok(report)
     }
 
     pub fn get_backup_path(&self, filename: &str) -> PathBuf {
         self.backup_root.join(filename)
     }
+
+    // --- Schedules ---
+
+    fn save_schedules(&self, schedules: &HashMap<Uuid, BackupSchedule>) -> Result<(), String> {
+        save_map(&self.backup_root.join("schedules.json"), schedules)
+    }
+
+    /// Creates or updates the schedule of a KB; the state of past runs is kept.
+    pub async fn set_schedule(&self, kb_id: Uuid, user_id: Uuid, request: ScheduleRequest) -> Result<BackupSchedule, String> {
+        let kb = self.kb_repo.find_by_id(&KnowledgeBaseId(kb_id))
+            .await.map_err(|e| e.to_string())?
+            .ok_or("Knowledge Base not found")?;
+        if kb.author_id != user_id {
+            return Err("Unauthorized".to_string());
+        }
+        if request.interval_hours == 0 {
+            return Err("Interval must be at least one hour".to_string());
+        }
+
+        let mut schedules = self.schedules.lock().unwrap();
+        let schedule = schedules.entry(kb_id).or_insert_with(|| BackupSchedule {
+            kb_id,
+            owner_id: user_id,
+            enabled: true,
+            interval_hours: request.interval_hours,
+            retention: Retention::default(),
+            last_run: None,
+            last_success: None,
+            last_error: None,
+            consecutive_failures: 0,
+        });
+        schedule.enabled = request.enabled;
+        schedule.interval_hours = request.interval_hours;
+        schedule.retention = request.retention;
+        let schedule = schedule.clone();
+        self.save_schedules(&schedules)?;
+        Ok(schedule)
+    }
+
+    pub fn remove_schedule(&self, kb_id: Uuid, user_id: Uuid) -> Result<(), String> {
+        let mut schedules = self.schedules.lock().unwrap();
+        match schedules.get(&kb_id) {
+            None => return Err("Schedule not found".to_string()),
+            Some(schedule) if schedule.owner_id != user_id => return Err("Unauthorized".to_string()),
+            Some(_) => {}
+        }
+        schedules.remove(&kb_id);
+        self.save_schedules(&schedules)
+    }
+
+    /// The backups of a KB on disk, oldest first.
+    async fn kb_backups(&self, kb_id: Uuid) -> Result<Vec<BackupEntry>, String> {
+        let mut backups = Vec::new();
+        for filename in self.list_backups().await? {
+            let Some((id, created_at)) = parse_backup_name(&filename) else { continue };
+            if id != kb_id {
+                continue;
+            }
+            let size = tokio::fs::metadata(self.get_backup_path(&filename)).await.map(|m| m.len()).unwrap_or(0);
+            backups.push(BackupEntry { filename, created_at, size });
+        }
+        backups.sort_by_key(|b| b.created_at);
+        Ok(backups)
+    }
+
+    /// The user's schedules with their state and the backups kept so far.
+    pub async fn list_schedules(&self, user_id: Uuid) -> Result<Vec<ScheduleStatus>, String> {
+        let mut schedules: Vec<BackupSchedule> = self.schedules.lock().unwrap().values()
+            .filter(|s| s.owner_id == user_id)
+            .cloned()
+            .collect();
+        schedules.sort_by_key(|s| s.kb_id);
+
+        let mut statuses = Vec::new();
+        for schedule in schedules {
+            let backups = self.kb_backups(schedule.kb_id).await?;
+            statuses.push(ScheduleStatus {
+                next_run: schedule.next_run(),
+                total_size: backups.iter().map(|b| b.size).sum(),
+                backups,
+                schedule,
+            });
+        }
+        Ok(statuses)
+    }
+
+    /// Deletes the backups of a KB that `retention` does not keep, sparing
+    /// the bases of the incremental backups kept.
+    pub async fn prune(&self, kb_id: Uuid, retention: &Retention) -> Result<Vec<String>, String> {
+        let backups = self.kb_backups(kb_id).await?;
+        let chains = self.chains.lock().unwrap().clone();
+        let keep = with_bases(retained(&backups, retention), &chains);
+        let mut removed = Vec::new();
+        for backup in backups.into_iter().filter(|b| !keep.contains(&b.filename)) {
+            tokio::fs::remove_file(self.get_backup_path(&backup.filename)).await.map_err(|e| e.to_string())?;
+            removed.push(backup.filename);
+        }
+        if !removed.is_empty() {
+            let mut chains = self.chains.lock().unwrap();
+            chains.retain(|filename, _| !removed.contains(filename));
+            save_map(&self.backup_root.join("chains.json"), &*chains)?;
+        }
+        Ok(removed)
+    }
+
+    async fn audit(&self, action: &str, actor_id: Uuid, kb_id: Uuid, details: serde_json::Value) {
+        if let Err(e) = self.audit_repo.log_event(action, actor_id, &format!("kb:{}", kb_id), details).await {
+            tracing::error!("Failed to write audit log for KB {}: {}", kb_id, e);
+        }
+    }
+
+    /// Backs up one scheduled KB, then prunes by its retention.
+    async fn run_schedule(&self, schedule: BackupSchedule, now: DateTime<Utc>) {
+        let result = self.create_backup(schedule.kb_id, schedule.owner_id, None).await;
+        let pruned = match &result {
+            Ok(_) => self.prune(schedule.kb_id, &schedule.retention).await,
+            Err(_) => Ok(vec![]),
+        };
+
+        let failures = {
+            let mut schedules = self.schedules.lock().unwrap();
+            // Removed while the backup ran
+            let Some(state) = schedules.get_mut(&schedule.kb_id) else { return };
+            state.last_run = Some(now);
+            match &result {
+                Ok(_) => {
+                    state.last_success = Some(Utc::now());
+                    state.last_error = None;
+                    state.consecutive_failures = 0;
+                }
+                Err(e) => {
+                    state.last_error = Some(e.clone());
+                    state.consecutive_failures += 1;
+                }
+            }
+            let failures = state.consecutive_failures;
+            if let Err(e) = self.save_schedules(&schedules) {
+                tracing::error!("Failed to save backup schedules: {}", e);
+            }
+            failures
+        };
+
+        match (result, pruned) {
+            (Err(e), _) => {
+                tracing::error!("Scheduled backup of KB {} failed: {}", schedule.kb_id, e);
+                self.audit("backup_failed", schedule.owner_id, schedule.kb_id, serde_json::json!({
+                    "error": e,
+                    "consecutive_failures": failures,
+                })).await;
+            }
+            (Ok(filename), Ok(removed)) => {
+                tracing::info!("Scheduled backup of KB {}: {} ({} pruned)", schedule.kb_id, filename, removed.len());
+                if !removed.is_empty() {
+                    self.audit("backup_pruned", schedule.owner_id, schedule.kb_id, serde_json::json!({
+                        "removed": removed,
+                        "retention": schedule.retention,
+                    })).await;
+                }
+            }
+            (Ok(_), Err(e)) => {
+                tracing::error!("Pruning backups of KB {} failed: {}", schedule.kb_id, e);
+                self.audit("backup_prune_failed", schedule.owner_id, schedule.kb_id, serde_json::json!({ "error": e })).await;
+            }
+        }
+    }
+
+    /// Runs the schedules that are due, one after the other.
+    pub async fn run_due_backups(&self) {
+        let now = Utc::now();
+        let due: Vec<BackupSchedule> = self.schedules.lock().unwrap().values()
+            .filter(|s| s.is_due(now))
+            .cloned()
+            .collect();
+        for schedule in due {
+            self.run_schedule(schedule, now).await;
+        }
+    }
+
+    /// Starts the background loop running scheduled backups.
+    pub fn spawn_scheduler(&self) {
+        let service = self.clone();
+        tokio::spawn(async move {
+            let mut tick = tokio::time::interval(SCHEDULER_TICK);
+            loop {
+                tick.tick().await;
+                service.run_due_backups().await;
+            }
+        });
+    }
 }
@@ -0,0 +1,430 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+use serde::{Deserialize, Serialize};
+use tokio::sync::{broadcast, Mutex};
+use uuid::Uuid;
+use chrono::Utc;
+use anyhow::{anyhow, Result};
+
+use crate::domain::collab::ot::TextOperation;
+use crate::domain::collab::{CollabDocument, CollabError, Selection};
+use crate::domain::indexer_service::IndexerService;
+use crate::domain::models::{ContentBody, ContentItem, UserId};
+use crate::domain::ports::{ArticleRepository, RepositoryError};
+use crate::domain::sentence_parser::{SentenceMap, SentenceParser};
+use crate::infrastructure::services::site::SiteGenerator;
+
+/// No edits for this long ends an editing burst: the merged text is saved
+/// with a change reason, so it shows up as a named version in the history.
+const IDLE_SNAPSHOT: Duration = Duration::from_secs(10);
+/// Saves during long uninterrupted sessions, so a crash loses little.
+const CHECKPOINT_INTERVAL: Duration = Duration::from_secs(60);
+const TICK: Duration = Duration::from_secs(1);
+/// Failed saves in a row after which the room gives up and closes; each
+/// failure doubles the wait before the next attempt.
+const MAX_SAVE_FAILURES: u32 = 5;
+const EVENT_BUFFER: usize = 256;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Participant {
+    pub client_id: u64,
+    pub user_id: Uuid,
+    pub username: String,
+    pub selection: Option<Selection>,
+}
+
+/// Messages from an editor over the socket.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ClientMessage {
+    /// `operation` was made against `revision`; `selection` is the cursor after it.
+    Operation {
+        revision: u64,
+        operation: TextOperation,
+        #[serde(default)]
+        selection: Option<Selection>,
+    },
+    Selection { selection: Option<Selection> },
+}
+
+/// Messages to editors.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ServerMessage {
+    Init { client_id: u64, revision: u64, text: String, participants: Vec<Participant> },
+    /// The sender's operation was applied as `revision`.
+    Ack { revision: u64 },
+    /// Someone else's operation, already transformed to apply after `revision - 1`.
+    Operation { client_id: u64, revision: u64, operation: TextOperation },
+    Joined { participant: Participant },
+    Left { client_id: u64 },
+    Selection { client_id: u64, selection: Option<Selection> },
+    /// Merged text persisted; `snapshot` when saved as a named version.
+    Saved { revision: u64, snapshot: bool },
+    Error { message: String },
+    /// The room shut down; edits not yet saved are lost and the client has to rejoin.
+    Closed { message: String },
+}
+
+/// A broadcast to everyone in the room except `origin`.
+#[derive(Debug, Clone)]
+pub struct RoomEvent {
+    pub origin: Option<u64>,
+    pub message: ServerMessage,
+}
+
+/// One article being edited live.
+pub struct Room {
+    article_id: Uuid,
+    state: Mutex<RoomState>,
+    events: broadcast::Sender<RoomEvent>,
+}
+
+struct RoomState {
+    document: CollabDocument,
+    participants: HashMap<u64, Participant>,
+    dirty: bool, // Edited since the last save
+    last_edit: Instant,
+    last_save: Instant,
+    editors: Vec<(Uuid, String)>, // Who edited since the last save, most recent last
+    stored: Stored,
+    failures: u32, // Failed saves in a row
+    retry_at: Instant,
+}
+
+/// The article as last loaded or saved by the room. Saves expect its
+/// revision, so a change made elsewhere meanwhile is merged, not overwritten.
+struct Stored {
+    revision: i64,
+    text: String,
+    /// Document revision with the same text; `None` after a merge, until the next save.
+    at: Option<u64>,
+}
+
+/// A connection's handle on its room.
+pub struct Session {
+    pub client_id: u64,
+    pub room: Arc<Room>,
+    pub events: broadcast::Receiver<RoomEvent>,
+    pub init: ServerMessage,
+}
+
+enum Persist {
+    Save { text: String, revision: u64, expected: i64, editors: Vec<(Uuid, String)>, snapshot: bool },
+    Close,
+    Nothing,
+}
+
+/// Real-time collaborative editing of article bodies. Each article being
+/// edited gets a room with the authoritative text (operational transform,
+/// see `domain::collab`), the participants' cursors, and a background task
+/// that persists the merged text through `ArticleRepository::save_if_revision`.
+pub struct CollaborationService {
+    articles: Arc<dyn ArticleRepository>,
+    indexer: Arc<IndexerService>,
+    site_generator: Arc<SiteGenerator>,
+    rooms: Mutex<HashMap<Uuid, Arc<Room>>>,
+    next_client: AtomicU64,
+}
+
+impl CollaborationService {
+    pub fn new(articles: Arc<dyn ArticleRepository>, indexer: Arc<IndexerService>, site_generator: Arc<SiteGenerator>) -> Self {
+        Self {
+            articles,
+            indexer,
+            site_generator,
+            rooms: Mutex::new(HashMap::new()),
+            next_client: AtomicU64::new(1),
+        }
+    }
+
+    /// The stored Markdown body of an article.
+    async fn load_text(&self, article_id: Uuid) -> Result<String> {
+        match self.articles.find_by_id(&article_id).await? {
+            Some(ContentItem::Article(article)) => match article.body {
+                ContentBody::Markdown(text) => Ok(text),
+                _ => Err(anyhow!("Only Markdown articles can be edited collaboratively")),
+            },
+            _ => Err(RepositoryError::NotFound(format!("Article {}", article_id)).into()),
+        }
+    }
+
+    /// Enters the article's room, opening it from the stored body if nobody
+    /// is editing yet. Only Markdown articles can be edited live.
+    pub async fn join(self: &Arc<Self>, article_id: Uuid, user_id: Uuid, username: String) -> Result<Session> {
+        let mut loaded = None;
+        loop {
+            let mut rooms = self.rooms.lock().await;
+            let room = match (rooms.get(&article_id).cloned(), loaded.take()) {
+                (Some(room), _) => room,
+                (None, Some((revision, text))) => {
+                    let room = Arc::new(Room {
+                        article_id,
+                        state: Mutex::new(RoomState {
+                            document: CollabDocument::new(text.clone()),
+                            participants: HashMap::new(),
+                            dirty: false,
+                            last_edit: Instant::now(),
+                            last_save: Instant::now(),
+                            editors: Vec::new(),
+                            stored: Stored { revision, text, at: Some(0) },
+                            failures: 0,
+                            retry_at: Instant::now(),
+                        }),
+                        events: broadcast::channel(EVENT_BUFFER).0,
+                    });
+                    rooms.insert(article_id, room.clone());
+                    tokio::spawn(self.clone().run_room(room.clone()));
+                    room
+                }
+                (None, None) => {
+                    // Read without holding the rooms; revision first, so a
+                    // save in between shows up as a conflict
+                    drop(rooms);
+                    let revision = self.articles.current_revision(&article_id).await?;
+                    loaded = Some((revision, self.load_text(article_id).await?));
+                    continue;
+                }
+            };
+
+            // Still holding the rooms: the room cannot close before the participant is in
+            let client_id = self.next_client.fetch_add(1, Ordering::Relaxed);
+            let participant = Participant { client_id, user_id, username, selection: None };
+            let mut state = room.state.lock().await;
+            drop(rooms);
+            state.participants.insert(client_id, participant.clone());
+            // Subscribe under the lock so no operation falls between init and the first event
+            let events = room.events.subscribe();
+            let init = ServerMessage::Init {
+                client_id,
+                revision: state.document.revision(),
+                text: state.document.text().to_string(),
+                participants: state.participants.values().cloned().collect(),
+            };
+            room.broadcast(Some(client_id), ServerMessage::Joined { participant });
+            drop(state);
+
+            return Ok(Session { client_id, room, events, init });
+        }
+    }
+
+    /// Saves on idle and at checkpoints; closes the room once everyone left
+    /// and the text is saved, or when the article is gone or keeps failing to save.
+    async fn run_room(self: Arc<Self>, room: Arc<Room>) {
+        let mut tick = tokio::time::interval(TICK);
+        loop {
+            tick.tick().await;
+
+            let action = {
+                let mut rooms = self.rooms.lock().await;
+                let mut state = room.state.lock().await;
+                let empty = state.participants.is_empty();
+                let due = state.dirty && state.retry_at <= Instant::now();
+                if due && (empty || state.last_edit.elapsed() >= IDLE_SNAPSHOT) {
+                    state.take_save(true)
+                } else if due && state.last_save.elapsed() >= CHECKPOINT_INTERVAL {
+                    state.take_save(false)
+                } else if empty && !state.dirty {
+                    rooms.remove(&room.article_id);
+                    Persist::Close
+                } else {
+                    Persist::Nothing
+                }
+            };
+
+            match action {
+                Persist::Save { text, revision, expected, editors, snapshot } => {
+                    match self.save(room.article_id, &text, &editors, snapshot, expected).await {
+                        Ok(saved) => {
+                            let mut state = room.state.lock().await;
+                            state.stored = Stored { revision: saved, text, at: Some(revision) };
+                            state.failures = 0;
+                            room.broadcast(None, ServerMessage::Saved { revision, snapshot });
+                        }
+                        Err(e) => {
+                            room.state.lock().await.unsave(editors);
+                            // A conflict is merged and saved on the next tick; only failures count
+                            let failure = match e.downcast_ref::<RepositoryError>() {
+                                Some(RepositoryError::RevisionConflict { .. }) => self.merge_stored(&room).await
+                                    .map_err(|e| ("Failed to merge outside edits of", e)).err(),
+                                _ => Some(("Failed to save collaborative edits of", e)),
+                            };
+                            let Some((context, e)) = failure else { continue };
+                            tracing::error!("{} {}: {}", context, room.article_id, e);
+
+                            if matches!(e.downcast_ref::<RepositoryError>(), Some(RepositoryError::NotFound(_))) {
+                                self.close(&room, "The article was deleted".to_string()).await;
+                                break;
+                            }
+                            if room.state.lock().await.fail() >= MAX_SAVE_FAILURES {
+                                self.close(&room, format!("The edits could not be saved: {}", e)).await;
+                                break;
+                            }
+                        }
+                    }
+                }
+                Persist::Close => break,
+                Persist::Nothing => {}
+            }
+        }
+    }
+
+    /// Shuts the room down: its editors are told why and disconnected, and
+    /// whoever joins next opens a new room from the stored article.
+    async fn close(&self, room: &Room, message: String) {
+        let mut rooms = self.rooms.lock().await;
+        let _state = room.state.lock().await;
+        rooms.remove(&room.article_id);
+        room.broadcast(None, ServerMessage::Closed { message });
+    }
+
+    /// Brings an article changed outside the session (REST update, block
+    /// edit, link rewrite...) into the room: the change is applied as an
+    /// operation of its own, so it is transformed past the room's edits.
+    async fn merge_stored(&self, room: &Room) -> Result<()> {
+        let revision = self.articles.current_revision(&room.article_id).await?;
+        let text = self.load_text(room.article_id).await?;
+
+        let mut state = room.state.lock().await;
+        let change = TextOperation::diff(&state.stored.text, &text);
+        let mut at = state.stored.at;
+        if !change.is_noop() {
+            let merged = at.and_then(|base| state.document.apply_client(base, change).ok());
+            match merged {
+                Some(applied) => {
+                    for p in state.participants.values_mut() {
+                        p.selection = p.selection.map(|s| s.transform(&applied));
+                    }
+                    // Client ids start at 1: 0 is the server
+                    room.broadcast(None, ServerMessage::Operation { client_id: 0, revision: state.document.revision(), operation: applied });
+                }
+                None => room.broadcast(None, ServerMessage::Error {
+                    message: "The article was changed elsewhere and could not be merged; that version stays in its history".to_string(),
+                }),
+            }
+            at = None;
+        }
+        state.stored = Stored { revision, text, at };
+        Ok(())
+    }
+
+    /// Saves the room's text over the stored revision `expected`; returns the new revision.
+    async fn save(&self, article_id: Uuid, text: &str, editors: &[(Uuid, String)], snapshot: bool, expected: i64) -> Result<i64> {
+        let mut article = match self.articles.find_by_id(&article_id).await? {
+            Some(ContentItem::Article(article)) => article,
+            _ => return Err(RepositoryError::NotFound(format!("Article {}", article_id)).into()),
+        };
+        let editor = editors.last().map(|(id, _)| *id).unwrap_or(article.node.author_id);
+
+        let old_map = article.derived_data.take()
+            .and_then(|v| serde_json::from_value::<SentenceMap>(v).ok());
+        article.derived_data = Some(serde_json::to_value(SentenceParser::parse(text, old_map.as_ref()))?);
+        article.body = ContentBody::Markdown(text.to_string());
+        article.node.updated_at = Utc::now();
+
+        let reason = snapshot.then(|| {
+            let names: Vec<&str> = editors.iter().map(|(_, name)| name.as_str()).collect();
+            format!("Collaborative edit by {}", names.join(", "))
+        });
+        let kb_id = article.node.knowledge_base_id;
+        let revision = self.articles.save_if_revision(article, UserId(editor), reason, Some(expected)).await?;
+        if let Some(kb_id) = kb_id {
+            self.site_generator.schedule_rebuild(kb_id);
+        }
+
+        let indexer = self.indexer.clone();
+        let text = text.to_string();
+        tokio::spawn(async move {
+            if let Err(e) = indexer.index_article(article_id, &text).await {
+                tracing::error!("Async Indexing failed for {}: {}", article_id, e);
+            }
+        });
+        Ok(revision)
+    }
+}
+
+impl RoomState {
+    fn take_save(&mut self, snapshot: bool) -> Persist {
+        self.dirty = false;
+        self.last_save = Instant::now();
+        Persist::Save {
+            text: self.document.text().to_string(),
+            revision: self.document.revision(),
+            expected: self.stored.revision,
+            editors: std::mem::take(&mut self.editors),
+            snapshot,
+        }
+    }
+
+    /// Records a failed save and backs off; returns the failures in a row.
+    fn fail(&mut self) -> u32 {
+        self.failures += 1;
+        self.retry_at = Instant::now() + TICK * 2u32.pow(self.failures);
+        self.failures
+    }
+
+    /// A save did not happen: the text is dirty again and keeps its editors.
+    fn unsave(&mut self, editors: Vec<(Uuid, String)>) {
+        self.dirty = true;
+        for editor in editors.into_iter().rev() {
+            if !self.editors.iter().any(|(id, _)| *id == editor.0) {
+                self.editors.insert(0, editor);
+            }
+        }
+    }
+}
+
+impl Room {
+    fn broadcast(&self, origin: Option<u64>, message: ServerMessage) {
+        // No receivers is fine: everyone may have left
+        let _ = self.events.send(RoomEvent { origin, message });
+    }
+
+    /// Applies a client's operation and forwards it to the others.
+    /// Returns the new revision for the sender's acknowledgement.
+    pub async fn apply(&self, client_id: u64, revision: u64, operation: TextOperation, selection: Option<Selection>) -> Result<u64, CollabError> {
+        let mut state = self.state.lock().await;
+        let applied = state.document.apply_client(revision, operation)?;
+        let revision = state.document.revision();
+
+        for p in state.participants.values_mut() {
+            p.selection = p.selection.map(|s| s.transform(&applied));
+        }
+        let editor = state.participants.get_mut(&client_id).map(|p| {
+            if selection.is_some() {
+                p.selection = selection;
+            }
+            (p.user_id, p.username.clone())
+        });
+        if !applied.is_noop() {
+            state.dirty = true;
+            state.last_edit = Instant::now();
+            if let Some(editor) = editor {
+                state.editors.retain(|(id, _)| *id != editor.0);
+                state.editors.push(editor);
+            }
+        }
+
+        self.broadcast(Some(client_id), ServerMessage::Operation { client_id, revision, operation: applied });
+        if selection.is_some() {
+            self.broadcast(Some(client_id), ServerMessage::Selection { client_id, selection });
+        }
+        Ok(revision)
+    }
+
+    pub async fn select(&self, client_id: u64, selection: Option<Selection>) {
+        let mut state = self.state.lock().await;
+        if let Some(p) = state.participants.get_mut(&client_id) {
+            p.selection = selection;
+        }
+        self.broadcast(Some(client_id), ServerMessage::Selection { client_id, selection });
+    }
+
+    pub async fn leave(&self, client_id: u64) {
+        let mut state = self.state.lock().await;
+        state.participants.remove(&client_id);
+        self.broadcast(Some(client_id), ServerMessage::Left { client_id });
+    }
+}
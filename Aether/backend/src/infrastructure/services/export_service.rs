@@ -1,14 +1,154 @@
 use crate::domain::ports::{ExportService, RepositoryError, ExportFormat,
-    ArticleRepository, MemoRepository, CommentRepository};
-use crate::domain::models::UserId;
-use uuid::Uuid;
+    ArticleRepository, MemoRepository, CommentRepository, KnowledgeBaseRepository};
+use crate::domain::models::{UserId, Comment, ContentItem, ContentBody, KnowledgeBaseId, Visibility};
+use crate::domain::blocks::html::{self, escape, HtmlResolver};
+use crate::domain::blocks::parser::parse_markdown_to_blocks;
+use crate::infrastructure::services::asset_manager::AssetManager;
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use std::collections::{HashMap, HashSet};
+use std::io::Write;
 use std::sync::Arc;
+use uuid::Uuid;
 use async_trait::async_trait;
 
+pub const STYLE: &str = r#"
+body { max-width: 46rem; margin: 2rem auto; padding: 0 1rem; font: 17px/1.65 Georgia, "Times New Roman", serif; color: #222; }
+h1, h2, h3, h4 { font-family: -apple-system, "Segoe UI", Helvetica, Arial, sans-serif; line-height: 1.25; }
+a { color: #1a5fb4; }
+pre { background: #f6f8fa; padding: .8rem 1rem; overflow-x: auto; border-radius: 4px; }
+code { font-family: "SFMono-Regular", Consolas, monospace; font-size: .9em; }
+blockquote { margin: 0; padding-left: 1rem; border-left: 3px solid #ccc; color: #555; }
+table { border-collapse: collapse; } th, td { border: 1px solid #ddd; padding: .3rem .6rem; }
+img { max-width: 100%; } figure { margin: 1.5rem 0; text-align: center; }
+.math-block { margin: 1rem 0; } .qed { float: right; }
+.wiki-link.wanted { color: #a51d2d; border-bottom: 1px dashed; }
+.comments { margin-top: 3rem; border-top: 1px solid #ddd; } .comments ul { list-style: none; padding-left: 1.2rem; }
+.comment-meta { font-size: .85em; color: #777; }
+nav.site { font-family: sans-serif; font-size: .9em; margin-bottom: 2rem; }
+"#;
+
+// KaTeX picks up the `\(...\)` / `\[...\]` spans left by the block renderer
+const KATEX: &str = r#"<link rel="stylesheet" href="https://cdn.jsdelivr.net/npm/katex@0.16.11/dist/katex.min.css">
+<script defer src="https://cdn.jsdelivr.net/npm/katex@0.16.11/dist/katex.min.js"></script>
+<script defer src="https://cdn.jsdelivr.net/npm/katex@0.16.11/dist/contrib/auto-render.min.js" onload="renderMathInElement(document.body, { delimiters: [{ left: '\\[', right: '\\]', display: true }, { left: '\\(', right: '\\)', display: false }] });"></script>"#;
+
+pub use crate::domain::assets::asset_ids;
+
+fn asset_id(src: &str) -> Option<Uuid> {
+    asset_ids(src).into_iter().next()
+}
+
+pub fn extension(mime: &str) -> &'static str {
+    match mime {
+        "image/png" => "png",
+        "image/jpeg" => "jpg",
+        "image/gif" => "gif",
+        "image/webp" => "webp",
+        "image/svg+xml" => "svg",
+        _ => "bin",
+    }
+}
+
+struct LoadedAsset {
+    mime: String,
+    data: Vec<u8>,
+}
+
+/// Inlines assets as data URIs (single page) or points at `assets/` files
+/// (site); wiki links resolve to the pages of the site.
+struct ExportResolver<'a> {
+    assets: &'a HashMap<Uuid, LoadedAsset>,
+    asset_prefix: Option<&'a str>, // None: data URIs
+    pages: Option<&'a HashMap<String, String>>, // Lowercased title -> file
+}
+
+impl HtmlResolver for ExportResolver<'_> {
+    fn image_src(&mut self, src: &str) -> Option<String> {
+        let Some((id, asset)) = asset_id(src).and_then(|id| self.assets.get(&id).map(|a| (id, a))) else {
+            return html::safe_url(src);
+        };
+        Some(match self.asset_prefix {
+            Some(prefix) => format!("{}{}.{}", prefix, id, extension(&asset.mime)),
+            None => format!("data:{};base64,{}", asset.mime, STANDARD.encode(&asset.data)),
+        })
+    }
+
+    fn wiki_href(&self, title: &str) -> Option<String> {
+        self.pages?.get(&title.to_lowercase()).cloned()
+    }
+}
+
+/// Nests replies under their parents; the repository returns comments flat.
+fn comment_tree(comments: Vec<Comment>) -> Vec<Comment> {
+    let ids: HashSet<Uuid> = comments.iter().map(|c| c.id.0).collect();
+    let mut children: HashMap<Uuid, Vec<Comment>> = HashMap::new();
+    let mut roots = Vec::new();
+    for comment in comments {
+        match comment.parent_id.as_ref().map(|p| p.0).filter(|p| ids.contains(p)) {
+            Some(parent) => children.entry(parent).or_default().push(comment),
+            None => roots.push(comment),
+        }
+    }
+    fn attach(mut comment: Comment, children: &mut HashMap<Uuid, Vec<Comment>>) -> Comment {
+        let replies = children.remove(&comment.id.0).unwrap_or_default();
+        comment.replies.extend(replies.into_iter().map(|r| attach(r, children)));
+        comment
+    }
+    roots.into_iter().map(|c| attach(c, &mut children)).collect()
+}
+
+fn render_comments(comments: &[Comment], resolver: &mut dyn HtmlResolver) -> String {
+    let items: Vec<String> = comments.iter().map(|c| {
+        let replies = if c.replies.is_empty() { String::new() } else { render_comments(&c.replies, resolver) };
+        format!(
+            "<li><div class=\"comment\"><div class=\"comment-meta\"><strong>{}</strong> · <time datetime=\"{}\">{}</time></div><div class=\"comment-body\">{}</div></div>{}</li>",
+            escape(c.user_name.as_deref().unwrap_or("Anon")),
+            c.created_at.to_rfc3339(),
+            c.created_at.format("%Y-%m-%d %H:%M"),
+            html::render_inline(&c.text, resolver),
+            replies
+        )
+    }).collect();
+    format!("<ul>\n{}\n</ul>", items.join("\n"))
+}
+
+/// A standalone, styled page: the body rendered from Markdown, then the comment threads.
+pub fn html_page(title: &str, markdown: &str, comments: &[Comment], nav: Option<&str>, resolver: &mut dyn HtmlResolver) -> String {
+    let blocks = parse_markdown_to_blocks(Uuid::nil(), markdown);
+    let body = html::render_html(&blocks, resolver);
+    let comments = if comments.is_empty() {
+        String::new()
+    } else {
+        format!("<section class=\"comments\">\n<h2>Comments</h2>\n{}\n</section>\n", render_comments(comments, resolver))
+    };
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n<title>{title}</title>\n<style>{STYLE}</style>\n{KATEX}\n</head>\n<body>\n{nav}<article>\n<h1>{title}</h1>\n{body}\n</article>\n{comments}</body>\n</html>\n",
+        title = escape(title),
+        nav = nav.unwrap_or(""),
+    )
+}
+
+/// A file name for a page, unique within the site.
+pub fn page_file(slug: &str, id: Uuid, taken: &mut HashSet<String>) -> String {
+    let mut name: String = slug.chars()
+        .map(|c| if c.is_alphanumeric() || c == '-' || c == '_' { c } else { '-' })
+        .collect();
+    if name.trim_matches('-').is_empty() {
+        name = id.to_string();
+    }
+    if !taken.insert(name.clone()) {
+        name = format!("{}-{}", name, &id.simple().to_string()[..8]);
+        taken.insert(name.clone());
+    }
+    format!("{}.html", name)
+}
+
 pub struct DataExportService {
     article_repo: Arc<dyn ArticleRepository>,
     memo_repo: Arc<dyn MemoRepository>,
     comment_repo: Arc<dyn CommentRepository>,
+    kb_repo: Arc<dyn KnowledgeBaseRepository>,
+    asset_manager: Arc<AssetManager>,
 }
 
 impl DataExportService {
@@ -16,8 +156,10 @@ impl DataExportService {
         article_repo: Arc<dyn ArticleRepository>,
         memo_repo: Arc<dyn MemoRepository>,
         comment_repo: Arc<dyn CommentRepository>,
+        kb_repo: Arc<dyn KnowledgeBaseRepository>,
+        asset_manager: Arc<AssetManager>,
     ) -> Self {
-        Self { article_repo, memo_repo, comment_repo }
+        Self { article_repo, memo_repo, comment_repo, kb_repo, asset_manager }
     }
 
     fn format_markdown(&self, title: &str, body: &str, comments: &[String]) -> String {
@@ -30,6 +172,34 @@ impl DataExportService {
         }
         md
     }
+
+    /// Reads the assets a text references; ones the requester cannot see (or
+    /// that are gone) are left out and keep their original URL.
+    async fn load_assets(&self, text: &str, context_id: Uuid, requester: Option<&UserId>, into: &mut HashMap<Uuid, LoadedAsset>) {
+        let Some(requester) = requester else { return };
+        for id in asset_ids(text) {
+            if into.contains_key(&id) {
+                continue;
+            }
+            let loaded = match self.asset_manager.get_asset_file(id, Some(context_id), requester.0).await {
+                Ok((path, mime)) => tokio::fs::read(&path).await.map(|data| LoadedAsset { mime, data }).map_err(|e| e.to_string()),
+                Err(e) => Err(e),
+            };
+            match loaded {
+                Ok(asset) => { into.insert(id, asset); }
+                Err(e) => tracing::warn!("Skipping asset {} in export: {}", id, e),
+            }
+        }
+    }
+
+    async fn export_html(&self, node_id: &Uuid, title: &str, body: &str, comments: Vec<Comment>, requester: Option<UserId>) -> Vec<u8> {
+        let mut assets = HashMap::new();
+        let comment_text: String = comments.iter().map(|c| c.text.as_str()).collect::<Vec<_>>().join("\n");
+        self.load_assets(&format!("{}\n{}", body, comment_text), *node_id, requester.as_ref(), &mut assets).await;
+
+        let mut resolver = ExportResolver { assets: &assets, asset_prefix: None, pages: None };
+        html_page(title, body, &comment_tree(comments), None, &mut resolver).into_bytes()
+    }
 }
 
 #[async_trait]
@@ -38,25 +208,25 @@ impl ExportService for DataExportService {
         &self,
         node_id: &Uuid,
         format: ExportFormat,
-        _requester: Option<UserId>
+        requester: Option<UserId>
     ) -> Result<Vec<u8>, RepositoryError> {
         // 1. Try to find in Article Repo
         let article_opt = match self.article_repo.find_by_id(node_id).await {
-            Ok(Some(crate::domain::models::ContentItem::Article(a))) => Some(a),
-            _ => None, 
+            Ok(Some(ContentItem::Article(a))) => Some(a),
+            _ => None,
         };
 
         if let Some(article) = article_opt {
             let comments = self.comment_repo.get_comments(node_id).await.unwrap_or_default();
-            let comment_texts: Vec<String> = comments.into_iter().map(|c| format!("{}: {}", c.user_name.unwrap_or("Anon".into()), c.text)).collect();
-            
+            let comment_texts: Vec<String> = comments.iter().map(|c| format!("{}: {}", c.user_name.as_deref().unwrap_or("Anon"), c.text)).collect();
+
             let content_str = match article.body {
-                crate::domain::models::ContentBody::Markdown(s) => s,
+                ContentBody::Markdown(s) => s,
                 _ => "Non-text content".to_string(),
             };
 
             let md = self.format_markdown(&article.node.title, &content_str, &comment_texts);
-            
+
             // Format handling
             match format {
                 ExportFormat::Markdown => Ok(md.into_bytes()),
@@ -65,12 +235,12 @@ impl ExportService for DataExportService {
                     "body": content_str,
                     "comments": comment_texts
                 })).unwrap()),
-                ExportFormat::Html => Ok(format!("<html><h1>{}</h1><p>{}</p></html>", article.node.title, content_str).into_bytes()),
+                ExportFormat::Html => Ok(self.export_html(node_id, &article.node.title, &content_str, comments, requester).await),
             }
         } else if let Ok(Some(memo)) = self.memo_repo.find_by_id(node_id).await {
              let comments = self.comment_repo.get_comments(node_id).await.unwrap_or_default();
-             let comment_texts: Vec<String> = comments.into_iter().map(|c| format!("{}: {}", c.user_name.unwrap_or("Anon".into()), c.text)).collect();
-             
+             let comment_texts: Vec<String> = comments.iter().map(|c| format!("{}: {}", c.user_name.as_deref().unwrap_or("Anon"), c.text)).collect();
+
              let md = self.format_markdown(&memo.node.title, &memo.content, &comment_texts);
              match format {
                 ExportFormat::Markdown => Ok(md.into_bytes()),
@@ -79,10 +249,76 @@ impl ExportService for DataExportService {
                     "body": memo.content,
                     "comments": comment_texts
                 })).unwrap()),
-                ExportFormat::Html => Ok(format!("<html><h1>{}</h1><p>{}</p></html>", memo.node.title, memo.content).into_bytes()),
+                ExportFormat::Html => Ok(self.export_html(node_id, &memo.node.title, &memo.content, comments, requester).await),
              }
         } else {
             Err(RepositoryError::NotFound("Node not found".to_string()))
         }
     }
+
+    async fn export_knowledge_base_site(
+        &self,
+        kb_id: &Uuid,
+        requester: Option<UserId>
+    ) -> Result<Vec<u8>, RepositoryError> {
+        let kb = self.kb_repo.find_by_id(&KnowledgeBaseId(*kb_id)).await?
+            .ok_or_else(|| RepositoryError::NotFound("Knowledge base not found".to_string()))?;
+        let is_author = requester.as_ref().is_some_and(|u| u.0 == kb.author_id);
+        if !is_author && kb.visibility != Visibility::Public {
+            return Err(RepositoryError::Unauthorized("Knowledge base is not public".to_string()));
+        }
+
+        // 1. Articles visible to the requester, with their comments and assets
+        let items = self.article_repo.list(requester.clone(), None, Some(*kb_id), None, None, 10000, 0).await?;
+        let mut taken = HashSet::new();
+        let mut pages = Vec::new();
+        let mut assets = HashMap::new();
+        for item in items {
+            let ContentItem::Article(article) = item else { continue };
+            let ContentBody::Markdown(body) = &article.body else { continue };
+            let comments = self.comment_repo.get_comments(&article.node.id).await.unwrap_or_default();
+            let comment_text: String = comments.iter().map(|c| c.text.as_str()).collect::<Vec<_>>().join("\n");
+            self.load_assets(&format!("{}\n{}", body, comment_text), article.node.id, requester.as_ref(), &mut assets).await;
+
+            let file = page_file(&article.slug, article.node.id, &mut taken);
+            pages.push((file, article.node.title.clone(), body.clone(), comment_tree(comments)));
+        }
+        let by_title: HashMap<String, String> = pages.iter()
+            .map(|(file, title, _, _)| (title.to_lowercase(), file.clone()))
+            .collect();
+
+        // 2. Zip: index.html, pages/*.html, assets/*
+        let mut zip = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+        let options = zip::write::FileOptions::<()>::default()
+            .compression_method(zip::CompressionMethod::Deflated);
+        let zip_err = |e: zip::result::ZipError| RepositoryError::Unknown(e.to_string());
+        let io_err = |e: std::io::Error| RepositoryError::Unknown(e.to_string());
+
+        let nav = format!("<nav class=\"site\"><a href=\"../index.html\">← {}</a></nav>\n", escape(&kb.title));
+        for (file, title, body, comments) in &pages {
+            let mut resolver = ExportResolver { assets: &assets, asset_prefix: Some("../assets/"), pages: Some(&by_title) };
+            let page = html_page(title, body, comments, Some(&nav), &mut resolver);
+            zip.start_file(format!("pages/{}", file), options).map_err(zip_err)?;
+            zip.write_all(page.as_bytes()).map_err(io_err)?;
+        }
+
+        let mut index = String::new();
+        if let Some(description) = kb.description.as_deref().filter(|d| !d.is_empty()) {
+            index.push_str(&format!("{}\n\n", description));
+        }
+        for (file, title, _, _) in &pages {
+            index.push_str(&format!("- [{}](pages/{})\n", title.replace(['[', ']'], ""), file));
+        }
+        let mut resolver = ExportResolver { assets: &assets, asset_prefix: Some("assets/"), pages: None };
+        let index = html_page(&kb.title, &index, &[], None, &mut resolver);
+        zip.start_file("index.html", options).map_err(zip_err)?;
+        zip.write_all(index.as_bytes()).map_err(io_err)?;
+
+        for (id, asset) in &assets {
+            zip.start_file(format!("assets/{}.{}", id, extension(&asset.mime)), options).map_err(zip_err)?;
+            zip.write_all(&asset.data).map_err(io_err)?;
+        }
+
+        Ok(zip.finish().map_err(zip_err)?.into_inner())
+    }
 }
@@ -0,0 +1,246 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::domain::blocks::html::{self, escape, HtmlResolver};
+use crate::domain::blocks::parser::parse_markdown_to_blocks;
+use crate::domain::blocks::strategies::extract_text;
+use crate::domain::models::{Article, ContentBody, ContentItem, ContentStatus, KnowledgeBaseId, PermissionMode, UserId, Visibility};
+use crate::domain::ports::{ArticleRepository, KnowledgeBaseRepository, RepositoryError, UserRepository};
+use crate::infrastructure::services::export_service::asset_ids;
+
+mod tests;
+
+const FEED_ENTRIES: usize = 50;
+const SCAN_LIMIT: u64 = 200; // Candidates read before filtering and sorting by update time
+const SUMMARY_CHARS: usize = 400;
+
+pub struct FeedEntry {
+    pub id: Uuid,
+    pub title: String,
+    pub link: String,
+    pub author: Option<String>,
+    pub tags: Vec<String>,
+    pub published: DateTime<Utc>,
+    pub updated: DateTime<Utc>,
+    pub summary_html: String,
+}
+
+pub struct FeedMeta {
+    pub id: String,
+    pub title: String,
+    pub subtitle: Option<String>,
+    pub self_link: String,
+    pub alternate_link: String,
+    /// `updated` of a feed without entries.
+    pub fallback_updated: DateTime<Utc>,
+}
+
+pub struct FeedDocument {
+    pub xml: String,
+    pub updated: DateTime<Utc>,
+}
+
+/// Makes asset and relative URLs absolute: feed readers resolve nothing
+/// against the app. Wiki links have no public page and stay plain text.
+struct FeedResolver<'a> {
+    base: &'a str,
+}
+
+impl FeedResolver<'_> {
+    fn absolute(&self, url: &str) -> Option<String> {
+        if let Some(id) = asset_ids(url).into_iter().next() {
+            return Some(format!("{}/api/assets/{}", self.base, id));
+        }
+        let url = html::safe_url(url)?;
+        Some(if url.starts_with('/') { format!("{}{}", self.base, url) } else { url })
+    }
+}
+
+impl HtmlResolver for FeedResolver<'_> {
+    fn image_src(&mut self, src: &str) -> Option<String> {
+        self.absolute(src)
+    }
+
+    fn link_href(&mut self, href: &str) -> Option<String> {
+        self.absolute(href)
+    }
+}
+
+fn encode_segment(segment: &str) -> String {
+    segment.bytes().map(|b| {
+        if b.is_ascii_alphanumeric() || b"-_.~".contains(&b) { (b as char).to_string() } else { format!("%{:02X}", b) }
+    }).collect()
+}
+
+/// The leading blocks of an article, rendered, up to about `SUMMARY_CHARS` of text.
+pub fn summary_html(markdown: &str, base: &str) -> String {
+    let blocks = parse_markdown_to_blocks(Uuid::nil(), markdown);
+    let mut taken = Vec::new();
+    let mut chars = 0;
+    for block in blocks {
+        if chars >= SUMMARY_CHARS {
+            break;
+        }
+        chars += extract_text(&block.type_name, &block.payload).chars().count();
+        taken.push(block);
+    }
+    html::render_html(&taken, &mut FeedResolver { base })
+}
+
+pub fn render_atom(meta: &FeedMeta, entries: &[FeedEntry]) -> FeedDocument {
+    let updated = entries.iter().map(|e| e.updated).max().unwrap_or(meta.fallback_updated);
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<feed xmlns=\"http://www.w3.org/2005/Atom\">\n");
+    xml.push_str(&format!("<id>{}</id>\n<title>{}</title>\n", escape(&meta.id), escape(&meta.title)));
+    if let Some(subtitle) = meta.subtitle.as_deref().filter(|s| !s.is_empty()) {
+        xml.push_str(&format!("<subtitle>{}</subtitle>\n", escape(subtitle)));
+    }
+    xml.push_str(&format!(
+        "<updated>{}</updated>\n<link rel=\"self\" type=\"application/atom+xml\" href=\"{}\"/>\n<link rel=\"alternate\" type=\"text/html\" href=\"{}\"/>\n<generator>Aether</generator>\n",
+        updated.to_rfc3339(), escape(&meta.self_link), escape(&meta.alternate_link)
+    ));
+    for entry in entries {
+        let categories: String = entry.tags.iter().map(|t| format!("<category term=\"{}\"/>", escape(t))).collect();
+        xml.push_str(&format!(
+            "<entry>\n<id>urn:uuid:{}</id>\n<title>{}</title>\n<link rel=\"alternate\" type=\"text/html\" href=\"{}\"/>\n<published>{}</published>\n<updated>{}</updated>\n<author><name>{}</name></author>\n{}<summary type=\"html\">{}</summary>\n</entry>\n",
+            entry.id,
+            escape(&entry.title),
+            escape(&entry.link),
+            entry.published.to_rfc3339(),
+            entry.updated.to_rfc3339(),
+            escape(entry.author.as_deref().unwrap_or("Anonymous")),
+            categories,
+            escape(&entry.summary_html),
+        ));
+    }
+    xml.push_str("</feed>\n");
+    FeedDocument { xml, updated }
+}
+
+/// Atom feeds of public, published articles: per knowledge base, author and tag.
+pub struct FeedService {
+    article_repo: Arc<dyn ArticleRepository>,
+    kb_repo: Arc<dyn KnowledgeBaseRepository>,
+    user_repo: Arc<dyn UserRepository>,
+    base_url: Option<String>,
+}
+
+impl FeedService {
+    pub fn new(
+        article_repo: Arc<dyn ArticleRepository>,
+        kb_repo: Arc<dyn KnowledgeBaseRepository>,
+        user_repo: Arc<dyn UserRepository>,
+        base_url: Option<String>,
+    ) -> Self {
+        let base_url = base_url.map(|u| u.trim_end_matches('/').to_string());
+        Self { article_repo, kb_repo, user_repo, base_url }
+    }
+
+    /// The configured public URL of the app, if any.
+    pub fn base_url(&self) -> Option<&str> {
+        self.base_url.as_deref()
+    }
+
+    /// `base` is the absolute URL of the app (no trailing slash).
+    pub async fn knowledge_base_feed(&self, kb_id: Uuid, base: &str) -> Result<FeedDocument, RepositoryError> {
+        let kb = self.kb_repo.find_by_id(&KnowledgeBaseId(kb_id)).await?
+            .filter(|kb| kb.visibility == Visibility::Public)
+            .ok_or_else(|| RepositoryError::NotFound("Knowledge Base not found".to_string()))?;
+
+        let items = self.article_repo.list(None, None, Some(kb_id), None, None, SCAN_LIMIT, 0).await?;
+        let entries = self.entries(items, |_| true, base).await?;
+        let meta = FeedMeta {
+            id: format!("urn:uuid:{}", kb_id),
+            title: kb.title,
+            subtitle: kb.description,
+            self_link: format!("{}/api/feeds/kb/{}.atom", base, kb_id),
+            alternate_link: format!("{}/kb/{}/tree", base, kb_id),
+            fallback_updated: kb.updated_at,
+        };
+        Ok(render_atom(&meta, &entries))
+    }
+
+    pub async fn user_feed(&self, user_id: Uuid, base: &str) -> Result<FeedDocument, RepositoryError> {
+        let user = self.user_repo.find_by_id(&UserId(user_id)).await?
+            .ok_or_else(|| RepositoryError::NotFound("User not found".to_string()))?;
+
+        let items = self.article_repo.list(None, Some(UserId(user_id)), None, None, None, SCAN_LIMIT, 0).await?;
+        let entries = self.entries(items, |_| true, base).await?;
+        let name = user.display_name.unwrap_or(user.username);
+        let meta = FeedMeta {
+            id: format!("urn:uuid:{}", user_id),
+            title: name,
+            subtitle: user.bio,
+            self_link: format!("{}/api/feeds/user/{}.atom", base, user_id),
+            alternate_link: format!("{}/profile/{}", base, user_id),
+            fallback_updated: DateTime::<Utc>::UNIX_EPOCH,
+        };
+        Ok(render_atom(&meta, &entries))
+    }
+
+    pub async fn tag_feed(&self, tag: &str, base: &str) -> Result<FeedDocument, RepositoryError> {
+        let items = self.article_repo.list(None, None, None, Some(tag.to_string()), None, SCAN_LIMIT, 0).await?;
+        // The repository matches tags with LIKE; keep exact matches only
+        let entries = self.entries(items, |a| a.tags.iter().any(|t| t == tag), base).await?;
+        let self_link = format!("{}/api/feeds/tag/{}.atom", base, encode_segment(tag));
+        let meta = FeedMeta {
+            id: self_link.clone(),
+            title: format!("#{}", tag),
+            subtitle: None,
+            self_link,
+            alternate_link: base.to_string(),
+            fallback_updated: DateTime::<Utc>::UNIX_EPOCH,
+        };
+        Ok(render_atom(&meta, &entries))
+    }
+
+    /// Public, published articles outside private knowledge bases, most
+    /// recently updated first; the update time comes from the latest version.
+    async fn entries(&self, items: Vec<ContentItem>, keep: impl Fn(&Article) -> bool, base: &str) -> Result<Vec<FeedEntry>, RepositoryError> {
+        let mut public_kbs: HashMap<Uuid, bool> = HashMap::new();
+        let mut entries = Vec::new();
+        for item in items {
+            let ContentItem::Article(article) = item else { continue };
+            if article.status != ContentStatus::Published || article.node.permission_mode != PermissionMode::Public || !keep(&article) {
+                continue;
+            }
+            if let Some(kb_id) = article.node.knowledge_base_id {
+                let public = match public_kbs.get(&kb_id) {
+                    Some(public) => *public,
+                    None => {
+                        let public = self.kb_repo.find_by_id(&KnowledgeBaseId(kb_id)).await?
+                            .is_some_and(|kb| kb.visibility == Visibility::Public);
+                        public_kbs.insert(kb_id, public);
+                        public
+                    }
+                };
+                if !public {
+                    continue;
+                }
+            }
+
+            let updated = self.article_repo.get_history(&article.node.id).await?
+                .first()
+                .map(|v| v.created_at)
+                .unwrap_or(article.node.updated_at);
+            let summary_html = match &article.body {
+                ContentBody::Markdown(markdown) => summary_html(markdown, base),
+                _ => String::new(),
+            };
+            entries.push(FeedEntry {
+                id: article.node.id,
+                link: format!("{}/article/{}", base, article.node.id),
+                title: article.node.title,
+                author: article.author_name,
+                tags: article.tags,
+                published: article.node.created_at,
+                updated,
+                summary_html,
+            });
+        }
+        entries.sort_by(|a, b| b.updated.cmp(&a.updated));
+        entries.truncate(FEED_ENTRIES);
+        Ok(entries)
+    }
+}
@@ -0,0 +1,71 @@
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, Utc};
+    use quick_xml::events::Event;
+    use quick_xml::Reader;
+    use uuid::Uuid;
+    use crate::infrastructure::services::feeds::{render_atom, summary_html, FeedEntry, FeedMeta};
+
+    #[test]
+    fn test_summary_html() {
+        let asset = Uuid::new_v4();
+        let markdown = format!(
+            "Intro with a [link](/article/x) and [[Wiki Page]].\n\n![fig](/api/assets/{})\n\n{}\n\nNever reached.\n",
+            asset,
+            "long ".repeat(100)
+        );
+        let summary = summary_html(&markdown, "https://aether.example");
+        assert!(summary.contains("href=\"https://aether.example/article/x\""));
+        assert!(summary.contains(&format!("src=\"https://aether.example/api/assets/{}\"", asset)));
+        assert!(summary.contains("Wiki Page"));
+        assert!(summary.contains("long long"));
+        assert!(!summary.contains("Never reached"));
+    }
+
+    #[test]
+    fn test_atom_feed() {
+        let now = Utc::now();
+        let entry = |title: &str, updated| FeedEntry {
+            id: Uuid::new_v4(),
+            title: title.to_string(),
+            link: format!("https://aether.example/article/{}", title),
+            author: None,
+            tags: vec!["r&d".to_string()],
+            published: now - Duration::days(10),
+            updated,
+            summary_html: "<p>Hello <em>world</em></p>".to_string(),
+        };
+        let meta = FeedMeta {
+            id: "urn:uuid:00000000-0000-0000-0000-000000000000".to_string(),
+            title: "Notes <draft>".to_string(),
+            subtitle: Some(String::new()),
+            self_link: "https://aether.example/api/feeds/tag/x.atom".to_string(),
+            alternate_link: "https://aether.example".to_string(),
+            fallback_updated: now - Duration::days(100),
+        };
+
+        let newest = now - Duration::hours(1);
+        let feed = render_atom(&meta, &[entry("a", newest), entry("b", now - Duration::days(2))]);
+        assert_eq!(feed.updated, newest);
+
+        // Well-formed, with the summary as escaped HTML
+        let mut reader = Reader::from_str(&feed.xml);
+        let mut entries = 0;
+        loop {
+            match reader.read_event().unwrap_or_else(|e| panic!("malformed feed: {}\n{}", e, feed.xml)) {
+                Event::Start(e) if e.name().as_ref() == b"entry" => entries += 1,
+                Event::Eof => break,
+                _ => {}
+            }
+        }
+        assert_eq!(entries, 2);
+        assert!(feed.xml.contains("<summary type=\"html\">&lt;p&gt;Hello &lt;em&gt;world&lt;/em&gt;&lt;/p&gt;</summary>"));
+        assert!(feed.xml.contains("<title>Notes &lt;draft&gt;</title>"));
+        assert!(!feed.xml.contains("<subtitle>"));
+        assert!(feed.xml.contains("<category term=\"r&amp;d\"/>"));
+        assert!(feed.xml.contains("<author><name>Anonymous</name></author>"));
+
+        let empty = render_atom(&meta, &[]);
+        assert_eq!(empty.updated, meta.fallback_updated);
+    }
+}
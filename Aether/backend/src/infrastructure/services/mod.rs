@@ -3,5 +3,9 @@ pub mod arxiv;
 pub mod rss;
 pub mod asset_manager;
 pub mod backup_service;
+pub mod backup;
+pub mod collab_service;
 pub mod portability_service;
-pub mod portability;
\ No newline at end of file
+pub mod portability;
+pub mod site;
+pub mod feeds;
@@ -60,7 +60,7 @@ impl PortabilityProvider for DefaultPortabilityProvider {
             error: None,
         }).await;
 
-        let filename = self.backup_service.create_backup(kb_id, user_id).await
+        let filename = self.backup_service.create_backup(kb_id, user_id, None).await
             .map_err(|e| e.to_string())?;
 
         let file_path = self.backup_service.get_backup_path(&filename);
@@ -81,6 +81,7 @@ impl PortabilityProvider for DefaultPortabilityProvider {
             total_items: 0,
             sections: vec![],
             conflicts: vec![],
+            unresolved_links: vec![],
         })
     }
 
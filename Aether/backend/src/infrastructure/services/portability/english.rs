@@ -259,6 +259,7 @@ impl PortabilityProvider for EnglishPortabilityProvider {
             total_items: 0,
             sections: vec![],
             conflicts: vec![],
+            unresolved_links: vec![],
         })
     }
 
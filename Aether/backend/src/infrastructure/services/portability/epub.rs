@@ -0,0 +1,437 @@
+use async_trait::async_trait;
+use std::collections::{HashMap, HashSet};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Arc;
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+use tokio::sync::mpsc::Sender;
+
+use crate::domain::portability::ports::PortabilityProvider;
+use crate::domain::portability::models::{ExportSummary, ExportSection, ImportSummary, ProgressEvent};
+use crate::domain::ports::{ArticleRepository, KnowledgeBaseRepository, UserRepository};
+use crate::domain::models::{ContentBody, ContentItem, KnowledgeBaseId, NodeType, UserId};
+use crate::domain::blocks::html::{escape, render_html, HtmlResolver};
+use crate::domain::blocks::parser::parse_markdown_to_blocks;
+use crate::infrastructure::services::asset_manager::AssetManager;
+use crate::infrastructure::services::export_service::{asset_ids, extension};
+
+/// Image types EPUB reading systems must support.
+const EPUB_IMAGE_TYPES: &[&str] = &["image/png", "image/jpeg", "image/gif", "image/svg+xml", "image/webp"];
+
+const STYLE: &str = "body { font-family: serif; line-height: 1.5; }\nh1, h2, h3 { font-family: sans-serif; }\npre { white-space: pre-wrap; font-size: 0.85em; }\nimg { max-width: 100%; }\nfigure { margin: 1em 0; text-align: center; }\n.math-block { margin: 1em 0; }\n";
+
+pub struct EpubImage {
+    pub id: Uuid,
+    pub media_type: String,
+    pub data: Vec<u8>,
+}
+
+impl EpubImage {
+    fn href(&self) -> String {
+        format!("images/{}.{}", self.id, extension(&self.media_type))
+    }
+}
+
+/// A node of the knowledge base: an article, or a folder when `markdown` is `None`.
+pub struct SourceNode {
+    pub id: Uuid,
+    pub parent_id: Option<Uuid>,
+    pub title: String,
+    pub markdown: Option<String>,
+}
+
+pub struct EpubMetadata {
+    pub identifier: Uuid,
+    pub title: String,
+    pub description: Option<String>,
+    pub author: String,
+    pub language: String,
+    pub modified: DateTime<Utc>,
+    pub cover: Option<Uuid>, // One of the images
+}
+
+pub struct TocEntry {
+    pub title: String,
+    pub file: Option<String>, // Folders have no page
+    pub children: Vec<TocEntry>,
+}
+
+pub struct Chapter {
+    pub file: String,
+    pub title: String,
+    pub xhtml: String,
+}
+
+pub struct EpubBook {
+    pub metadata: EpubMetadata,
+    pub toc: Vec<TocEntry>,
+    pub chapters: Vec<Chapter>, // Reading order
+    pub images: Vec<EpubImage>,
+}
+
+/// Points `[[asset:uuid]]` images at the packaged files and wiki links at chapters.
+/// Images that are not in the package are dropped: EPUB content must be local.
+struct ChapterResolver<'a> {
+    images: &'a HashMap<Uuid, &'a EpubImage>,
+    chapters: &'a HashMap<String, String>, // Lowercased title -> file
+}
+
+impl HtmlResolver for ChapterResolver<'_> {
+    fn image_src(&mut self, src: &str) -> Option<String> {
+        let image = asset_ids(src).into_iter().next().and_then(|id| self.images.get(&id))?;
+        Some(format!("../{}", image.href()))
+    }
+
+    fn wiki_href(&self, title: &str) -> Option<String> {
+        self.chapters.get(&title.to_lowercase()).cloned()
+    }
+}
+
+/// Orders the nodes into a table of contents following `parent_id` (siblings
+/// keep their input order) and renders every article to an XHTML chapter.
+pub fn assemble_book(metadata: EpubMetadata, nodes: Vec<SourceNode>, images: Vec<EpubImage>) -> EpubBook {
+    let ids: HashSet<Uuid> = nodes.iter().map(|n| n.id).collect();
+    let mut children: HashMap<Option<Uuid>, Vec<&SourceNode>> = HashMap::new();
+    for node in &nodes {
+        let parent = node.parent_id.filter(|p| ids.contains(p) && *p != node.id);
+        children.entry(parent).or_default().push(node);
+    }
+
+    // 1. Reading order: depth-first, a parent before its children
+    let mut order: Vec<&SourceNode> = Vec::new();
+    let mut visited = HashSet::new();
+    fn walk<'a>(parent: Option<Uuid>, children: &HashMap<Option<Uuid>, Vec<&'a SourceNode>>, visited: &mut HashSet<Uuid>, order: &mut Vec<&'a SourceNode>) {
+        for node in children.get(&parent).into_iter().flatten() {
+            if visited.insert(node.id) {
+                order.push(node);
+                walk(Some(node.id), children, visited, order);
+            }
+        }
+    }
+    walk(None, &children, &mut visited, &mut order);
+
+    let files: HashMap<Uuid, String> = order.iter()
+        .filter(|n| n.markdown.is_some())
+        .enumerate()
+        .map(|(i, n)| (n.id, format!("chapter-{:03}.xhtml", i + 1)))
+        .collect();
+    let by_title: HashMap<String, String> = order.iter()
+        .filter_map(|n| files.get(&n.id).map(|f| (n.title.to_lowercase(), f.clone())))
+        .collect();
+
+    // 2. Chapters
+    let image_map: HashMap<Uuid, &EpubImage> = images.iter().map(|i| (i.id, i)).collect();
+    let mut resolver = ChapterResolver { images: &image_map, chapters: &by_title };
+    let chapters = order.iter().filter_map(|node| {
+        let markdown = node.markdown.as_deref()?;
+        let body = render_html(&parse_markdown_to_blocks(node.id, markdown), &mut resolver);
+        Some(Chapter {
+            file: files[&node.id].clone(),
+            title: node.title.clone(),
+            xhtml: chapter_xhtml(&node.title, &body, &metadata.language),
+        })
+    }).collect();
+
+    // 3. Table of contents, without folders that hold no article
+    fn toc(parent: Option<Uuid>, children: &HashMap<Option<Uuid>, Vec<&SourceNode>>, files: &HashMap<Uuid, String>, seen: &mut HashSet<Uuid>) -> Vec<TocEntry> {
+        children.get(&parent).into_iter().flatten().filter_map(|node| {
+            if !seen.insert(node.id) {
+                return None;
+            }
+            let entry = TocEntry {
+                title: node.title.clone(),
+                file: files.get(&node.id).map(|f| format!("text/{}", f)),
+                children: toc(Some(node.id), children, files, seen),
+            };
+            (entry.file.is_some() || !entry.children.is_empty()).then_some(entry)
+        }).collect()
+    }
+    let toc = toc(None, &children, &files, &mut HashSet::new());
+
+    EpubBook { metadata, toc, chapters, images }
+}
+
+fn chapter_xhtml(title: &str, body: &str, language: &str) -> String {
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<!DOCTYPE html>\n<html xmlns=\"http://www.w3.org/1999/xhtml\" xmlns:epub=\"http://www.idpf.org/2007/ops\" xml:lang=\"{lang}\" lang=\"{lang}\">\n<head>\n<meta charset=\"UTF-8\" />\n<title>{title}</title>\n<link rel=\"stylesheet\" type=\"text/css\" href=\"../style.css\" />\n</head>\n<body>\n<section epub:type=\"chapter\">\n<h1>{title}</h1>\n{body}\n</section>\n</body>\n</html>\n",
+        lang = escape(language),
+        title = escape(title),
+    )
+}
+
+fn nav_list(entries: &[TocEntry]) -> String {
+    let items: Vec<String> = entries.iter().map(|e| {
+        let label = match &e.file {
+            Some(file) => format!("<a href=\"{}\">{}</a>", escape(file), escape(&e.title)),
+            None => format!("<span>{}</span>", escape(&e.title)),
+        };
+        if e.children.is_empty() {
+            format!("<li>{}</li>", label)
+        } else {
+            format!("<li>{}\n{}\n</li>", label, nav_list(&e.children))
+        }
+    }).collect();
+    format!("<ol>\n{}\n</ol>", items.join("\n"))
+}
+
+fn nav_xhtml(book: &EpubBook) -> String {
+    let toc = if book.toc.is_empty() { "<ol>\n<li><span>Empty</span></li>\n</ol>".to_string() } else { nav_list(&book.toc) };
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<!DOCTYPE html>\n<html xmlns=\"http://www.w3.org/1999/xhtml\" xmlns:epub=\"http://www.idpf.org/2007/ops\" xml:lang=\"{lang}\" lang=\"{lang}\">\n<head>\n<meta charset=\"UTF-8\" />\n<title>{title}</title>\n</head>\n<body>\n<nav epub:type=\"toc\" id=\"toc\">\n<h1>{title}</h1>\n{toc}\n</nav>\n</body>\n</html>\n",
+        lang = escape(&book.metadata.language),
+        title = escape(&book.metadata.title),
+    )
+}
+
+fn package_opf(book: &EpubBook) -> String {
+    let meta = &book.metadata;
+    let mut metadata = vec![
+        format!("<dc:identifier id=\"book-id\">urn:uuid:{}</dc:identifier>", meta.identifier),
+        format!("<dc:title>{}</dc:title>", escape(&meta.title)),
+        format!("<dc:language>{}</dc:language>", escape(&meta.language)),
+        format!("<dc:creator>{}</dc:creator>", escape(&meta.author)),
+    ];
+    if let Some(description) = meta.description.as_deref().filter(|d| !d.is_empty()) {
+        metadata.push(format!("<dc:description>{}</dc:description>", escape(description)));
+    }
+    metadata.push(format!("<meta property=\"dcterms:modified\">{}</meta>", meta.modified.format("%Y-%m-%dT%H:%M:%SZ")));
+
+    let mut manifest = vec![
+        "<item id=\"nav\" href=\"nav.xhtml\" media-type=\"application/xhtml+xml\" properties=\"nav\" />".to_string(),
+        "<item id=\"style\" href=\"style.css\" media-type=\"text/css\" />".to_string(),
+    ];
+    let mut spine = vec!["<itemref idref=\"nav\" />".to_string()];
+    for (i, chapter) in book.chapters.iter().enumerate() {
+        manifest.push(format!("<item id=\"chapter-{}\" href=\"text/{}\" media-type=\"application/xhtml+xml\" />", i + 1, chapter.file));
+        spine.push(format!("<itemref idref=\"chapter-{}\" />", i + 1));
+    }
+    for image in &book.images {
+        let properties = if meta.cover == Some(image.id) { " properties=\"cover-image\"" } else { "" };
+        manifest.push(format!(
+            "<item id=\"img-{}\" href=\"{}\" media-type=\"{}\"{} />",
+            image.id, image.href(), escape(&image.media_type), properties
+        ));
+    }
+
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<package xmlns=\"http://www.idpf.org/2007/opf\" version=\"3.0\" unique-identifier=\"book-id\" xml:lang=\"{}\">\n<metadata xmlns:dc=\"http://purl.org/dc/elements/1.1/\">\n{}\n</metadata>\n<manifest>\n{}\n</manifest>\n<spine>\n{}\n</spine>\n</package>\n",
+        escape(&meta.language),
+        metadata.join("\n"),
+        manifest.join("\n"),
+        spine.join("\n"),
+    )
+}
+
+const CONTAINER_XML: &str = "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<container version=\"1.0\" xmlns=\"urn:oasis:names:tc:opendocument:xmlns:container\">\n<rootfiles>\n<rootfile full-path=\"OEBPS/content.opf\" media-type=\"application/oebps-package+xml\" />\n</rootfiles>\n</container>\n";
+
+/// Packages the book. `mimetype` comes first and uncompressed, as the OCF spec requires.
+pub fn write_epub<W: Write + std::io::Seek>(book: &EpubBook, writer: W) -> Result<W, String> {
+    let mut zip = zip::ZipWriter::new(writer);
+    let stored = zip::write::FileOptions::<()>::default()
+        .compression_method(zip::CompressionMethod::Stored);
+    let deflated = zip::write::FileOptions::<()>::default()
+        .compression_method(zip::CompressionMethod::Deflated);
+
+    zip.start_file("mimetype", stored).map_err(|e| e.to_string())?;
+    zip.write_all(b"application/epub+zip").map_err(|e| e.to_string())?;
+
+    let mut files: Vec<(String, Vec<u8>)> = vec![
+        ("META-INF/container.xml".to_string(), CONTAINER_XML.as_bytes().to_vec()),
+        ("OEBPS/content.opf".to_string(), package_opf(book).into_bytes()),
+        ("OEBPS/nav.xhtml".to_string(), nav_xhtml(book).into_bytes()),
+        ("OEBPS/style.css".to_string(), STYLE.as_bytes().to_vec()),
+    ];
+    files.extend(book.chapters.iter().map(|c| (format!("OEBPS/text/{}", c.file), c.xhtml.clone().into_bytes())));
+    for (name, data) in files {
+        zip.start_file(name, deflated).map_err(|e| e.to_string())?;
+        zip.write_all(&data).map_err(|e| e.to_string())?;
+    }
+    for image in &book.images {
+        // Already compressed
+        zip.start_file(format!("OEBPS/{}", image.href()), stored).map_err(|e| e.to_string())?;
+        zip.write_all(&image.data).map_err(|e| e.to_string())?;
+    }
+
+    zip.finish().map_err(|e| e.to_string())
+}
+
+pub struct EpubPortabilityProvider {
+    article_repo: Arc<dyn ArticleRepository>,
+    kb_repo: Arc<dyn KnowledgeBaseRepository>,
+    user_repo: Arc<dyn UserRepository>,
+    asset_manager: Arc<AssetManager>,
+}
+
+impl EpubPortabilityProvider {
+    pub fn new(
+        article_repo: Arc<dyn ArticleRepository>,
+        kb_repo: Arc<dyn KnowledgeBaseRepository>,
+        user_repo: Arc<dyn UserRepository>,
+        asset_manager: Arc<AssetManager>,
+    ) -> Self {
+        Self { article_repo, kb_repo, user_repo, asset_manager }
+    }
+
+    async fn load_nodes(&self, kb_id: Uuid, author_id: Uuid) -> Result<Vec<SourceNode>, String> {
+        let mut items = self.article_repo.list(
+            Some(UserId(author_id)), None, Some(kb_id), None, None, 100000, 0
+        ).await.map_err(|e| e.to_string())?;
+        items.sort_by_key(|item| match item {
+            ContentItem::Article(a) => a.node.created_at,
+            ContentItem::Node(n) => n.created_at,
+        });
+
+        Ok(items.into_iter().filter_map(|item| match item {
+            ContentItem::Article(a) => {
+                let markdown = match a.body {
+                    ContentBody::Markdown(s) => s,
+                    _ => String::new(),
+                };
+                Some(SourceNode { id: a.node.id, parent_id: a.node.parent_id, title: a.node.title, markdown: Some(markdown) })
+            }
+            ContentItem::Node(n) if n.r#type == NodeType::Folder => {
+                Some(SourceNode { id: n.id, parent_id: n.parent_id, title: n.title, markdown: None })
+            }
+            ContentItem::Node(_) => None,
+        }).collect())
+    }
+}
+
+#[async_trait]
+impl PortabilityProvider for EpubPortabilityProvider {
+    fn provider_id(&self) -> String {
+        "epub".to_string()
+    }
+
+    async fn analyze_export(&self, kb_id: Uuid) -> Result<ExportSummary, String> {
+        let kb = self.kb_repo.find_by_id(&KnowledgeBaseId(kb_id))
+            .await.map_err(|e| e.to_string())?
+            .ok_or("KB not found")?;
+        let nodes = self.load_nodes(kb_id, kb.author_id).await?;
+
+        let articles: Vec<&SourceNode> = nodes.iter().filter(|n| n.markdown.is_some()).collect();
+        let folders = nodes.len() - articles.len();
+        let images: HashSet<Uuid> = articles.iter().flat_map(|n| asset_ids(n.markdown.as_deref().unwrap_or(""))).collect();
+
+        Ok(ExportSummary {
+            total_items: articles.len() + images.len(),
+            estimated_size: format!("{:.1} KB", (articles.len() * 4096) as f64 / 1024.0),
+            sections: vec![
+                ExportSection {
+                    name: "Chapters".to_string(),
+                    count: articles.len(),
+                    details: format!("Articles as XHTML, in a table of contents of {} folders", folders),
+                },
+                ExportSection {
+                    name: "Images".to_string(),
+                    count: images.len(),
+                    details: "Referenced assets (PNG, JPEG, GIF, SVG, WebP)".to_string(),
+                },
+            ],
+        })
+    }
+
+    async fn export(&self, kb_id: Uuid, user_id: Uuid, task_id: Uuid, progress: Sender<ProgressEvent>) -> Result<PathBuf, String> {
+        let _ = progress.send(ProgressEvent {
+            task_id,
+            stage: "Initialization".to_string(),
+            percent: 0,
+            message: "Starting EPUB export...".to_string(),
+            error: None,
+        }).await;
+
+        // 1. Fetch Data
+        let kb = self.kb_repo.find_by_id(&KnowledgeBaseId(kb_id))
+            .await.map_err(|e| e.to_string())?
+            .ok_or("KB not found")?;
+        let author = self.user_repo.find_by_id(&UserId(kb.author_id)).await.map_err(|e| e.to_string())?
+            .map(|u| u.display_name.filter(|n| !n.is_empty()).unwrap_or(u.username))
+            .unwrap_or_else(|| "Unknown".to_string());
+        let nodes = self.load_nodes(kb_id, kb.author_id).await?;
+
+        // 2. Images: the cover and every `[[asset:uuid]]` the articles use
+        let cover_id = kb.cover_image.as_deref().and_then(|c| asset_ids(c).into_iter().next());
+        let mut wanted: Vec<Uuid> = cover_id.into_iter().collect();
+        for node in &nodes {
+            for id in asset_ids(node.markdown.as_deref().unwrap_or("")) {
+                if !wanted.contains(&id) {
+                    wanted.push(id);
+                }
+            }
+        }
+
+        let total_images = wanted.len();
+        let mut images = Vec::new();
+        for (i, id) in wanted.into_iter().enumerate() {
+            match self.asset_manager.get_asset_file(id, None, user_id).await {
+                Ok((path, mime)) if EPUB_IMAGE_TYPES.contains(&mime.as_str()) => {
+                    let data = tokio::fs::read(&path).await.map_err(|e| e.to_string())?;
+                    images.push(EpubImage { id, media_type: mime, data });
+                }
+                Ok((_, mime)) => tracing::warn!("Skipping asset {} in EPUB export: unsupported type {}", id, mime),
+                Err(e) => tracing::warn!("Skipping asset {} in EPUB export: {}", id, e),
+            }
+
+            let percent = 10 + ((i + 1) as f32 / total_images as f32 * 40.0) as u8;
+            let _ = progress.send(ProgressEvent {
+                task_id,
+                stage: "Collecting Images".to_string(),
+                percent,
+                message: format!("Loading image {}/{}", i + 1, total_images),
+                error: None,
+            }).await;
+        }
+
+        // 3. Render chapters
+        let _ = progress.send(ProgressEvent {
+            task_id,
+            stage: "Rendering".to_string(),
+            percent: 60,
+            message: format!("Rendering {} nodes to XHTML...", nodes.len()),
+            error: None,
+        }).await;
+
+        let metadata = EpubMetadata {
+            identifier: kb.id.0,
+            title: kb.title.clone(),
+            description: kb.description.clone(),
+            author,
+            language: "en".to_string(),
+            modified: Utc::now(),
+            cover: cover_id.filter(|id| images.iter().any(|i| i.id == *id)),
+        };
+        let book = assemble_book(metadata, nodes, images);
+
+        // 4. Package
+        let _ = progress.send(ProgressEvent {
+            task_id,
+            stage: "Finalizing".to_string(),
+            percent: 90,
+            message: "Packaging EPUB...".to_string(),
+            error: None,
+        }).await;
+
+        let file_path = std::env::temp_dir().join(format!("epub_export_{}_{}.epub", kb_id, Utc::now().timestamp()));
+        let file = std::fs::File::create(&file_path).map_err(|e| e.to_string())?;
+        write_epub(&book, file)?;
+
+        let _ = progress.send(ProgressEvent {
+            task_id,
+            stage: "Completed".to_string(),
+            percent: 100,
+            message: "Export ready for download.".to_string(),
+            error: None,
+        }).await;
+
+        Ok(file_path)
+    }
+
+    async fn analyze_import(&self, _file_path: PathBuf) -> Result<ImportSummary, String> {
+        Err("EPUB import is not supported".to_string())
+    }
+
+    async fn import(&self, _kb_id: Uuid, _file_path: PathBuf, _task_id: Uuid, _progress: Sender<ProgressEvent>) -> Result<(), String> {
+        Err("EPUB import is not supported".to_string())
+    }
+}
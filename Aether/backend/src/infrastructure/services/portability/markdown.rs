@@ -0,0 +1,270 @@
+use async_trait::async_trait;
+use std::collections::{HashMap, HashSet};
+use std::io::Cursor;
+use std::path::PathBuf;
+use std::sync::Arc;
+use uuid::Uuid;
+use tokio::sync::mpsc::Sender;
+
+use crate::domain::portability::ports::PortabilityProvider;
+use crate::domain::portability::models::{ExportSummary, ImportSection, ImportSummary, ProgressEvent};
+use crate::domain::ports::{ArticleRepository, KnowledgeBaseRepository, NodeRepository};
+use crate::infrastructure::services::asset_manager::AssetManager;
+use crate::infrastructure::services::portability::vault::{
+    file_name, finish, is_note, parent_dir, plan_vault, read_archive, read_zip, report, stem, PreparedVault, VaultFile,
+    VaultImporter, VaultPlan, MAX_EXPANDED_BYTES,
+};
+
+// Notion exports are Markdown trees with a few conventions of their own:
+// names carry the page id, a page's subpages live in a folder named like the
+// page, and databases are exported as CSV next to a folder of row pages.
+
+/// Page properties carried over as front matter; the others stay in the body.
+const PROPERTIES: &[(&str, &str)] = &[("tags", "tags"), ("tag", "tags"), ("category", "category"), ("status", "status")];
+
+/// `Page 1a2b…` (32 hex digits) -> `Page`.
+pub fn strip_notion_id(name: &str) -> &str {
+    match name.rsplit_once(' ') {
+        Some((base, id)) if !base.is_empty() && id.len() == 32 && id.bytes().all(|b| b.is_ascii_hexdigit()) => base,
+        _ => name,
+    }
+}
+
+fn has_notion_id(path: &str) -> bool {
+    let name = stem(path);
+    strip_notion_id(name).len() != name.len()
+}
+
+fn encode_path(path: &str) -> String {
+    path.bytes().map(|b| {
+        if b.is_ascii_alphanumeric() || b"-_.~/".contains(&b) { (b as char).to_string() } else { format!("%{:02X}", b) }
+    }).collect()
+}
+
+fn yaml_scalar(value: &str) -> String {
+    format!("\"{}\"", value.replace('"', "'"))
+}
+
+/// Turns the header of an exported Notion page (`# Title`, then a block of
+/// `Property: value` lines) into front matter, so titles lose the page id
+/// and tags, category and status become the article's.
+pub fn notion_front_matter(text: &str) -> String {
+    if text.starts_with("---") {
+        return text.to_string();
+    }
+    let mut lines = text.lines().peekable();
+    let Some(title) = lines.peek().and_then(|l| l.strip_prefix("# ")).map(str::trim) else {
+        return text.to_string();
+    };
+    let mut yaml = vec![format!("title: {}", yaml_scalar(title))];
+    lines.next();
+    while lines.peek().is_some_and(|l| l.trim().is_empty()) {
+        lines.next();
+    }
+
+    // The property block runs up to the first blank line
+    let block: Vec<&str> = lines.clone().take_while(|l| !l.trim().is_empty()).collect();
+    let is_property = |line: &str| line.split_once(": ").is_some_and(|(key, _)| !key.is_empty() && key.len() <= 40 && !key.starts_with(['#', '!', '[', '-', '*', '>', '|']));
+    let mut kept = Vec::new();
+    if !block.is_empty() && block.iter().all(|l| is_property(l)) {
+        for line in &block {
+            lines.next();
+            let (key, value) = line.split_once(": ").unwrap_or_default();
+            match PROPERTIES.iter().find(|(name, _)| key.trim().eq_ignore_ascii_case(name)) {
+                Some((_, "tags")) => {
+                    let tags: Vec<String> = value.split(',').map(|t| yaml_scalar(t.trim())).collect();
+                    yaml.push(format!("tags: [{}]", tags.join(", ")));
+                }
+                Some((_, field)) => yaml.push(format!("{}: {}", field, yaml_scalar(value.trim()))),
+                None => kept.push(*line),
+            }
+        }
+    }
+
+    let mut out = format!("---\n{}\n---\n", yaml.join("\n"));
+    if !kept.is_empty() {
+        out.push_str(&kept.join("  \n"));
+        out.push_str("\n\n");
+    }
+    let rest: Vec<&str> = lines.collect();
+    out.push_str(rest.join("\n").trim_start_matches('\n'));
+    out.push('\n');
+    out
+}
+
+fn escape_cell(cell: &str) -> String {
+    cell.replace('|', "\\|").replace(['\r', '\n'], " ").trim().to_string()
+}
+
+/// A Notion database as a Markdown table. The first column names the row;
+/// when the row has its page in `rows_dir`, the name links to it.
+pub fn database_table(csv_data: &[u8], rows_dir: &str, row_pages: &HashMap<String, String>) -> Result<String, String> {
+    let mut reader = csv::ReaderBuilder::new().flexible(true).from_reader(csv_data);
+    let headers: Vec<String> = reader.headers().map_err(|e| e.to_string())?
+        .iter()
+        .map(|h| escape_cell(h.trim_start_matches('\u{feff}')))
+        .collect();
+    if headers.is_empty() {
+        return Ok(String::new());
+    }
+
+    let mut table = format!("| {} |\n|{}\n", headers.join(" | "), " --- |".repeat(headers.len()));
+    for record in reader.records() {
+        let record = record.map_err(|e| e.to_string())?;
+        let mut cells: Vec<String> = (0..headers.len()).map(|i| escape_cell(record.get(i).unwrap_or(""))).collect();
+        if let Some(page) = row_pages.get(&cells[0].to_lowercase()) {
+            let link = format!("{}/{}", file_name(rows_dir), file_name(page));
+            cells[0] = format!("[{}]({})", cells[0], encode_path(&link));
+        }
+        table.push_str(&format!("| {} |\n", cells.join(" | ")));
+    }
+    Ok(table)
+}
+
+/// Rewrites Notion conventions so `plan_vault` sees a plain Markdown tree:
+/// page headers become front matter and each database CSV a table note
+/// (`Database.md`, beside its rows). Returns the number of databases.
+pub fn normalize_notion(files: &mut Vec<VaultFile>) -> Result<usize, String> {
+    for file in files.iter_mut().filter(|f| is_note(&f.path) && has_notion_id(&f.path)) {
+        file.data = notion_front_matter(&String::from_utf8_lossy(&file.data)).into_bytes();
+    }
+
+    // `Database_all.csv` (every row) is preferred over the filtered view
+    let csv_paths: Vec<String> = files.iter().map(|f| f.path.clone()).filter(|p| p.to_lowercase().ends_with(".csv")).collect();
+    let mut databases: HashMap<String, String> = HashMap::new();
+    for path in &csv_paths {
+        let base = &path[..path.len() - 4];
+        let (base, all) = match base.strip_suffix("_all") {
+            Some(base) => (base, true),
+            None => (base, false),
+        };
+        if all || !databases.contains_key(base) {
+            databases.insert(base.to_string(), path.clone());
+        }
+    }
+
+    for (base, csv_path) in &databases {
+        let row_pages: HashMap<String, String> = files.iter()
+            .filter(|f| is_note(&f.path) && parent_dir(&f.path) == Some(base.as_str()))
+            .map(|f| (strip_notion_id(stem(&f.path)).to_lowercase(), f.path.clone()))
+            .collect();
+        let data = &files.iter().find(|f| &f.path == csv_path).ok_or("Missing database file")?.data;
+        let table = database_table(data, base, &row_pages)?;
+
+        // An inline database belongs to the page of the same name
+        let note_path = format!("{}.md", base);
+        match files.iter_mut().find(|f| f.path == note_path) {
+            Some(page) => {
+                page.data.extend_from_slice(b"\n\n");
+                page.data.extend_from_slice(table.as_bytes());
+            }
+            None => {
+                let title = strip_notion_id(file_name(base));
+                let text = format!("---\ntitle: {}\n---\n{}", yaml_scalar(title), table);
+                files.push(VaultFile { path: note_path, data: text.into_bytes() });
+            }
+        }
+    }
+
+    files.retain(|f| !csv_paths.contains(&f.path));
+    Ok(databases.len())
+}
+
+/// Strips page ids from titles and moves each page into the folder of its
+/// subpages (`Page.md` next to `Page/`), where Notion shows them.
+pub fn adjust_plan(plan: &mut VaultPlan) {
+    let folders: HashSet<String> = plan.folders.iter().map(|f| f.path.clone()).collect();
+    for folder in &mut plan.folders {
+        folder.title = strip_notion_id(&folder.title).to_string();
+    }
+    for note in &mut plan.notes {
+        if note.front.title.is_none() {
+            note.title = strip_notion_id(&note.title).to_string();
+        }
+        let own = &note.path[..note.path.len() - 3];
+        if folders.contains(own) {
+            note.folder = Some(own.to_string());
+        }
+    }
+}
+
+/// Imports zipped Markdown trees: loose Markdown folders and Notion exports
+/// (Markdown & CSV). Folders become `Folder` nodes, pages articles, databases
+/// tables, and referenced images and files are uploaded as assets.
+pub struct MarkdownPortabilityProvider {
+    importer: VaultImporter,
+    id_override: Option<String>,
+}
+
+impl MarkdownPortabilityProvider {
+    pub fn new(
+        article_repo: Arc<dyn ArticleRepository>,
+        node_repo: Arc<dyn NodeRepository>,
+        kb_repo: Arc<dyn KnowledgeBaseRepository>,
+        asset_manager: Arc<AssetManager>,
+    ) -> Self {
+        Self { importer: VaultImporter::new(article_repo, node_repo, kb_repo, asset_manager), id_override: None }
+    }
+
+    pub fn with_id(mut self, id: String) -> Self {
+        self.id_override = Some(id);
+        self
+    }
+
+    /// Returns the prepared tree and the number of databases.
+    async fn prepare(&self, file_path: PathBuf) -> Result<(PreparedVault, usize), String> {
+        let (files, databases) = tokio::task::spawn_blocking(move || -> Result<_, String> {
+            let mut files = read_archive(&file_path)?;
+            // Large Notion exports come as a zip of zips, expanding within one budget together
+            if !files.is_empty() && files.iter().all(|f| f.path.to_lowercase().ends_with(".zip")) {
+                let mut budget = MAX_EXPANDED_BYTES;
+                let mut inner = Vec::new();
+                for file in files {
+                    inner.extend(read_zip(Cursor::new(file.data), &mut budget)?);
+                }
+                files = inner;
+            }
+            let databases = normalize_notion(&mut files)?;
+            Ok((files, databases))
+        }).await.map_err(|e| e.to_string())??;
+
+        let mut plan = plan_vault(&files);
+        adjust_plan(&mut plan);
+        Ok((self.importer.prepare(files, plan).await?, databases))
+    }
+
+    async fn run_import(&self, kb_id: Uuid, file_path: PathBuf, task_id: Uuid, progress: &Sender<ProgressEvent>) -> Result<String, String> {
+        report(progress, task_id, 0, "Reading", "Reading archive...".to_string()).await;
+        let (prepared, _) = self.prepare(file_path).await?;
+        self.importer.run(kb_id, &prepared, "Imported from Markdown", task_id, progress).await
+    }
+}
+
+#[async_trait]
+impl PortabilityProvider for MarkdownPortabilityProvider {
+    fn provider_id(&self) -> String {
+        self.id_override.clone().unwrap_or_else(|| "markdown".to_string())
+    }
+
+    async fn analyze_export(&self, _kb_id: Uuid) -> Result<ExportSummary, String> {
+        Err("Markdown archives can only be imported".to_string())
+    }
+
+    async fn export(&self, _kb_id: Uuid, _user_id: Uuid, _task_id: Uuid, _progress: Sender<ProgressEvent>) -> Result<PathBuf, String> {
+        Err("Markdown archives can only be imported".to_string())
+    }
+
+    async fn analyze_import(&self, file_path: PathBuf) -> Result<ImportSummary, String> {
+        let (prepared, databases) = self.prepare(file_path).await?;
+        let mut summary = prepared.summary();
+        if databases > 0 {
+            summary.sections.push(ImportSection { name: "Databases".to_string(), count: databases, action: "Convert".to_string() });
+        }
+        Ok(summary)
+    }
+
+    async fn import(&self, kb_id: Uuid, file_path: PathBuf, task_id: Uuid, progress: Sender<ProgressEvent>) -> Result<(), String> {
+        let result = self.run_import(kb_id, file_path, task_id, &progress).await;
+        finish(result, task_id, &progress).await
+    }
+}
@@ -0,0 +1,210 @@
+use async_trait::async_trait;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::io::Write;
+use uuid::Uuid;
+use tokio::sync::mpsc::Sender;
+use chrono::Utc;
+
+use crate::domain::portability::ports::PortabilityProvider;
+use crate::domain::portability::models::{ExportSummary, ExportSection, ImportSummary, ProgressEvent};
+use crate::domain::portability::latex::render_project;
+use crate::domain::ports::KnowledgeBaseRepository;
+use crate::domain::models::KnowledgeBaseId;
+use crate::infrastructure::persistence::repositories::block_repository::BlockRepository;
+use crate::infrastructure::services::asset_manager::AssetManager;
+
+/// Exports a math knowledge base as a compilable LaTeX project (zip).
+pub struct MathPortabilityProvider {
+    block_repo: BlockRepository,
+    kb_repo: Arc<dyn KnowledgeBaseRepository>,
+    asset_manager: Arc<AssetManager>,
+    id_override: Option<String>,
+}
+
+impl MathPortabilityProvider {
+    pub fn new(
+        block_repo: BlockRepository,
+        kb_repo: Arc<dyn KnowledgeBaseRepository>,
+        asset_manager: Arc<AssetManager>,
+    ) -> Self {
+        Self {
+            block_repo,
+            kb_repo,
+            asset_manager,
+            id_override: None,
+        }
+    }
+
+    pub fn with_id(mut self, id: String) -> Self {
+        self.id_override = Some(id);
+        self
+    }
+}
+
+/// Extensions `\includegraphics` finds on its own under pdflatex.
+fn image_extension(mime: &str) -> Option<&'static str> {
+    match mime {
+        "image/png" => Some("png"),
+        "image/jpeg" | "image/jpg" => Some("jpg"),
+        "application/pdf" => Some("pdf"),
+        _ => None,
+    }
+}
+
+#[async_trait]
+impl PortabilityProvider for MathPortabilityProvider {
+    fn provider_id(&self) -> String {
+        self.id_override.clone().unwrap_or_else(|| "math_v1".to_string())
+    }
+
+    async fn analyze_export(&self, kb_id: Uuid) -> Result<ExportSummary, String> {
+        tracing::info!("Analyzing export for KB {} using Math Provider", kb_id);
+
+        let blocks = self.block_repo.find_by_kb_id(kb_id).await.map_err(|e| e.to_string())?;
+
+        let count = |types: &[&str]| blocks.iter().filter(|b| {
+            let math_type = b.payload.get("math_type").and_then(|v| v.as_str());
+            types.contains(&b.type_name.as_str()) || math_type.is_some_and(|t| types.contains(&t))
+        }).count();
+        let statements = count(&["axiom", "definition", "theorem"]);
+        let proofs = count(&["proof"]);
+        let other = blocks.len() - statements - proofs;
+
+        // ~1KB of LaTeX per block
+        let est_bytes = blocks.len() * 1024;
+        let est_str = if est_bytes < 1024 * 1024 {
+            format!("{:.1} KB", est_bytes as f64 / 1024.0)
+        } else {
+            format!("{:.1} MB", est_bytes as f64 / 1024.0 / 1024.0)
+        };
+
+        Ok(ExportSummary {
+            total_items: blocks.len(),
+            estimated_size: est_str,
+            sections: vec![
+                ExportSection {
+                    name: "Statements".to_string(),
+                    count: statements,
+                    details: "Axioms, definitions and theorems (amsthm environments)".to_string(),
+                },
+                ExportSection {
+                    name: "Proofs".to_string(),
+                    count: proofs,
+                    details: "Proofs linked to their theorems".to_string(),
+                },
+                ExportSection {
+                    name: "Other Blocks".to_string(),
+                    count: other,
+                    details: "Headings, prose, equations and figures".to_string(),
+                },
+            ],
+        })
+    }
+
+    async fn export(&self, kb_id: Uuid, user_id: Uuid, task_id: Uuid, progress: Sender<ProgressEvent>) -> Result<PathBuf, String> {
+        let _ = progress.send(ProgressEvent {
+            task_id,
+            stage: "Initialization".to_string(),
+            percent: 0,
+            message: "Starting LaTeX export...".to_string(),
+            error: None,
+        }).await;
+
+        // 1. Fetch Data
+        let kb = self.kb_repo.find_by_id(&KnowledgeBaseId(kb_id))
+            .await.map_err(|e| e.to_string())?
+            .ok_or("KB not found")?;
+
+        let blocks = self.block_repo.find_by_kb_id(kb_id).await.map_err(|e| e.to_string())?;
+
+        // 2. Render in dependency order
+        let _ = progress.send(ProgressEvent {
+            task_id,
+            stage: "Rendering".to_string(),
+            percent: 20,
+            message: format!("Rendering {} blocks to LaTeX...", blocks.len()),
+            error: None,
+        }).await;
+
+        let project = render_project(&kb.title, blocks);
+
+        // 3. Initialize Zip
+        let temp_dir = std::env::temp_dir();
+        let filename = format!("math_export_{}_{}.zip", kb_id, Utc::now().timestamp());
+        let file_path = temp_dir.join(&filename);
+
+        let file = std::fs::File::create(&file_path).map_err(|e| e.to_string())?;
+        let mut zip = zip::ZipWriter::new(file);
+        let options = zip::write::FileOptions::<()>::default()
+            .compression_method(zip::CompressionMethod::Deflated)
+            .unix_permissions(0o755);
+
+        for (name, content) in &project.files {
+            zip.start_file(name.as_str(), options).map_err(|e| e.to_string())?;
+            zip.write_all(content.as_bytes()).map_err(|e| e.to_string())?;
+        }
+
+        // 4. Images (40% -> 90%)
+        let total_assets = project.assets.len();
+        for (i, asset_id) in project.assets.iter().enumerate() {
+            let (path, mime) = match self.asset_manager.get_asset_file(*asset_id, None, user_id).await {
+                Ok(found) => found,
+                Err(e) => {
+                    tracing::warn!("Skipping asset {} in LaTeX export: {}", asset_id, e);
+                    continue;
+                }
+            };
+            let Some(ext) = image_extension(&mime) else {
+                tracing::warn!("Skipping asset {} in LaTeX export: unsupported type {}", asset_id, mime);
+                continue;
+            };
+            let data = tokio::fs::read(&path).await.map_err(|e| e.to_string())?;
+            zip.start_file(format!("images/{}.{}", asset_id, ext), options).map_err(|e| e.to_string())?;
+            zip.write_all(&data).map_err(|e| e.to_string())?;
+
+            let percent = 40 + ((i + 1) as f32 / total_assets as f32 * 50.0) as u8;
+            let _ = progress.send(ProgressEvent {
+                task_id,
+                stage: "Packaging Images".to_string(),
+                percent,
+                message: format!("Packaging image {}/{}", i + 1, total_assets),
+                error: None,
+            }).await;
+        }
+
+        // 5. Finalizing
+        let _ = progress.send(ProgressEvent {
+            task_id,
+            stage: "Finalizing".to_string(),
+            percent: 95,
+            message: "Compressing archive...".to_string(),
+            error: None,
+        }).await;
+
+        zip.finish().map_err(|e| e.to_string())?;
+
+        let _ = progress.send(ProgressEvent {
+            task_id,
+            stage: "Completed".to_string(),
+            percent: 100,
+            message: "Export ready for download.".to_string(),
+            error: None,
+        }).await;
+
+        Ok(file_path)
+    }
+
+    async fn analyze_import(&self, _file_path: PathBuf) -> Result<ImportSummary, String> {
+        Ok(ImportSummary {
+            total_items: 0,
+            sections: vec![],
+            conflicts: vec![],
+            unresolved_links: vec![],
+        })
+    }
+
+    async fn import(&self, _kb_id: Uuid, _file_path: PathBuf, _task_id: Uuid, _progress: Sender<ProgressEvent>) -> Result<(), String> {
+        Ok(())
+    }
+}
@@ -1,2 +1,9 @@
 pub mod english;
 pub mod default;
+pub mod math;
+pub mod epub;
+pub mod vault;
+pub mod obsidian;
+pub mod markdown;
+
+mod tests;
@@ -0,0 +1,64 @@
+use async_trait::async_trait;
+use std::path::PathBuf;
+use std::sync::Arc;
+use uuid::Uuid;
+use tokio::sync::mpsc::Sender;
+
+use crate::domain::portability::ports::PortabilityProvider;
+use crate::domain::portability::models::{ExportSummary, ImportSummary, ProgressEvent};
+use crate::domain::ports::{ArticleRepository, KnowledgeBaseRepository, NodeRepository};
+use crate::infrastructure::services::asset_manager::AssetManager;
+use crate::infrastructure::services::portability::vault::{finish, plan_vault, read_archive, report, PreparedVault, VaultImporter};
+
+/// Imports a zipped Obsidian vault: folders become `Folder` nodes, notes
+/// articles, and referenced attachments are uploaded as assets.
+pub struct ObsidianPortabilityProvider {
+    importer: VaultImporter,
+}
+
+impl ObsidianPortabilityProvider {
+    pub fn new(
+        article_repo: Arc<dyn ArticleRepository>,
+        node_repo: Arc<dyn NodeRepository>,
+        kb_repo: Arc<dyn KnowledgeBaseRepository>,
+        asset_manager: Arc<AssetManager>,
+    ) -> Self {
+        Self { importer: VaultImporter::new(article_repo, node_repo, kb_repo, asset_manager) }
+    }
+
+    async fn prepare(&self, file_path: PathBuf) -> Result<PreparedVault, String> {
+        let files = tokio::task::spawn_blocking(move || read_archive(&file_path)).await.map_err(|e| e.to_string())??;
+        let plan = plan_vault(&files);
+        self.importer.prepare(files, plan).await
+    }
+
+    async fn run_import(&self, kb_id: Uuid, file_path: PathBuf, task_id: Uuid, progress: &Sender<ProgressEvent>) -> Result<String, String> {
+        report(progress, task_id, 0, "Reading", "Reading vault...".to_string()).await;
+        let prepared = self.prepare(file_path).await?;
+        self.importer.run(kb_id, &prepared, "Imported from Obsidian", task_id, progress).await
+    }
+}
+
+#[async_trait]
+impl PortabilityProvider for ObsidianPortabilityProvider {
+    fn provider_id(&self) -> String {
+        "obsidian".to_string()
+    }
+
+    async fn analyze_export(&self, _kb_id: Uuid) -> Result<ExportSummary, String> {
+        Err("Obsidian vaults can only be imported".to_string())
+    }
+
+    async fn export(&self, _kb_id: Uuid, _user_id: Uuid, _task_id: Uuid, _progress: Sender<ProgressEvent>) -> Result<PathBuf, String> {
+        Err("Obsidian vaults can only be imported".to_string())
+    }
+
+    async fn analyze_import(&self, file_path: PathBuf) -> Result<ImportSummary, String> {
+        Ok(self.prepare(file_path).await?.summary())
+    }
+
+    async fn import(&self, kb_id: Uuid, file_path: PathBuf, task_id: Uuid, progress: Sender<ProgressEvent>) -> Result<(), String> {
+        let result = self.run_import(kb_id, file_path, task_id, &progress).await;
+        finish(result, task_id, &progress).await
+    }
+}
@@ -0,0 +1,302 @@
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::io::{Cursor, Read, Write};
+    use quick_xml::events::Event;
+    use quick_xml::Reader;
+    use uuid::Uuid;
+    use crate::domain::models::ContentStatus;
+    use crate::infrastructure::services::portability::epub::{assemble_book, write_epub, EpubImage, EpubMetadata, SourceNode};
+    use crate::infrastructure::services::portability::markdown::{adjust_plan, normalize_notion, notion_front_matter, strip_notion_id};
+    use crate::infrastructure::services::portability::vault::{convert_note, disambiguate, plan_vault, split_front_matter, percent_decode, read_zip, LinkIndex, VaultFile};
+
+    fn node(parent_id: Option<Uuid>, title: &str, markdown: Option<&str>) -> SourceNode {
+        SourceNode { id: Uuid::new_v4(), parent_id, title: title.to_string(), markdown: markdown.map(str::to_string) }
+    }
+
+    /// Element name -> attributes, for every element of a well-formed document.
+    fn parse_xml(xml: &str) -> Vec<(String, HashMap<String, String>)> {
+        let mut reader = Reader::from_str(xml);
+        let mut elements = Vec::new();
+        loop {
+            match reader.read_event().unwrap_or_else(|e| panic!("malformed XML: {}\n{}", e, xml)) {
+                Event::Start(e) | Event::Empty(e) => {
+                    let attrs = e.attributes().map(|a| {
+                        let a = a.unwrap();
+                        (String::from_utf8(a.key.as_ref().to_vec()).unwrap(), a.unescape_value().unwrap().into_owned())
+                    }).collect();
+                    elements.push((String::from_utf8(e.name().as_ref().to_vec()).unwrap(), attrs));
+                }
+                Event::Eof => break,
+                _ => {}
+            }
+        }
+        elements
+    }
+
+    #[test]
+    fn test_epub_structure() {
+        let cover = EpubImage { id: Uuid::new_v4(), media_type: "image/png".to_string(), data: vec![0x89, b'P', b'N', b'G'] };
+        let figure = EpubImage { id: Uuid::new_v4(), media_type: "image/jpeg".to_string(), data: vec![0xFF, 0xD8] };
+
+        let part = node(None, "Part I", None);
+        let intro = node(Some(part.id), "Intro & Setup", Some(&format!(
+            "# Start\n\nSee [[Details]] and [[Missing]].\n\n[[asset:{}]]\n\n- [x] a < b\n\n<div>raw</div>\n",
+            figure.id
+        )));
+        let details = node(Some(part.id), "Details", Some("Text with $x^2$ and `code`.\n\n---\n"));
+        let empty = node(None, "Empty folder", None);
+        let preface = node(None, "Preface", Some(""));
+
+        let metadata = EpubMetadata {
+            identifier: Uuid::new_v4(),
+            title: "My <Book>".to_string(),
+            description: Some("About things".to_string()),
+            author: "Ada".to_string(),
+            language: "en".to_string(),
+            modified: chrono::Utc::now(),
+            cover: Some(cover.id),
+        };
+        let book = assemble_book(metadata, vec![intro, part, details, empty, preface], vec![cover, figure]);
+
+        // Reading order follows the hierarchy; folders have no chapter
+        let titles: Vec<&str> = book.chapters.iter().map(|c| c.title.as_str()).collect();
+        assert_eq!(titles, vec!["Intro & Setup", "Details", "Preface"]);
+        assert_eq!(book.toc.len(), 2);
+        assert_eq!(book.toc[0].title, "Part I");
+        assert!(book.toc[0].file.is_none());
+        assert_eq!(book.toc[0].children.len(), 2);
+
+        let bytes = write_epub(&book, Cursor::new(Vec::new())).unwrap().into_inner();
+        let mut zip = zip::ZipArchive::new(Cursor::new(bytes)).unwrap();
+        let read = |zip: &mut zip::ZipArchive<Cursor<Vec<u8>>>, name: &str| {
+            let mut s = String::new();
+            zip.by_name(name).unwrap_or_else(|_| panic!("missing {}", name)).read_to_string(&mut s).unwrap();
+            s
+        };
+
+        // OCF: `mimetype` first and stored
+        {
+            let mut first = zip.by_index(0).unwrap();
+            assert_eq!(first.name(), "mimetype");
+            assert_eq!(first.compression(), zip::CompressionMethod::Stored);
+            let mut s = String::new();
+            first.read_to_string(&mut s).unwrap();
+            assert_eq!(s, "application/epub+zip");
+        }
+
+        let container = parse_xml(&read(&mut zip, "META-INF/container.xml"));
+        let rootfile = container.iter().find(|(name, _)| name == "rootfile").unwrap();
+        assert_eq!(rootfile.1["full-path"], "OEBPS/content.opf");
+
+        // Package document: every manifest item exists, every spine entry is in the manifest
+        let opf = read(&mut zip, "OEBPS/content.opf");
+        let elements = parse_xml(&opf);
+        assert!(opf.contains("<dc:title>My &lt;Book&gt;</dc:title>"));
+        assert!(opf.contains("property=\"dcterms:modified\""));
+        let manifest: HashMap<String, HashMap<String, String>> = elements.iter()
+            .filter(|(name, _)| name == "item")
+            .map(|(_, attrs)| (attrs["id"].clone(), attrs.clone()))
+            .collect();
+        for item in manifest.values() {
+            let path = format!("OEBPS/{}", item["href"]);
+            assert!(zip.by_name(&path).is_ok(), "manifest item {} missing from the archive", path);
+        }
+        assert_eq!(manifest.values().filter(|i| i.get("properties").map(String::as_str) == Some("nav")).count(), 1);
+        assert_eq!(manifest.values().filter(|i| i.get("properties").map(String::as_str) == Some("cover-image")).count(), 1);
+        let spine: Vec<&String> = elements.iter().filter(|(name, _)| name == "itemref").map(|(_, a)| &a["idref"]).collect();
+        assert_eq!(spine.len(), 4);
+        assert!(spine.iter().all(|id| manifest.contains_key(*id)));
+
+        // Content documents are well-formed XHTML whose links stay inside the package
+        let nav = read(&mut zip, "OEBPS/nav.xhtml");
+        assert!(nav.contains("epub:type=\"toc\""));
+        assert!(nav.contains("<span>Part I</span>"));
+        assert!(!nav.contains("Empty folder"));
+        let mut xhtml = vec![("OEBPS/nav.xhtml".to_string(), nav)];
+        for item in manifest.values().filter(|i| i["media-type"] == "application/xhtml+xml") {
+            let path = format!("OEBPS/{}", item["href"]);
+            xhtml.push((path.clone(), read(&mut zip, &path)));
+        }
+        for (path, doc) in &xhtml {
+            let dir = path.rsplit_once('/').map(|(d, _)| d).unwrap_or("");
+            for (name, attrs) in parse_xml(doc) {
+                let target = match name.as_str() {
+                    "a" | "link" => attrs.get("href"),
+                    "img" => attrs.get("src"),
+                    _ => None,
+                };
+                let Some(target) = target.map(|t| t.split('#').next().unwrap_or("")).filter(|t| !t.is_empty()) else { continue };
+                let mut parts: Vec<&str> = dir.split('/').collect();
+                for segment in target.split('/') {
+                    if segment == ".." { parts.pop(); } else { parts.push(segment); }
+                }
+                let resolved = parts.join("/");
+                assert!(zip.by_name(&resolved).is_ok(), "{} links to missing {}", path, resolved);
+            }
+        }
+
+        let intro = &book.chapters[0].xhtml;
+        assert!(intro.contains("<title>Intro &amp; Setup</title>"));
+        assert!(intro.contains("href=\"chapter-002.xhtml\">Details</a>"));
+        assert!(intro.contains(&format!("src=\"../images/{}.jpg\"", book.images[1].id)));
+        assert!(intro.contains("<!-- raw HTML omitted -->"));
+    }
+
+    #[test]
+    fn test_vault_front_matter() {
+        let note = "---\ntitle: \"Graph Theory\"\ntags: [math, \"#graphs\"]\naliases:\n  - Graphs\n  - 'Network theory'\ncategory: Notes\npublish: true\n---\n# Body\n";
+        let (front, body) = split_front_matter(note);
+        assert_eq!(front.title.as_deref(), Some("Graph Theory"));
+        assert_eq!(front.tags, vec!["math", "graphs"]);
+        assert_eq!(front.aliases, vec!["Graphs", "Network theory"]);
+        assert_eq!(front.category.as_deref(), Some("Notes"));
+        assert_eq!(front.status, Some(ContentStatus::Published));
+        assert_eq!(body, "# Body\n");
+
+        let (front, _) = split_front_matter("---\ntags: a, b\nstatus: archived\n---\n");
+        assert_eq!(front.tags, vec!["a", "b"]);
+        assert_eq!(front.status, Some(ContentStatus::Archived));
+
+        // No closing fence: not front matter
+        let (front, body) = split_front_matter("---\ntitle: x\n");
+        assert!(front.title.is_none());
+        assert_eq!(body, "---\ntitle: x\n");
+
+        assert_eq!(disambiguate("Index", Some("Projects/Web"), 1), "Index (Web)");
+        assert_eq!(disambiguate("Index", None, 2), "Index (3)");
+    }
+
+    fn zip_of(files: &[(&str, &[u8])]) -> Vec<u8> {
+        let mut out = Cursor::new(Vec::new());
+        let mut zip = zip::ZipWriter::new(&mut out);
+        for (name, data) in files {
+            zip.start_file(*name, zip::write::FileOptions::<()>::default()).unwrap();
+            zip.write_all(data).unwrap();
+        }
+        zip.finish().unwrap();
+        out.into_inner()
+    }
+
+    #[test]
+    fn test_vault_archive_size_limit() {
+        let archive = zip_of(&[("Vault/a.md", b"# A\n"), ("Vault/.obsidian/app.json", b"{}"), ("Vault/b.bin", &[0u8; 4096])]);
+
+        let mut budget = 8192;
+        let files = read_zip(Cursor::new(&archive), &mut budget).unwrap();
+        assert_eq!(files.iter().map(|f| f.path.as_str()).collect::<Vec<_>>(), vec!["a.md", "b.bin"]);
+        assert_eq!(budget, 8192 - 4 - 4096);
+
+        // Highly compressible content past the budget is refused, not expanded
+        let mut budget = 1024;
+        assert!(read_zip(Cursor::new(&archive), &mut budget).is_err());
+    }
+
+    #[test]
+    fn test_percent_decode() {
+        assert_eq!(percent_decode("My%20Note.md"), "My Note.md");
+        assert_eq!(percent_decode("%E4%B8%AD.md"), "中.md");
+        // Not escapes: kept as written, whatever follows the `%`
+        assert_eq!(percent_decode("%中文.md"), "%中文.md");
+        assert_eq!(percent_decode("a%2中.md"), "a%2中.md");
+        assert_eq!(percent_decode("100%"), "100%");
+    }
+
+    #[test]
+    fn test_vault_link_conversion() {
+        let file = |path: &str, text: &str| VaultFile { path: path.to_string(), data: text.as_bytes().to_vec() };
+        let files = vec![
+            file("Home.md", "---\naliases: [Start]\n---\nHome"),
+            file("Projects/Plan.md", "Plan"),
+            file("Projects/Web/Index.md", "Web"),
+            file("Archive/Index.md", "Old"),
+            file("Projects/Web/diagram.png", "png"),
+            file("attachments/diagram.png", "png"),
+            file("attachments/spec.pdf", "pdf"),
+            file("attachments/unused.png", "png"),
+        ];
+        let plan = plan_vault(&files);
+        let folders: Vec<&str> = plan.folders.iter().map(|f| f.path.as_str()).collect();
+        assert_eq!(folders, vec!["Archive", "Projects", "Projects/Web"]);
+        assert_eq!(plan.attachments.len(), 4);
+
+        let index = LinkIndex::new(&plan);
+        let titles: HashMap<String, String> = plan.notes.iter()
+            .map(|n| (n.path.clone(), if n.path == "Archive/Index.md" { "Index (Archive)".to_string() } else { n.title.clone() }))
+            .collect();
+        let (diagram, root_diagram, spec) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let assets = HashMap::from([
+            ("Projects/Web/diagram.png".to_string(), diagram),
+            ("attachments/diagram.png".to_string(), root_diagram),
+            ("attachments/spec.pdf".to_string(), spec),
+        ]);
+
+        let body = "See [[Start]], [[Plan#Goals]], [[Index|the index]] and [[../../Archive/Index]].\n\
+                    ![[diagram.png]] ![alt](../../attachments/diagram.png) [[spec.pdf]]\n\
+                    [plan](../Plan.md) [site](https://example.com) [[Missing]] ![[gone.png]]\n\
+                    `[[Plan]]`\n\n```\n[[Plan]]\n```\n";
+        let converted = convert_note(body, "Projects/Web/Index.md", &index, &titles, &assets);
+        let expected = format!(
+            "See [[Home|Start]], [[Plan|Plan#Goals]], [[Index|the index]] and [[Index (Archive)|Index]].\n\
+             [[asset:{}]] [[asset:{}]] [spec.pdf](/api/assets/{})\n\
+             [[Plan|plan]] [site](https://example.com) [[Missing]] ![[gone.png]]\n\
+             `[[Plan]]`\n\n```\n[[Plan]]\n```\n",
+            diagram, root_diagram, spec
+        );
+        assert_eq!(converted.body, expected);
+        assert_eq!(converted.unresolved, vec!["Missing", "gone.png"]);
+        assert_eq!(converted.attachments, vec!["Projects/Web/diagram.png", "attachments/diagram.png", "attachments/spec.pdf"]);
+
+        // Closest match: from the vault root, the shallower diagram wins
+        let converted = convert_note("![[diagram.png]]", "Home.md", &index, &titles, &assets);
+        assert_eq!(converted.body, format!("[[asset:{}]]", root_diagram));
+    }
+
+    #[test]
+    fn test_notion_export() {
+        const ID: &str = "0123456789abcdef0123456789abcdef";
+        assert_eq!(strip_notion_id(&format!("Roadmap {}", ID)), "Roadmap");
+        assert_eq!(strip_notion_id("Roadmap 2024"), "Roadmap 2024");
+
+        let page = notion_front_matter("# Launch: v2\n\nTags: release, q3 goals\nOwner: Ada\nStatus: Done\n\nBody text\n");
+        let (front, body) = split_front_matter(&page);
+        assert_eq!(front.title.as_deref(), Some("Launch: v2"));
+        assert_eq!(front.tags, vec!["release", "q3 goals"]);
+        assert_eq!(front.status, Some(ContentStatus::Published));
+        assert_eq!(body, "Owner: Ada\n\nBody text\n");
+
+        let file = |path: String, text: &str| VaultFile { path, data: text.as_bytes().to_vec() };
+        let mut files = vec![
+            file(format!("Projects {}.md", ID), &format!("# Projects\n\nSee [Tasks](Projects%20{id}/Tasks%20{id}.md)\n", id = ID)),
+            file(format!("Projects {}/Tasks {}.csv", ID, ID), "Name,Due\nShip,May\n"),
+            file(format!("Projects {}/Tasks {}_all.csv", ID, ID), "\u{feff}Name,Due\nShip,May\n\"Plan | review\",\"June\nlate\"\n"),
+            file(format!("Projects {}/Tasks {}/Ship {}.md", ID, ID, ID), "# Ship\n\nStatus: Draft\n\n![Untitled](Untitled.png)\n"),
+            file(format!("Projects {}/Tasks {}/Untitled.png", ID, ID), "png"),
+        ];
+        assert_eq!(normalize_notion(&mut files).unwrap(), 1);
+        assert!(files.iter().all(|f| !f.path.ends_with(".csv")));
+
+        let mut plan = plan_vault(&files);
+        adjust_plan(&mut plan);
+        let folders: Vec<&str> = plan.folders.iter().map(|f| f.title.as_str()).collect();
+        assert_eq!(folders, vec!["Projects", "Tasks"]);
+        let table = plan.notes.iter().find(|n| n.title == "Tasks").unwrap();
+        assert_eq!(table.folder.as_deref(), Some(format!("Projects {}/Tasks {}", ID, ID).as_str()));
+        assert_eq!(
+            table.body,
+            format!("| Name | Due |\n| --- | --- |\n| [Ship](Tasks%20{id}/Ship%20{id}.md) | May |\n| Plan \\| review | June late |\n", id = ID)
+        );
+        let projects = plan.notes.iter().find(|n| n.title == "Projects").unwrap();
+        assert_eq!(projects.folder.as_deref(), Some(format!("Projects {}", ID).as_str()));
+
+        let index = LinkIndex::new(&plan);
+        let titles: HashMap<String, String> = plan.notes.iter().map(|n| (n.path.clone(), n.title.clone())).collect();
+        let image = Uuid::new_v4();
+        let assets = HashMap::from([(format!("Projects {}/Tasks {}/Untitled.png", ID, ID), image)]);
+        assert_eq!(convert_note(&table.body, &table.path, &index, &titles, &assets).body.lines().nth(2), Some("| [[Ship]] | May |"));
+        assert_eq!(convert_note(&projects.body, &projects.path, &index, &titles, &assets).body, "See [[Tasks]]\n");
+        let ship = plan.notes.iter().find(|n| n.title == "Ship").unwrap();
+        assert_eq!(ship.front.status, Some(ContentStatus::Draft));
+        assert_eq!(convert_note(&ship.body, &ship.path, &index, &titles, &assets).body, format!("[[asset:{}]]\n", image));
+    }
+}
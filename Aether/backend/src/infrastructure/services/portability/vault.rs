@@ -0,0 +1,857 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::io::{Read, Seek};
+use std::path::Path;
+use std::sync::Arc;
+use chrono::Utc;
+use uuid::Uuid;
+use tokio::sync::mpsc::Sender;
+use zip::ZipArchive;
+
+use crate::domain::blocks::links::code_spans;
+use crate::domain::models::{Article, ContentBody, ContentStatus, KnowledgeBaseId, Node, NodeType, PermissionMode, UserId};
+use crate::domain::portability::models::{ImportSection, ImportSummary, ProgressEvent, UnresolvedLink};
+use crate::domain::ports::{ArticleRepository, KnowledgeBaseRepository, NodeRepository, RepositoryError};
+use crate::infrastructure::services::asset_manager::AssetManager;
+
+// Shared by the importers of Markdown vaults (folders of `.md` notes plus
+// attachments, zipped): reading the archive, front matter, the folder/note
+// plan, link conversion, and saving the result into a knowledge base.
+
+/// A file of an imported archive, by its path inside the vault.
+pub struct VaultFile {
+    pub path: String,
+    pub data: Vec<u8>,
+}
+
+/// Most bytes an archive may expand to: its files are held in memory.
+pub const MAX_EXPANDED_BYTES: u64 = 512 << 20;
+
+/// Reads every file of a zipped vault. Hidden files and folders (`.obsidian/`,
+/// `.trash/`, `.DS_Store`) and macOS metadata are skipped, and a single
+/// top-level folder wrapping everything is stripped.
+pub fn read_archive(path: &Path) -> Result<Vec<VaultFile>, String> {
+    let file = std::fs::File::open(path).map_err(|e| e.to_string())?;
+    let mut budget = MAX_EXPANDED_BYTES;
+    read_zip(file, &mut budget)
+}
+
+/// `read_archive` over any zip, such as one nested in an upload. `budget` is
+/// how many bytes the files may still expand to; what is read is taken off it.
+pub fn read_zip<R: Read + Seek>(reader: R, budget: &mut u64) -> Result<Vec<VaultFile>, String> {
+    let mut archive = ZipArchive::new(reader).map_err(|e| e.to_string())?;
+
+    let too_large = || format!("The archive expands to more than {} MiB", MAX_EXPANDED_BYTES >> 20);
+    let mut files = Vec::new();
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i).map_err(|e| e.to_string())?;
+        if entry.is_dir() {
+            continue;
+        }
+        // Entries escaping the archive root are dropped
+        let Some(name) = entry.enclosed_name() else { continue };
+        let path = name.to_string_lossy().replace('\\', "/");
+        if path.split('/').any(|c| c.starts_with('.') || c == "__MACOSX") {
+            continue;
+        }
+        // Declared sizes can lie: the read is cut off past what is left as well
+        if entry.size() > *budget {
+            return Err(too_large());
+        }
+        let mut data = Vec::new();
+        entry.by_ref().take(*budget + 1).read_to_end(&mut data).map_err(|e| e.to_string())?;
+        if data.len() as u64 > *budget {
+            return Err(too_large());
+        }
+        *budget -= data.len() as u64;
+        files.push(VaultFile { path, data });
+    }
+
+    let root = files.first().and_then(|f| f.path.split_once('/')).map(|(root, _)| format!("{}/", root));
+    if let Some(root) = root.filter(|root| files.iter().all(|f| f.path.starts_with(root.as_str()))) {
+        for file in &mut files {
+            file.path = file.path[root.len()..].to_string();
+        }
+    }
+    Ok(files)
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct FrontMatter {
+    pub title: Option<String>,
+    pub tags: Vec<String>,
+    pub aliases: Vec<String>,
+    pub category: Option<String>,
+    pub status: Option<ContentStatus>,
+}
+
+fn unquote(value: &str) -> String {
+    let value = value.trim();
+    for quote in ['"', '\''] {
+        if let Some(inner) = value.strip_prefix(quote).and_then(|v| v.strip_suffix(quote)) {
+            return inner.to_string();
+        }
+    }
+    value.to_string()
+}
+
+/// Splits YAML front matter off a note. Only the subset notes use is read:
+/// `key: value`, `key: [a, b]` and `key:` followed by `- item` lines.
+pub fn split_front_matter(text: &str) -> (FrontMatter, String) {
+    let text = text.strip_prefix('\u{feff}').unwrap_or(text);
+    let Some(rest) = text.strip_prefix("---\n").or_else(|| text.strip_prefix("---\r\n")) else {
+        return (FrontMatter::default(), text.to_string());
+    };
+    let mut offset = 0;
+    let mut end = None;
+    for line in rest.split_inclusive('\n') {
+        if matches!(line.trim_end(), "---" | "...") {
+            end = Some((offset, offset + line.len()));
+            break;
+        }
+        offset += line.len();
+    }
+    let Some((yaml_end, body_start)) = end else {
+        return (FrontMatter::default(), text.to_string());
+    };
+
+    let mut values: HashMap<String, Vec<String>> = HashMap::new();
+    let mut scalars = HashSet::new();
+    let mut list_key: Option<String> = None;
+    for line in rest[..yaml_end].lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        if let (Some(key), Some(item)) = (&list_key, trimmed.strip_prefix('-')) {
+            values.entry(key.clone()).or_default().push(unquote(item));
+            continue;
+        }
+        let Some((key, value)) = line.split_once(':') else { continue };
+        let key = key.trim().to_lowercase();
+        let value = value.trim();
+        list_key = None;
+        if value.is_empty() {
+            list_key = Some(key.clone());
+            values.entry(key).or_default();
+        } else if let Some(items) = value.strip_prefix('[').and_then(|v| v.strip_suffix(']')) {
+            values.insert(key, items.split(',').map(unquote).filter(|i| !i.is_empty()).collect());
+        } else {
+            scalars.insert(key.clone());
+            values.insert(key, vec![unquote(value)]);
+        }
+    }
+
+    let first = |keys: &[&str]| keys.iter().find_map(|k| values.get(*k)?.first().cloned()).filter(|v| !v.is_empty());
+    let list = |keys: &[&str]| -> Vec<String> {
+        let Some((key, items)) = keys.iter().find_map(|k| values.get_key_value(*k)) else { return vec![] };
+        // A scalar may hold several: `tags: a, b` or `tags: a b`
+        let split: Vec<String> = if scalars.contains(key) {
+            items[0].split([',', ' ']).map(str::to_string).collect()
+        } else {
+            items.clone()
+        };
+        let mut out = Vec::new();
+        for item in split {
+            let item = item.trim().trim_start_matches('#').to_string();
+            if !item.is_empty() && !out.contains(&item) {
+                out.push(item);
+            }
+        }
+        out
+    };
+    let flag = |key: &str| first(&[key]).is_some_and(|v| v.eq_ignore_ascii_case("true"));
+
+    let status = match first(&["status"]).map(|s| s.to_lowercase()).as_deref() {
+        Some("published" | "public" | "done") => Some(ContentStatus::Published),
+        Some("archived") => Some(ContentStatus::Archived),
+        Some("draft") => Some(ContentStatus::Draft),
+        _ if flag("publish") || flag("published") => Some(ContentStatus::Published),
+        _ if flag("draft") => Some(ContentStatus::Draft),
+        _ => None,
+    };
+    let aliases = {
+        let keys = ["aliases", "alias"];
+        match keys.iter().find_map(|k| values.get_key_value(*k)) {
+            // Aliases may contain spaces: a scalar is split on commas only
+            Some((key, items)) if scalars.contains(key) => items[0].split(',').map(unquote).filter(|a| !a.is_empty()).collect(),
+            Some((_, items)) => items.clone(),
+            None => vec![],
+        }
+    };
+
+    let front = FrontMatter {
+        title: first(&["title"]),
+        tags: list(&["tags", "tag"]),
+        aliases,
+        category: first(&["category", "categories"]),
+        status,
+    };
+    (front, rest[body_start..].to_string())
+}
+
+pub struct PlannedFolder {
+    pub path: String,
+    pub parent: Option<String>,
+    pub title: String,
+}
+
+pub struct PlannedNote {
+    pub path: String,
+    pub folder: Option<String>,
+    pub title: String,
+    pub front: FrontMatter,
+    pub body: String,
+}
+
+/// Folders (parents first), notes and attachments of a vault, by path.
+pub struct VaultPlan {
+    pub folders: Vec<PlannedFolder>,
+    pub notes: Vec<PlannedNote>,
+    pub attachments: Vec<String>,
+}
+
+pub fn is_note(path: &str) -> bool {
+    path.to_lowercase().ends_with(".md")
+}
+
+pub fn file_name(path: &str) -> &str {
+    path.rsplit('/').next().unwrap_or(path)
+}
+
+pub fn parent_dir(path: &str) -> Option<&str> {
+    path.rsplit_once('/').map(|(dir, _)| dir)
+}
+
+/// The file name without its extension.
+pub fn stem(path: &str) -> &str {
+    let name = file_name(path);
+    name.rsplit_once('.').map(|(stem, _)| stem).filter(|s| !s.is_empty()).unwrap_or(name)
+}
+
+/// Folders are the directories holding notes (and their ancestors); a
+/// directory with only attachments in it does not become a folder.
+pub fn plan_vault(files: &[VaultFile]) -> VaultPlan {
+    let mut dirs = BTreeSet::new();
+    let mut notes = Vec::new();
+    let mut attachments = Vec::new();
+    for file in files {
+        if !is_note(&file.path) {
+            attachments.push(file.path.clone());
+            continue;
+        }
+        let mut dir = parent_dir(&file.path);
+        while let Some(d) = dir {
+            dirs.insert(d.to_string());
+            dir = parent_dir(d);
+        }
+        let (front, body) = split_front_matter(&String::from_utf8_lossy(&file.data));
+        notes.push(PlannedNote {
+            path: file.path.clone(),
+            folder: parent_dir(&file.path).map(str::to_string),
+            title: front.title.clone().unwrap_or_else(|| stem(&file.path).to_string()),
+            front,
+            body,
+        });
+    }
+
+    let mut folders: Vec<PlannedFolder> = dirs.into_iter().map(|path| PlannedFolder {
+        parent: parent_dir(&path).map(str::to_string),
+        title: file_name(&path).to_string(),
+        path,
+    }).collect();
+    folders.sort_by_key(|f| f.path.matches('/').count());
+    VaultPlan { folders, notes, attachments }
+}
+
+/// Candidate titles for a note whose title is taken: `Title`, `Title (Folder)`, `Title (2)`...
+pub fn disambiguate(title: &str, folder: Option<&str>, attempt: usize) -> String {
+    match (attempt, folder.map(file_name)) {
+        (0, _) => title.to_string(),
+        (1, Some(folder)) => format!("{} ({})", title, folder),
+        (n, _) => format!("{} ({})", title, n + 1),
+    }
+}
+
+pub fn mime_for(path: &str) -> &'static str {
+    let ext = file_name(path).rsplit_once('.').map(|(_, e)| e.to_lowercase()).unwrap_or_default();
+    match ext.as_str() {
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "svg" => "image/svg+xml",
+        "bmp" => "image/bmp",
+        "pdf" => "application/pdf",
+        "mp3" => "audio/mpeg",
+        "wav" => "audio/wav",
+        "ogg" => "audio/ogg",
+        "mp4" => "video/mp4",
+        "webm" => "video/webm",
+        "txt" => "text/plain",
+        "csv" => "text/csv",
+        _ => "application/octet-stream",
+    }
+}
+
+/// `%20`-style escapes, as found in Markdown link targets.
+pub fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' && i + 2 < bytes.len() {
+            // Byte-wise: what follows `%` may be any character, not just hex digits
+            let hex = |b: u8| (b as char).to_digit(16);
+            if let (Some(hi), Some(lo)) = (hex(bytes[i + 1]), hex(bytes[i + 2])) {
+                out.push((hi * 16 + lo) as u8);
+                i += 3;
+                continue;
+            }
+        }
+        out.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+const IMAGE_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "gif", "webp", "svg", "bmp"];
+
+/// `a/./b/../c` -> `a/c`; `None` if it climbs out of the vault.
+fn normalize(path: &str) -> Option<String> {
+    let mut parts: Vec<&str> = Vec::new();
+    for part in path.split('/') {
+        match part {
+            "" | "." => {}
+            ".." => { parts.pop()?; }
+            part => parts.push(part),
+        }
+    }
+    Some(parts.join("/"))
+}
+
+fn strip_md(path: &str) -> &str {
+    if is_note(path) { &path[..path.len() - 3] } else { path }
+}
+
+fn is_image(path: &str) -> bool {
+    let name = file_name(path).to_lowercase();
+    name.rsplit_once('.').is_some_and(|(_, ext)| IMAGE_EXTENSIONS.contains(&ext))
+}
+
+/// Resolves link targets to vault paths the way Obsidian does: a path
+/// relative to the note or to the vault root, or else a bare name (or note
+/// alias) matched case-insensitively, preferring the file closest to the note.
+pub struct LinkIndex {
+    /// Lowercase note name or alias -> (lowercase path without `.md`, path)
+    notes: HashMap<String, Vec<(String, String)>>,
+    /// Lowercase file name -> (lowercase path, path)
+    attachments: HashMap<String, Vec<(String, String)>>,
+}
+
+impl LinkIndex {
+    pub fn new(plan: &VaultPlan) -> Self {
+        let mut notes: HashMap<String, Vec<(String, String)>> = HashMap::new();
+        for note in &plan.notes {
+            let key = strip_md(&note.path).to_lowercase();
+            let names = std::iter::once(file_name(&key).to_string())
+                .chain(note.front.aliases.iter().map(|a| a.to_lowercase()));
+            for name in names {
+                notes.entry(name).or_default().push((key.clone(), note.path.clone()));
+            }
+        }
+        let mut attachments: HashMap<String, Vec<(String, String)>> = HashMap::new();
+        for path in &plan.attachments {
+            attachments.entry(file_name(path).to_lowercase()).or_default().push((path.to_lowercase(), path.clone()));
+        }
+        Self { notes, attachments }
+    }
+
+    fn pick<'a>(candidates: Option<&'a Vec<(String, String)>>, target: &str, source: &str) -> Option<&'a str> {
+        let candidates = candidates?;
+        let target = target.to_lowercase();
+        let dir = parent_dir(source).unwrap_or("").to_lowercase();
+        if target.contains('/') {
+            let relative = normalize(&format!("{}/{}", dir, target));
+            let rooted = normalize(&target);
+            let suffix = format!("/{}", target.trim_start_matches('/'));
+            return candidates.iter()
+                .find(|(key, _)| Some(key) == relative.as_ref())
+                .or_else(|| candidates.iter().find(|(key, _)| Some(key) == rooted.as_ref()))
+                .or_else(|| candidates.iter().find(|(key, _)| key.ends_with(&suffix)))
+                .map(|(_, path)| path.as_str());
+        }
+        // Closest: most leading folders shared with the note, then shallowest
+        let shared = |key: &str| {
+            let folder = parent_dir(key).unwrap_or("");
+            folder.split('/').zip(dir.split('/')).take_while(|(a, b)| a == b && !a.is_empty()).count()
+        };
+        candidates.iter()
+            .max_by(|(a, _), (b, _)| shared(a).cmp(&shared(b)).then(b.matches('/').count().cmp(&a.matches('/').count())))
+            .map(|(_, path)| path.as_str())
+    }
+
+    /// The note a link points to; `target` has no `#heading` part.
+    pub fn note(&self, target: &str, source: &str) -> Option<&str> {
+        let target = strip_md(target.trim());
+        Self::pick(self.notes.get(&file_name(target).to_lowercase()), target, source)
+    }
+
+    pub fn attachment(&self, target: &str, source: &str) -> Option<&str> {
+        let target = target.trim();
+        Self::pick(self.attachments.get(&file_name(target).to_lowercase()), target, source)
+    }
+}
+
+pub struct ConvertedNote {
+    pub body: String,
+    /// Link targets that match no note or attachment in the vault.
+    pub unresolved: Vec<String>,
+    /// Paths of the attachments the note references.
+    pub attachments: Vec<String>,
+}
+
+struct Converter<'a> {
+    source: &'a str,
+    index: &'a LinkIndex,
+    titles: &'a HashMap<String, String>,
+    assets: &'a HashMap<String, Uuid>,
+    unresolved: Vec<String>,
+    attachments: Vec<String>,
+}
+
+impl Converter<'_> {
+    fn unresolved(&mut self, target: &str) {
+        if !self.unresolved.iter().any(|t| t == target) {
+            self.unresolved.push(target.to_string());
+        }
+    }
+
+    /// The asset an attachment was uploaded as, recording the reference.
+    fn asset(&mut self, path: &str, target: &str) -> Option<Uuid> {
+        if !self.attachments.iter().any(|p| p == path) {
+            self.attachments.push(path.to_string());
+        }
+        let id = self.assets.get(path).copied();
+        if id.is_none() {
+            self.unresolved(target);
+        }
+        id
+    }
+
+    fn note_link(title: &str, alias: Option<&str>) -> String {
+        match alias {
+            Some(alias) if alias != title => format!("[[{}|{}]]", title, alias),
+            _ => format!("[[{}]]", title),
+        }
+    }
+
+    /// `[[target#heading|alias]]` or `![[target]]`; `None` leaves it untouched.
+    fn wiki(&mut self, inner: &str, embed: bool) -> Option<String> {
+        let (target, alias) = match inner.split_once('|') {
+            Some((target, alias)) => (target.trim(), Some(alias.trim()).filter(|a| !a.is_empty())),
+            None => (inner.trim(), None),
+        };
+        let (path, fragment) = match target.split_once('#') {
+            Some((path, fragment)) => (path.trim(), Some(fragment.trim())),
+            None => (target, None),
+        };
+        if path.is_empty() {
+            // A heading of the same note: no equivalent, keep the text
+            return Some(alias.or(fragment).unwrap_or_default().trim_start_matches('^').to_string());
+        }
+
+        if !is_note(path) {
+            if let Some(found) = self.index.attachment(path, self.source).map(str::to_string) {
+                let id = self.asset(&found, target)?;
+                return Some(if embed {
+                    format!("[[asset:{}]]", id)
+                } else {
+                    format!("[{}](/api/assets/{})", alias.unwrap_or(file_name(path)), id)
+                });
+            }
+        }
+        if let Some(found) = self.index.note(path, self.source) {
+            let title = self.titles.get(found)?;
+            // Keeps the link's wording when the title differs (aliases, renames,
+            // sections, which are not addressable)
+            let wording = if fragment.is_some() { target } else { file_name(strip_md(path)) };
+            let alias = alias.or(Some(wording));
+            return Some(Self::note_link(title, alias));
+        }
+
+        self.unresolved(target);
+        if embed && !is_note(path) && path.contains('.') {
+            return None;
+        }
+        // A wanted page: the note may be written later under this title
+        Some(Self::note_link(file_name(strip_md(path)), alias))
+    }
+
+    /// `[label](target)` or `![alt](target)` with a vault-local target.
+    fn markdown(&mut self, label: &str, target: &str, image: bool) -> Option<String> {
+        let target = target.trim();
+        let target = target.strip_prefix('<').and_then(|t| t.strip_suffix('>')).unwrap_or_else(|| {
+            // Drop a `"title"` after the URL
+            target.split_once(" \"").map_or(target, |(url, _)| url)
+        });
+        if target.is_empty() || target.starts_with('#') || target.starts_with('/') || target.contains(':') {
+            return None;
+        }
+        let decoded = percent_decode(target);
+        let (path, fragment) = match decoded.split_once('#') {
+            Some((path, fragment)) => (path.to_string(), Some(fragment.to_string())),
+            None => (decoded.clone(), None),
+        };
+
+        if is_note(&path) {
+            if let Some(found) = self.index.note(&path, self.source) {
+                let title = self.titles.get(found)?;
+                let alias = Some(label).filter(|l| !l.is_empty()).or(fragment.as_deref());
+                return Some(Self::note_link(title, alias));
+            }
+            self.unresolved(&decoded);
+            return None;
+        }
+        match self.index.attachment(&path, self.source).map(str::to_string) {
+            Some(found) => {
+                let id = self.asset(&found, &decoded)?;
+                Some(if image || (is_image(&found) && label.is_empty()) {
+                    format!("[[asset:{}]]", id)
+                } else {
+                    format!("[{}](/api/assets/{})", label, id)
+                })
+            }
+            None => {
+                self.unresolved(&decoded);
+                None
+            }
+        }
+    }
+}
+
+/// Parses `[label](target)` at the start of `text`: (label, target, length).
+fn markdown_link(text: &str) -> Option<(&str, &str, usize)> {
+    let close = text.find(']')?;
+    let label = &text[1..close];
+    if label.contains(['[', '\n']) {
+        return None;
+    }
+    let rest = text[close + 1..].strip_prefix('(')?;
+    let end = rest.find(')')?;
+    let target = &rest[..end];
+    if target.contains('\n') {
+        return None;
+    }
+    Some((label, target, close + 1 + 1 + end + 1))
+}
+
+/// Rewrites a note's links for Aether: wiki links and links to `.md` files
+/// become `[[Title]]` links to the imported articles, embedded attachments
+/// `[[asset:uuid]]`. `titles` maps note paths to article titles and `assets`
+/// attachment paths to uploaded asset ids. Code is left alone.
+pub fn convert_note(
+    body: &str,
+    source: &str,
+    index: &LinkIndex,
+    titles: &HashMap<String, String>,
+    assets: &HashMap<String, Uuid>,
+) -> ConvertedNote {
+    let mut converter = Converter { source, index, titles, assets, unresolved: vec![], attachments: vec![] };
+    let code = code_spans(body);
+    let mut out = String::with_capacity(body.len());
+    let mut copied = 0;
+    let mut i = 0;
+    while i < body.len() {
+        if let Some(span) = code.iter().find(|r| r.start == i) {
+            i = span.end;
+            continue;
+        }
+        let rest = &body[i..];
+        let embed = rest.starts_with('!');
+        let link = &rest[embed as usize..];
+
+        let mut replaced = None;
+        if let Some(inner) = link.strip_prefix("[[") {
+            if let Some(len) = inner.find("]]").filter(|len| !inner[..*len].contains(['\n', '['])) {
+                let end = embed as usize + 2 + len + 2;
+                replaced = Some((converter.wiki(&inner[..len], embed), end));
+            }
+        } else if link.starts_with('[') {
+            if let Some((label, target, len)) = markdown_link(link) {
+                replaced = Some((converter.markdown(label, target, embed), embed as usize + len));
+            }
+        }
+
+        match replaced {
+            Some((replacement, end)) => {
+                if let Some(replacement) = replacement {
+                    out.push_str(&body[copied..i]);
+                    out.push_str(&replacement);
+                    copied = i + end;
+                }
+                i += end;
+            }
+            None => i += rest.chars().next().map_or(1, char::len_utf8),
+        }
+    }
+    out.push_str(&body[copied..]);
+    ConvertedNote { body: out, unresolved: converter.unresolved, attachments: converter.attachments }
+}
+
+/// A vault read and planned, with the article titles assigned.
+pub struct PreparedVault {
+    pub files: Vec<VaultFile>,
+    pub plan: VaultPlan,
+    index: LinkIndex,
+    titles: HashMap<String, String>,
+    renamed: Vec<String>,
+}
+
+impl PreparedVault {
+    /// Every attachment as if uploaded: only missing files are unresolved.
+    fn placeholders(&self) -> HashMap<String, Uuid> {
+        self.plan.attachments.iter().map(|p| (p.clone(), Uuid::nil())).collect()
+    }
+
+    /// Converts every note; returns the bodies, unresolved links and referenced attachments.
+    fn convert_all(&self, assets: &HashMap<String, Uuid>) -> (HashMap<String, String>, Vec<UnresolvedLink>, Vec<String>) {
+        let mut bodies = HashMap::new();
+        let mut unresolved = Vec::new();
+        let mut attachments: Vec<String> = Vec::new();
+        for note in &self.plan.notes {
+            let converted = convert_note(&note.body, &note.path, &self.index, &self.titles, assets);
+            unresolved.extend(converted.unresolved.into_iter().map(|target| UnresolvedLink { source: note.path.clone(), target }));
+            for path in converted.attachments {
+                if !attachments.contains(&path) {
+                    attachments.push(path);
+                }
+            }
+            bodies.insert(note.path.clone(), converted.body);
+        }
+        (bodies, unresolved, attachments)
+    }
+
+    pub fn summary(&self) -> ImportSummary {
+        let (_, unresolved_links, attachments) = self.convert_all(&self.placeholders());
+        let plan = &self.plan;
+        let mut sections = vec![
+            ImportSection { name: "Folders".to_string(), count: plan.folders.len(), action: "Create".to_string() },
+            ImportSection { name: "Notes".to_string(), count: plan.notes.len(), action: "Create".to_string() },
+            ImportSection { name: "Attachments".to_string(), count: attachments.len(), action: "Upload".to_string() },
+        ];
+        let unused = plan.attachments.len() - attachments.len();
+        if unused > 0 {
+            sections.push(ImportSection { name: "Unreferenced files".to_string(), count: unused, action: "Skip".to_string() });
+        }
+        ImportSummary {
+            total_items: plan.folders.len() + plan.notes.len() + attachments.len(),
+            sections,
+            conflicts: self.renamed.clone(),
+            unresolved_links,
+        }
+    }
+}
+
+pub async fn report(progress: &Sender<ProgressEvent>, task_id: Uuid, percent: u8, stage: &str, message: String) {
+    let _ = progress.send(ProgressEvent { task_id, stage: stage.to_string(), percent, message, error: None }).await;
+}
+
+/// Reports the outcome of an import as its last progress event.
+pub async fn finish(result: Result<String, String>, task_id: Uuid, progress: &Sender<ProgressEvent>) -> Result<(), String> {
+    match result {
+        Ok(message) => {
+            report(progress, task_id, 100, "Completed", message).await;
+            Ok(())
+        }
+        Err(e) => {
+            let _ = progress.send(ProgressEvent {
+                task_id,
+                stage: "Failed".to_string(),
+                percent: 100,
+                message: "Import failed".to_string(),
+                error: Some(e.clone()),
+            }).await;
+            Err(e)
+        }
+    }
+}
+
+pub struct ImportCounts {
+    pub folders: usize,
+    pub notes: usize,
+    pub failed: Vec<String>,
+}
+
+/// Writes a planned vault into a knowledge base.
+pub struct VaultImporter {
+    article_repo: Arc<dyn ArticleRepository>,
+    node_repo: Arc<dyn NodeRepository>,
+    kb_repo: Arc<dyn KnowledgeBaseRepository>,
+    asset_manager: Arc<AssetManager>,
+}
+
+impl VaultImporter {
+    pub fn new(
+        article_repo: Arc<dyn ArticleRepository>,
+        node_repo: Arc<dyn NodeRepository>,
+        kb_repo: Arc<dyn KnowledgeBaseRepository>,
+        asset_manager: Arc<AssetManager>,
+    ) -> Self {
+        Self { article_repo, node_repo, kb_repo, asset_manager }
+    }
+
+    /// Indexes the links of a planned vault and assigns the article titles.
+    pub async fn prepare(&self, files: Vec<VaultFile>, plan: VaultPlan) -> Result<PreparedVault, String> {
+        if plan.notes.is_empty() {
+            return Err("No Markdown notes found in the archive".to_string());
+        }
+        let index = LinkIndex::new(&plan);
+        let (titles, renamed) = self.assign_titles(&plan.notes).await?;
+        Ok(PreparedVault { files, plan, index, titles, renamed })
+    }
+
+    /// Uploads the referenced attachments, then creates folders and notes.
+    /// Returns the completion message.
+    pub async fn run(
+        &self,
+        kb_id: Uuid,
+        prepared: &PreparedVault,
+        reason: &str,
+        task_id: Uuid,
+        progress: &Sender<ProgressEvent>,
+    ) -> Result<String, String> {
+        let kb = self.kb_repo.find_by_id(&KnowledgeBaseId(kb_id)).await.map_err(|e| e.to_string())?
+            .ok_or_else(|| "Knowledge Base not found".to_string())?;
+
+        // First pass finds the attachments in use; only those are uploaded
+        let (_, _, used) = prepared.convert_all(&prepared.placeholders());
+        report(progress, task_id, 20, "Attachments", format!("Uploading {} attachments...", used.len())).await;
+        let files: Vec<&VaultFile> = prepared.files.iter().filter(|f| used.contains(&f.path)).collect();
+        let assets = self.upload_attachments(kb.author_id, &files).await;
+
+        let (bodies, unresolved, _) = prepared.convert_all(&assets);
+        report(progress, task_id, 50, "Notes", format!("Creating {} notes...", prepared.plan.notes.len())).await;
+        let counts = self.save(kb_id, kb.author_id, &prepared.plan, &prepared.titles, bodies, reason).await?;
+
+        for failure in &counts.failed {
+            tracing::warn!("Import into {}: {}", kb_id, failure);
+        }
+        Ok(format!(
+            "Imported {} notes, {} folders and {} attachments; {} unresolved links, {} notes failed.",
+            counts.notes, counts.folders, assets.len(), unresolved.len(), counts.failed.len()
+        ))
+    }
+
+    /// Titles are unique across all articles: each note gets the first
+    /// candidate of `disambiguate` free both in the vault and in the database.
+    /// Returns note path -> title, and a message per renamed note.
+    pub async fn assign_titles(&self, notes: &[PlannedNote]) -> Result<(HashMap<String, String>, Vec<String>), String> {
+        let mut taken = HashSet::new();
+        let mut titles = HashMap::new();
+        let mut renamed = Vec::new();
+        for note in notes {
+            let mut attempt = 0;
+            let title = loop {
+                let candidate = disambiguate(&note.title, note.folder.as_deref(), attempt);
+                let free = !taken.contains(&candidate.to_lowercase())
+                    && self.article_repo.find_by_title(&candidate).await.map_err(|e| e.to_string())?.is_none();
+                if free {
+                    break candidate;
+                }
+                attempt += 1;
+            };
+            if title != note.title {
+                renamed.push(format!("'{}' ({}) will be imported as '{}'", note.title, note.path, title));
+            }
+            taken.insert(title.to_lowercase());
+            titles.insert(note.path.clone(), title);
+        }
+        Ok((titles, renamed))
+    }
+
+    /// Uploads attachments through the asset manager; path -> asset id.
+    pub async fn upload_attachments(&self, user_id: Uuid, files: &[&VaultFile]) -> HashMap<String, Uuid> {
+        let mut uploaded = HashMap::new();
+        for file in files {
+            let name = file_name(&file.path).to_string();
+            let mime = mime_for(&file.path).to_string();
+            // Assets are titled by file name, which must be unique too
+            let mut result = self.asset_manager.upload_asset(user_id, name.clone(), mime.clone(), &file.data).await;
+            if matches!(&result, Err(e) if e.starts_with("Duplicate title")) {
+                let suffix = &Uuid::new_v4().simple().to_string()[..8];
+                let unique = match name.rsplit_once('.') {
+                    Some((stem, ext)) => format!("{}-{}.{}", stem, suffix, ext),
+                    None => format!("{}-{}", name, suffix),
+                };
+                result = self.asset_manager.upload_asset(user_id, unique, mime, &file.data).await;
+            }
+            match result {
+                Ok(asset) => { uploaded.insert(file.path.clone(), asset.node.id); }
+                Err(e) => tracing::warn!("Failed to import attachment {}: {}", file.path, e),
+            }
+        }
+        uploaded
+    }
+
+    /// Creates the folders, then the notes with their converted bodies
+    /// (path -> Markdown). Notes that fail to save are reported, not fatal.
+    pub async fn save(
+        &self,
+        kb_id: Uuid,
+        user_id: Uuid,
+        plan: &VaultPlan,
+        titles: &HashMap<String, String>,
+        mut bodies: HashMap<String, String>,
+        reason: &str,
+    ) -> Result<ImportCounts, String> {
+        let mut folder_ids: HashMap<&str, Uuid> = HashMap::new();
+        for folder in &plan.folders {
+            let node = Node {
+                id: Uuid::new_v4(),
+                parent_id: folder.parent.as_deref().and_then(|p| folder_ids.get(p).copied()),
+                author_id: user_id,
+                knowledge_base_id: Some(kb_id),
+                r#type: NodeType::Folder,
+                title: folder.title.clone(),
+                permission_mode: PermissionMode::Private,
+                created_at: Utc::now(),
+                updated_at: Utc::now(),
+            };
+            let id = self.node_repo.save(node, UserId(user_id)).await.map_err(|e| e.to_string())?;
+            folder_ids.insert(folder.path.as_str(), id);
+        }
+
+        let mut counts = ImportCounts { folders: folder_ids.len(), notes: 0, failed: vec![] };
+        for note in &plan.notes {
+            let id = Uuid::new_v4();
+            let title = titles.get(&note.path).cloned().unwrap_or_else(|| note.title.clone());
+            let article = Article {
+                node: Node {
+                    id,
+                    parent_id: note.folder.as_deref().and_then(|f| folder_ids.get(f).copied()),
+                    author_id: user_id,
+                    knowledge_base_id: Some(kb_id),
+                    r#type: NodeType::Article,
+                    title: title.clone(),
+                    permission_mode: PermissionMode::Private,
+                    created_at: Utc::now(),
+                    updated_at: Utc::now(),
+                },
+                slug: format!("{}-{}", title.to_lowercase().replace(' ', "-"), &id.to_string()[..8]),
+                status: note.front.status.unwrap_or(ContentStatus::Draft),
+                category: note.front.category.clone(),
+                body: ContentBody::Markdown(bodies.remove(&note.path).unwrap_or_else(|| note.body.clone())),
+                tags: note.front.tags.clone(),
+                author_name: None,
+                author_avatar: None,
+                derived_data: None,
+            };
+            match self.article_repo.save(article, UserId(user_id), Some(reason.to_string())).await {
+                Ok(_) => counts.notes += 1,
+                Err(RepositoryError::ConnectionError(e)) => return Err(e),
+                Err(e) => counts.failed.push(format!("{}: {}", note.path, e)),
+            }
+        }
+        Ok(counts)
+    }
+}
@@ -1,10 +1,24 @@
 use std::collections::HashMap;
-use std::sync::{Arc, RwLock};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, SystemTime};
 
 use uuid::Uuid;
 use tokio::sync::mpsc::{self, Receiver};
 use crate::domain::portability::ports::PortabilityProvider;
-use crate::domain::portability::models::{ExportSummary, ProgressEvent};
+use crate::domain::portability::models::{ExportSummary, ImportSummary, ProgressEvent};
+use crate::infrastructure::services::site::SiteGenerator;
+
+/// Uploads not imported within this long are deleted.
+const UPLOAD_TTL: Duration = Duration::from_secs(60 * 60);
+
+/// An archive uploaded for a preview, waiting for its import to be started
+/// by the same user into the same knowledge base.
+struct PendingUpload {
+    owner_id: Uuid,
+    kb_id: Uuid,
+    uploaded_at: SystemTime,
+}
 
 pub struct PortabilityService {
     providers: HashMap<String, Arc<dyn PortabilityProvider>>,
@@ -12,13 +26,17 @@ pub struct PortabilityService {
     // In production, this might need Redis or DB to survive restarts, 
     // but for "Download" tasks, memory is usually fine.
     active_tasks: Arc<RwLock<HashMap<Uuid, Receiver<ProgressEvent>>>>,
+    uploads: Mutex<HashMap<Uuid, PendingUpload>>,
+    site_generator: Arc<SiteGenerator>,
 }
 
 impl PortabilityService {
-    pub fn new() -> Self {
+    pub fn new(site_generator: Arc<SiteGenerator>) -> Self {
         Self {
             providers: HashMap::new(),
             active_tasks: Arc::new(RwLock::new(HashMap::new())),
+            uploads: Mutex::new(HashMap::new()),
+            site_generator,
         }
     }
 
@@ -56,6 +74,86 @@ impl PortabilityService {
         Ok(task_id)
     }
 
+    pub async fn analyze_import(&self, format: &str, file_path: PathBuf) -> Result<ImportSummary, String> {
+        let provider = self.get_provider(format)?;
+        provider.analyze_import(file_path).await
+    }
+
+    /// Imports an uploaded file into `kb_id`; the file is deleted once done.
+    pub async fn start_import(&self, format: &str, kb_id: Uuid, file_path: PathBuf) -> Result<Uuid, String> {
+        let provider = self.get_provider(format)?;
+        let task_id = Uuid::new_v4();
+        let (tx, rx) = mpsc::channel(100);
+
+        self.active_tasks.write().unwrap().insert(task_id, rx);
+
+        let site_generator = self.site_generator.clone();
+        tokio::spawn(async move {
+            // A failed import may still have written part of its content
+            if let Err(e) = provider.import(kb_id, file_path.clone(), task_id, tx).await {
+                tracing::error!("Import into {} failed: {}", kb_id, e);
+            }
+            site_generator.schedule_rebuild(kb_id);
+            let _ = tokio::fs::remove_file(file_path).await;
+        });
+
+        Ok(task_id)
+    }
+
+    pub fn upload_path(upload_id: Uuid) -> PathBuf {
+        std::env::temp_dir().join(format!("import_{}.zip", upload_id))
+    }
+
+    /// Reserves a temp file for an upload by `owner_id` into `kb_id`, and
+    /// deletes uploads that were never imported.
+    pub fn register_upload(&self, owner_id: Uuid, kb_id: Uuid) -> (Uuid, PathBuf) {
+        self.sweep_uploads();
+        let upload_id = Uuid::new_v4();
+        self.uploads.lock().unwrap().insert(upload_id, PendingUpload { owner_id, kb_id, uploaded_at: SystemTime::now() });
+        (upload_id, Self::upload_path(upload_id))
+    }
+
+    /// The upload's file, handed over once: only to its owner, for the same KB.
+    pub fn take_upload(&self, upload_id: Uuid, owner_id: Uuid, kb_id: Uuid) -> Option<PathBuf> {
+        let mut uploads = self.uploads.lock().unwrap();
+        match uploads.get(&upload_id) {
+            Some(upload) if upload.owner_id == owner_id && upload.kb_id == kb_id => {
+                uploads.remove(&upload_id);
+                Some(Self::upload_path(upload_id)).filter(|path| path.exists())
+            }
+            _ => None,
+        }
+    }
+
+    pub fn discard_upload(&self, upload_id: Uuid) {
+        self.uploads.lock().unwrap().remove(&upload_id);
+        let _ = std::fs::remove_file(Self::upload_path(upload_id));
+    }
+
+    /// Deletes expired uploads, including files left over from before a restart.
+    fn sweep_uploads(&self) {
+        let expired = |at: SystemTime| at.elapsed().is_ok_and(|age| age > UPLOAD_TTL);
+        self.uploads.lock().unwrap().retain(|upload_id, upload| {
+            let keep = !expired(upload.uploaded_at);
+            if !keep {
+                let _ = std::fs::remove_file(Self::upload_path(*upload_id));
+            }
+            keep
+        });
+
+        let Ok(entries) = std::fs::read_dir(std::env::temp_dir()) else { return };
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            let Some(upload_id) = name.strip_prefix("import_").and_then(|n| n.strip_suffix(".zip")).and_then(|id| Uuid::parse_str(id).ok()) else {
+                continue;
+            };
+            let stale = entry.metadata().and_then(|m| m.modified()).is_ok_and(expired);
+            if stale && !self.uploads.lock().unwrap().contains_key(&upload_id) {
+                let _ = std::fs::remove_file(entry.path());
+            }
+        }
+    }
+
     // This is a simplified polling mechanism. 
     // Real-world would use SSE, but for now we'll just pop the latest event or peek?
     // MPSC consumes messages. So "polling" means "give me all events since last check".
@@ -0,0 +1,392 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::Path;
+use chrono::{DateTime, Utc};
+use serde::{Serialize, Deserialize};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use crate::domain::blocks::html::{self, escape, HtmlResolver};
+use crate::domain::blocks::parser::parse_markdown_to_blocks;
+use crate::domain::blocks::strategies::extract_text;
+use crate::infrastructure::services::export_service::{asset_ids, extension, html_page, page_file, STYLE};
+
+/// Bump when the page templates change so incremental builds rewrite every page.
+const RENDER_VERSION: &str = "site-v1";
+pub const MANIFEST: &str = ".site-manifest.json";
+const FEED_ITEMS: usize = 50;
+const SEARCH_TEXT_CHARS: usize = 2000;
+
+pub struct SiteArticle {
+    pub id: Uuid,
+    pub title: String,
+    pub slug: String,
+    pub tags: Vec<String>,
+    pub author: Option<String>,
+    pub markdown: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+pub struct SiteInput {
+    pub title: String,
+    pub description: Option<String>,
+    /// Absolute URL the site is served from (with trailing slash); feed links are relative without it.
+    pub base_url: Option<String>,
+    pub articles: Vec<SiteArticle>,
+}
+
+/// What the last build wrote, so the next one only touches what changed.
+#[derive(Serialize, Deserialize, Default)]
+pub struct SiteManifest {
+    pub pages: HashMap<Uuid, PageEntry>,
+    pub assets: HashMap<Uuid, String>, // Asset UUID -> file under assets/
+    pub tags: Vec<String>,             // Files under tags/
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct PageEntry {
+    pub file: String,
+    pub hash: String,
+}
+
+#[derive(Serialize, Debug, Default)]
+pub struct PublishReport {
+    pub articles: usize,
+    pub written: usize,
+    pub unchanged: usize,
+    pub removed: usize,
+    pub assets_copied: usize,
+    pub assets_removed: usize,
+    pub url: Option<String>,
+}
+
+pub fn read_manifest(dir: &Path) -> SiteManifest {
+    std::fs::read(dir.join(MANIFEST)).ok()
+        .and_then(|data| serde_json::from_slice(&data).ok())
+        .unwrap_or_default()
+}
+
+/// Assets referenced anywhere in the site.
+pub fn referenced_assets(site: &SiteInput) -> Vec<Uuid> {
+    let mut ids = Vec::new();
+    for article in &site.articles {
+        for id in asset_ids(&article.markdown) {
+            if !ids.contains(&id) {
+                ids.push(id);
+            }
+        }
+    }
+    ids
+}
+
+/// Writes `assets/<id>.<ext>` and returns the file name.
+pub fn write_asset(dir: &Path, id: Uuid, mime: &str, data: &[u8]) -> Result<String, String> {
+    let file = format!("{}.{}", id, extension(mime));
+    std::fs::create_dir_all(dir.join("assets")).map_err(|e| e.to_string())?;
+    std::fs::write(dir.join("assets").join(&file), data).map_err(|e| e.to_string())?;
+    Ok(file)
+}
+
+/// Points assets at the copies under `assets/` and wiki links at the article
+/// pages; asset URLs without a copy would reach into the app, so they are dropped.
+struct SiteResolver<'a> {
+    assets: &'a HashMap<Uuid, String>,
+    asset_prefix: &'a str,
+    pages: &'a HashMap<String, String>, // Lowercased title -> file
+    page_prefix: &'a str,
+}
+
+impl SiteResolver<'_> {
+    fn rewrite(&self, url: &str) -> Option<String> {
+        match asset_ids(url).into_iter().next() {
+            Some(id) => self.assets.get(&id).map(|file| format!("{}{}", self.asset_prefix, file)),
+            None => html::safe_url(url),
+        }
+    }
+}
+
+impl HtmlResolver for SiteResolver<'_> {
+    fn image_src(&mut self, src: &str) -> Option<String> {
+        self.rewrite(src)
+    }
+
+    fn link_href(&mut self, href: &str) -> Option<String> {
+        self.rewrite(href)
+    }
+
+    fn wiki_href(&self, title: &str) -> Option<String> {
+        self.pages.get(&title.to_lowercase()).map(|file| format!("{}{}", self.page_prefix, file))
+    }
+}
+
+fn hash(parts: &[&str]) -> String {
+    let mut hasher = Sha256::new();
+    for part in parts {
+        hasher.update(part.len().to_le_bytes());
+        hasher.update(part.as_bytes());
+    }
+    format!("{:x}", hasher.finalize())
+}
+
+fn tag_file(tag: &str) -> String {
+    let name: String = tag.to_lowercase().chars()
+        .map(|c| if c.is_alphanumeric() || c == '-' || c == '_' { c } else { '-' })
+        .collect();
+    format!("{}-{}.html", name.trim_matches('-'), &hash(&[tag])[..8])
+}
+
+fn plain_text(markdown: &str) -> String {
+    let text: Vec<String> = parse_markdown_to_blocks(Uuid::nil(), markdown).iter()
+        .map(|b| extract_text(&b.type_name, &b.payload))
+        .filter(|t| !t.is_empty())
+        .collect();
+    let text = text.join(" ").split_whitespace().collect::<Vec<_>>().join(" ");
+    text.chars().take(SEARCH_TEXT_CHARS).collect()
+}
+
+fn write(dir: &Path, file: &str, content: &str) -> Result<(), String> {
+    let path = dir.join(file);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    std::fs::write(path, content).map_err(|e| e.to_string())
+}
+
+fn remove(dir: &Path, file: &str) {
+    if let Err(e) = std::fs::remove_file(dir.join(file)) {
+        if e.kind() != std::io::ErrorKind::NotFound {
+            tracing::warn!("Failed to remove stale site file {}: {}", file, e);
+        }
+    }
+}
+
+/// Renders `site` into `dir`. Article pages whose inputs are unchanged since
+/// the manifest was written are kept as they are (unless `full`); the index,
+/// tag pages, search index and feed are cheap and always regenerated. `assets`
+/// are the copies already under `assets/`; any other file there is removed.
+pub fn build_site(dir: &Path, site: &SiteInput, assets: &HashMap<Uuid, String>, full: bool) -> Result<PublishReport, String> {
+    std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    let previous = read_manifest(dir);
+    let mut report = PublishReport { articles: site.articles.len(), ..Default::default() };
+
+    // 1. Stable file names: keep the previous one, name new pages after their slug
+    let mut taken = HashSet::new();
+    let mut files: HashMap<Uuid, String> = HashMap::new();
+    for article in &site.articles {
+        if let Some(entry) = previous.pages.get(&article.id) {
+            if taken.insert(entry.file.trim_end_matches(".html").to_string()) {
+                files.insert(article.id, entry.file.clone());
+            }
+        }
+    }
+    for article in &site.articles {
+        files.entry(article.id).or_insert_with(|| page_file(&article.slug, article.id, &mut taken));
+    }
+    let by_title: HashMap<String, String> = site.articles.iter()
+        .map(|a| (a.title.to_lowercase(), files[&a.id].clone()))
+        .collect();
+
+    // Everything a page depends on besides its own article: titles (wiki links) and the site title (nav)
+    let mut titles: Vec<(&String, &String)> = by_title.iter().collect();
+    titles.sort();
+    let fingerprint = hash(&[
+        RENDER_VERSION,
+        &site.title,
+        &titles.iter().map(|(t, f)| format!("{}\t{}", t, f)).collect::<Vec<_>>().join("\n"),
+    ]);
+
+    // 2. Article pages
+    let mut pages = HashMap::new();
+    for article in &site.articles {
+        let file = &files[&article.id];
+        let page_assets: Vec<String> = asset_ids(&article.markdown).iter()
+            .map(|id| assets.get(id).cloned().unwrap_or_default())
+            .collect();
+        let page_hash = hash(&[
+            &fingerprint,
+            &article.title,
+            &article.tags.join("\n"),
+            &article.markdown,
+            &page_assets.join("\n"),
+        ]);
+        let unchanged = !full
+            && previous.pages.get(&article.id).is_some_and(|p| p.file == *file && p.hash == page_hash)
+            && dir.join("articles").join(file).exists();
+
+        if unchanged {
+            report.unchanged += 1;
+        } else {
+            let tags: String = article.tags.iter()
+                .map(|t| format!(" · <a href=\"../tags/{}\">#{}</a>", tag_file(t), escape(t)))
+                .collect();
+            let nav = format!("<nav class=\"site\"><a href=\"../index.html\">← {}</a>{}</nav>\n", escape(&site.title), tags);
+            let mut resolver = SiteResolver { assets, asset_prefix: "../assets/", pages: &by_title, page_prefix: "" };
+            let page = html_page(&article.title, &article.markdown, &[], Some(&nav), &mut resolver);
+            write(dir, &format!("articles/{}", file), &page)?;
+            report.written += 1;
+        }
+        pages.insert(article.id, PageEntry { file: file.clone(), hash: page_hash });
+    }
+    for (id, entry) in &previous.pages {
+        let kept = pages.get(id).is_some_and(|p| p.file == entry.file);
+        if !kept && !pages.values().any(|p| p.file == entry.file) {
+            remove(dir, &format!("articles/{}", entry.file));
+            report.removed += 1;
+        }
+    }
+
+    // Newest first everywhere below
+    let mut articles: Vec<&SiteArticle> = site.articles.iter().collect();
+    articles.sort_by(|a, b| b.created_at.cmp(&a.created_at).then_with(|| a.title.cmp(&b.title)));
+    let list_item = |a: &SiteArticle, prefix: &str| {
+        format!("- [{}]({}{}) — {}\n", a.title.replace(['[', ']'], ""), prefix, files[&a.id], a.created_at.format("%Y-%m-%d"))
+    };
+    let mut resolver = SiteResolver { assets, asset_prefix: "assets/", pages: &by_title, page_prefix: "articles/" };
+
+    // 3. Tag pages
+    let mut by_tag: BTreeMap<&str, Vec<&SiteArticle>> = BTreeMap::new();
+    for article in &articles {
+        for tag in &article.tags {
+            by_tag.entry(tag.as_str()).or_default().push(article);
+        }
+    }
+    let nav = format!("<nav class=\"site\"><a href=\"../index.html\">← {}</a></nav>\n", escape(&site.title));
+    let mut tag_files = Vec::new();
+    let mut tag_index = String::new();
+    for (tag, tagged) in &by_tag {
+        let file = tag_file(tag);
+        let list: String = tagged.iter().map(|a| list_item(a, "../articles/")).collect();
+        write(dir, &format!("tags/{}", file), &html_page(&format!("#{}", tag), &list, &[], Some(&nav), &mut resolver))?;
+        tag_index.push_str(&format!("- [#{}]({}) ({})\n", tag.replace(['[', ']'], ""), file, tagged.len()));
+        tag_files.push(file);
+    }
+    write(dir, "tags/index.html", &html_page("Tags", &tag_index, &[], Some(&nav), &mut resolver))?;
+    for stale in previous.tags.iter().filter(|f| !tag_files.contains(f)) {
+        remove(dir, &format!("tags/{}", stale));
+    }
+
+    // 4. Index
+    let mut index = String::new();
+    if let Some(description) = site.description.as_deref().filter(|d| !d.is_empty()) {
+        index.push_str(&format!("{}\n\n", description));
+    }
+    index.push_str("[Search](search.html) · [Tags](tags/index.html) · [RSS](feed.xml)\n\n");
+    for article in &articles {
+        index.push_str(&list_item(article, "articles/"));
+    }
+    write(dir, "index.html", &html_page(&site.title, &index, &[], None, &mut resolver))?;
+
+    // 5. Search: a JSON index, also inlined into the search page so it works from file://
+    let entries: Vec<serde_json::Value> = articles.iter().map(|a| serde_json::json!({
+        "title": a.title,
+        "url": format!("articles/{}", files[&a.id]),
+        "tags": a.tags,
+        "date": a.created_at.to_rfc3339(),
+        "text": plain_text(&a.markdown),
+    })).collect();
+    let search_index = serde_json::to_string(&entries).map_err(|e| e.to_string())?;
+    write(dir, "search-index.json", &search_index)?;
+    write(dir, "search.html", &search_page(&site.title, &search_index))?;
+
+    // 6. Feed
+    write(dir, "feed.xml", &rss_feed(site, &articles, &files, assets, &by_title))?;
+
+    // 7. Assets nobody references any more
+    if let Ok(entries) = std::fs::read_dir(dir.join("assets")) {
+        let live: HashSet<&String> = assets.values().collect();
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if !live.contains(&name) {
+                remove(dir, &format!("assets/{}", name));
+                report.assets_removed += 1;
+            }
+        }
+    }
+
+    let manifest = SiteManifest { pages, assets: assets.clone(), tags: tag_files };
+    write(dir, MANIFEST, &serde_json::to_string_pretty(&manifest).map_err(|e| e.to_string())?)?;
+    report.url = site.base_url.clone();
+    Ok(report)
+}
+
+fn search_page(title: &str, index: &str) -> String {
+    // `</` cannot appear inside a script element
+    let index = index.replace("</", "<\\/");
+    format!(
+        r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<title>Search · {title}</title>
+<style>{STYLE}
+#q {{ width: 100%; font-size: 1.1em; padding: .4rem; }} .hit p {{ margin: .2rem 0 1rem; color: #555; }}</style>
+</head>
+<body>
+<nav class="site"><a href="index.html">← {title}</a></nav>
+<h1>Search</h1>
+<input id="q" type="search" placeholder="Search articles..." autofocus>
+<div id="results"></div>
+<script type="application/json" id="search-index">{index}</script>
+<script>
+const index = JSON.parse(document.getElementById('search-index').textContent);
+const results = document.getElementById('results');
+const esc = s => s.replace(/[&<>"]/g, c => ({{ '&': '&amp;', '<': '&lt;', '>': '&gt;', '"': '&quot;' }})[c]);
+function search(query) {{
+  const terms = query.toLowerCase().split(/\s+/).filter(Boolean);
+  const hits = terms.length === 0 ? [] : index.map(entry => {{
+    const title = entry.title.toLowerCase(), tags = entry.tags.join(' ').toLowerCase(), text = entry.text.toLowerCase();
+    let score = 0;
+    for (const term of terms) {{
+      const s = (title.includes(term) ? 10 : 0) + (tags.includes(term) ? 5 : 0) + (text.includes(term) ? 1 : 0);
+      if (s === 0) return null;
+      score += s;
+    }}
+    return {{ entry, score }};
+  }}).filter(Boolean).sort((a, b) => b.score - a.score);
+  results.innerHTML = hits.map(({{ entry }}) =>
+    `<div class="hit"><a href="${{esc(entry.url)}}">${{esc(entry.title)}}</a><p>${{esc(entry.text.slice(0, 160))}}</p></div>`
+  ).join('');
+}}
+const q = document.getElementById('q');
+q.addEventListener('input', () => search(q.value));
+q.value = new URLSearchParams(location.search).get('q') || '';
+search(q.value);
+</script>
+</body>
+</html>
+"#,
+        title = escape(title),
+    )
+}
+
+fn rss_feed(
+    site: &SiteInput,
+    articles: &[&SiteArticle],
+    files: &HashMap<Uuid, String>,
+    assets: &HashMap<Uuid, String>,
+    by_title: &HashMap<String, String>,
+) -> String {
+    let base = site.base_url.as_deref().unwrap_or("");
+    let asset_prefix = format!("{}assets/", base);
+    let page_prefix = format!("{}articles/", base);
+    let items: String = articles.iter().take(FEED_ITEMS).map(|a| {
+        let link = format!("{}{}", page_prefix, files[&a.id]);
+        let mut resolver = SiteResolver { assets, asset_prefix: &asset_prefix, pages: by_title, page_prefix: &page_prefix };
+        let body = html::render_html(&parse_markdown_to_blocks(a.id, &a.markdown), &mut resolver);
+        let categories: String = a.tags.iter().map(|t| format!("<category>{}</category>", escape(t))).collect();
+        let author = a.author.as_deref().map(|n| format!("<dc:creator>{}</dc:creator>", escape(n))).unwrap_or_default();
+        format!(
+            "<item><title>{}</title><link>{}</link><guid isPermaLink=\"false\">{}</guid><pubDate>{}</pubDate>{}{}<description>{}</description></item>\n",
+            escape(&a.title), escape(&link), a.id, a.created_at.to_rfc2822(), author, categories, escape(&body)
+        )
+    }).collect();
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<rss version=\"2.0\" xmlns:dc=\"http://purl.org/dc/elements/1.1/\">\n<channel>\n<title>{}</title>\n<link>{}</link>\n<description>{}</description>\n<lastBuildDate>{}</lastBuildDate>\n{}</channel>\n</rss>\n",
+        escape(&site.title),
+        escape(if base.is_empty() { "index.html" } else { base }),
+        escape(site.description.as_deref().unwrap_or(&site.title)),
+        Utc::now().to_rfc2822(),
+        items
+    )
+}
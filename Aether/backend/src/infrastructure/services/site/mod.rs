@@ -0,0 +1,183 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
+
+use crate::domain::models::{ContentBody, ContentItem, ContentStatus, KnowledgeBaseId, PermissionMode, UserId, Visibility};
+use crate::domain::ports::{ArticleRepository, KnowledgeBaseRepository};
+use crate::infrastructure::services::asset_manager::AssetManager;
+
+pub mod builder;
+
+mod tests;
+
+use builder::{build_site, read_manifest, referenced_assets, write_asset, PublishReport, SiteArticle, SiteInput, MANIFEST};
+
+/// Renders public knowledge bases into static sites under `public_sites/<kb_id>/`.
+#[derive(Clone)]
+pub struct SiteGenerator {
+    article_repo: Arc<dyn ArticleRepository>,
+    kb_repo: Arc<dyn KnowledgeBaseRepository>,
+    asset_manager: Arc<AssetManager>,
+    sites_root: PathBuf,
+    base_url: Option<String>,
+    // One build at a time per KB
+    locks: Arc<Mutex<HashMap<Uuid, Arc<tokio::sync::Mutex<()>>>>>,
+}
+
+impl SiteGenerator {
+    pub fn new(
+        article_repo: Arc<dyn ArticleRepository>,
+        kb_repo: Arc<dyn KnowledgeBaseRepository>,
+        asset_manager: Arc<AssetManager>,
+        storage_root: String,
+        base_url: Option<String>,
+    ) -> Self {
+        let sites_root = PathBuf::from(storage_root).join("public_sites");
+        std::fs::create_dir_all(&sites_root).unwrap_or_default();
+
+        Self {
+            article_repo,
+            kb_repo,
+            asset_manager,
+            sites_root,
+            base_url: base_url.map(|u| u.trim_end_matches('/').to_string()),
+            locks: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    pub fn site_dir(&self, kb_id: Uuid) -> PathBuf {
+        self.sites_root.join(kb_id.to_string())
+    }
+
+    /// A KB is published once a build has written its manifest.
+    pub fn is_published(&self, kb_id: Uuid) -> bool {
+        self.site_dir(kb_id).join(MANIFEST).exists()
+    }
+
+    fn lock(&self, kb_id: Uuid) -> Arc<tokio::sync::Mutex<()>> {
+        self.locks.lock().unwrap().entry(kb_id).or_default().clone()
+    }
+
+    /// Builds (or incrementally rebuilds) the site of a public KB. `requester`
+    /// must be the author; the CLI passes `None`.
+    pub async fn publish(&self, kb_id: Uuid, requester: Option<Uuid>, full: bool) -> Result<PublishReport, String> {
+        let kb = self.kb_repo.find_by_id(&KnowledgeBaseId(kb_id))
+            .await.map_err(|e| e.to_string())?
+            .ok_or("Knowledge Base not found")?;
+
+        if requester.is_some_and(|id| id != kb.author_id) {
+            return Err("Unauthorized".to_string());
+        }
+        if kb.visibility != Visibility::Public {
+            return Err("Only public knowledge bases can be published".to_string());
+        }
+
+        let lock = self.lock(kb_id);
+        let _guard = lock.lock().await;
+
+        // 1. Published, public articles
+        let items = self.article_repo.list(Some(UserId(kb.author_id)), None, Some(kb_id), None, None, 10000, 0)
+            .await.map_err(|e| e.to_string())?;
+        let articles: Vec<SiteArticle> = items.into_iter().filter_map(|item| {
+            let ContentItem::Article(article) = item else { return None };
+            if article.status != ContentStatus::Published || article.node.permission_mode != PermissionMode::Public {
+                return None;
+            }
+            let ContentBody::Markdown(markdown) = article.body else { return None };
+            Some(SiteArticle {
+                id: article.node.id,
+                title: article.node.title,
+                slug: article.slug,
+                tags: article.tags,
+                author: article.author_name,
+                markdown,
+                created_at: article.node.created_at,
+                updated_at: article.node.updated_at,
+            })
+        }).collect();
+
+        let site = SiteInput {
+            title: kb.title.clone(),
+            description: kb.description.clone(),
+            base_url: self.base_url.as_ref().map(|u| format!("{}/{}/", u, kb_id)),
+            articles,
+        };
+
+        // 2. Copy the assets the previous build has not
+        let dir = self.site_dir(kb_id);
+        let previous = read_manifest(&dir);
+        let mut assets = HashMap::new();
+        let mut copied = 0;
+        for id in referenced_assets(&site) {
+            if let Some(file) = previous.assets.get(&id).filter(|f| dir.join("assets").join(f).exists()) {
+                assets.insert(id, file.clone());
+                continue;
+            }
+            let context = site.articles.iter().find(|a| a.markdown.contains(&id.to_string())).map(|a| a.id);
+            let loaded = match self.asset_manager.get_asset_file(id, context, kb.author_id).await {
+                Ok((path, mime)) => tokio::fs::read(&path).await.map(|data| (mime, data)).map_err(|e| e.to_string()),
+                Err(e) => Err(e),
+            };
+            match loaded {
+                Ok((mime, data)) => {
+                    assets.insert(id, write_asset(&dir, id, &mime, &data)?);
+                    copied += 1;
+                }
+                Err(e) => tracing::warn!("Skipping asset {} in site of KB {}: {}", id, kb_id, e),
+            }
+        }
+
+        // 3. Render (blocking file IO)
+        let mut report = tokio::task::spawn_blocking(move || build_site(&dir, &site, &assets, full))
+            .await.map_err(|e| e.to_string())??;
+        report.assets_copied = copied;
+        if report.url.is_none() {
+            report.url = Some(format!("/sites/{}/", kb_id));
+        }
+        tracing::info!("Published KB {}: {} written, {} unchanged, {} removed", kb_id, report.written, report.unchanged, report.removed);
+        Ok(report)
+    }
+
+    pub async fn unpublish(&self, kb_id: Uuid, requester: Uuid) -> Result<(), String> {
+        let kb = self.kb_repo.find_by_id(&KnowledgeBaseId(kb_id))
+            .await.map_err(|e| e.to_string())?
+            .ok_or("Knowledge Base not found")?;
+        if kb.author_id != requester {
+            return Err("Unauthorized".to_string());
+        }
+        self.remove_site(kb_id).await
+    }
+
+    async fn remove_site(&self, kb_id: Uuid) -> Result<(), String> {
+        let lock = self.lock(kb_id);
+        let _guard = lock.lock().await;
+        match tokio::fs::remove_dir_all(self.site_dir(kb_id)).await {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.to_string()),
+            _ => Ok(()),
+        }
+    }
+
+    /// Incrementally rebuilds a published site in the background after its
+    /// content changed; a KB that is no longer public is taken down instead.
+    pub fn schedule_rebuild(&self, kb_id: Uuid) {
+        if !self.is_published(kb_id) {
+            return;
+        }
+        let generator = self.clone();
+        tokio::spawn(async move {
+            let public = matches!(
+                generator.kb_repo.find_by_id(&KnowledgeBaseId(kb_id)).await,
+                Ok(Some(kb)) if kb.visibility == Visibility::Public
+            );
+            let result = if public {
+                generator.publish(kb_id, None, false).await.map(|_| ())
+            } else {
+                generator.remove_site(kb_id).await
+            };
+            if let Err(e) = result {
+                tracing::error!("Site rebuild failed for KB {}: {}", kb_id, e);
+            }
+        });
+    }
+}
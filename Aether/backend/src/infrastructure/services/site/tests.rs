@@ -0,0 +1,119 @@
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::path::Path;
+    use chrono::{Duration, Utc};
+    use quick_xml::events::Event;
+    use quick_xml::Reader;
+    use uuid::Uuid;
+    use crate::infrastructure::services::site::builder::{build_site, referenced_assets, write_asset, SiteArticle, SiteInput};
+
+    fn article(title: &str, tags: &[&str], markdown: &str, age_days: i64) -> SiteArticle {
+        let date = Utc::now() - Duration::days(age_days);
+        SiteArticle {
+            id: Uuid::new_v4(),
+            title: title.to_string(),
+            slug: title.to_lowercase().replace(' ', "-"),
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            author: Some("Ada".to_string()),
+            markdown: markdown.to_string(),
+            created_at: date,
+            updated_at: date,
+        }
+    }
+
+    fn read(dir: &Path, file: &str) -> String {
+        std::fs::read_to_string(dir.join(file)).unwrap_or_else(|_| panic!("missing {}", file))
+    }
+
+    #[test]
+    fn test_static_site_incremental_build() {
+        let dir = std::env::temp_dir().join(format!("aether_site_test_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+
+        let image = Uuid::new_v4();
+        let private = Uuid::new_v4();
+        let first = article("First Post", &["rust", "notes"], &format!(
+            "Hello! See [[Second Post]].\n\n![figure](/api/assets/{})\n\n![secret]([[asset:{}]])\n\n<script>x()</script>\n", image, private
+        ), 2);
+        let second = article("Second Post", &["rust"], "Searchable **words** here.\n", 1);
+        let mut site = SiteInput {
+            title: "My <KB>".to_string(),
+            description: Some("A public knowledge base".to_string()),
+            base_url: Some("https://example.org/sites/kb/".to_string()),
+            articles: vec![first, second],
+        };
+        assert_eq!(referenced_assets(&site), vec![image, private]);
+
+        // Only `image` could be copied
+        let mut assets = HashMap::new();
+        assets.insert(image, write_asset(&dir, image, "image/png", b"png").unwrap());
+
+        let report = build_site(&dir, &site, &assets, false).unwrap();
+        assert_eq!((report.written, report.unchanged, report.removed), (2, 0, 0));
+
+        let first_page = read(&dir, "articles/first-post.html");
+        assert!(first_page.contains(&format!("src=\"../assets/{}.png\"", image)));
+        assert!(!first_page.contains(&private.to_string()));
+        assert!(!first_page.contains("/api/assets/"));
+        assert!(first_page.contains("href=\"second-post.html\""));
+        assert!(first_page.contains("<!-- raw HTML omitted -->"));
+        assert!(first_page.contains("href=\"../tags/"));
+
+        let index = read(&dir, "index.html");
+        assert!(index.contains("<title>My &lt;KB&gt;</title>"));
+        // Newest first
+        assert!(index.find("articles/second-post.html").unwrap() < index.find("articles/first-post.html").unwrap());
+        let tags = read(&dir, "tags/index.html");
+        assert!(tags.contains("#rust") && tags.contains("(2)"));
+
+        let search: Vec<serde_json::Value> = serde_json::from_str(&read(&dir, "search-index.json")).unwrap();
+        assert_eq!(search.len(), 2);
+        assert_eq!(search[0]["url"], "articles/second-post.html");
+        assert!(search[0]["text"].as_str().unwrap().contains("words"));
+        assert!(read(&dir, "search.html").contains("Searchable"));
+
+        // The feed is well-formed, with absolute links
+        let feed = read(&dir, "feed.xml");
+        let mut reader = Reader::from_str(&feed);
+        let mut items = 0;
+        loop {
+            match reader.read_event().unwrap_or_else(|e| panic!("malformed feed: {}\n{}", e, feed)) {
+                Event::Start(e) if e.name().as_ref() == b"item" => items += 1,
+                Event::Eof => break,
+                _ => {}
+            }
+        }
+        assert_eq!(items, 2);
+        assert!(feed.contains("<link>https://example.org/sites/kb/articles/first-post.html</link>"));
+
+        // Unchanged content: nothing rewritten
+        let report = build_site(&dir, &site, &assets, false).unwrap();
+        assert_eq!((report.written, report.unchanged), (0, 2));
+
+        // Editing one article rewrites only that page; `full` rewrites all
+        site.articles[1].markdown.push_str("\nMore.\n");
+        let report = build_site(&dir, &site, &assets, false).unwrap();
+        assert_eq!((report.written, report.unchanged), (1, 1));
+        assert!(read(&dir, "articles/second-post.html").contains("More."));
+        let report = build_site(&dir, &site, &assets, true).unwrap();
+        assert_eq!(report.written, 2);
+
+        // Renaming a page another one links to rebuilds the linking page too
+        site.articles[1].title = "Renamed".to_string();
+        let report = build_site(&dir, &site, &assets, false).unwrap();
+        assert_eq!(report.written, 2);
+        assert_eq!(read(&dir, "articles/second-post.html").matches("Renamed").count(), 2);
+
+        // Unpublishing an article removes its page, its tag and its now unused asset
+        site.articles.remove(0);
+        let report = build_site(&dir, &site, &HashMap::new(), false).unwrap();
+        assert_eq!((report.removed, report.assets_removed), (1, 1));
+        assert!(!dir.join("articles/first-post.html").exists());
+        assert!(!dir.join("assets").join(format!("{}.png", image)).exists());
+        assert!(!read(&dir, "tags/index.html").contains("#notes"));
+        assert_eq!(std::fs::read_dir(dir.join("tags")).unwrap().count(), 2); // index + rust
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,123 @@
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use tokio::io::AsyncWriteExt;
+use uuid::Uuid;
+
+/// Files addressed by the SHA-256 of their content, at `<root>/objects/ab/<hash>`.
+/// Asset articles used to be stored at `<root>/ab/<hash>`; those are still
+/// found until `AssetGcService::migrate_layout` moves them.
+#[derive(Debug, Clone)]
+pub struct BlobStore {
+    root: PathBuf,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BlobInfo {
+    pub hash: String,
+    pub path: PathBuf,
+    pub size: u64,
+    pub modified: DateTime<Utc>,
+    /// In the old `<root>/ab/<hash>` layout.
+    pub legacy: bool,
+}
+
+pub fn content_hash(data: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(data))
+}
+
+pub fn is_hash(name: &str) -> bool {
+    name.len() == 64 && name.bytes().all(|b| b.is_ascii_digit() || (b'a'..=b'f').contains(&b))
+}
+
+/// Marks a blob as just written, so a sweep in progress keeps it.
+fn touch(path: &Path) -> Result<(), String> {
+    std::fs::File::options().write(true).open(path)
+        .and_then(|file| file.set_modified(SystemTime::now()))
+        .map_err(|e| e.to_string())
+}
+
+impl BlobStore {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    pub fn path(&self, hash: &str) -> PathBuf {
+        self.root.join("objects").join(&hash[0..2]).join(hash)
+    }
+
+    pub fn legacy_path(&self, hash: &str) -> PathBuf {
+        self.root.join(&hash[0..2]).join(hash)
+    }
+
+    /// How records refer to a blob: its path from the working directory.
+    pub fn storage_path(&self, hash: &str) -> String {
+        self.path(hash).to_string_lossy().replace('\\', "/")
+    }
+
+    /// Where the blob is, in either layout.
+    pub fn locate(&self, hash: &str) -> Option<PathBuf> {
+        if !is_hash(hash) {
+            return None;
+        }
+        [self.path(hash), self.legacy_path(hash)].into_iter().find(|p| p.is_file())
+    }
+
+    /// Stores `data` once and returns its hash. The file appears complete or
+    /// not at all: it is written aside, then renamed into place.
+    pub async fn put(&self, data: &[u8]) -> Result<String, String> {
+        let hash = content_hash(data);
+        let path = self.path(&hash);
+        if tokio::fs::metadata(&path).await.is_ok_and(|m| m.len() == data.len() as u64) {
+            touch(&path)?;
+            return Ok(hash);
+        }
+
+        let dir = self.root.join("objects").join(&hash[0..2]);
+        tokio::fs::create_dir_all(&dir).await.map_err(|e| e.to_string())?;
+        let temp_path = dir.join(format!(".{}.{}.tmp", hash, Uuid::new_v4().simple()));
+        let mut file = tokio::fs::File::create(&temp_path).await.map_err(|e| e.to_string())?;
+        file.write_all(data).await.map_err(|e| e.to_string())?;
+        file.sync_all().await.map_err(|e| e.to_string())?;
+        drop(file);
+        if let Err(e) = tokio::fs::rename(&temp_path, &path).await {
+            let _ = tokio::fs::remove_file(&temp_path).await;
+            return Err(e.to_string());
+        }
+        Ok(hash)
+    }
+
+    /// Every blob on disk, in both layouts. Other files under the root
+    /// (avatars, temporary files) are not blobs and are left out.
+    pub fn list(&self) -> Vec<BlobInfo> {
+        let mut blobs = Vec::new();
+        for (dir, legacy) in [(self.root.join("objects"), false), (self.root.clone(), true)] {
+            let Ok(shards) = std::fs::read_dir(&dir) else { continue };
+            for shard in shards.flatten() {
+                let prefix = shard.file_name().to_string_lossy().to_string();
+                if prefix.len() != 2 || !shard.path().is_dir() {
+                    continue;
+                }
+                let Ok(files) = std::fs::read_dir(shard.path()) else { continue };
+                for file in files.flatten() {
+                    let hash = file.file_name().to_string_lossy().to_string();
+                    let Ok(meta) = file.metadata() else { continue };
+                    if !is_hash(&hash) || !hash.starts_with(&prefix) || !meta.is_file() {
+                        continue;
+                    }
+                    blobs.push(BlobInfo {
+                        hash,
+                        path: file.path(),
+                        size: meta.len(),
+                        modified: meta.modified().map(DateTime::<Utc>::from).unwrap_or_else(|_| Utc::now()),
+                        legacy,
+                    });
+                }
+            }
+        }
+        blobs.sort_by(|a, b| a.hash.cmp(&b.hash).then(a.legacy.cmp(&b.legacy)));
+        blobs
+    }
+}
@@ -0,0 +1,263 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use uuid::Uuid;
+use crate::domain::models::{AssetRecord, AssetUsage};
+use crate::domain::ports::AssetRepository;
+use crate::infrastructure::storage::blob_store::{content_hash, is_hash, BlobInfo, BlobStore};
+
+/// Blobs younger than this are never swept: their record may not be saved yet.
+pub const GRACE_PERIOD_HOURS: i64 = 1;
+
+/// What is stored and what is wasted. Unused assets are kept (they are the
+/// users' uploads) but listed; orphan blobs go at the next collection.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct StorageReport {
+    pub blobs: usize,
+    pub total_bytes: u64,
+    pub legacy_blobs: usize,
+    pub records: usize,
+    /// Blobs held by more than one record.
+    pub shared_blobs: usize,
+    /// Blobs no record holds.
+    pub orphan_blobs: Vec<BlobInfo>,
+    pub reclaimable_bytes: u64,
+    /// Records whose blob is gone.
+    pub missing_blobs: Vec<AssetRecord>,
+    /// Records nothing references.
+    pub unused_assets: Vec<AssetRecord>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct GcReport {
+    pub dry_run: bool,
+    pub marked: usize,
+    pub deleted: Vec<String>,
+    pub freed_bytes: u64,
+    pub errors: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct MigrationReport {
+    pub dry_run: bool,
+    /// Legacy blobs copied to the unified layout.
+    pub moved: usize,
+    /// Legacy blobs the unified layout already had.
+    pub merged: usize,
+    /// Records pointed at the unified layout.
+    pub repointed: usize,
+    pub errors: Vec<String>,
+}
+
+/// An asset, how many records share its blob, and where it is used.
+#[derive(Debug, Clone, Serialize)]
+pub struct AssetReferences {
+    pub asset: AssetRecord,
+    pub blob_ref_count: usize,
+    pub usages: Vec<AssetUsage>,
+}
+
+/// Blob hash -> number of records holding it.
+pub fn ref_counts(records: &[AssetRecord]) -> HashMap<String, usize> {
+    let mut counts = HashMap::new();
+    for record in records {
+        *counts.entry(record.hash.clone()).or_insert(0) += 1;
+    }
+    counts
+}
+
+/// The blobs no record holds, past the grace period.
+pub fn sweepable<'a>(blobs: &'a [BlobInfo], counts: &HashMap<String, usize>, now: DateTime<Utc>) -> Vec<&'a BlobInfo> {
+    blobs.iter()
+        .filter(|b| !counts.contains_key(&b.hash) && b.modified + Duration::hours(GRACE_PERIOD_HOURS) <= now)
+        .collect()
+}
+
+/// The records no usage refers to.
+pub fn unused<'a>(records: &'a [AssetRecord], usages: &[AssetUsage]) -> Vec<&'a AssetRecord> {
+    let used: HashSet<Uuid> = usages.iter().map(|u| u.asset_id).collect();
+    records.iter().filter(|r| !used.contains(&r.id)).collect()
+}
+
+/// Mark-and-sweep collection of the blob store, with reports and the move
+/// of legacy blobs. Collection and migration never run at the same time.
+#[derive(Clone)]
+pub struct AssetGcService {
+    repo: Arc<dyn AssetRepository>,
+    blobs: Arc<BlobStore>,
+    running: Arc<tokio::sync::Mutex<()>>,
+}
+
+impl AssetGcService {
+    pub fn new(repo: Arc<dyn AssetRepository>, blobs: Arc<BlobStore>) -> Self {
+        Self { repo, blobs, running: Arc::new(tokio::sync::Mutex::new(())) }
+    }
+
+    async fn list_blobs(&self) -> Result<Vec<BlobInfo>, String> {
+        let blobs = self.blobs.clone();
+        tokio::task::spawn_blocking(move || blobs.list()).await.map_err(|e| e.to_string())
+    }
+
+    async fn records(&self) -> Result<Vec<AssetRecord>, String> {
+        self.repo.list_asset_records().await.map_err(|e| e.to_string())
+    }
+
+    pub async fn report(&self) -> Result<StorageReport, String> {
+        let blobs = self.list_blobs().await?;
+        let records = self.records().await?;
+        let usages = self.repo.list_asset_usages(None).await.map_err(|e| e.to_string())?;
+        let counts = ref_counts(&records);
+
+        let orphan_blobs: Vec<BlobInfo> = blobs.iter().filter(|b| !counts.contains_key(&b.hash)).cloned().collect();
+        let on_disk: HashSet<&str> = blobs.iter().map(|b| b.hash.as_str()).collect();
+        Ok(StorageReport {
+            blobs: blobs.len(),
+            total_bytes: blobs.iter().map(|b| b.size).sum(),
+            legacy_blobs: blobs.iter().filter(|b| b.legacy).count(),
+            records: records.len(),
+            shared_blobs: counts.values().filter(|c| **c > 1).count(),
+            reclaimable_bytes: orphan_blobs.iter().map(|b| b.size).sum(),
+            orphan_blobs,
+            missing_blobs: records.iter().filter(|r| !on_disk.contains(r.hash.as_str())).cloned().collect(),
+            unused_assets: unused(&records, &usages).into_iter().cloned().collect(),
+        })
+    }
+
+    /// Deletes the blobs no record holds. The blobs are listed before the
+    /// records are read, so one uploaded meanwhile is unlisted or too young.
+    pub async fn collect(&self, dry_run: bool) -> Result<GcReport, String> {
+        let _running = self.running.lock().await;
+        let blobs = self.list_blobs().await?;
+        let counts = ref_counts(&self.records().await?);
+
+        let mut report = GcReport { dry_run, marked: counts.len(), ..Default::default() };
+        for blob in sweepable(&blobs, &counts, Utc::now()) {
+            // Stored again since it was listed: a record is on its way
+            let touched = tokio::fs::metadata(&blob.path).await.ok()
+                .and_then(|m| m.modified().ok())
+                .is_some_and(|at| DateTime::<Utc>::from(at) > blob.modified);
+            if touched {
+                continue;
+            }
+            if !dry_run {
+                if let Err(e) = tokio::fs::remove_file(&blob.path).await {
+                    report.errors.push(format!("{}: {}", blob.hash, e));
+                    continue;
+                }
+            }
+            report.deleted.push(blob.hash.clone());
+            report.freed_bytes += blob.size;
+        }
+        Ok(report)
+    }
+
+    /// Moves the blobs of the legacy layout into the unified one: each is
+    /// copied and checked, the records are pointed at the copy, and only then
+    /// is the original deleted. Reads find either, so it can stop anywhere
+    /// and run again.
+    pub async fn migrate_layout(&self, dry_run: bool) -> Result<MigrationReport, String> {
+        let _running = self.running.lock().await;
+        let blobs = self.list_blobs().await?;
+        let mut report = MigrationReport { dry_run, ..Default::default() };
+
+        // 1. Copy
+        let mut unified: HashSet<String> = blobs.iter().filter(|b| !b.legacy).map(|b| b.hash.clone()).collect();
+        let mut copied = Vec::new();
+        for blob in blobs.iter().filter(|b| b.legacy) {
+            let data = match tokio::fs::read(&blob.path).await {
+                Ok(data) => data,
+                Err(e) => {
+                    report.errors.push(format!("{}: {}", blob.hash, e));
+                    continue;
+                }
+            };
+            if content_hash(&data) != blob.hash {
+                report.errors.push(format!("{}: content does not match its name, left in place", blob.hash));
+                continue;
+            }
+            let target = self.blobs.path(&blob.hash);
+            let intact = unified.contains(&blob.hash)
+                && tokio::fs::read(&target).await.is_ok_and(|existing| content_hash(&existing) == blob.hash);
+            if intact {
+                report.merged += 1;
+            } else {
+                if !dry_run {
+                    // A damaged copy would be kept by `put`, which only checks the size
+                    let _ = tokio::fs::remove_file(&target).await;
+                    if let Err(e) = self.blobs.put(&data).await {
+                        report.errors.push(format!("{}: {}", blob.hash, e));
+                        continue;
+                    }
+                }
+                report.moved += 1;
+            }
+            unified.insert(blob.hash.clone());
+            copied.push(blob);
+        }
+
+        // 2. Repoint
+        let mut unmoved: HashSet<String> = HashSet::new();
+        for record in self.records().await? {
+            let path = self.blobs.storage_path(&record.hash);
+            if !is_hash(&record.hash) || record.storage_path == path || !unified.contains(&record.hash) {
+                continue;
+            }
+            if !dry_run {
+                if let Err(e) = self.repo.set_asset_path(&record, &path).await {
+                    report.errors.push(format!("{} {}: {}", record.kind, record.id, e));
+                    unmoved.insert(record.hash.clone());
+                    continue;
+                }
+            }
+            report.repointed += 1;
+        }
+
+        // 3. Delete the originals, except those a record still points to
+        if !dry_run {
+            for blob in copied {
+                if unmoved.contains(&blob.hash) {
+                    report.errors.push(format!("{}: still referenced by its old path, left in place", blob.hash));
+                    continue;
+                }
+                if let Err(e) = tokio::fs::remove_file(&blob.path).await {
+                    report.errors.push(format!("{}: {}", blob.hash, e));
+                }
+            }
+        }
+        Ok(report)
+    }
+
+    /// `None` when no record has this id.
+    pub async fn references(&self, asset_id: Uuid) -> Result<Option<AssetReferences>, String> {
+        let records = self.records().await?;
+        let Some(asset) = records.iter().find(|r| r.id == asset_id).cloned() else { return Ok(None) };
+        let usages = self.repo.list_asset_usages(Some(asset_id)).await.map_err(|e| e.to_string())?;
+        Ok(Some(AssetReferences {
+            blob_ref_count: ref_counts(&records).get(&asset.hash).copied().unwrap_or(0),
+            asset,
+            usages,
+        }))
+    }
+
+    /// Starts the background loop collecting garbage every `interval_hours`.
+    pub fn spawn_gc(&self, interval_hours: u64) {
+        if interval_hours == 0 {
+            return;
+        }
+        let service = self.clone();
+        tokio::spawn(async move {
+            let mut tick = tokio::time::interval(std::time::Duration::from_secs(interval_hours * 3600));
+            loop {
+                tick.tick().await;
+                match service.collect(false).await {
+                    Ok(report) => tracing::info!(
+                        "Asset GC: {} blob(s) deleted, {} bytes freed, {} error(s)",
+                        report.deleted.len(), report.freed_bytes, report.errors.len()
+                    ),
+                    Err(e) => tracing::error!("Asset GC failed: {}", e),
+                }
+            }
+        });
+    }
+}
@@ -1 +1,5 @@
+pub mod blob_store;
+pub mod gc;
 pub mod service;
+
+mod tests;
@@ -1,50 +1,36 @@
 use std::sync::Arc;
-use sha2::{Digest, Sha256};
-use tokio::io::AsyncWriteExt;
 use uuid::Uuid;
 use chrono::Utc;
 use crate::domain::models::VrkbAsset;
 use crate::domain::ports::{VrkbRepository};
-// use std::path::Path;
+use crate::infrastructure::storage::blob_store::BlobStore;
 
 #[derive(Clone)]
 pub struct AssetStorageService {
     repo: Arc<dyn VrkbRepository>,
-    storage_root: String,
+    blobs: Arc<BlobStore>,
 }
 
 impl AssetStorageService {
-    pub fn new(repo: Arc<dyn VrkbRepository>, storage_root: String) -> Self {
-        Self { repo, storage_root }
+    pub fn new(repo: Arc<dyn VrkbRepository>, blobs: Arc<BlobStore>) -> Self {
+        Self { repo, blobs }
     }
 
     pub async fn store_asset(&self, _mapped_file_name: &str, data: &[u8], mime_type: &str) -> Result<VrkbAsset, String> {
-        // 1. Compute Hash
-        let mut hasher = Sha256::new();
-        hasher.update(data);
-        let hash_result = hasher.finalize();
-        let hash_hex = format!("{:x}", hash_result);
+        // 1. Save File (once per content, shared with asset articles)
+        let hash_hex = self.blobs.put(data).await?;
+        let file_path = self.blobs.storage_path(&hash_hex);
 
         // 2. Check Deduplication
         if let Ok(Some(existing)) = self.repo.get_asset_by_hash(&hash_hex).await {
             return Ok(existing);
         }
 
-        // 3. Save File
-        // Use first 2 chars for subfolder to avoid directory bloat: objects/ab/abcdef...
-        let sharded_dir = format!("{}/objects/{}", self.storage_root, &hash_hex[0..2]);
-        let file_path = format!("{}/{}", sharded_dir, hash_hex);
-        
-        tokio::fs::create_dir_all(&sharded_dir).await.map_err(|e| e.to_string())?;
-        
-        let mut file = tokio::fs::File::create(&file_path).await.map_err(|e| e.to_string())?;
-        file.write_all(data).await.map_err(|e| e.to_string())?;
-
-        // 4. Create Entity
+        // 3. Create Entity
         let asset = VrkbAsset {
             id: Uuid::new_v4(),
             hash: hash_hex,
-            storage_path: file_path, // Relative or Absolute? Storing full path for now or relative to root? Let's use relative to app root or whatever
+            storage_path: file_path, // Relative to the working directory
             mime_type: mime_type.to_string(),
             size_bytes: data.len() as i64,
             created_at: Utc::now(),
@@ -0,0 +1,90 @@
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use chrono::{Duration, Utc};
+    use uuid::Uuid;
+    use crate::domain::models::{AssetRecord, AssetUsage};
+    use crate::infrastructure::storage::blob_store::{content_hash, BlobInfo, BlobStore};
+    use crate::infrastructure::storage::gc::{ref_counts, sweepable, unused};
+
+    fn record(kind: &str, hash: &str) -> AssetRecord {
+        AssetRecord {
+            id: Uuid::new_v4(),
+            kind: kind.to_string(),
+            hash: hash.to_string(),
+            storage_path: String::new(),
+            owner_id: None,
+            size_bytes: 1,
+            created_at: Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_blob_store_layouts() {
+        let root = std::env::temp_dir().join(format!("aether_blobs_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        let store = BlobStore::new(&root);
+
+        let hash = store.put(b"diagram").await.unwrap();
+        assert_eq!(hash, content_hash(b"diagram"));
+        assert_eq!(store.put(b"diagram").await.unwrap(), hash);
+        assert_eq!(store.locate(&hash), Some(store.path(&hash)));
+        assert!(store.storage_path(&hash).ends_with(&format!("objects/{}/{}", &hash[0..2], hash)));
+
+        // A file of the old layout is still found; avatars and stray files are not blobs
+        let legacy = content_hash(b"screenshot");
+        std::fs::create_dir_all(store.legacy_path(&legacy).parent().unwrap()).unwrap();
+        std::fs::write(store.legacy_path(&legacy), b"screenshot").unwrap();
+        std::fs::create_dir_all(root.join("avatars")).unwrap();
+        std::fs::write(root.join("avatars").join(format!("{}.png", Uuid::new_v4())), b"me").unwrap();
+        std::fs::write(root.join("objects").join(&hash[0..2]).join("notes.txt"), b"x").unwrap();
+        assert_eq!(store.locate(&legacy), Some(store.legacy_path(&legacy)));
+        assert_eq!(store.locate("../../etc/passwd"), None);
+
+        let blobs = store.list();
+        let listed: Vec<(&str, bool)> = blobs.iter().map(|b| (b.hash.as_str(), b.legacy)).collect();
+        let mut expected = vec![(hash.as_str(), false), (legacy.as_str(), true)];
+        expected.sort();
+        assert_eq!(listed, expected);
+        assert!(blobs.iter().all(|b| b.size > 0));
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_gc_mark_and_sweep() {
+        let now = Utc::now();
+        let (shared, single, orphan, fresh) = (content_hash(b"a"), content_hash(b"b"), content_hash(b"c"), content_hash(b"d"));
+        let records = vec![record("article", &shared), record("vrkb", &shared), record("article", &single)];
+
+        let counts = ref_counts(&records);
+        assert_eq!(counts, HashMap::from([(shared.clone(), 2), (single.clone(), 1)]));
+
+        let blob = |hash: &str, age: i64, legacy: bool| BlobInfo {
+            hash: hash.to_string(),
+            path: format!("/tmp/{}", hash).into(),
+            size: 10,
+            modified: now - Duration::minutes(age),
+            legacy,
+        };
+        let blobs = vec![
+            blob(&shared, 600, false),
+            blob(&shared, 600, true),
+            blob(&single, 600, false),
+            blob(&orphan, 600, true),
+            // Just uploaded, its record may not be saved yet
+            blob(&fresh, 5, false),
+        ];
+        let swept: Vec<&str> = sweepable(&blobs, &counts, now).iter().map(|b| b.hash.as_str()).collect();
+        assert_eq!(swept, [orphan.as_str()]);
+        assert_eq!(sweepable(&blobs, &counts, now + Duration::hours(2)).len(), 2);
+
+        // Unused: no article, finding, doc, project or cover refers to it
+        let usages = vec![
+            AssetUsage { asset_id: records[0].id, source_kind: "article".to_string(), source_id: Uuid::new_v4() },
+            AssetUsage { asset_id: records[1].id, source_kind: "project".to_string(), source_id: Uuid::new_v4() },
+        ];
+        let idle: Vec<Uuid> = unused(&records, &usages).iter().map(|r| r.id).collect();
+        assert_eq!(idle, [records[2].id]);
+    }
+}
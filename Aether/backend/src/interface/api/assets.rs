@@ -11,17 +11,27 @@ use tokio_util::io::ReaderStream;
 use tokio::fs::File;
 use uuid::Uuid;
 use crate::interface::state::AppState;
-use crate::interface::api::auth::AuthenticatedUser;
+use crate::interface::api::auth::{require_admin, AuthenticatedUser};
 
 #[derive(serde::Deserialize)]
 pub struct AssetQuery {
     context: Option<Uuid>,
 }
 
+#[derive(serde::Deserialize)]
+pub struct DryRunQuery {
+    #[serde(default)]
+    dry_run: bool,
+}
+
 pub fn router() -> Router<AppState> {
     Router::new()
         .route("/", post(upload_asset))
         .route("/:id", get(get_asset))
+        .route("/:id/references", get(asset_references))
+        .route("/storage/report", get(storage_report))
+        .route("/storage/gc", post(collect_garbage))
+        .route("/storage/migrate", post(migrate_storage))
 }
 
 async fn upload_asset(
@@ -79,3 +89,52 @@ async fn get_asset(
 
     Ok((headers, body))
 }
+
+/// How many records share the asset's file and where the asset is used.
+async fn asset_references(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    user: AuthenticatedUser,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    let references = state.asset_gc.references(id).await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?
+        .ok_or((StatusCode::NOT_FOUND, "Asset not found".to_string()))?;
+    if references.asset.owner_id.is_some_and(|owner| owner != user.id) && !user.is_admin() {
+        return Err((StatusCode::FORBIDDEN, "Unauthorized".to_string()));
+    }
+    Ok(Json(references))
+}
+
+/// Orphan blobs, missing files and unused assets across the store.
+async fn storage_report(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    require_admin(&user)?;
+    let report = state.asset_gc.report().await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?;
+    Ok(Json(report))
+}
+
+async fn collect_garbage(
+    State(state): State<AppState>,
+    Query(query): Query<DryRunQuery>,
+    user: AuthenticatedUser,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    require_admin(&user)?;
+    let report = state.asset_gc.collect(query.dry_run).await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?;
+    Ok(Json(report))
+}
+
+/// Moves files of the old `uploads/ab/<hash>` layout into the unified store.
+async fn migrate_storage(
+    State(state): State<AppState>,
+    Query(query): Query<DryRunQuery>,
+    user: AuthenticatedUser,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    require_admin(&user)?;
+    let report = state.asset_gc.migrate_layout(query.dry_run).await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?;
+    Ok(Json(report))
+}
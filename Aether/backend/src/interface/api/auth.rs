@@ -55,6 +55,15 @@ impl AuthenticatedUser {
     }
 }
 
+/// Rejects callers without the admin permission, for `?` in admin-only handlers.
+pub fn require_admin(user: &AuthenticatedUser) -> Result<(), (StatusCode, String)> {
+    if user.is_admin() {
+        Ok(())
+    } else {
+        Err((StatusCode::FORBIDDEN, "Admin access required".to_string()))
+    }
+}
+
 #[axum::async_trait]
 impl FromRequestParts<crate::interface::state::AppState> for AuthenticatedUser
 {
@@ -130,6 +139,18 @@ impl FromRequestParts<crate::interface::state::AppState> for MaybeAuthenticatedU
     }
 }
 
+/// Resolves a raw bearer token, for connections that cannot send an
+/// `Authorization` header (browsers opening a WebSocket pass it in the query).
+pub async fn authenticate_token(state: &crate::interface::state::AppState, token: &str) -> Option<AuthenticatedUser> {
+    let claims = state.auth_service.verify_token(token).ok()?;
+    let id = Uuid::parse_str(&claims.sub).ok()?;
+    let user_repo: Arc<dyn UserRepository> = FromRef::from_ref(state);
+    match user_repo.find_by_id(&crate::domain::models::UserId(id)).await {
+        Ok(Some(user)) => Some(AuthenticatedUser { id: user.id.0, permissions: claims.perms }),
+        _ => None,
+    }
+}
+
 pub async fn login_handler(
     State(state): State<crate::interface::state::AppState>, 
     Json(payload): Json<LoginRequest>,
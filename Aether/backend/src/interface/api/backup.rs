@@ -1,8 +1,8 @@
 use axum::{
-    extract::{Path, State, Multipart},
+    extract::{Path, Query, State, Multipart},
     response::IntoResponse,
-    http::{StatusCode, header},
-    routing::{get, post},
+    http::{StatusCode, header, HeaderMap},
+    routing::{get, post, put},
     Json, Router,
     body::Body,
 };
@@ -11,17 +11,71 @@ use tokio::fs::File;
 use uuid::Uuid;
 use crate::interface::state::AppState;
 use crate::interface::api::auth::AuthenticatedUser;
+use crate::infrastructure::services::backup::crypto::BackupEncryption;
+use crate::infrastructure::services::backup::restore::RestoreOptions;
+use crate::infrastructure::services::backup::schedule::ScheduleRequest;
 
 #[derive(serde::Deserialize)]
 pub struct CreateBackupRequest {
     kb_id: Uuid,
+    /// Encrypts the backup when set.
+    #[serde(default)]
+    passphrase: Option<String>,
+    #[serde(default)]
+    hint: Option<String>,
+    /// Stores only what changed since `base`, by default the KB's latest backup.
+    #[serde(default)]
+    incremental: bool,
+    #[serde(default)]
+    base: Option<String>,
+}
+
+/// Re-encrypts a backup: `passphrase` opens it if it is encrypted, and
+/// without a `new_passphrase` it is stored unencrypted.
+#[derive(serde::Deserialize)]
+pub struct EncryptBackupRequest {
+    #[serde(default)]
+    passphrase: Option<String>,
+    #[serde(default)]
+    new_passphrase: Option<String>,
+    #[serde(default)]
+    hint: Option<String>,
+}
+
+#[derive(serde::Deserialize)]
+pub struct VerifyQuery {
+    #[serde(default)]
+    allow_unsigned_legacy: bool,
+}
+
+/// Header carrying the passphrase of an encrypted backup to verify.
+const PASSPHRASE_HEADER: &str = "x-backup-passphrase";
+
+fn error_status(e: &str) -> StatusCode {
+    match e {
+        "Unauthorized" => StatusCode::FORBIDDEN,
+        "Knowledge Base not found" | "Backup file not found" => StatusCode::NOT_FOUND,
+        "Schedule not found" => StatusCode::NOT_FOUND,
+        "A target knowledge base is required" | "Passphrase required" | "Passphrase must not be empty" | "Hint is too long"
+            | "Interval must be at least one hour" | "No base backup to build on"
+            | "Base backup belongs to another knowledge base" => StatusCode::BAD_REQUEST,
+        "A backup of this knowledge base was just made" => StatusCode::CONFLICT,
+        e if e.starts_with("Base backup") => StatusCode::UNPROCESSABLE_ENTITY,
+        e if e.starts_with("Backup failed verification") || e.starts_with("Wrong passphrase") => StatusCode::UNPROCESSABLE_ENTITY,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    }
 }
 
 pub fn router() -> Router<AppState> {
     Router::new()
         .route("/", get(list_backups).post(create_backup))
         .route("/download/:filename", get(download_backup))
+        .route("/:filename/info", get(backup_info))
+        .route("/:filename/verify", get(verify_backup))
+        .route("/:filename/encrypt", post(encrypt_backup))
         .route("/restore", post(restore_backup))
+        .route("/schedules", get(list_schedules))
+        .route("/schedules/:kb_id", put(set_schedule).delete(remove_schedule))
 }
 
 async fn create_backup(
@@ -30,8 +84,12 @@ async fn create_backup(
     Json(payload): Json<CreateBackupRequest>,
 ) -> Result<impl IntoResponse, (StatusCode, String)> {
     // 1. Trigger Backup
-    let filename = state.backup_service.create_backup(payload.kb_id, user.id).await
-        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?;
+    let encryption = payload.passphrase.map(|passphrase| BackupEncryption { passphrase, hint: payload.hint });
+    let filename = if payload.incremental {
+        state.backup_service.create_incremental_backup(payload.kb_id, user.id, payload.base, encryption.as_ref()).await
+    } else {
+        state.backup_service.create_backup(payload.kb_id, user.id, encryption.as_ref()).await
+    }.map_err(|e| (error_status(&e), e))?;
 
     Ok(Json(serde_json::json!({
         "status": "success",
@@ -49,11 +107,8 @@ async fn list_backups(
     Ok(Json(files))
 }
 
-async fn download_backup(
-    State(state): State<AppState>,
-    user: AuthenticatedUser, // Require auth
-    Path(filename): Path<String>,
-) -> Result<impl IntoResponse, (StatusCode, String)> {
+/// Backups are named `<kb_id>_<timestamp>.akb`; only the KB's author may use them.
+async fn authorize_backup(state: &AppState, filename: &str, user_id: Uuid) -> Result<(), (StatusCode, String)> {
     // Security: Prevent path traversal
     if filename.contains("..") || filename.contains("/") || !filename.ends_with(".akb") {
         return Err((StatusCode::BAD_REQUEST, "Invalid filename".to_string()));
@@ -74,9 +129,18 @@ async fn download_backup(
         .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
         .ok_or((StatusCode::NOT_FOUND, "Knowledge Base not found".to_string()))?;
 
-    if kb.author_id != user.id {
+    if kb.author_id != user_id {
         return Err((StatusCode::FORBIDDEN, "Unauthorized".to_string()));
     }
+    Ok(())
+}
+
+async fn download_backup(
+    State(state): State<AppState>,
+    user: AuthenticatedUser, // Require auth
+    Path(filename): Path<String>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    authorize_backup(&state, &filename, user.id).await?;
 
     // Serve File
     let path = std::path::Path::new("backups").join(&filename); // Hardcoded relative path matching service
@@ -95,49 +159,172 @@ async fn download_backup(
     Ok((headers, body))
 }
 
+/// Whether the backup is encrypted, with the cipher, KDF and key hint if so.
+async fn backup_info(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Path(filename): Path<String>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    authorize_backup(&state, &filename, user.id).await?;
+
+    let encryption = state.backup_service.encryption_info(&filename).map_err(|e| (error_status(&e), e))?;
+
+    Ok(Json(serde_json::json!({
+        "filename": filename,
+        "encrypted": encryption.is_some(),
+        "encryption": encryption
+    })))
+}
+
+/// Checks the backup against its signed manifest without restoring it.
+/// Encrypted backups need the passphrase in the `X-Backup-Passphrase` header;
+/// `?allow_unsigned_legacy=true` accepts an unsigned 1.0 backup.
+async fn verify_backup(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Path(filename): Path<String>,
+    Query(query): Query<VerifyQuery>,
+    headers: HeaderMap,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    authorize_backup(&state, &filename, user.id).await?;
+
+    let passphrase = headers.get(PASSPHRASE_HEADER).and_then(|v| v.to_str().ok()).map(str::to_string);
+    // An archive that cannot even be opened is a failed verification too
+    let report = state.backup_service.verify_backup(&filename, passphrase, query.allow_unsigned_legacy).await.map_err(|e| {
+        let status = error_status(&e);
+        if status == StatusCode::INTERNAL_SERVER_ERROR { (StatusCode::UNPROCESSABLE_ENTITY, e) } else { (status, e) }
+    })?;
+
+    Ok(Json(report))
+}
+
+async fn encrypt_backup(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Path(filename): Path<String>,
+    Json(payload): Json<EncryptBackupRequest>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    authorize_backup(&state, &filename, user.id).await?;
+
+    let encryption = payload.new_passphrase.map(|passphrase| BackupEncryption { passphrase, hint: payload.hint });
+    let encrypted = encryption.is_some();
+    state.backup_service.reencrypt_backup(&filename, payload.passphrase, encryption).await
+        .map_err(|e| (error_status(&e), e))?;
+
+    Ok(Json(serde_json::json!({
+        "status": "success",
+        "filename": filename,
+        "encrypted": encrypted
+    })))
+}
+
+/// Multipart: the `file`, plus optional `mode` (`new_kb`, `merge`, `overwrite`),
+/// `target_kb_id`, `conflict_policy` (`rename`, `skip`, `replace`), `dry_run` and
+/// `allow_unsigned_legacy` fields, and the `passphrase` of an encrypted backup.
 async fn restore_backup(
     State(state): State<AppState>,
     user: AuthenticatedUser,
     mut multipart: Multipart,
 ) -> Result<impl IntoResponse, (StatusCode, String)> {
-    // 1. Receive File
+    // 1. Receive File and Options
     let mut file_path = None;
+    let mut options = serde_json::Map::new();
+    let mut passphrase = None;
     
     while let Some(field) = multipart.next_field().await.map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))? {
-        if field.name() == Some("file") {
-            let filename = field.file_name().unwrap_or("backup.akb").to_string();
-            // Validate extension
-            if !filename.ends_with(".akb") && !filename.ends_with(".zip") {
-                return Err((StatusCode::BAD_REQUEST, "Invalid file type. Must be .akb or .zip".to_string()));
-            }
+        match field.name() {
+            Some("file") if file_path.is_none() => {
+                let filename = field.file_name().unwrap_or("backup.akb").to_string();
+                // Validate extension
+                if !filename.ends_with(".akb") && !filename.ends_with(".zip") {
+                    return Err((StatusCode::BAD_REQUEST, "Invalid file type. Must be .akb or .zip".to_string()));
+                }
 
-            // Save to temp
-            let temp_dir = std::env::temp_dir();
-            let target_path = temp_dir.join(format!("restore_{}_{}", Uuid::new_v4(), filename));
-            
-            let data = field.bytes().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
-            tokio::fs::write(&target_path, data).await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
-            
-            file_path = Some(target_path);
-            break; // One file only
+                // Save to temp
+                let temp_dir = std::env::temp_dir();
+                let target_path = temp_dir.join(format!("restore_{}_{}", Uuid::new_v4(), filename));
+                
+                let data = field.bytes().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+                tokio::fs::write(&target_path, data).await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+                
+                file_path = Some(target_path);
+            }
+            Some("passphrase") => {
+                let text = field.text().await.map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))?;
+                passphrase = Some(text).filter(|t| !t.is_empty());
+            }
+            Some(name @ ("mode" | "target_kb_id" | "conflict_policy" | "dry_run" | "allow_unsigned_legacy")) => {
+                let name = name.to_string();
+                let text = field.text().await.map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))?;
+                let value = match name.as_str() {
+                    "dry_run" | "allow_unsigned_legacy" => serde_json::Value::Bool(text == "true" || text == "1"),
+                    _ if text.is_empty() => continue,
+                    _ => serde_json::Value::String(text),
+                };
+                options.insert(name, value);
+            }
+            _ => {}
         }
     }
 
     let path = file_path.ok_or((StatusCode::BAD_REQUEST, "No file uploaded".to_string()))?;
+    let options: RestoreOptions = match serde_json::from_value(serde_json::Value::Object(options)) {
+        Ok(options) => options,
+        Err(e) => {
+            let _ = std::fs::remove_file(&path);
+            return Err((StatusCode::BAD_REQUEST, format!("Invalid restore options: {}", e)));
+        }
+    };
 
     // 2. Trigger Restore
-    let new_kb_id = state.backup_service.restore_backup(path.clone(), user.id).await
-        .map_err(|e| {
-            // Try to cleanup
-            let _ = std::fs::remove_file(&path);
-            (StatusCode::INTERNAL_SERVER_ERROR, e)
-        })?;
+    let result = state.backup_service.restore(path.clone(), user.id, options, passphrase).await;
 
     // Cleanup
     let _ = std::fs::remove_file(path);
 
+    let report = result.map_err(|e| (error_status(&e), e))?;
+    if !report.dry_run {
+        state.site_generator.schedule_rebuild(report.kb_id);
+    }
+
     Ok(Json(serde_json::json!({
         "status": "success",
-        "new_kb_id": new_kb_id
+        "new_kb_id": report.kb_id,
+        "report": report
     })))
 }
+
+/// The user's backup schedules: state, last success, next run and the kept
+/// backups (oldest first, with sizes).
+async fn list_schedules(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    let schedules = state.backup_service.list_schedules(user.id).await
+        .map_err(|e| (error_status(&e), e))?;
+
+    Ok(Json(schedules))
+}
+
+async fn set_schedule(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Path(kb_id): Path<Uuid>,
+    Json(payload): Json<ScheduleRequest>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    let schedule = state.backup_service.set_schedule(kb_id, user.id, payload).await
+        .map_err(|e| (error_status(&e), e))?;
+
+    Ok(Json(schedule))
+}
+
+async fn remove_schedule(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Path(kb_id): Path<Uuid>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    state.backup_service.remove_schedule(kb_id, user.id)
+        .map_err(|e| (error_status(&e), e))?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
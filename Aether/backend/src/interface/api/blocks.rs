@@ -0,0 +1,461 @@
+use axum::{
+    Json, extract::{State, Path, Query}, response::{IntoResponse, Response},
+    http::{StatusCode, HeaderMap, header::ETAG},
+};
+use std::collections::{HashMap, HashSet};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use uuid::Uuid;
+use chrono::Utc;
+use crate::domain::blocks::editing::{self, BlockEditError};
+use crate::domain::blocks::models::{Block, BlockReference, ReferenceKind};
+use crate::domain::models::{ContentBody, ContentItem, ContentStatus, UserId};
+use crate::domain::ports::{ArticleRepository, RepositoryError};
+use crate::infrastructure::persistence::repositories::block_repository::BlockRepository;
+use crate::interface::api::auth::{AuthenticatedUser, MaybeAuthenticatedUser};
+use crate::interface::api::concurrency::{conflict_response, etag, expected_revision};
+use crate::interface::api::content::{check_edit_permission, check_view_permission};
+use crate::interface::state::AppState;
+
+/// A block edit is retried when another save of the same article slips in
+/// between loading its blocks and writing them back.
+const MAX_ATTEMPTS: usize = 3;
+
+#[derive(Deserialize)]
+pub struct InsertBlockRequest {
+    #[serde(rename = "type")]
+    pub type_name: String,
+    pub payload: Value,
+    pub ordinal: Option<usize>, // Position among the blocks; appended when missing
+}
+
+#[derive(Deserialize)]
+pub struct UpdateBlockRequest {
+    pub payload: Value,
+    pub revision: Option<i64>, // Alternative to If-Match
+}
+
+#[derive(Deserialize)]
+pub struct MoveBlockRequest {
+    pub ordinal: usize,
+    pub revision: Option<i64>,
+}
+
+#[derive(Deserialize)]
+pub struct DeleteBlockParams {
+    pub revision: Option<i64>,
+}
+
+/// A `((uuid))` embed or `[[uuid]]` link as the reader gets it: embeds carry
+/// the target block, links only where it lives. Targets in documents the
+/// reader cannot see are `forbidden`, deleted ones `missing`.
+#[derive(Serialize)]
+pub struct ResolvedReference {
+    pub block_id: Uuid,
+    pub kind: ReferenceKind,
+    pub status: &'static str, // "ok", "forbidden", "missing"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub document_id: Option<Uuid>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub document_title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub block: Option<Block>,
+}
+
+#[derive(Serialize)]
+pub struct BlockUsage {
+    pub document_id: Uuid,
+    pub document_title: String,
+    pub block_id: Uuid,
+    pub kind: ReferenceKind,
+}
+
+/// Usages of a block, minus those in documents the reader cannot see (only counted).
+#[derive(Serialize)]
+pub struct BlockUsages {
+    pub usages: Vec<BlockUsage>,
+    pub hidden: usize,
+}
+
+enum BlockEdit {
+    Insert { type_name: String, payload: Value, ordinal: Option<usize> },
+    Update { id: Uuid, payload: Value, expected: Option<i64> },
+    Move { id: Uuid, ordinal: usize, expected: Option<i64> },
+    Delete { id: Uuid, expected: Option<i64> },
+}
+
+fn error(status: StatusCode, message: impl ToString) -> Response {
+    (status, Json(serde_json::json!({ "error": message.to_string() }))).into_response()
+}
+
+/// Helpers fail with a finished response, boxed to keep their `Result`s small.
+type Rejection = Box<Response>;
+
+fn reject(status: StatusCode, message: impl ToString) -> Rejection {
+    Box::new(error(status, message))
+}
+
+/// Title of a document if `user` may read it (drafts only by their author).
+pub(crate) async fn readable_title(state: &AppState, id: Uuid, user: &Option<AuthenticatedUser>) -> Option<String> {
+    let item = ArticleRepository::find_by_id(&*state.repo, &id).await.ok().flatten()?;
+    let node = match &item {
+        ContentItem::Article(a) => {
+            let is_author = user.as_ref().is_some_and(|u| u.id == a.node.author_id);
+            if a.status == ContentStatus::Draft && !is_author {
+                return None;
+            }
+            &a.node
+        }
+        ContentItem::Node(n) => n,
+    };
+    if !check_view_permission(&state.repo, node, user).await {
+        return None;
+    }
+    Some(node.title.clone())
+}
+
+/// Resolves the references a document makes, one level deep: embeds inside
+/// an embedded block are returned as written, which keeps cycles harmless.
+pub(crate) async fn resolve_references(state: &AppState, document_id: Uuid, user: &Option<AuthenticatedUser>) -> Vec<ResolvedReference> {
+    let repo = BlockRepository::new(state.repo.db.clone());
+    let references = match repo.find_references_from(document_id).await {
+        Ok(references) => references,
+        Err(e) => {
+            tracing::warn!("Failed to load references of {}: {}", document_id, e);
+            return Vec::new();
+        }
+    };
+    let mut seen = HashSet::new();
+    let references: Vec<BlockReference> = references.into_iter()
+        .filter(|r| seen.insert((r.target_block_id, r.kind)))
+        .collect();
+    let ids: Vec<Uuid> = references.iter().map(|r| r.target_block_id).collect();
+    let targets: HashMap<Uuid, Block> = repo.find_by_ids(&ids).await.unwrap_or_default()
+        .into_iter().map(|b| (b.id, b)).collect();
+
+    let mut titles: HashMap<Uuid, Option<String>> = HashMap::new();
+    let mut resolved = Vec::with_capacity(references.len());
+    for reference in references {
+        let mut entry = ResolvedReference {
+            block_id: reference.target_block_id,
+            kind: reference.kind,
+            status: "missing",
+            document_id: None,
+            document_title: None,
+            block: None,
+        };
+        if let Some(block) = targets.get(&reference.target_block_id) {
+            let title = match titles.get(&block.document_id) {
+                Some(title) => title.clone(),
+                None => {
+                    let title = readable_title(state, block.document_id, user).await;
+                    titles.insert(block.document_id, title.clone());
+                    title
+                }
+            };
+            match title {
+                Some(title) => {
+                    entry.status = "ok";
+                    entry.document_id = Some(block.document_id);
+                    entry.document_title = Some(title);
+                    if reference.kind == ReferenceKind::Embed {
+                        entry.block = Some(block.clone());
+                    }
+                }
+                None => entry.status = "forbidden",
+            }
+        }
+        resolved.push(entry);
+    }
+    resolved
+}
+
+/// Where a block is used, apart from in itself.
+async fn find_usages(state: &AppState, block_id: Uuid, user: &Option<AuthenticatedUser>) -> Result<BlockUsages, Rejection> {
+    let references = BlockRepository::new(state.repo.db.clone()).find_usages(block_id).await
+        .map_err(|e| reject(StatusCode::INTERNAL_SERVER_ERROR, e))?;
+
+    let mut titles: HashMap<Uuid, Option<String>> = HashMap::new();
+    let mut result = BlockUsages { usages: Vec::new(), hidden: 0 };
+    for reference in references.into_iter().filter(|r| r.source_block_id != block_id) {
+        let title = match titles.get(&reference.source_document_id) {
+            Some(title) => title.clone(),
+            None => {
+                let title = readable_title(state, reference.source_document_id, user).await;
+                titles.insert(reference.source_document_id, title.clone());
+                title
+            }
+        };
+        match title {
+            Some(title) => result.usages.push(BlockUsage {
+                document_id: reference.source_document_id,
+                document_title: title,
+                block_id: reference.source_block_id,
+                kind: reference.kind,
+            }),
+            None => result.hidden += 1,
+        }
+    }
+    Ok(result)
+}
+
+pub async fn block_usages_handler(
+    State(state): State<AppState>,
+    user: MaybeAuthenticatedUser,
+    Path(block_id): Path<Uuid>,
+) -> impl IntoResponse {
+    match find_usages(&state, block_id, &user.0).await {
+        Ok(usages) => (StatusCode::OK, Json(usages)).into_response(),
+        Err(response) => *response,
+    }
+}
+
+/// The article's blocks; articles saved before the block table existed are split on the fly.
+async fn load_blocks(state: &AppState, id: Uuid, body: &str) -> Result<Vec<Block>, Rejection> {
+    let blocks = BlockRepository::new(state.repo.db.clone()).find_by_document_id(id).await
+        .map_err(|e| reject(StatusCode::INTERNAL_SERVER_ERROR, e))?;
+    if blocks.is_empty() && !body.trim().is_empty() {
+        return Ok(editing::split_unstored(id, body));
+    }
+    Ok(blocks)
+}
+
+pub async fn list_blocks_handler(
+    State(state): State<AppState>,
+    user: MaybeAuthenticatedUser,
+    Path(id): Path<Uuid>,
+) -> impl IntoResponse {
+    let article = match ArticleRepository::find_by_id(&*state.repo, &id).await {
+        Ok(Some(ContentItem::Article(a))) => a,
+        Ok(_) => return error(StatusCode::NOT_FOUND, "Article not found"),
+        Err(e) => return error(StatusCode::INTERNAL_SERVER_ERROR, e),
+    };
+    if !check_view_permission(&state.repo, &article.node, &user.0).await {
+        return error(StatusCode::FORBIDDEN, "Access denied");
+    }
+    let ContentBody::Markdown(body) = &article.body else {
+        return error(StatusCode::BAD_REQUEST, "Only Markdown articles have blocks");
+    };
+    let blocks = match load_blocks(&state, id, body).await {
+        Ok(blocks) => blocks,
+        Err(response) => return *response,
+    };
+    let revision = ArticleRepository::current_revision(&*state.repo, &id).await.unwrap_or(0);
+    (StatusCode::OK, [(ETAG, etag(revision))], Json(blocks)).into_response()
+}
+
+pub async fn insert_block_handler(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<InsertBlockRequest>,
+) -> impl IntoResponse {
+    let edit = BlockEdit::Insert { type_name: payload.type_name, payload: payload.payload, ordinal: payload.ordinal };
+    apply_edit(&state, &user, id, edit).await
+}
+
+pub async fn update_block_handler(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Path((id, block_id)): Path<(Uuid, Uuid)>,
+    headers: HeaderMap,
+    Json(payload): Json<UpdateBlockRequest>,
+) -> impl IntoResponse {
+    let expected = match expected_revision(&headers, payload.revision) {
+        Ok(expected) => expected,
+        Err(rejection) => return rejection.into_response(),
+    };
+    apply_edit(&state, &user, id, BlockEdit::Update { id: block_id, payload: payload.payload, expected }).await
+}
+
+pub async fn move_block_handler(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Path((id, block_id)): Path<(Uuid, Uuid)>,
+    headers: HeaderMap,
+    Json(payload): Json<MoveBlockRequest>,
+) -> impl IntoResponse {
+    let expected = match expected_revision(&headers, payload.revision) {
+        Ok(expected) => expected,
+        Err(rejection) => return rejection.into_response(),
+    };
+    apply_edit(&state, &user, id, BlockEdit::Move { id: block_id, ordinal: payload.ordinal, expected }).await
+}
+
+pub async fn delete_block_handler(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Path((id, block_id)): Path<(Uuid, Uuid)>,
+    headers: HeaderMap,
+    Query(params): Query<DeleteBlockParams>,
+) -> impl IntoResponse {
+    let expected = match expected_revision(&headers, params.revision) {
+        Ok(expected) => expected,
+        Err(rejection) => return rejection.into_response(),
+    };
+    // Checked before the usages, which only editors get to see
+    let article = match ArticleRepository::find_by_id(&*state.repo, &id).await {
+        Ok(Some(ContentItem::Article(a))) => a,
+        Ok(_) => return error(StatusCode::NOT_FOUND, "Article not found"),
+        Err(e) => return error(StatusCode::INTERNAL_SERVER_ERROR, e),
+    };
+    if !check_edit_permission(&state.repo, &article.node, &user).await {
+        return error(StatusCode::FORBIDDEN, "Access denied");
+    }
+    // Deleting a referenced block would leave broken embeds and links behind
+    let usages = match find_usages(&state, block_id, &Some(user)).await {
+        Ok(usages) => usages,
+        Err(response) => return *response,
+    };
+    if !usages.usages.is_empty() || usages.hidden > 0 {
+        return (StatusCode::CONFLICT, Json(serde_json::json!({
+            "error": "Block is referenced elsewhere",
+            "usages": usages.usages,
+            "hidden": usages.hidden,
+        }))).into_response();
+    }
+    apply_edit(&state, &user, id, BlockEdit::Delete { id: block_id, expected }).await
+}
+
+/// Applies one block edit: validates the payload against the schema registry,
+/// checks the block's revision, reassembles the article body from the blocks
+/// and saves both as a new article version.
+async fn apply_edit(state: &AppState, user: &AuthenticatedUser, id: Uuid, edit: BlockEdit) -> Response {
+    for _ in 0..MAX_ATTEMPTS {
+        // Read first: any save after this point makes ours fail and retry
+        let revision = match ArticleRepository::current_revision(&*state.repo, &id).await {
+            Ok(revision) => revision,
+            Err(e) => return error(StatusCode::INTERNAL_SERVER_ERROR, e),
+        };
+        let mut article = match ArticleRepository::find_by_id(&*state.repo, &id).await {
+            Ok(Some(ContentItem::Article(a))) => a,
+            Ok(_) => return error(StatusCode::NOT_FOUND, "Article not found"),
+            Err(e) => return error(StatusCode::INTERNAL_SERVER_ERROR, e),
+        };
+        if !check_edit_permission(&state.repo, &article.node, user).await {
+            return error(StatusCode::FORBIDDEN, "Access denied");
+        }
+        let ContentBody::Markdown(body) = &article.body else {
+            return error(StatusCode::BAD_REQUEST, "Only Markdown articles have blocks");
+        };
+        let mut blocks = match load_blocks(state, id, body).await {
+            Ok(blocks) => blocks,
+            Err(response) => return *response,
+        };
+
+        let target = match apply_to(state, id, &mut blocks, &edit) {
+            Ok(target) => target,
+            Err(response) => return *response,
+        };
+        let body = match editing::normalize(id, &mut blocks) {
+            Ok(body) => body,
+            Err(e) => return error(StatusCode::BAD_REQUEST, e),
+        };
+
+        let old_map = article.derived_data.take()
+            .and_then(|v| serde_json::from_value::<crate::domain::sentence_parser::SentenceMap>(v).ok());
+        article.derived_data = serde_json::to_value(
+            crate::domain::sentence_parser::SentenceParser::parse(&body, old_map.as_ref())
+        ).ok();
+        article.body = ContentBody::Markdown(body.clone());
+        article.node.updated_at = Utc::now();
+        let kb_id = article.node.knowledge_base_id;
+
+        match ArticleRepository::save_with_blocks(&*state.repo, article, blocks.clone(), UserId(user.id), None, revision).await {
+            Ok(revision) => {
+                let indexer = state.indexer_service.clone();
+                tokio::spawn(async move {
+                    if let Err(e) = indexer.index_article(id, &body).await {
+                        tracing::error!("Async Indexing failed for {}: {}", id, e);
+                    }
+                });
+                if let Some(kb_id) = kb_id {
+                    state.site_generator.schedule_rebuild(kb_id);
+                }
+                let block = target.and_then(|t| blocks.into_iter().find(|b| b.id == t));
+                let status = if matches!(edit, BlockEdit::Insert { .. }) { StatusCode::CREATED } else { StatusCode::OK };
+                return (status, [(ETAG, etag(revision))], Json(serde_json::json!({
+                    "block": block,
+                    "article_revision": revision,
+                }))).into_response();
+            }
+            Err(RepositoryError::RevisionConflict { .. }) => continue,
+            Err(RepositoryError::DuplicateTitle(msg)) => return error(StatusCode::CONFLICT, msg),
+            Err(e) => return error(StatusCode::INTERNAL_SERVER_ERROR, e),
+        }
+    }
+    error(StatusCode::CONFLICT, "The article is being edited, try again")
+}
+
+/// Applies the edit to the loaded blocks and returns the block to report back.
+fn apply_to(state: &AppState, id: Uuid, blocks: &mut Vec<Block>, edit: &BlockEdit) -> Result<Option<Uuid>, Rejection> {
+    match edit {
+        BlockEdit::Insert { type_name, payload, ordinal } => {
+            validate(state, type_name, payload)?;
+            let block = editing::new_block(id, type_name, payload.clone()).map_err(edit_error)?;
+            let block_id = block.id;
+            editing::insert(blocks, block, ordinal.unwrap_or(usize::MAX));
+            Ok(Some(block_id))
+        }
+        BlockEdit::Update { id: block_id, payload, expected } => {
+            let current = current_block(blocks, *block_id, *expected, payload)?;
+            validate(state, &current.type_name, payload)?;
+            editing::update(blocks, *block_id, payload.clone()).map_err(edit_error)?;
+            Ok(Some(*block_id))
+        }
+        BlockEdit::Move { id: block_id, ordinal, expected } => {
+            current_block(blocks, *block_id, *expected, &Value::Null)?;
+            editing::move_to(blocks, *block_id, *ordinal).map_err(edit_error)?;
+            Ok(Some(*block_id))
+        }
+        BlockEdit::Delete { id: block_id, expected } => {
+            current_block(blocks, *block_id, *expected, &Value::Null)?;
+            editing::remove(blocks, *block_id).map_err(edit_error)?;
+            Ok(None)
+        }
+    }
+}
+
+/// The block being edited, if it is still at the revision the client saw.
+/// A stale edit gets the current block and a diff of its text against the
+/// client's (sent) payload.
+fn current_block(blocks: &[Block], block_id: Uuid, expected: Option<i64>, sent: &Value) -> Result<Block, Rejection> {
+    let block = blocks.iter().find(|b| b.id == block_id).cloned()
+        .ok_or_else(|| reject(StatusCode::NOT_FOUND, BlockEditError::NotFound(block_id)))?;
+    if let Err(BlockEditError::StaleRevision { expected, current }) = editing::check_revision(&block, expected) {
+        let mirror = |v: &Value| v.get("text_mirror").and_then(|t| t.as_str()).unwrap_or("").to_string();
+        let mut sent_block = block.clone();
+        sent_block.payload = sent.clone();
+        crate::domain::blocks::strategies::apply_searchable_trait(&mut sent_block);
+        return Err(Box::new(conflict_response(expected, current, &block, &mirror(&sent_block.payload), &mirror(&block.payload))));
+    }
+    Ok(block)
+}
+
+fn validate(state: &AppState, type_name: &str, payload: &Value) -> Result<(), Rejection> {
+    let block = crate::domain::kb::ast::Block {
+        id: Uuid::nil(),
+        block_type: type_name.to_string(),
+        payload: payload.clone(),
+        children: Vec::new(),
+    };
+    state.schema_registry.validate_block(&block).map_err(|e| reject(StatusCode::BAD_REQUEST, e))
+}
+
+fn edit_error(e: BlockEditError) -> Rejection {
+    let status = match e {
+        BlockEditError::NotFound(_) => StatusCode::NOT_FOUND,
+        BlockEditError::StaleRevision { .. } => StatusCode::CONFLICT,
+        BlockEditError::UnsupportedType(_) | BlockEditError::NotRoundTrip(_) => StatusCode::BAD_REQUEST,
+    };
+    reject(status, e)
+}
+
+pub fn router() -> axum::Router<AppState> {
+    use axum::routing::{get, post, put};
+    axum::Router::new()
+        .route("/api/documents/:id/blocks", get(list_blocks_handler).post(insert_block_handler))
+        .route("/api/documents/:id/blocks/:block_id", put(update_block_handler).delete(delete_block_handler))
+        .route("/api/documents/:id/blocks/:block_id/move", post(move_block_handler))
+        .route("/api/blocks/:block_id/usages", get(block_usages_handler))
+}
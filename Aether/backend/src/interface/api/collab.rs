@@ -0,0 +1,138 @@
+use axum::{
+    Json, extract::{State, Path, Query, ws::{Message, WebSocket, WebSocketUpgrade}},
+    response::IntoResponse, http::{StatusCode, HeaderMap, header::AUTHORIZATION},
+};
+use serde::Deserialize;
+use tokio::sync::broadcast::error::RecvError;
+use uuid::Uuid;
+use crate::domain::{
+    ports::{ArticleRepository, UserRepository},
+    models::{ContentBody, ContentItem, UserId},
+};
+use crate::infrastructure::services::collab_service::{ClientMessage, ServerMessage, Session};
+use crate::interface::api::auth::authenticate_token;
+use crate::interface::api::content::check_edit_permission;
+use crate::interface::state::AppState;
+
+#[derive(Deserialize)]
+pub struct CollabParams {
+    pub token: Option<String>, // Browsers cannot set headers on WebSocket requests
+}
+
+/// Opens a live editing session on an article. Editors exchange operations
+/// in the ot.js format; see `collab_service::ClientMessage`/`ServerMessage`.
+pub async fn collab_socket_handler(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Query(params): Query<CollabParams>,
+    headers: HeaderMap,
+    ws: WebSocketUpgrade,
+) -> impl IntoResponse {
+    let token = headers.get(AUTHORIZATION)
+        .and_then(|h| h.to_str().ok())
+        .and_then(|h| h.strip_prefix("Bearer "))
+        .map(str::to_string)
+        .or(params.token);
+    let user = match token {
+        Some(token) => authenticate_token(&state, &token).await,
+        None => None,
+    };
+    let Some(user) = user else {
+        return (StatusCode::UNAUTHORIZED, Json(serde_json::json!({ "error": "Missing or invalid token" }))).into_response();
+    };
+
+    let article = match ArticleRepository::find_by_id(&*state.repo, &id).await {
+        Ok(Some(ContentItem::Article(article))) => article,
+        Ok(_) => return (StatusCode::NOT_FOUND, Json(serde_json::json!({ "error": "Article not found" }))).into_response(),
+        Err(e) => return (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({ "error": e.to_string() }))).into_response(),
+    };
+    if !matches!(article.body, ContentBody::Markdown(_)) {
+        return (StatusCode::BAD_REQUEST, Json(serde_json::json!({ "error": "Only Markdown articles can be edited collaboratively" }))).into_response();
+    }
+    if !check_edit_permission(&state.repo, &article.node, &user).await {
+        return (StatusCode::FORBIDDEN, Json(serde_json::json!({ "error": "Permission denied" }))).into_response();
+    }
+
+    let username = match UserRepository::find_by_id(&*state.repo, &UserId(user.id)).await {
+        Ok(Some(u)) => u.display_name.unwrap_or(u.username),
+        _ => user.id.to_string(),
+    };
+
+    ws.on_upgrade(move |socket| async move {
+        match state.collab_service.join(id, user.id, username).await {
+            Ok(session) => run_session(socket, session).await,
+            Err(e) => {
+                let mut socket = socket;
+                let _ = send(&mut socket, &ServerMessage::Error { message: e.to_string() }).await;
+            }
+        }
+    })
+}
+
+async fn send(socket: &mut WebSocket, message: &ServerMessage) -> Result<(), axum::Error> {
+    let text = serde_json::to_string(message).unwrap_or_default();
+    socket.send(Message::Text(text)).await
+}
+
+async fn run_session(mut socket: WebSocket, mut session: Session) {
+    let client_id = session.client_id;
+    if send(&mut socket, &session.init).await.is_err() {
+        session.room.leave(client_id).await;
+        return;
+    }
+
+    loop {
+        tokio::select! {
+            incoming = socket.recv() => {
+                let text = match incoming {
+                    Some(Ok(Message::Text(text))) => text,
+                    Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                    Some(Ok(_)) => continue, // Ping/pong are answered by axum
+                };
+                let reply = match serde_json::from_str::<ClientMessage>(&text) {
+                    Ok(ClientMessage::Operation { revision, operation, selection }) => {
+                        match session.room.apply(client_id, revision, operation, selection).await {
+                            Ok(revision) => Some(ServerMessage::Ack { revision }),
+                            Err(e) => Some(ServerMessage::Error { message: e.to_string() }),
+                        }
+                    }
+                    Ok(ClientMessage::Selection { selection }) => {
+                        session.room.select(client_id, selection).await;
+                        None
+                    }
+                    Err(e) => Some(ServerMessage::Error { message: format!("Invalid message: {}", e) }),
+                };
+                if let Some(reply) = reply {
+                    if send(&mut socket, &reply).await.is_err() {
+                        break;
+                    }
+                }
+            }
+            event = session.events.recv() => {
+                match event {
+                    Ok(event) if event.origin == Some(client_id) => {}
+                    Ok(event) => {
+                        let closed = matches!(event.message, ServerMessage::Closed { .. });
+                        if send(&mut socket, &event.message).await.is_err() || closed {
+                            break;
+                        }
+                    }
+                    Err(RecvError::Lagged(_)) => {
+                        // Missed operations cannot be recovered: the client has to rejoin
+                        let _ = send(&mut socket, &ServerMessage::Error { message: "Connection fell behind, reconnect".to_string() }).await;
+                        break;
+                    }
+                    Err(RecvError::Closed) => break,
+                }
+            }
+        }
+    }
+
+    session.room.leave(client_id).await;
+}
+
+pub fn router() -> axum::Router<AppState> {
+    use axum::routing::get;
+    axum::Router::new()
+        .route("/api/content/:id/collab", get(collab_socket_handler))
+}
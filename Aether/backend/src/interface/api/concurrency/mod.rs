@@ -0,0 +1,56 @@
+mod tests;
+
+use axum::{
+    Json, response::{IntoResponse, Response},
+    http::{StatusCode, HeaderMap, HeaderValue, header::{ETAG, IF_MATCH}},
+};
+use serde::Serialize;
+use crate::domain::diff_service::DiffService;
+
+/// `ETag` value for a resource revision.
+pub fn etag(revision: i64) -> HeaderValue {
+    HeaderValue::from_str(&format!("\"{}\"", revision)).expect("a quoted number is a valid header value")
+}
+
+/// Parses an `If-Match` value: `"12"`, `W/"12"` or a bare `12`.
+/// `*` matches any revision and yields `None`.
+pub fn parse_if_match(value: &str) -> Result<Option<i64>, String> {
+    let value = value.trim();
+    if value == "*" {
+        return Ok(None);
+    }
+    let tag = value.strip_prefix("W/").unwrap_or(value);
+    let tag = tag.strip_prefix('"').and_then(|t| t.strip_suffix('"')).unwrap_or(tag);
+    tag.parse::<i64>()
+        .map(Some)
+        .map_err(|_| format!("Invalid If-Match value: {}", value))
+}
+
+/// The revision an update is based on: the `If-Match` header, else the
+/// `expected_revision` from the request body. `None` means unconditional.
+pub fn expected_revision(headers: &HeaderMap, from_body: Option<i64>) -> Result<Option<i64>, (StatusCode, Json<serde_json::Value>)> {
+    match headers.get(IF_MATCH) {
+        Some(value) => {
+            let value = value.to_str().unwrap_or_default();
+            parse_if_match(value).map_err(|e| (StatusCode::BAD_REQUEST, Json(serde_json::json!({ "error": e }))))
+        }
+        None => Ok(from_body),
+    }
+}
+
+/// 409 reply for a stale update: the current server state with its revision,
+/// and a line diff from `base_text` (what the client started from, or what it
+/// sent when the base is not kept) to the current text, so the client can merge.
+pub fn conflict_response<T: Serialize>(expected: i64, current_revision: i64, current: &T, base_text: &str, current_text: &str) -> Response {
+    let diff = DiffService::compute_diff(base_text, current_text);
+    let body = serde_json::json!({
+        "error": "The resource was modified by someone else",
+        "expected_revision": expected,
+        "current_revision": current_revision,
+        "current": current,
+        "diff": diff.changes,
+    });
+    let mut response = (StatusCode::CONFLICT, Json(body)).into_response();
+    response.headers_mut().insert(ETAG, etag(current_revision));
+    response
+}
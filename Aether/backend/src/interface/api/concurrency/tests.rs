@@ -0,0 +1,37 @@
+#[cfg(test)]
+mod tests {
+    use axum::http::{HeaderMap, HeaderValue, StatusCode, header::{ETAG, IF_MATCH}};
+    use crate::interface::api::concurrency::{conflict_response, etag, expected_revision, parse_if_match};
+
+    #[test]
+    fn test_parse_if_match() {
+        assert_eq!(parse_if_match("\"12\""), Ok(Some(12)));
+        assert_eq!(parse_if_match("W/\"12\""), Ok(Some(12)));
+        assert_eq!(parse_if_match(" 7 "), Ok(Some(7)));
+        assert_eq!(parse_if_match("*"), Ok(None));
+        assert!(parse_if_match("\"abc\"").is_err());
+        assert_eq!(etag(12), HeaderValue::from_static("\"12\""));
+    }
+
+    #[test]
+    fn test_expected_revision_prefers_header() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(expected_revision(&headers, Some(3)).ok(), Some(Some(3)));
+        assert_eq!(expected_revision(&headers, None).ok(), Some(None));
+
+        headers.insert(IF_MATCH, HeaderValue::from_static("\"5\""));
+        assert_eq!(expected_revision(&headers, Some(3)).ok(), Some(Some(5)));
+
+        headers.insert(IF_MATCH, HeaderValue::from_static("nonsense"));
+        let (status, _) = expected_revision(&headers, None).unwrap_err();
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+
+    #[test]
+    fn test_conflict_response() {
+        let current = serde_json::json!({ "title": "Notes" });
+        let response = conflict_response(3, 4, &current, "a\nb\n", "a\nc\n");
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+        assert_eq!(response.headers().get(ETAG), Some(&HeaderValue::from_static("\"4\"")));
+    }
+}
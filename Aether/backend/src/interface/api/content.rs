@@ -1,5 +1,5 @@
 use axum::{
-    Json, extract::{State, Path, Query}, response::IntoResponse, http::StatusCode,
+    Json, extract::{State, Path, Query}, response::IntoResponse, http::{StatusCode, HeaderMap, header::ETAG},
 };
 use crate::domain::{
     ports::{ArticleRepository, RepositoryError, PermissionRepository, UserRepository}, 
@@ -9,6 +9,8 @@ use crate::domain::{
 use uuid::Uuid;
 use chrono::Utc;
 use crate::interface::api::auth::{AuthenticatedUser, MaybeAuthenticatedUser};
+use crate::interface::api::concurrency::{conflict_response, etag, expected_revision};
+use crate::infrastructure::persistence::repositories::link_repository::LinkRepository;
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize)]
@@ -24,6 +26,8 @@ pub struct ContentResponse {
     pub item: crate::domain::models::ContentItem,
     pub user_permission: String, // "author", "editor", "viewer"
     pub collaborators: Vec<CollaboratorInfo>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub references: Vec<crate::interface::api::blocks::ResolvedReference>, // Embedded and linked blocks
 }
 
 #[derive(Deserialize)]
@@ -43,6 +47,7 @@ pub struct CreateContentRequest {
     slug: Option<String>,
     #[serde(rename = "type")]
     content_type: Option<String>,
+    expected_revision: Option<i64>, // Alternative to If-Match on updates
 }
 
 #[derive(Deserialize)]
@@ -58,7 +63,7 @@ pub struct SearchQuery {
 
 // --- Permission Helpers ---
 
-async fn check_view_permission(
+pub(crate) async fn check_view_permission(
     repo: &crate::infrastructure::persistence::postgres::PostgresRepository,
     node: &Node,
     user: &Option<AuthenticatedUser>,
@@ -91,7 +96,7 @@ async fn check_view_permission(
     }
 }
 
-async fn check_edit_permission(
+pub(crate) async fn check_edit_permission(
     repo: &crate::infrastructure::persistence::postgres::PostgresRepository,
     node: &Node,
     user: &AuthenticatedUser,
@@ -228,6 +233,10 @@ pub async fn create_content_handler(
                     }
                 });
 
+                if let Some(kb_id) = payload.knowledge_base_id {
+                    state.site_generator.schedule_rebuild(kb_id);
+                }
+
                 (StatusCode::CREATED, Json(serde_json::json!({ "id": id }))).into_response()
             },
             Err(RepositoryError::DuplicateTitle(msg)) => (StatusCode::CONFLICT, Json(serde_json::json!({ "error": msg }))).into_response(),
@@ -242,7 +251,7 @@ pub async fn create_content_handler(
 
 // Helper to get node from ContentItem
 impl crate::domain::models::ContentItem {
-    fn node(&self) -> &Node {
+    pub(crate) fn node(&self) -> &Node {
         match self {
             crate::domain::models::ContentItem::Article(a) => &a.node,
             crate::domain::models::ContentItem::Node(n) => n,
@@ -254,8 +263,14 @@ pub async fn update_content_handler(
     State(state): State<crate::interface::state::AppState>,
     user: AuthenticatedUser,
     Path(id): Path<Uuid>,
+    headers: HeaderMap,
     Json(payload): Json<CreateContentRequest>, 
 ) -> impl IntoResponse {
+    let expected = match expected_revision(&headers, payload.expected_revision) {
+        Ok(expected) => expected,
+        Err(rejection) => return rejection.into_response(),
+    };
+
     let existing_item = match ArticleRepository::find_by_id(&*state.repo, &id).await {
         Ok(Some(c)) => c,
         Ok(None) => return (StatusCode::NOT_FOUND, Json(serde_json::json!({ "error": "Content not found" }))).into_response(),
@@ -274,12 +289,22 @@ pub async fn update_content_handler(
              if payload.content_type.as_deref() == Some("Article") {
                  return (StatusCode::BAD_REQUEST, Json(serde_json::json!({ "error": "Cannot change type to Article" }))).into_response();
             }
+             // Plain nodes have no versions: their revision is the update time
+             let stored = n.clone();
              n.title = payload.title;
              n.updated_at = Utc::now();
              
              use crate::domain::ports::NodeRepository;
-             match NodeRepository::save(&*state.repo, n, UserId(user.id)).await {
-                Ok(_) => (StatusCode::OK, Json(serde_json::json!({ "id": id }))).into_response(),
+             let saved = match expected {
+                 Some(expected) => NodeRepository::save_if_revision(&*state.repo, n.clone(), expected).await,
+                 None => NodeRepository::save(&*state.repo, n.clone(), UserId(user.id)).await.map(|_| n.updated_at.timestamp_micros()),
+             };
+             match saved {
+                Ok(revision) => (StatusCode::OK, [(ETAG, etag(revision))], Json(serde_json::json!({ "id": id, "revision": revision }))).into_response(),
+                Err(RepositoryError::RevisionConflict { expected, current }) => {
+                    let latest = NodeRepository::find_by_id(&*state.repo, &id).await.ok().flatten().unwrap_or(stored);
+                    conflict_response(expected, current, &latest, &n.title, &latest.title)
+                },
                 Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({ "error": e.to_string() }))).into_response(),
             }
         },
@@ -298,6 +323,11 @@ pub async fn update_content_handler(
             };
 
             let body_content = payload.body.clone();
+            let base_version = expected.map(|v| v.to_string());
+            // A rename leaves `[[Old Title]]` links behind: the response offers to rewrite them
+            let renamed_from = (existing.node.title != payload.title).then(|| existing.node.title.clone());
+            let link_scope = payload.knowledge_base_id.or(existing.node.knowledge_base_id);
+            let previous_scope = existing.node.knowledge_base_id;
 
             // Prepare derived_data before moving payload
             let derived_data_value = {
@@ -347,8 +377,8 @@ pub async fn update_content_handler(
                 }
             }
 
-            match ArticleRepository::save(&*state.repo, updated_article, UserId(user.id), payload._reason).await {
-                Ok(_) => {
+            match ArticleRepository::save_if_revision(&*state.repo, updated_article, UserId(user.id), payload._reason, expected).await {
+                Ok(revision) => {
                     // Background Indexing
                     let indexer = state.indexer_service.clone();
                     // body_content is owned string here, we move it to async block
@@ -357,7 +387,39 @@ pub async fn update_content_handler(
                              tracing::error!("Async Indexing failed for {}: {}", id, e);
                          }
                     });
-                    (StatusCode::OK, Json(serde_json::json!({ "id": id }))).into_response()
+                    // Published sites of the KB(s) the article is in, or was moved out of
+                    for kb_id in [link_scope, previous_scope.filter(|p| Some(*p) != link_scope)].into_iter().flatten() {
+                        state.site_generator.schedule_rebuild(kb_id);
+                    }
+                    let mut response = serde_json::json!({ "id": id, "revision": revision });
+                    if let Some(from) = renamed_from {
+                        let links = LinkRepository::new(state.repo.db.clone())
+                            .find_links_to(id, &from, link_scope).await
+                            .map(|links| links.len())
+                            .unwrap_or(0);
+                        if links > 0 {
+                            response["rename"] = serde_json::json!({ "from": from, "links": links });
+                        }
+                    }
+                    (StatusCode::OK, [(ETAG, etag(revision))], Json(response)).into_response()
+                },
+                Err(RepositoryError::RevisionConflict { expected, current }) => {
+                    // Diff from the version the client edited to the current one
+                    let base = match base_version {
+                        Some(v) => ArticleRepository::get_version(&*state.repo, &id, &v).await.ok().flatten(),
+                        None => None,
+                    };
+                    match ArticleRepository::find_by_id(&*state.repo, &id).await {
+                        Ok(Some(item)) => {
+                            let base_text = base.and_then(|b| b.body).map(|b| body_text(&b)).unwrap_or_default();
+                            let current_text = match &item {
+                                crate::domain::models::ContentItem::Article(a) => body_text(&a.body),
+                                crate::domain::models::ContentItem::Node(_) => String::new(),
+                            };
+                            conflict_response(expected, current, &item, &base_text, &current_text)
+                        },
+                        _ => (StatusCode::NOT_FOUND, Json(serde_json::json!({ "error": "Content not found" }))).into_response(),
+                    }
                 },
                 Err(RepositoryError::DuplicateTitle(msg)) => (StatusCode::CONFLICT, Json(serde_json::json!({ "error": msg }))).into_response(),
                 Err(RepositoryError::ValidationError(msg)) => (StatusCode::BAD_REQUEST, Json(serde_json::json!({ "error": msg }))).into_response(),
@@ -367,6 +429,15 @@ pub async fn update_content_handler(
     }
 }
 
+fn body_text(body: &ContentBody) -> String {
+    match body {
+        ContentBody::Markdown(t) => t.clone(),
+        ContentBody::CodeSnippet { code, .. } => code.clone(),
+        ContentBody::Custom(v) => v.to_string(),
+        ContentBody::Video { url, .. } => url.clone(),
+    }
+}
+
 // ... ListContentHandler
 #[derive(serde::Deserialize)]
 pub struct ListParams {
@@ -492,13 +563,24 @@ pub async fn get_content_handler(
                     }
                 }
 
+                let revision = match &item {
+                    crate::domain::models::ContentItem::Article(_) => ArticleRepository::current_revision(&*state.repo, &id).await.unwrap_or(0),
+                    crate::domain::models::ContentItem::Node(n) => n.updated_at.timestamp_micros(),
+                };
+
+                let references = match &item {
+                    crate::domain::models::ContentItem::Article(_) => crate::interface::api::blocks::resolve_references(&state, id, &user.0).await,
+                    crate::domain::models::ContentItem::Node(_) => Vec::new(),
+                };
+
                 let response = ContentResponse {
                     item,
                     user_permission: user_permission.to_string(),
                     collaborators,
+                    references,
                 };
 
-                 (StatusCode::OK, Json(response)).into_response()
+                 (StatusCode::OK, [(ETAG, etag(revision))], Json(response)).into_response()
             } else {
                  (StatusCode::FORBIDDEN, Json(serde_json::json!({ "error": "Access denied" }))).into_response()
             }
@@ -525,8 +607,14 @@ pub async fn delete_content_handler(
         return (StatusCode::FORBIDDEN, Json(serde_json::json!({ "error": "Access denied" }))).into_response();
     }
 
+    let kb_id = node.knowledge_base_id;
     match state.repo.delete_recursive(&id).await {
-        Ok(_) => (StatusCode::NO_CONTENT, ()).into_response(),
+        Ok(_) => {
+            if let Some(kb_id) = kb_id {
+                state.site_generator.schedule_rebuild(kb_id);
+            }
+            (StatusCode::NO_CONTENT, ()).into_response()
+        },
         Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({ "error": e.to_string() }))).into_response(),
     }
 }
@@ -789,9 +877,15 @@ pub async fn publish_draft_handler(
         updated_article.body = crate::domain::models::ContentBody::Markdown(draft_body.to_string());
         updated_article.derived_data = derived_data_value;
         updated_article.node.title = draft_title; // Update Node Title
+        let kb_id = updated_article.node.knowledge_base_id;
 
         match ArticleRepository::save(&*state.repo, updated_article, UserId(user.id), Some("Published from Draft".to_string())).await {
-             Ok(_) => (StatusCode::OK, Json(serde_json::json!({ "status": "published" }))).into_response(),
+             Ok(_) => {
+                 if let Some(kb_id) = kb_id {
+                     state.site_generator.schedule_rebuild(kb_id);
+                 }
+                 (StatusCode::OK, Json(serde_json::json!({ "status": "published" }))).into_response()
+             },
              Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({ "error": e.to_string() }))).into_response(),
         }
     } else {
@@ -1,19 +1,57 @@
 use axum::{
     Router,
-    routing::get,
-    extract::{Query, State},
+    routing::{get, patch, post, put},
+    extract::{Multipart, Path, Query, State},
     Json,
     response::IntoResponse,
-    http::StatusCode,
+    http::{header, StatusCode},
 };
 use serde::{Deserialize, Serialize};
+use crate::domain::models::UserId;
+use crate::domain::morphology::Morphology;
+use crate::domain::ports::VocabularyRepository;
+use crate::infrastructure::dictionary::loader::{DictionaryAdminError, SourceDefinition};
+use crate::infrastructure::dictionary::parser::EntryParserRegistry;
+use crate::infrastructure::dictionary::reverse::{sort_hits, ReverseHit, ReverseIndex};
+pub use crate::infrastructure::dictionary::parser::{Definition, Meaning};
+use crate::infrastructure::persistence::repositories::settings::SettingsRepository;
+use crate::interface::api::auth::{require_admin, AuthenticatedUser, MaybeAuthenticatedUser};
 use crate::interface::state::AppState;
 
+/// `user_module_settings` key holding a user's dictionary set.
+const SETTINGS_MODULE: &str = "dictionary";
+/// Remote sources, orderable next to local dictionary ids.
+const REMOTE_FREE_DICTIONARY: &str = "remote:free_dictionary";
+const REMOTE_DATAMUSE: &str = "remote:datamuse";
+/// Source label of reverse-search hits from the user's own vocabulary.
+const VOCABULARY_SOURCE: &str = "vocabulary";
+const REVERSE_DEFAULT_LIMIT: usize = 20;
+const REVERSE_MAX_LIMIT: usize = 100;
+
 #[derive(Deserialize)]
 pub struct LookupRequest {
     pub word: String,
 }
 
+#[derive(Deserialize)]
+pub struct ResourceRequest {
+    pub path: String,
+}
+
+#[derive(Deserialize)]
+pub struct ReverseRequest {
+    pub q: String,
+    pub limit: Option<usize>,
+}
+
+#[derive(Serialize)]
+pub struct ReverseResponse {
+    pub query: String,
+    pub results: Vec<ReverseHit>,
+    /// Dictionary definitions are still being indexed; results only cover vocabulary so far.
+    pub indexing: bool,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct DictionaryEntry {
     pub word: String,
@@ -21,25 +59,279 @@ pub struct DictionaryEntry {
     pub meanings: Vec<Meaning>,
     pub translation: Option<String>,
     pub source: String,
+    /// Per-dictionary results in the user's order; the fields above merge them.
+    #[serde(default)]
+    pub entries: Vec<SourceEntry>,
 }
 
+/// One dictionary's result for a lookup, labeled by source.
 #[derive(Serialize, Deserialize, Debug, Clone)]
-pub struct Meaning {
-    #[serde(rename = "partOfSpeech")]
-    pub part_of_speech: String,
-    pub definitions: Vec<Definition>,
+pub struct SourceEntry {
+    pub dictionary_id: String,
+    pub source: String,
+    pub phonetic: Option<String>,
+    pub meanings: Vec<Meaning>,
+    /// Sanitized original markup of HTML dictionaries (MDict)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub html: Option<String>,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
-pub struct Definition {
-    pub definition: String,
-    pub example: Option<String>,
+#[derive(Deserialize)]
+pub struct ConfigureDictionaryRequest {
+    pub enabled: Option<bool>,
+    pub priority: Option<i32>,
+}
+
+#[derive(Deserialize)]
+pub struct ReorderRequest {
+    pub ids: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct DictionaryPreferences {
+    /// Ordered dictionary ids, local or `remote:*`. Empty means the global order.
+    #[serde(default)]
+    pub dictionaries: Vec<String>,
+}
+
+#[derive(Serialize)]
+pub struct AvailableSource {
+    pub id: String,
+    pub name: String,
+    pub remote: bool,
+}
+
+#[derive(Serialize)]
+pub struct PreferencesResponse {
+    pub dictionaries: Vec<String>,
+    pub custom: bool,
+    pub available: Vec<AvailableSource>,
 }
 
 pub fn router() -> Router<AppState> {
     Router::new()
         .route("/api/dictionary/lookup", get(lookup_word))
         .route("/api/dictionary/fuzzy", get(fuzzy_search))
+        .route("/api/dictionary/reverse", get(reverse_search))
+        .route("/api/dictionary/resource", get(get_resource))
+        .route("/api/dictionary/sources", get(list_sources).post(upload_source))
+        .route("/api/dictionary/sources/order", put(reorder_sources))
+        .route("/api/dictionary/sources/:id", patch(configure_source).delete(delete_source))
+        .route("/api/dictionary/reload", post(reload_sources))
+        .route("/api/dictionary/preferences", get(get_preferences).put(update_preferences))
+}
+
+fn admin_error(e: DictionaryAdminError) -> (StatusCode, String) {
+    let status = match e {
+        DictionaryAdminError::NotFound(_) => StatusCode::NOT_FOUND,
+        DictionaryAdminError::InvalidInput(_) => StatusCode::BAD_REQUEST,
+        DictionaryAdminError::Io(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    };
+    (status, e.to_string())
+}
+
+async fn list_sources(State(state): State<AppState>) -> impl IntoResponse {
+    Json(state.dictionary.list())
+}
+
+/// Multipart: optional `id` text field, one or more `file` fields
+/// (StarDict .ifo/.idx/.dict[.dz], MDict .mdx/.mdd, or a .zip of them).
+async fn upload_source(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    mut multipart: Multipart,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    require_admin(&user)?;
+
+    let mut id: Option<String> = None;
+    let mut files = Vec::new();
+    while let Some(field) = multipart.next_field().await.map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))? {
+        let name = field.name().map(|n| n.to_string());
+        match name.as_deref() {
+            Some("id") => {
+                let text = field.text().await.map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))?;
+                id = Some(text.trim().to_string()).filter(|s| !s.is_empty());
+            }
+            Some("file") => {
+                let filename = field.file_name().unwrap_or("upload").to_string();
+                let data = field.bytes().await.map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))?;
+                files.push((filename, data.to_vec()));
+            }
+            _ => {}
+        }
+    }
+
+    if files.is_empty() {
+        return Err((StatusCode::BAD_REQUEST, "No file uploaded".to_string()));
+    }
+
+    // Default id: first file name up to the first dot ("oxford.dict.dz" -> "oxford")
+    let id = id.unwrap_or_else(|| {
+        files[0].0.rsplit(['/', '\\']).next().unwrap_or("")
+            .split('.').next().unwrap_or("")
+            .chars().map(|c| if c.is_ascii_alphanumeric() || c == '-' { c } else { '_' })
+            .collect()
+    });
+
+    let installed = state.dictionary.install(&id, files).await.map_err(admin_error)?;
+    state.dictionary_cache.invalidate_all();
+    Ok((StatusCode::CREATED, Json(installed)))
+}
+
+async fn configure_source(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Path(id): Path<String>,
+    Json(payload): Json<ConfigureDictionaryRequest>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    require_admin(&user)?;
+    let info = state.dictionary.configure(&id, payload.enabled, payload.priority).await.map_err(admin_error)?;
+    state.dictionary_cache.invalidate_all();
+    Ok(Json(info))
+}
+
+async fn reorder_sources(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Json(payload): Json<ReorderRequest>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    require_admin(&user)?;
+    let list = state.dictionary.reorder(&payload.ids).await.map_err(admin_error)?;
+    state.dictionary_cache.invalidate_all();
+    Ok(Json(list))
+}
+
+async fn delete_source(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    require_admin(&user)?;
+    state.dictionary.remove(&id).await.map_err(admin_error)?;
+    state.dictionary_cache.invalidate_all();
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Picks up dictionaries copied into `data/dictionary` by hand.
+async fn reload_sources(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    require_admin(&user)?;
+    state.dictionary.reload().await.map_err(admin_error)?;
+    state.dictionary_cache.invalidate_all();
+    Ok(Json(state.dictionary.list()))
+}
+
+/// Enabled local dictionaries by global priority, then the remote sources.
+fn default_sources(state: &AppState) -> Vec<String> {
+    let mut ids: Vec<String> = state.dictionary.list().into_iter()
+        .filter(|d| d.enabled)
+        .map(|d| d.id)
+        .collect();
+    ids.push(REMOTE_FREE_DICTIONARY.to_string());
+    ids.push(REMOTE_DATAMUSE.to_string());
+    ids
+}
+
+async fn user_preferences(state: &AppState, user: &AuthenticatedUser) -> DictionaryPreferences {
+    SettingsRepository::get_settings(&state.repo.db, user.id, SETTINGS_MODULE).await
+        .ok()
+        .flatten()
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or_default()
+}
+
+/// The user's ordered dictionary set, or the global default.
+async fn resolve_sources(state: &AppState, user: Option<&AuthenticatedUser>) -> Vec<String> {
+    if let Some(user) = user {
+        let prefs = user_preferences(state, user).await;
+        if !prefs.dictionaries.is_empty() {
+            return prefs.dictionaries;
+        }
+    }
+    default_sources(state)
+}
+
+fn preferences_response(state: &AppState, prefs: DictionaryPreferences) -> PreferencesResponse {
+    let mut available: Vec<AvailableSource> = state.dictionary.list().into_iter()
+        .filter(|d| d.enabled)
+        .map(|d| AvailableSource { id: d.id, name: d.name, remote: false })
+        .collect();
+    available.push(AvailableSource { id: REMOTE_FREE_DICTIONARY.to_string(), name: "FreeDictionaryAPI".to_string(), remote: true });
+    available.push(AvailableSource { id: REMOTE_DATAMUSE.to_string(), name: "Datamuse".to_string(), remote: true });
+
+    let custom = !prefs.dictionaries.is_empty();
+    PreferencesResponse {
+        dictionaries: if custom { prefs.dictionaries } else { default_sources(state) },
+        custom,
+        available,
+    }
+}
+
+async fn get_preferences(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+) -> impl IntoResponse {
+    let prefs = user_preferences(&state, &user).await;
+    Json(preferences_response(&state, prefs))
+}
+
+/// Saves the user's ordered dictionary set. An empty list resets to the global order.
+async fn update_preferences(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Json(payload): Json<DictionaryPreferences>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    let mut dictionaries: Vec<String> = Vec::new();
+    for id in payload.dictionaries {
+        let known = id == REMOTE_FREE_DICTIONARY || id == REMOTE_DATAMUSE || state.dictionary.exists(&id);
+        if !known {
+            return Err((StatusCode::BAD_REQUEST, format!("Unknown dictionary: {}", id)));
+        }
+        if !dictionaries.contains(&id) {
+            dictionaries.push(id);
+        }
+    }
+
+    let prefs = DictionaryPreferences { dictionaries };
+    let value = serde_json::to_value(&prefs).map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    SettingsRepository::update_settings(&state.repo.db, user.id, SETTINGS_MODULE, value).await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(Json(preferences_response(&state, prefs)))
+}
+
+/// Serves audio/images/stylesheets embedded in MDict `.mdd` files,
+/// e.g. `sound://hello.mp3` -> `/api/dictionary/resource?path=hello.mp3`.
+async fn get_resource(
+    State(state): State<AppState>,
+    Query(params): Query<ResourceRequest>,
+) -> impl IntoResponse {
+    match state.dictionary.resource(&params.path) {
+        Some(bytes) => {
+            let ext = params.path.rsplit('.').next().unwrap_or("").to_lowercase();
+            let mime = match ext.as_str() {
+                "mp3" => "audio/mpeg",
+                "wav" => "audio/wav",
+                "ogg" => "audio/ogg",
+                "spx" => "audio/ogg",
+                "png" => "image/png",
+                "jpg" | "jpeg" => "image/jpeg",
+                "gif" => "image/gif",
+                "svg" => "image/svg+xml",
+                "css" => "text/css",
+                "js" => "application/javascript",
+                _ => "application/octet-stream",
+            };
+            (
+                StatusCode::OK,
+                [(header::CONTENT_TYPE, mime), (header::CACHE_CONTROL, "public, max-age=86400")],
+                bytes,
+            ).into_response()
+        }
+        None => (StatusCode::NOT_FOUND, "Resource not found").into_response(),
+    }
 }
 
 async fn fuzzy_search(
@@ -50,121 +342,125 @@ async fn fuzzy_search(
     Json(matches)
 }
 
+/// Words by meaning: full-text search over definitions in the user's local
+/// dictionaries and, when signed in, their own vocabulary.
+async fn reverse_search(
+    State(state): State<AppState>,
+    user: MaybeAuthenticatedUser,
+    Query(params): Query<ReverseRequest>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    let query = params.q.trim().to_string();
+    if query.is_empty() {
+        return Err((StatusCode::BAD_REQUEST, "Query must not be empty".to_string()));
+    }
+    let limit = params.limit.unwrap_or(REVERSE_DEFAULT_LIMIT).clamp(1, REVERSE_MAX_LIMIT);
+
+    let sources = resolve_sources(&state, user.0.as_ref()).await;
+    let local_ids: Vec<String> = sources.into_iter().filter(|s| !s.starts_with("remote:")).collect();
+    let dictionary_hits = state.dictionary.reverse_search(&query, Some(&local_ids), limit).await;
+    let indexing = dictionary_hits.is_none();
+    let mut results = dictionary_hits.unwrap_or_default();
+
+    // The user's words are few enough to index per request
+    if let Some(user) = user.0.as_ref() {
+        let definitions = state.repo.list_definitions(&UserId(user.id)).await
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+        let mut index = ReverseIndex::new();
+        for (word, definition, translation) in &definitions {
+            let text = format!("{} {}", definition, translation.as_deref().unwrap_or(""));
+            index.add(VOCABULARY_SOURCE, word, &text);
+        }
+        for mut hit in index.search(&query, None, limit) {
+            hit.snippet = definitions.iter().find(|(w, _, _)| *w == hit.word).map(|(_, d, _)| d.clone());
+            match results.iter_mut().find(|r| r.word.eq_ignore_ascii_case(&hit.word)) {
+                // Found in both: the user's own word ranks higher
+                Some(existing) => {
+                    existing.score += hit.score;
+                    existing.sources.push(VOCABULARY_SOURCE.to_string());
+                }
+                None => results.push(hit),
+            }
+        }
+        sort_hits(&mut results);
+        results.truncate(limit);
+    }
+
+    Ok(Json(ReverseResponse { query, results, indexing }))
+}
+
+/// Turns a local dictionary's raw text into a labeled entry, using the parser
+/// that best fits the dictionary's markup.
+fn parse_local_definition(parsers: &EntryParserRegistry, def: SourceDefinition) -> SourceEntry {
+    let parsed = parsers.parse(&def.text, def.markup);
+    SourceEntry {
+        dictionary_id: def.dictionary_id,
+        source: def.name,
+        phonetic: parsed.phonetic,
+        meanings: parsed.meanings,
+        html: parsed.html,
+    }
+}
+
+fn to_source_entry(entry: DictionaryEntry, dictionary_id: &str) -> SourceEntry {
+    SourceEntry {
+        dictionary_id: dictionary_id.to_string(),
+        source: entry.source,
+        phonetic: entry.phonetic,
+        meanings: entry.meanings,
+        html: None,
+    }
+}
+
 async fn lookup_word(
     State(state): State<AppState>,
+    user: MaybeAuthenticatedUser,
     Query(params): Query<LookupRequest>,
 ) -> impl IntoResponse {
     let word = params.word;
+    let sources = resolve_sources(&state, user.0.as_ref()).await;
+    // Results depend on the dictionary set, so it is part of the cache key
+    let cache_key = format!("{}|{}", sources.join(","), word);
 
     // 1. Check Cache
-    if let Some(cached_json) = state.dictionary_cache.get(&word).await {
+    if let Some(cached_json) = state.dictionary_cache.get(&cache_key).await {
         if let Ok(entry) = serde_json::from_str::<DictionaryEntry>(&cached_json) {
             return (StatusCode::OK, Json(entry)).into_response();
         }
     }
     
-    // 0. Local StarDict
-    let mut local_entry: Option<DictionaryEntry> = None;
-    if let Some(raw_definitions) = state.dictionary.lookup(&word) {
-        let mut meanings = Vec::new();
-        let mut phonetic = None;
-        let mut current_source = "Local StarDict".to_string();
-
-        for raw_text in raw_definitions {
-            // Check if this is likely Oxford by looking for specific patterns or just general parsing
-            // Heuristic Parsing for complex formats like Oxford:
-            // 1. Extract Phonetic: /.../
-            if let Some(start) = raw_text.find('/') {
-                if let Some(end) = raw_text[start+1..].find('/') {
-                    let p = &raw_text[start..=start+1+end];
-                    // Basic validation to ensure it's not just a slash in text
-                    if p.len() < 50 { 
-                         phonetic = Some(p.to_string());
-                    }
-                }
-            }
-
-            // 2. Identify common POS markers to split main blocks
-            // Oxford often doesn't use newlines, just spaces. e.g. "word... n 1 [C]..."
-            // We need a Regex or smart split. Regex is cleaner but requires dependency.
-            // Let's use a manual state machine for stability without adding generic regex dep if possible,
-            // or just add `regex` crate which is standard. Using regex is safer for this complexity.
-            
-            // For now, let's try to split by known POS tags if they are surrounded by spaces/start
-            // Tags: " n ", " v ", " adj ", " adv ", " prep ", " conj "
-            // Also numbered lists: " 1 ", " 2 "
-            
-            // SIMPLIFIED STRATEGY: 
-            // 1. Split by numbers " 1 ", " 2 " to get main definitions.
-            // 2. Inside each, look for (a), (b).
-            
-            // Let's treat the whole text as one block if we can't easily split POS, 
-            // but we can try to improve the display by inserting newlines for the frontend parser.
-            
-            // ACTUALLY, the user wants "Clear Separation".
-            // Let's clean the text: 
-            // Replace " * " with "\n* " (New bullet point)
-            // Replace " 1 " with "\n1. "
-            // Replace " 2 " with "\n2. "
-            // Replace "(a)" with "\n(a)"
-            
-            let mut cleaned = raw_text.clone();
-            cleaned = cleaned.replace(" * ", "\n* ");
-            
-            // Insert breaks before numbers 1-9
-            for i in 1..10 {
-                cleaned = cleaned.replace(&format!(" {} ", i), &format!("\n{}. ", i));
-            }
-            
-            // Insert breaks for (a), (b)...
-            for c in 'a'..'z' {
-                 cleaned = cleaned.replace(&format!(" ({}) ", c), &format!("\n({}) ", c));
-            }
-
-            // Try to set source name if possible (heuristic)
-            if cleaned.contains("Oxford") {
-                current_source = "Oxford Dictionary".to_string();
-            }
-
-            // Create a generic meaning block with this formatted text
-            // The frontend will rendering newlines as separate blocks
-            meanings.push(Meaning {
-                part_of_speech: "Definition".to_string(), 
-                definitions: vec![Definition {
-                    definition: cleaned,
-                    example: None
-                }],
-            });
+    // 0. Local dictionaries (falls back to the lemma: "running" -> "run")
+    let local_ids: Vec<String> = sources.iter().filter(|s| !s.starts_with("remote:")).cloned().collect();
+    let mut local_hits = state.dictionary.lookup_sources(&word, Some(&local_ids));
+    // The dictionary decides which lemma guess is right
+    for candidate in Morphology::lemma_candidates(&word) {
+        if !local_hits.is_empty() {
+            break;
+        }
+        if candidate != word {
+            local_hits = state.dictionary.lookup_sources(&candidate, Some(&local_ids));
         }
-
-        local_entry = Some(DictionaryEntry {
-            word: word.clone(),
-            phonetic, 
-            meanings, 
-            translation: None,
-            source: current_source,
-        });
     }
+    let mut entries: Vec<SourceEntry> = local_hits.into_iter()
+        .map(|def| parse_local_definition(&state.entry_parsers, def))
+        .collect();
 
-    // 1. FreeDictionaryAPI, 2. Datamuse, 3. MyMemory
-    let mut primary_entry: Option<DictionaryEntry> = None;
-    let mut datamuse_entry: Option<DictionaryEntry> = None;
-
+    // 1. FreeDictionaryAPI, 2. Datamuse (only if in the set), 3. MyMemory
+    let want_free_dictionary = sources.iter().any(|s| s == REMOTE_FREE_DICTIONARY);
+    let want_datamuse = sources.iter().any(|s| s == REMOTE_DATAMUSE);
     let fd_url = format!("https://api.dictionaryapi.dev/api/v2/entries/en/{}", word);
     let dm_url = format!("https://api.datamuse.com/words?sp={}&md=dr&max=1", word);
 
-    // Timeout: 1500ms. If external APIs are slow, we fallback to Local or None.
     let external_task = async {
         tokio::join!(
-            reqwest::get(&fd_url),
-            reqwest::get(&dm_url),
+            async { if want_free_dictionary { reqwest::get(&fd_url).await.ok() } else { None } },
+            async { if want_datamuse { reqwest::get(&dm_url).await.ok() } else { None } },
             fetch_translation(&word)
         )
     };
 
-    // Timeout: 1500ms.
+    // Timeout: 1500ms. If external APIs are slow, we fallback to Local or None.
     let (fd_opt, dm_opt, translation) = match tokio::time::timeout(std::time::Duration::from_millis(1500), external_task).await {
-        Ok((fd_res, dm_res, trans)) => (fd_res.ok(), dm_res.ok(), trans),
+        Ok(results) => results,
         Err(_) => {
             tracing::warn!("External dictionary API timed out for '{}'", word);
             (None, None, None)
@@ -174,9 +470,9 @@ async fn lookup_word(
     // 1. Process FreeDictionaryAPI
     if let Some(response) = fd_opt {
         if response.status().is_success() {
-            if let Ok(entries) = response.json::<Vec<serde_json::Value>>().await {
-                if let Some(first) = entries.first() {
-                    primary_entry = Some(map_free_dictionary_to_entry(first.clone()));
+            if let Ok(raw) = response.json::<Vec<serde_json::Value>>().await {
+                if let Some(first) = raw.first() {
+                    entries.push(to_source_entry(map_free_dictionary_to_entry(first.clone()), REMOTE_FREE_DICTIONARY));
                 }
             }
         }
@@ -185,62 +481,37 @@ async fn lookup_word(
     // 2. Process Datamuse
     if let Some(response) = dm_opt {
          if response.status().is_success() {
-            if let Ok(entries) = response.json::<Vec<serde_json::Value>>().await {
-                if let Some(first) = entries.first() {
-                    datamuse_entry = Some(map_datamuse_to_entry(first.clone(), &word));
+            if let Ok(raw) = response.json::<Vec<serde_json::Value>>().await {
+                if let Some(first) = raw.first() {
+                    entries.push(to_source_entry(map_datamuse_to_entry(first.clone(), &word), REMOTE_DATAMUSE));
                 }
             }
          }
     }
 
-    // Aggregation Logic
-    let mut final_entry = if let Some(mut local) = local_entry {
-        if let Some(p) = primary_entry {
-             if local.phonetic.is_none() { local.phonetic = p.phonetic; }
-             local.source = format!("{}, {}", local.source, p.source);
-             local.meanings.extend(p.meanings);
-        } else if let Some(d) = datamuse_entry {
-              if local.phonetic.is_none() { local.phonetic = d.phonetic; }
-               local.source = format!("{}, {}", local.source, d.source);
-               local.meanings.extend(d.meanings);
-        }
-        local
-    } else {
-        match (primary_entry, datamuse_entry) {
-            (Some(mut p), Some(d)) => {
-                p.source = format!("{}, Datamuse", p.source);
-                for m in d.meanings {
-                     p.meanings.push(m);
-                }
-                p
-            },
-            (Some(p), None) => p,
-            (None, Some(d)) => d,
-            (None, None) => DictionaryEntry {
-                word: word.clone(),
-                phonetic: None,
-                meanings: vec![],
-                translation: None,
-                source: "None".to_string(),
-            },
-        }
-    };
+    // Order by the user's set; the flat fields merge entries in that order
+    entries.sort_by_key(|e| sources.iter().position(|s| *s == e.dictionary_id).unwrap_or(usize::MAX));
 
-    if let Some(t) = translation {
-        final_entry.translation = Some(t);
-        if final_entry.source == "None" {
-             final_entry.source = "MyMemory".to_string();
-        } else {
-             final_entry.source = format!("{}, MyMemory", final_entry.source);
-        }
+    let mut source_names: Vec<String> = entries.iter().map(|e| e.source.clone()).collect();
+    if translation.is_some() {
+        source_names.push("MyMemory".to_string());
     }
 
+    let final_entry = DictionaryEntry {
+        word: word.clone(),
+        phonetic: entries.iter().find_map(|e| e.phonetic.clone()),
+        meanings: entries.iter().flat_map(|e| e.meanings.clone()).collect(),
+        translation,
+        source: if source_names.is_empty() { "None".to_string() } else { source_names.join(", ") },
+        entries,
+    };
+
     if final_entry.source == "None" {
         (StatusCode::NOT_FOUND, Json(final_entry)).into_response()
     } else {
         // Cache the result
         if let Ok(json_str) = serde_json::to_string(&final_entry) {
-            state.dictionary_cache.insert(word, json_str).await;
+            state.dictionary_cache.insert(cache_key, json_str).await;
         }
         (StatusCode::OK, Json(final_entry)).into_response()
     }
@@ -284,10 +555,10 @@ fn map_free_dictionary_to_entry(raw: serde_json::Value) -> DictionaryEntry {
             let mut defs_list = Vec::new();
             if let Some(defs) = m["definitions"].as_array() {
                 for d in defs {
-                     defs_list.push(Definition {
-                         definition: d["definition"].as_str().unwrap_or("").to_string(),
-                         example: d["example"].as_str().map(|s| s.to_string()),
-                     });
+                     defs_list.push(
+                         Definition::new(d["definition"].as_str().unwrap_or(""))
+                             .with_example(d["example"].as_str().map(|s| s.to_string())),
+                     );
                 }
             }
             meanings_list.push(Meaning {
@@ -303,6 +574,7 @@ fn map_free_dictionary_to_entry(raw: serde_json::Value) -> DictionaryEntry {
         meanings: meanings_list,
         translation: None, // Will be filled later
         source: "FreeDictionaryAPI".to_string(),
+        entries: vec![],
     }
 }
 
@@ -340,17 +612,11 @@ fn map_datamuse_to_entry(raw: serde_json::Value, word: &str) -> DictionaryEntry
                     let def_text = parts[1].to_string();
                     
                     if let Some(existing_meaning) = meanings_list.iter_mut().find(|m: &&mut Meaning| m.part_of_speech == pos) {
-                        existing_meaning.definitions.push(Definition {
-                            definition: def_text,
-                            example: None,
-                        });
+                        existing_meaning.definitions.push(Definition::new(def_text));
                     } else {
                         meanings_list.push(Meaning {
                             part_of_speech: pos,
-                            definitions: vec![Definition {
-                                definition: def_text,
-                                example: None,
-                            }],
+                            definitions: vec![Definition::new(def_text)],
                         });
                     }
                 }
@@ -364,5 +630,6 @@ fn map_datamuse_to_entry(raw: serde_json::Value, word: &str) -> DictionaryEntry
         meanings: meanings_list,
         translation: None,
         source: "Datamuse".to_string(),
+        entries: vec![],
     }
 }
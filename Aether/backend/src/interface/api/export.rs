@@ -7,7 +7,7 @@ use uuid::Uuid;
 use crate::domain::models::UserId;
 use crate::interface::api::auth::AuthenticatedUser;
 use crate::interface::state::AppState;
-use crate::domain::ports::ExportFormat; // Need Trait
+use crate::domain::ports::{ExportFormat, RepositoryError}; // Need Trait
 
 #[derive(serde::Deserialize, Debug)]
 pub struct ExportRequest {
@@ -52,8 +52,31 @@ pub async fn export_node_handler(
     }
 }
 
+pub async fn export_knowledge_base_handler(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Path(id): Path<Uuid>,
+) -> impl IntoResponse {
+    match state.export_service.export_knowledge_base_site(&id, Some(UserId(user.id))).await {
+        Ok(bytes) => {
+             let mut headers = HeaderMap::new();
+             headers.insert(header::CONTENT_TYPE, "application/zip".parse().unwrap());
+             headers.insert(header::CONTENT_DISPOSITION, format!("attachment; filename=\"site_{}.zip\"", id).parse().unwrap());
+
+             (StatusCode::OK, headers, bytes).into_response()
+        },
+        Err(RepositoryError::NotFound(_)) => (StatusCode::NOT_FOUND, "Knowledge base not found").into_response(),
+        Err(RepositoryError::Unauthorized(_)) => (StatusCode::FORBIDDEN, "Unauthorized").into_response(),
+        Err(e) => {
+            tracing::error!("Site export failed: {:?}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, "Export failed").into_response()
+        }
+    }
+}
+
 pub fn router() -> axum::Router<crate::interface::state::AppState> {
     use axum::routing::get;
     axum::Router::new()
         .route("/api/export/:id", get(export_node_handler))
+        .route("/api/export/kb/:id", get(export_knowledge_base_handler))
 }
@@ -0,0 +1,122 @@
+use axum::{
+    extract::{Path, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    routing::get,
+    Router,
+};
+use chrono::{DateTime, Utc};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+use crate::domain::ports::RepositoryError;
+use crate::infrastructure::services::feeds::FeedDocument;
+use crate::interface::state::AppState;
+
+pub fn router() -> Router<AppState> {
+    // `:file` is `<id>.atom`: a path parameter takes the whole segment
+    Router::new()
+        .route("/api/feeds/kb/:file", get(knowledge_base_feed_handler))
+        .route("/api/feeds/user/:file", get(user_feed_handler))
+        .route("/api/feeds/tag/:file", get(tag_feed_handler))
+}
+
+/// The app's public URL: `PUBLIC_APP_URL` when configured, else as the client
+/// sees it, behind a proxy or not. The flag tells whether it came from config.
+fn request_base(state: &AppState, headers: &HeaderMap) -> (String, bool) {
+    if let Some(base) = state.feed_service.base_url() {
+        return (base.to_string(), true);
+    }
+    let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok()).map(str::to_string);
+    let scheme = header("x-forwarded-proto").unwrap_or_else(|| "http".to_string());
+    let host = header("x-forwarded-host").or_else(|| header("host")).unwrap_or_else(|| "localhost".to_string());
+    (format!("{}://{}", scheme, host), false)
+}
+
+fn atom_name(file: &str) -> Result<&str, Response> {
+    file.strip_suffix(".atom")
+        .filter(|name| !name.is_empty())
+        .ok_or_else(|| StatusCode::NOT_FOUND.into_response())
+}
+
+/// Serves a feed with validators; readers polling an unchanged feed get a 304.
+/// Only feeds built on the configured URL may be kept by shared caches: links
+/// taken from request headers must not be served to other clients.
+fn feed_response(result: Result<FeedDocument, RepositoryError>, headers: &HeaderMap, configured: bool) -> Response {
+    let feed = match result {
+        Ok(feed) => feed,
+        Err(RepositoryError::NotFound(msg)) => return (StatusCode::NOT_FOUND, msg).into_response(),
+        Err(e) => {
+            tracing::error!("Feed generation failed: {:?}", e);
+            return (StatusCode::INTERNAL_SERVER_ERROR, "Feed generation failed").into_response();
+        }
+    };
+
+    let etag = format!("\"{:x}\"", Sha256::digest(feed.xml.as_bytes()));
+    let last_modified = feed.updated.format("%a, %d %b %Y %H:%M:%S GMT").to_string();
+    let cache_headers = [
+        (header::ETAG, etag.clone()),
+        (header::LAST_MODIFIED, last_modified),
+        (header::CACHE_CONTROL, if configured { "public, max-age=300" } else { "private, max-age=300" }.to_string()),
+    ];
+
+    let if_none_match = headers.get(header::IF_NONE_MATCH).and_then(|v| v.to_str().ok());
+    let not_modified = match if_none_match {
+        Some(tags) => tags.split(',').any(|t| t.trim() == etag || t.trim() == "*"),
+        None => headers.get(header::IF_MODIFIED_SINCE)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| DateTime::parse_from_rfc2822(v).ok())
+            .is_some_and(|since| feed.updated.timestamp() <= since.with_timezone(&Utc).timestamp()),
+    };
+    if not_modified {
+        return (StatusCode::NOT_MODIFIED, cache_headers).into_response();
+    }
+
+    (
+        StatusCode::OK,
+        cache_headers,
+        [(header::CONTENT_TYPE, "application/atom+xml; charset=utf-8")],
+        feed.xml,
+    ).into_response()
+}
+
+pub async fn knowledge_base_feed_handler(
+    State(state): State<AppState>,
+    Path(file): Path<String>,
+    headers: HeaderMap,
+) -> Response {
+    let kb_id = match atom_name(&file).map(Uuid::parse_str) {
+        Ok(Ok(id)) => id,
+        _ => return StatusCode::NOT_FOUND.into_response(),
+    };
+    let (base, configured) = request_base(&state, &headers);
+    let result = state.feed_service.knowledge_base_feed(kb_id, &base).await;
+    feed_response(result, &headers, configured)
+}
+
+pub async fn user_feed_handler(
+    State(state): State<AppState>,
+    Path(file): Path<String>,
+    headers: HeaderMap,
+) -> Response {
+    let user_id = match atom_name(&file).map(Uuid::parse_str) {
+        Ok(Ok(id)) => id,
+        _ => return StatusCode::NOT_FOUND.into_response(),
+    };
+    let (base, configured) = request_base(&state, &headers);
+    let result = state.feed_service.user_feed(user_id, &base).await;
+    feed_response(result, &headers, configured)
+}
+
+pub async fn tag_feed_handler(
+    State(state): State<AppState>,
+    Path(file): Path<String>,
+    headers: HeaderMap,
+) -> Response {
+    let tag = match atom_name(&file) {
+        Ok(tag) => tag.to_string(),
+        Err(response) => return response,
+    };
+    let (base, configured) = request_base(&state, &headers);
+    let result = state.feed_service.tag_feed(&tag, &base).await;
+    feed_response(result, &headers, configured)
+}
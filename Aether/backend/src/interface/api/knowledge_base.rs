@@ -165,6 +165,7 @@ pub async fn get_knowledge_base_handler(
 }
 
 pub async fn delete_knowledge_base_handler(
+    State(state): State<AppState>,
     State(repo): State<Arc<dyn KnowledgeBaseRepository>>,
     user: AuthenticatedUser,
     Path(id): Path<Uuid>,
@@ -181,13 +182,17 @@ pub async fn delete_knowledge_base_handler(
     }
 
     match repo.delete(&KnowledgeBaseId(id)).await {
-        Ok(_) => (StatusCode::NO_CONTENT, ()).into_response(),
+        Ok(_) => {
+            // Takes a published site down
+            state.site_generator.schedule_rebuild(id);
+            (StatusCode::NO_CONTENT, ()).into_response()
+        },
         Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({ "error": e.to_string() }))).into_response(),
     }
 }
 
 pub async fn update_knowledge_base_handler(
-    State(_state): State<AppState>,
+    State(state): State<AppState>,
     State(repo): State<Arc<dyn KnowledgeBaseRepository>>,
     user: AuthenticatedUser,
     Path(id): Path<Uuid>,
@@ -242,7 +247,11 @@ pub async fn update_knowledge_base_handler(
 
     // 4. Save
     match repo.save(existing).await {
-        Ok(_) => (StatusCode::OK, Json(serde_json::json!({ "id": id }))).into_response(),
+        Ok(_) => {
+            // Retitles a published site, or takes it down once the KB is no longer public
+            state.site_generator.schedule_rebuild(id);
+            (StatusCode::OK, Json(serde_json::json!({ "id": id }))).into_response()
+        },
         Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({ "error": e.to_string() }))).into_response(),
     }
 }
@@ -0,0 +1,235 @@
+use axum::{
+    Json, extract::{State, Path, Query}, response::{IntoResponse, Response},
+    http::StatusCode,
+};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use chrono::Utc;
+use crate::domain::blocks::links::rewrite_wiki_links;
+use crate::domain::models::{ContentBody, ContentItem, UserId};
+use crate::domain::ports::{ArticleRepository, RepositoryError};
+use crate::infrastructure::persistence::repositories::link_repository::LinkRepository;
+use crate::interface::api::auth::{AuthenticatedUser, MaybeAuthenticatedUser};
+use crate::interface::api::blocks::readable_title;
+use crate::interface::api::content::{check_edit_permission, check_view_permission};
+use crate::interface::state::AppState;
+
+#[derive(Serialize)]
+pub struct Backlink {
+    pub source_id: Uuid,
+    pub source_title: String,
+    pub source_block_id: Option<Uuid>,
+    pub alias: Option<String>,
+    pub context: String,
+}
+
+#[derive(Deserialize)]
+pub struct WantedParams {
+    pub knowledge_base_id: Option<Uuid>,
+}
+
+/// A title linked to but not written yet, with the articles wanting it.
+#[derive(Serialize)]
+pub struct WantedPage {
+    pub title: String,
+    pub count: usize,
+    pub sources: Vec<Uuid>,
+}
+
+#[derive(Deserialize)]
+pub struct RewriteLinksRequest {
+    pub from: String, // The old title
+}
+
+fn error(status: StatusCode, message: impl ToString) -> Response {
+    (status, Json(serde_json::json!({ "error": message.to_string() }))).into_response()
+}
+
+/// Incoming wiki links, from the articles the reader can see.
+pub async fn backlinks_handler(
+    State(state): State<AppState>,
+    user: MaybeAuthenticatedUser,
+    Path(id): Path<Uuid>,
+) -> impl IntoResponse {
+    let node = match ArticleRepository::find_by_id(&*state.repo, &id).await {
+        Ok(Some(item)) => item.node().clone(),
+        Ok(None) => return error(StatusCode::NOT_FOUND, "Content not found"),
+        Err(e) => return error(StatusCode::INTERNAL_SERVER_ERROR, e),
+    };
+    if !check_view_permission(&state.repo, &node, &user.0).await {
+        return error(StatusCode::FORBIDDEN, "Access denied");
+    }
+    let links = match LinkRepository::new(state.repo.db.clone()).find_backlinks(id).await {
+        Ok(links) => links,
+        Err(e) => return error(StatusCode::INTERNAL_SERVER_ERROR, e),
+    };
+
+    let mut titles: HashMap<Uuid, Option<String>> = HashMap::new();
+    let mut backlinks = Vec::new();
+    for link in links {
+        let title = match titles.get(&link.source_id) {
+            Some(title) => title.clone(),
+            None => {
+                let title = readable_title(&state, link.source_id, &user.0).await;
+                titles.insert(link.source_id, title.clone());
+                title
+            }
+        };
+        let Some(source_title) = title else { continue };
+        backlinks.push(Backlink {
+            source_id: link.source_id,
+            source_title,
+            source_block_id: link.source_block_id,
+            alias: link.alias,
+            context: link.context,
+        });
+    }
+    (StatusCode::OK, Json(backlinks)).into_response()
+}
+
+/// Unresolved links in a knowledge base, most wanted first.
+pub async fn wanted_pages_handler(
+    State(state): State<AppState>,
+    user: MaybeAuthenticatedUser,
+    Query(params): Query<WantedParams>,
+) -> impl IntoResponse {
+    let links = match LinkRepository::new(state.repo.db.clone()).find_wanted(params.knowledge_base_id).await {
+        Ok(links) => links,
+        Err(e) => return error(StatusCode::INTERNAL_SERVER_ERROR, e),
+    };
+
+    let mut readable: HashMap<Uuid, bool> = HashMap::new();
+    let mut pages: BTreeMap<String, WantedPage> = BTreeMap::new();
+    for link in links {
+        let visible = match readable.get(&link.source_id) {
+            Some(visible) => *visible,
+            None => {
+                let visible = readable_title(&state, link.source_id, &user.0).await.is_some();
+                readable.insert(link.source_id, visible);
+                visible
+            }
+        };
+        if !visible {
+            continue;
+        }
+        // Titles resolve ignoring case: `[[Rust]]` and `[[rust]]` want the same page
+        let page = pages.entry(link.target_title.to_lowercase()).or_insert_with(|| WantedPage {
+            title: link.target_title.clone(),
+            count: 0,
+            sources: Vec::new(),
+        });
+        page.count += 1;
+        if !page.sources.contains(&link.source_id) {
+            page.sources.push(link.source_id);
+        }
+    }
+
+    let mut pages: Vec<WantedPage> = pages.into_values().collect();
+    pages.sort_by(|a, b| b.count.cmp(&a.count));
+    (StatusCode::OK, Json(pages)).into_response()
+}
+
+/// After a rename, points the links written with the old title at the new
+/// one, in every linking article the caller may edit. Articles that are
+/// being edited concurrently are skipped rather than retried.
+pub async fn rewrite_links_handler(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<RewriteLinksRequest>,
+) -> impl IntoResponse {
+    let target = match ArticleRepository::find_by_id(&*state.repo, &id).await {
+        Ok(Some(item)) => item.node().clone(),
+        Ok(None) => return error(StatusCode::NOT_FOUND, "Content not found"),
+        Err(e) => return error(StatusCode::INTERNAL_SERVER_ERROR, e),
+    };
+    if !check_view_permission(&state.repo, &target, &Some(user)).await {
+        return error(StatusCode::FORBIDDEN, "Access denied");
+    }
+    if payload.from.trim().is_empty() || payload.from.trim().eq_ignore_ascii_case(&target.title) {
+        return error(StatusCode::BAD_REQUEST, "Nothing to rewrite");
+    }
+
+    let links = match LinkRepository::new(state.repo.db.clone())
+        .find_links_to(id, &payload.from, target.knowledge_base_id).await
+    {
+        Ok(links) => links,
+        Err(e) => return error(StatusCode::INTERNAL_SERVER_ERROR, e),
+    };
+    let mut sources: Vec<Uuid> = links.iter().map(|l| l.source_id).collect();
+    sources.sort();
+    sources.dedup();
+
+    let reason = format!("Rename links: {} → {}", payload.from.trim(), target.title);
+    let mut updated = Vec::new();
+    let mut skipped = Vec::new();
+    let mut sites = HashSet::new();
+    // Stops at the first failure; sites of what was rewritten by then are still refreshed
+    let outcome = async {
+        for source_id in sources {
+            let revision = match ArticleRepository::current_revision(&*state.repo, &source_id).await {
+                Ok(revision) => revision,
+                Err(e) => return Err(error(StatusCode::INTERNAL_SERVER_ERROR, e)),
+            };
+            let mut article = match ArticleRepository::find_by_id(&*state.repo, &source_id).await {
+                Ok(Some(ContentItem::Article(a))) => a,
+                Ok(_) => continue,
+                Err(e) => return Err(error(StatusCode::INTERNAL_SERVER_ERROR, e)),
+            };
+            if !check_edit_permission(&state.repo, &article.node, &user).await {
+                skipped.push(source_id);
+                continue;
+            }
+            let ContentBody::Markdown(body) = &article.body else { continue };
+            let (body, count) = rewrite_wiki_links(body, &payload.from, &target.title);
+            if count == 0 {
+                continue;
+            }
+
+            let old_map = article.derived_data.take()
+                .and_then(|v| serde_json::from_value::<crate::domain::sentence_parser::SentenceMap>(v).ok());
+            article.derived_data = serde_json::to_value(
+                crate::domain::sentence_parser::SentenceParser::parse(&body, old_map.as_ref())
+            ).ok();
+            article.body = ContentBody::Markdown(body.clone());
+            article.node.updated_at = Utc::now();
+
+            let kb_id = article.node.knowledge_base_id;
+            match ArticleRepository::save_if_revision(&*state.repo, article, UserId(user.id), Some(reason.clone()), Some(revision)).await {
+                Ok(_) => {
+                    let indexer = state.indexer_service.clone();
+                    tokio::spawn(async move {
+                        if let Err(e) = indexer.index_article(source_id, &body).await {
+                            tracing::error!("Async Indexing failed for {}: {}", source_id, e);
+                        }
+                    });
+                    updated.push(source_id);
+                    sites.extend(kb_id);
+                }
+                Err(RepositoryError::RevisionConflict { .. }) => skipped.push(source_id),
+                Err(e) => return Err(error(StatusCode::INTERNAL_SERVER_ERROR, e)),
+            }
+        }
+        Ok(())
+    }.await;
+    for kb_id in sites {
+        state.site_generator.schedule_rebuild(kb_id);
+    }
+    if let Err(response) = outcome {
+        return response;
+    }
+
+    (StatusCode::OK, Json(serde_json::json!({
+        "updated": updated,
+        "skipped": skipped,
+    }))).into_response()
+}
+
+pub fn router() -> axum::Router<AppState> {
+    use axum::routing::{get, post};
+    axum::Router::new()
+        .route("/api/content/wanted", get(wanted_pages_handler))
+        .route("/api/content/:id/backlinks", get(backlinks_handler))
+        .route("/api/content/:id/rewrite-links", post(rewrite_links_handler))
+}
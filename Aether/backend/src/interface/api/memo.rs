@@ -1,13 +1,14 @@
 use axum::{
     extract::{Path, State, Json, Query},
-    http::StatusCode,
+    http::{StatusCode, HeaderMap, header::ETAG},
     response::IntoResponse,
 };
 use sea_orm::EntityTrait;
 use uuid::Uuid;
 use chrono::{Utc, DateTime};
 use crate::domain::models::{Memo, Node, NodeType, PermissionMode, UserId};
-use crate::domain::ports::MemoRepository; // Import Trait
+use crate::domain::ports::{MemoRepository, RepositoryError}; // Import Trait
+use crate::interface::api::concurrency::{conflict_response, etag, expected_revision};
 use crate::interface::api::auth::{AuthenticatedUser, MaybeAuthenticatedUser};
 use crate::interface::state::AppState;
 
@@ -78,7 +79,7 @@ pub async fn get_memo_handler(
     Path(id): Path<Uuid>,
 ) -> impl IntoResponse {
     match state.repo.find_by_id(&id).await {
-        Ok(Some(memo)) => Json::<Memo>(memo).into_response(),
+        Ok(Some(memo)) => ([(ETAG, etag(memo.revision()))], Json::<Memo>(memo)).into_response(),
         Ok(None) => (StatusCode::NOT_FOUND, "Memo not found").into_response(),
         Err(e) => {
             tracing::error!("Failed to fetch memo: {:?}", e);
@@ -159,14 +160,21 @@ pub struct UpdateMemoRequest {
     pub is_pinned: Option<bool>,
     pub due_at: Option<DateTime<Utc>>,
     pub reminder_at: Option<DateTime<Utc>>,
+    pub expected_revision: Option<i64>, // Alternative to If-Match
 }
 
 pub async fn update_memo_handler(
     State(state): State<AppState>,
     user: AuthenticatedUser,
     Path(id): Path<Uuid>,
+    headers: HeaderMap,
     Json(payload): Json<UpdateMemoRequest>,
 ) -> impl IntoResponse {
+    let expected = match expected_revision(&headers, payload.expected_revision) {
+        Ok(expected) => expected,
+        Err(rejection) => return rejection.into_response(),
+    };
+
     // 1. Fetch Existing
     let existing_memo = match state.repo.find_by_id(&id).await {
         Ok(Some(m)) => m,
@@ -180,6 +188,8 @@ pub async fn update_memo_handler(
     }
 
     // 3. Update Fields
+    // Memos keep no history, so a conflict is diffed against what the client sent
+    let client_content = payload.content.clone().unwrap_or_else(|| existing_memo.content.clone());
     let mut updated_memo = existing_memo;
     if let Some(t) = payload.title { updated_memo.node.title = t; }
     if let Some(c) = payload.content { updated_memo.content = c; }
@@ -210,9 +220,20 @@ pub async fn update_memo_handler(
 
     updated_memo.node.updated_at = Utc::now();
 
-    // 4. Save
-    match state.repo.save(updated_memo).await {
-        Ok(_) => StatusCode::OK.into_response(),
+    // 4. Save (conditionally when the client named the revision it edited)
+    let saved = match expected {
+        Some(expected) => state.repo.save_if_revision(updated_memo, expected).await,
+        None => {
+            let revision = updated_memo.revision();
+            state.repo.save(updated_memo).await.map(|_| revision)
+        }
+    };
+    match saved {
+        Ok(revision) => (StatusCode::OK, [(ETAG, etag(revision))], Json(serde_json::json!({ "revision": revision }))).into_response(),
+        Err(RepositoryError::RevisionConflict { expected, current }) => match state.repo.find_by_id(&id).await {
+            Ok(Some(memo)) => conflict_response(expected, memo.revision(), &memo, &client_content, &memo.content),
+            _ => conflict_response(expected, current, &serde_json::Value::Null, &client_content, ""),
+        },
         Err(e) => {
             tracing::error!("Failed to update memo: {:?}", e);
             (StatusCode::INTERNAL_SERVER_ERROR, "Failed to update memo").into_response()
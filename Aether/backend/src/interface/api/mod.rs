@@ -3,6 +3,10 @@
 pub mod auth;pub mod user_settings;
 pub mod user;
 pub mod content;
+pub mod blocks;
+pub mod links;
+pub mod collab;
+pub mod concurrency;
 pub mod vocabulary;
 pub mod memo;
 pub mod comment;
@@ -14,6 +18,7 @@ pub mod permission;
 pub mod group;
 pub mod vrkb;
 pub mod export;
+pub mod feeds;
 pub mod graph;
 pub mod template;
 pub mod system;
@@ -23,4 +28,5 @@ pub mod prkb;
 pub mod assets;
 pub mod backup;
 pub mod portability;
+pub mod publish;
 pub mod openapi;
\ No newline at end of file
@@ -12,6 +12,8 @@ use super::vocabulary;
         vocabulary::increment_query_count,
         vocabulary::toggle_importance,
         vocabulary::search_sentences,
+        vocabulary::analyze_word,
+        vocabulary::get_word_family,
     ),
     components(
         schemas(
@@ -1,5 +1,5 @@
 use axum::{
-    extract::{Path, State},
+    extract::{Multipart, Path, Query, State},
     response::{IntoResponse, sse::{Sse, Event}},
     http::StatusCode,
     routing::{get, post},
@@ -13,14 +13,33 @@ use std::time::Duration;
 use std::pin::Pin;
 use crate::interface::state::AppState;
 use crate::interface::api::auth::AuthenticatedUser;
-use crate::domain::portability::models::ExportSummary;
+use crate::domain::portability::models::{ExportSummary, ImportSummary};
 
 use crate::domain::ports::KnowledgeBaseRepository; // Import Trait
 
+/// `?format=epub` picks a format provider instead of the KB type's own.
+#[derive(serde::Deserialize)]
+struct ExportQuery {
+    format: Option<String>,
+}
+
+/// `?format=obsidian` (or `markdown`, `notion`) names the format of the uploaded archive.
+#[derive(serde::Deserialize)]
+struct ImportQuery {
+    format: String,
+}
+
+#[derive(serde::Deserialize)]
+struct StartImportRequest {
+    upload_id: Uuid,
+}
+
 pub fn router() -> Router<AppState> {
     Router::new()
         .route("/:kb_id/export/preview", get(analyze_export))
         .route("/:kb_id/export/start", post(start_export))
+        .route("/:kb_id/import/preview", post(analyze_import))
+        .route("/:kb_id/import/start", post(start_import))
         .route("/tasks/:task_id/progress", get(task_progress))
         .route("/tasks/:task_id/download", get(download_export))
 }
@@ -29,6 +48,7 @@ async fn analyze_export(
     State(state): State<AppState>,
     user: AuthenticatedUser,
     Path(kb_id): Path<Uuid>,
+    Query(query): Query<ExportQuery>,
 ) -> Result<Json<ExportSummary>, (StatusCode, String)> {
     // 1. Get KB to find type
     let kb = state.repo.find_by_id(&crate::domain::models::KnowledgeBaseId(kb_id))
@@ -39,7 +59,7 @@ async fn analyze_export(
         return Err((StatusCode::FORBIDDEN, "Unauthorized".to_string()));
     }
 
-    let renderer_id = kb.renderer_id.unwrap_or_else(|| "default".to_string());
+    let renderer_id = query.format.or(kb.renderer_id).unwrap_or_else(|| "default".to_string());
     
     let summary_result = state.portability_service.analyze_export(&renderer_id, kb_id).await;
     
@@ -55,6 +75,7 @@ async fn start_export(
     State(state): State<AppState>,
     user: AuthenticatedUser,
     Path(kb_id): Path<Uuid>,
+    Query(query): Query<ExportQuery>,
 ) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
     let kb = state.repo.find_by_id(&crate::domain::models::KnowledgeBaseId(kb_id))
         .await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
@@ -64,7 +85,7 @@ async fn start_export(
         return Err((StatusCode::FORBIDDEN, "Unauthorized".to_string()));
     }
 
-    let renderer_id = kb.renderer_id.unwrap_or_else(|| "default".to_string());
+    let renderer_id = query.format.or(kb.renderer_id).unwrap_or_else(|| "default".to_string());
 
     let task_id = state.portability_service.start_export(&renderer_id, kb_id, user.id)
         .await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?;
@@ -72,6 +93,77 @@ async fn start_export(
     Ok(Json(serde_json::json!({ "task_id": task_id })))
 }
 
+async fn authorize_author(state: &AppState, kb_id: Uuid, user_id: Uuid) -> Result<(), (StatusCode, String)> {
+    let kb = state.repo.find_by_id(&crate::domain::models::KnowledgeBaseId(kb_id))
+        .await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or((StatusCode::NOT_FOUND, "KB not found".to_string()))?;
+
+    if kb.author_id != user_id {
+        return Err((StatusCode::FORBIDDEN, "Unauthorized".to_string()));
+    }
+    Ok(())
+}
+
+/// Multipart with a `file` field; returns the summary and the `upload_id` to start the import with.
+async fn analyze_import(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Path(kb_id): Path<Uuid>,
+    Query(query): Query<ImportQuery>,
+    mut multipart: Multipart,
+) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
+    authorize_author(&state, kb_id, user.id).await?;
+
+    // Uploads are kept until the import is started, the preview fails, or they expire
+    let mut upload = None;
+    while let Some(field) = multipart.next_field().await.map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))? {
+        if field.name() == Some("file") {
+            let data = field.bytes().await.map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))?;
+            let (upload_id, path) = state.portability_service.register_upload(user.id, kb_id);
+            if let Err(e) = tokio::fs::write(&path, data).await {
+                state.portability_service.discard_upload(upload_id);
+                return Err((StatusCode::INTERNAL_SERVER_ERROR, e.to_string()));
+            }
+            upload = Some((upload_id, path));
+            break;
+        }
+    }
+    let (upload_id, path) = upload.ok_or((StatusCode::BAD_REQUEST, "No file uploaded".to_string()))?;
+
+    let summary: ImportSummary = match state.portability_service.analyze_import(&query.format, path).await {
+        Ok(summary) => summary,
+        Err(e) => {
+            state.portability_service.discard_upload(upload_id);
+            return Err((StatusCode::BAD_REQUEST, e));
+        }
+    };
+
+    Ok(Json(serde_json::json!({ "upload_id": upload_id, "summary": summary })))
+}
+
+async fn start_import(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Path(kb_id): Path<Uuid>,
+    Query(query): Query<ImportQuery>,
+    Json(payload): Json<StartImportRequest>,
+) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
+    authorize_author(&state, kb_id, user.id).await?;
+
+    let path = state.portability_service.take_upload(payload.upload_id, user.id, kb_id)
+        .ok_or((StatusCode::NOT_FOUND, "Upload not found".to_string()))?;
+
+    let task_id = match state.portability_service.start_import(&query.format, kb_id, path.clone()).await {
+        Ok(task_id) => task_id,
+        Err(e) => {
+            let _ = tokio::fs::remove_file(path).await;
+            return Err((StatusCode::BAD_REQUEST, e));
+        }
+    };
+
+    Ok(Json(serde_json::json!({ "task_id": task_id })))
+}
+
 async fn task_progress(
     State(state): State<AppState>,
     _user: AuthenticatedUser,
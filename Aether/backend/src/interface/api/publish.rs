@@ -0,0 +1,67 @@
+use axum::{
+    extract::{Path, Query, State},
+    response::IntoResponse,
+    http::StatusCode,
+    routing::get,
+    Json, Router,
+};
+use uuid::Uuid;
+use crate::interface::state::AppState;
+use crate::interface::api::auth::AuthenticatedUser;
+
+#[derive(serde::Deserialize)]
+pub struct PublishQuery {
+    #[serde(default)]
+    full: bool,
+}
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/:kb_id", get(site_status).post(publish_site).delete(unpublish_site))
+}
+
+fn error_status(e: String) -> (StatusCode, String) {
+    let status = match e.as_str() {
+        "Knowledge Base not found" => StatusCode::NOT_FOUND,
+        "Unauthorized" => StatusCode::FORBIDDEN,
+        "Only public knowledge bases can be published" => StatusCode::BAD_REQUEST,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    };
+    (status, e)
+}
+
+async fn site_status(
+    State(state): State<AppState>,
+    _user: AuthenticatedUser, // Require auth
+    Path(kb_id): Path<Uuid>,
+) -> impl IntoResponse {
+    let published = state.site_generator.is_published(kb_id);
+    Json(serde_json::json!({
+        "published": published,
+        "url": published.then(|| format!("/sites/{}/", kb_id)),
+    }))
+}
+
+/// Builds the static site of a public KB; `?full=true` rewrites every page.
+async fn publish_site(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Path(kb_id): Path<Uuid>,
+    Query(query): Query<PublishQuery>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    let report = state.site_generator.publish(kb_id, Some(user.id), query.full).await
+        .map_err(error_status)?;
+
+    Ok(Json(report))
+}
+
+async fn unpublish_site(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Path(kb_id): Path<Uuid>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    state.site_generator.unpublish(kb_id, user.id).await
+        .map_err(error_status)?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
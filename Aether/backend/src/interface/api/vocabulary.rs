@@ -1,6 +1,6 @@
 use axum::{
     Router,
-    routing::{post, delete},
+    routing::{get, post, delete},
     extract::{State, Query, Path},
     Json,
     response::IntoResponse,
@@ -10,7 +10,8 @@ use serde::Deserialize;
 use utoipa::{ToSchema, IntoParams};
 use crate::{
     domain::{
-        models::{Vocabulary, Node, NodeType, PermissionMode, UserId},
+        models::{Vocabulary, VocabularyExample, Node, NodeType, PermissionMode, UserId},
+        morphology::Morphology,
         ports::VocabularyRepository,
     },
     interface::{api::auth::AuthenticatedUser, state::AppState},
@@ -64,6 +65,11 @@ pub struct ListVocabularyRequest {
     pub kb_id: Option<Uuid>,
 }
 
+#[derive(Deserialize, IntoParams)]
+pub struct AnalyzeWordRequest {
+    pub word: String,
+}
+
 #[derive(Deserialize, ToSchema)]
 pub struct BatchDeleteRequest {
     pub ids: Vec<Uuid>,
@@ -78,6 +84,8 @@ pub fn router() -> Router<AppState> {
         .route("/api/vocabulary/:id/increment_query", post(increment_query_count))
         .route("/api/vocabulary/:id/toggle_importance", post(toggle_importance))
         .route("/api/vocabulary/sentences/search", post(search_sentences))
+        .route("/api/vocabulary/analyze", get(analyze_word))
+        .route("/api/vocabulary/roots/:root", get(get_word_family))
 }
 
 #[derive(Deserialize, ToSchema)]
@@ -112,6 +120,20 @@ async fn search_sentences(
     }
 }
 
+fn example(e: ExampleRequest) -> VocabularyExample {
+    VocabularyExample {
+        id: Uuid::new_v4(),
+        sentence: e.sentence,
+        translation: e.translation,
+        note: e.note,
+        image_url: e.image_url,
+        article_id: e.article_id,
+        sentence_uuid: e.sentence_uuid,
+        created_at: Utc::now(),
+        global_sentence_id: None,
+    }
+}
+
 #[utoipa::path(
     post,
     path = "/api/vocabulary",
@@ -129,57 +151,82 @@ async fn save_vocabulary(
 ) -> impl IntoResponse {
     let user_id = UserId(auth.id);
     
-    // Check for existing word to Determine Upsert vs Create
-    let (id, _is_update, existing_count, existing_importance) = if let Ok(Some(existing)) = state.repo.find_by_word(&user_id, &payload.word).await {
-         (existing.node.id, true, existing.query_count, existing.is_important)
-    } else {
-         (Uuid::new_v4(), false, 0, false)
-    };
-    
-    // Map Examples
-    let examples = payload.examples.unwrap_or_default().into_iter().map(|e| {
-        use crate::domain::models::VocabularyExample;
-        VocabularyExample {
-            id: Uuid::new_v4(),
-            sentence: e.sentence,
-            translation: e.translation,
-            note: e.note,
-            image_url: e.image_url,
-            article_id: e.article_id,
-            sentence_uuid: e.sentence_uuid,
-            created_at: Utc::now(),
-            global_sentence_id: None,
+    // Check for existing word (exact or same lemma) to Determine Upsert vs Create
+    let existing = state.repo.find_by_word(&user_id, &payload.word).await.ok().flatten();
+
+    let new_examples = payload.examples.unwrap_or_default().into_iter().map(example);
+
+    let vocab = match existing {
+        // Saving an inflected form ("ran") merges into the existing entry ("run"):
+        // that entry keeps its own fields, and the form's meaning becomes an example.
+        Some(mut base) if Morphology::normalize(&base.word) != Morphology::normalize(&payload.word) => {
+            let sentence = payload.context_sentence.filter(|s| !s.trim().is_empty());
+            let definition = payload.definition.trim();
+            if sentence.is_some() || !definition.is_empty() {
+                let note = if definition.is_empty() {
+                    payload.word.clone()
+                } else {
+                    format!("{}: {}", payload.word, definition)
+                };
+                base.examples.push(example(ExampleRequest {
+                    sentence: sentence.unwrap_or_else(|| payload.word.clone()),
+                    translation: payload.translation,
+                    note: Some(note),
+                    image_url: payload.image_url,
+                    article_id: None,
+                    sentence_uuid: None,
+                }));
+            }
+            base.examples.extend(new_examples);
+            if base.root.is_none() {
+                base.root = payload.root.filter(|r| !r.trim().is_empty());
+            }
+            base.node.updated_at = Utc::now();
+            base
         }
-    }).collect();
+        existing => {
+            let (id, query_count, is_important) = existing
+                .map(|e| (e.node.id, e.query_count, e.is_important))
+                .unwrap_or((Uuid::new_v4(), 0, false));
+            let word = payload.word.clone();
 
-    let vocab = Vocabulary {
-        node: Node {
-            id,
-            parent_id: None,
-            author_id: user_id.0,
-            knowledge_base_id: payload.kb_id,
-            r#type: NodeType::Vocabulary,
-            title: payload.word.clone(), 
-            permission_mode: PermissionMode::Private, 
-            created_at: Utc::now(),
-            updated_at: Utc::now(),
-        },
-        word: payload.word,
-        definition: payload.definition,
-        translation: payload.translation,
-        phonetic: payload.phonetic,
-        context_sentence: payload.context_sentence,
-        image_url: payload.image_url,
-        language: payload.language.unwrap_or("en".to_string()),
-        status: "New".to_string(),
-        root: payload.root,
-        examples,
-        query_count: existing_count, // Preserve or 0
-        is_important: existing_importance, // Preserve or false
+            // Suggest a root from morphology when the client did not send one
+            let root = payload.root
+                .filter(|r| !r.trim().is_empty())
+                .or_else(|| Morphology::suggest_root(&word).map(|r| r.root));
+
+            Vocabulary {
+                node: Node {
+                    id,
+                    parent_id: None,
+                    author_id: user_id.0,
+                    knowledge_base_id: payload.kb_id,
+                    r#type: NodeType::Vocabulary,
+                    title: word.clone(), 
+                    permission_mode: PermissionMode::Private, 
+                    created_at: Utc::now(),
+                    updated_at: Utc::now(),
+                },
+                lemma: Some(Morphology::lemmatize(&word)),
+                word,
+                definition: payload.definition,
+                translation: payload.translation,
+                phonetic: payload.phonetic,
+                context_sentence: payload.context_sentence,
+                image_url: payload.image_url,
+                language: payload.language.unwrap_or("en".to_string()),
+                status: "New".to_string(),
+                root,
+                examples: new_examples.collect(),
+                query_count, // Preserve or 0
+                is_important, // Preserve or false
+            }
+        }
     };
 
+    let (word, lemma, root) = (vocab.word.clone(), vocab.lemma.clone(), vocab.root.clone());
     match state.repo.save(vocab).await {
-            Ok(id) => (StatusCode::CREATED, Json(serde_json::json!({ "id": id }))).into_response(),
+            Ok(id) => (StatusCode::CREATED, Json(serde_json::json!({ "id": id, "word": word, "lemma": lemma, "root": root }))).into_response(),
             Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({ "error": e.to_string() }))).into_response(),
     }
 }
@@ -363,3 +410,74 @@ async fn add_example(
         Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({ "error": e.to_string() }))).into_response(),
     }
 }
+
+#[utoipa::path(
+    get,
+    path = "/api/vocabulary/analyze",
+    params(
+        AnalyzeWordRequest
+    ),
+    responses(
+        (status = 200, description = "Lemma, affixes and suggested root", body = serde_json::Value)
+    ),
+    tag = "vocabulary"
+)]
+async fn analyze_word(
+    _auth: AuthenticatedUser,
+    Query(params): Query<AnalyzeWordRequest>,
+) -> impl IntoResponse {
+    let analysis = Morphology::decompose(&params.word);
+    (StatusCode::OK, Json(serde_json::to_value(analysis).unwrap_or_default())).into_response()
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/vocabulary/roots/{root}",
+    params(
+        ("root" = String, Path, description = "Root, e.g. spec")
+    ),
+    responses(
+        (status = 200, description = "Word family sharing the root", body = serde_json::Value),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "vocabulary"
+)]
+async fn get_word_family(
+    auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(root): Path<String>,
+) -> impl IntoResponse {
+    let canonical = Morphology::canonical_root(&root);
+
+    let stored = match state.repo.find_root(&canonical).await {
+        Ok(r) => r,
+        Err(e) => return (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({ "error": e.to_string() }))).into_response(),
+    };
+
+    let words = match state.repo.list_by_root(&UserId(auth.id), &canonical).await {
+        Ok(list) => list,
+        Err(e) => return (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({ "error": e.to_string() }))).into_response(),
+    };
+
+    let meaning = stored.and_then(|r| r.meaning)
+        .or_else(|| Morphology::root_meaning(&canonical).map(|m| m.to_string()));
+
+    let members: Vec<serde_json::Value> = words.into_iter().map(|v| {
+        let analysis = Morphology::decompose(&v.word);
+        serde_json::json!({
+            "id": v.node.id,
+            "word": v.word,
+            "lemma": v.lemma,
+            "definition": v.definition,
+            "translation": v.translation,
+            "prefixes": analysis.prefixes,
+            "suffixes": analysis.suffixes,
+        })
+    }).collect();
+
+    (StatusCode::OK, Json(serde_json::json!({
+        "root": canonical,
+        "meaning": meaning,
+        "words": members,
+    }))).into_response()
+}
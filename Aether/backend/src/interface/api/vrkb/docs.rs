@@ -1,12 +1,15 @@
 use axum::{
     extract::{Path, State},
     routing::get,
+    response::{IntoResponse, Response},
+    http::{HeaderMap, header::ETAG},
     Json, Router,
 };
 use uuid::Uuid;
 use crate::interface::state::AppState;
 use crate::domain::models::VrkbDoc;
-use crate::domain::ports::VrkbRepository;
+use crate::domain::ports::{VrkbRepository, RepositoryError};
+use crate::interface::api::concurrency::{conflict_response, etag, expected_revision};
 use axum::http::StatusCode;
 use chrono::Utc;
 
@@ -31,6 +34,7 @@ struct UpdateDocRequest {
     title: String,
     content: Option<serde_json::Value>,
     parent_id: Option<Uuid>,
+    expected_revision: Option<i64>, // Alternative to If-Match
 }
 
 async fn list_docs(
@@ -72,10 +76,10 @@ async fn create_doc(
 async fn get_doc(
     State(state): State<AppState>,
     Path(id): Path<Uuid>,
-) -> Result<Json<VrkbDoc>, (StatusCode, String)> {
+) -> Result<impl IntoResponse, (StatusCode, String)> {
     let doc = state.repo.get_doc(&id).await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
     match doc {
-        Some(d) => Ok(Json(d)),
+        Some(d) => Ok(([(ETAG, etag(d.revision()))], Json(d))),
         None => Err((StatusCode::NOT_FOUND, "Doc not found".to_string())),
     }
 }
@@ -83,23 +87,44 @@ async fn get_doc(
 async fn update_doc(
     State(state): State<AppState>,
     Path(id): Path<Uuid>,
+    headers: HeaderMap,
     Json(payload): Json<UpdateDocRequest>,
-) -> Result<Json<VrkbDoc>, (StatusCode, String)> {
-    let existing = state.repo.get_doc(&id).await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
-    if let Some(mut doc) = existing {
-        doc.title = payload.title;
-        doc.content = payload.content;
-        doc.parent_id = payload.parent_id;
-        doc.updated_at = Utc::now();
-        
-        // Clone doc because repo.update_doc consumes it
-        state.repo.update_doc(doc.clone()).await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
-        Ok(Json(doc))
-    } else {
-        Err((StatusCode::NOT_FOUND, "Doc not found".to_string()))
+) -> Response {
+    let expected = match expected_revision(&headers, payload.expected_revision) {
+        Ok(expected) => expected,
+        Err(rejection) => return rejection.into_response(),
+    };
+    let existing = match state.repo.get_doc(&id).await {
+        Ok(existing) => existing,
+        Err(e) => return (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+    };
+    let Some(mut doc) = existing else {
+        return (StatusCode::NOT_FOUND, "Doc not found".to_string()).into_response();
+    };
+    doc.title = payload.title;
+    doc.content = payload.content;
+    doc.parent_id = payload.parent_id;
+    doc.updated_at = Utc::now();
+
+    // Clone doc because repo.update_doc consumes it
+    match state.repo.update_doc(doc.clone(), expected).await {
+        Ok(()) => ([(ETAG, etag(doc.revision()))], Json(doc)).into_response(),
+        Err(RepositoryError::RevisionConflict { expected, .. }) => match state.repo.get_doc(&id).await {
+            // Docs keep no history, so the conflict is diffed against what the client sent
+            Ok(Some(current)) => conflict_response(expected, current.revision(), &current, &doc_text(&doc), &doc_text(&current)),
+            Ok(None) => (StatusCode::NOT_FOUND, "Doc not found".to_string()).into_response(),
+            Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+        },
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
     }
 }
 
+fn doc_text(doc: &VrkbDoc) -> String {
+    doc.content.as_ref()
+        .map(|c| serde_json::to_string_pretty(c).unwrap_or_default())
+        .unwrap_or_default()
+}
+
 async fn delete_doc(
     State(state): State<AppState>,
     Path(id): Path<Uuid>,
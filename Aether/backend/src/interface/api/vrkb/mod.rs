@@ -21,4 +21,5 @@ pub fn router() -> Router<AppState> {
         .merge(docs::router())
         .merge(stats::router())
         .route("/api/kb/:id/structure", axum::routing::get(structure::get_kb_structure))
+        .route("/api/kb/:id/structure/diagnostics", axum::routing::get(structure::get_kb_diagnostics))
 }
@@ -7,6 +7,7 @@ use uuid::Uuid;
 use serde_json::Value;
 
 use crate::domain::graph::computed_tree::ComputedTreeService;
+use crate::domain::graph::dependencies::{analyze, DependencyReport};
 use crate::infrastructure::persistence::repositories::block_repository::BlockRepository;
 use crate::interface::state::AppState;
 
@@ -34,3 +35,26 @@ pub async fn get_kb_structure(
 
     Ok(Json(json_blocks))
 }
+
+// GET /api/kb/:id/structure/diagnostics
+pub async fn get_kb_diagnostics(
+    Path(kb_id): Path<Uuid>,
+    State(state): State<AppState>,
+) -> Result<Json<DependencyReport>, (axum::http::StatusCode, String)> {
+    let repo = BlockRepository::new(state.repo.db.clone());
+    let blocks = repo.find_by_kb_id(kb_id).await
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let mut report = analyze(&blocks);
+
+    // References leaving the KB are fine as long as their target still exists
+    let targets: Vec<Uuid> = report.external.iter().map(|r| r.target_id).collect();
+    let existing = repo.find_by_ids(&targets).await
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .into_iter()
+        .map(|b| b.id)
+        .collect();
+    report.resolve_external(&existing);
+
+    Ok(Json(report))
+}
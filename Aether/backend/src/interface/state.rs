@@ -17,15 +17,20 @@ pub struct AppState {
     pub permission_service: crate::domain::permission_service::PermissionService<PostgresRepository>,
     pub dictionary: DictionaryLoader,
     pub dictionary_cache: moka::future::Cache<String, String>, // JSON serialized entry
+    pub entry_parsers: crate::infrastructure::dictionary::parser::EntryParserRegistry,
     pub indexer_service: Arc<IndexerService>,
     pub graph_service: Arc<crate::domain::graph_service::GraphService>,
     pub asset_storage: Arc<crate::infrastructure::storage::service::AssetStorageService>,
+    pub asset_gc: Arc<crate::infrastructure::storage::gc::AssetGcService>,
     pub schema_registry: crate::domain::kb::SchemaRegistry,
     pub arxiv_service: Arc<crate::infrastructure::services::arxiv::ArxivService>,
     pub rss_service: Arc<crate::infrastructure::services::rss::RssService>,
     pub asset_manager: Arc<crate::infrastructure::services::asset_manager::AssetManager>,
     pub backup_service: Arc<crate::infrastructure::services::backup_service::BackupService>,
+    pub collab_service: Arc<crate::infrastructure::services::collab_service::CollaborationService>,
     pub portability_service: Arc<crate::infrastructure::services::portability_service::PortabilityService>,
+    pub site_generator: Arc<crate::infrastructure::services::site::SiteGenerator>,
+    pub feed_service: Arc<crate::infrastructure::services::feeds::FeedService>,
     pub system_settings_repository: Arc<crate::infrastructure::persistence::repositories::system_settings_repository::SystemSettingsRepository>,
 }
 
@@ -38,9 +38,33 @@ async fn main() {
     // 4. Services Initialization
     let state = services::init_app_state(db.clone()).await;
 
+    // Static site of a public KB (CLI): publish <kb_id> [--full]
+    if args.len() > 1 && args[1] == "publish" {
+        let Some(kb_id) = args.get(2).and_then(|id| uuid::Uuid::parse_str(id).ok()) else {
+            eprintln!("Usage: publish <kb_id> [--full]");
+            std::process::exit(2);
+        };
+        let full = args.iter().any(|a| a == "--full");
+        match state.site_generator.publish(kb_id, None, full).await {
+            Ok(report) => println!("{}", serde_json::to_string_pretty(&report).unwrap()),
+            Err(e) => {
+                eprintln!("Publish failed: {}", e);
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+
     // 5. Seeding
     seeding::seed_all(&db, &state.repo).await;
 
+    // Scheduled backups
+    state.backup_service.spawn_scheduler();
+
+    // Asset garbage collection (ASSET_GC_INTERVAL_HOURS=0 turns it off)
+    let gc_interval = std::env::var("ASSET_GC_INTERVAL_HOURS").ok().and_then(|v| v.parse().ok()).unwrap_or(24);
+    state.asset_gc.spawn_gc(gc_interval);
+
     // 6. Router & Server
     let app = router::build_router(state);
     